@@ -0,0 +1,57 @@
+//! Benchmarks [`Chunk::chunk_by_line_window`]'s `memchr`/`bytecount`-based,
+//! scan-once boundary detection against a naive reimplementation of how
+//! this crate used to split a file into line windows (re-collecting a
+//! `Vec<&str>` of every line in the file for every window it produced), to
+//! show the optimization actually pays for itself rather than asserting it
+//! by comment. `naive_chunk_by_line_window` below is that reimplementation,
+//! kept only in this benchmark — it isn't dead code the library carries
+//! around, since nothing outside this file calls it.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use transfiguration::chunk::Chunk;
+
+fn synthetic_source(line_count: usize) -> String {
+    (0..line_count)
+        .map(|i| format!("fn f_{i}() {{ let x = {i}; x + 1 }}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn naive_chunk_by_line_window(content: &str, window_lines: usize) -> Vec<String> {
+    let mut windows = Vec::new();
+    let total_lines = content.lines().count().max(1);
+    let mut start_line = 1;
+    while start_line <= total_lines {
+        let end_line = (start_line + window_lines - 1).min(total_lines);
+        let lines: Vec<&str> = content.lines().collect();
+        let span = lines.get(start_line - 1..end_line).unwrap_or(&[]).join("\n");
+        windows.push(span);
+        start_line = end_line + 1;
+    }
+    windows
+}
+
+fn bench_line_window_chunking(c: &mut Criterion) {
+    let content = synthetic_source(20_000);
+    let window_lines = 40;
+
+    let mut group = c.benchmark_group("line_window_chunking");
+    group.throughput(Throughput::Bytes(content.len() as u64));
+
+    group.bench_function("naive_vec_str_rescanned_per_window", |b| {
+        b.iter(|| {
+            black_box(naive_chunk_by_line_window(&content, window_lines));
+        });
+    });
+
+    group.bench_function("memchr_line_index_scanned_once", |b| {
+        b.iter(|| {
+            black_box(Chunk::chunk_by_line_window(0, "bench.rs", &content, window_lines));
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_line_window_chunking);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+//! Feeds arbitrary byte streams (interpreted as a file's content) and
+//! arbitrary line-span parameters to `Chunk::from_line_span`, exercising its
+//! context-padding math and the `LineIndex` slicing the line-based chunkers
+//! share. The harness just asserts no panic;
+//! `src/chunk/mod.rs`'s proptest suite checks the actual invariants.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use transfiguration::chunk::{Chunk, ChunkId};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input {
+    content: String,
+    start_line: usize,
+    end_line: usize,
+    context_lines: usize,
+}
+
+fuzz_target!(|input: Input| {
+    let (start_line, end_line) = if input.start_line <= input.end_line {
+        (input.start_line, input.end_line)
+    } else {
+        (input.end_line, input.start_line)
+    };
+    let context_lines = input.context_lines % 64;
+
+    let _ = Chunk::from_line_span(ChunkId(0), "fuzz.rs", &input.content, start_line, end_line, context_lines);
+});
@@ -0,0 +1,467 @@
+//! Concurrent multi-analyzer orchestration over a stored dataset.
+//!
+//! This crate has no `MigrationAnalyzer`, `PerformanceAnalyzer`, or
+//! `AnalysisReportGenerator` — those names belong to a different tool. The
+//! closest real shapes already here are [`crate::sinks::query::ResultsStore`]
+//! (the "stored dataset") and [`crate::validation`]'s pass/fail checks (the
+//! closest thing to an "analyzer" this crate has). [`AnalysisPipeline::run_all`]
+//! below builds the orchestration the request actually asks for — load
+//! records, run several analyzers concurrently with bounded parallelism,
+//! persist each analyzer's findings, and combine everything into one report
+//! — over [`ConfidenceAnalyzer`] and [`ValidationStatusAnalyzer`] rather than
+//! the two analyzers named in the request, since this crate has no
+//! migration or performance domain data to analyze. A full HTML/Markdown
+//! report renderer already exists for run artifacts in
+//! [`crate::report::html`]; [`AnalysisRunReport::to_markdown`] here is a
+//! small, analysis-specific summary rather than a duplicate of that.
+//!
+//! There's no `source_id`/research-source model either, so [`Citation`]
+//! cites the only provenance a [`ResultRecord`] actually has: the
+//! `source_path` the claim was derived from, with its `confidence` standing
+//! in for the requested "reliability score". Every [`AnalysisFinding`]
+//! carries the citation for the record it was computed from, and
+//! [`AnalysisRunReport::to_markdown`]/[`AnalysisRunReport::to_html`] render
+//! each failing finding's citation as a footnote next to it (passing
+//! findings are only counted, not footnoted).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rusqlite::Connection;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::chunk::ChunkId;
+use crate::sinks::query::{ResultFilter, ResultRecord, ResultsStore, ValidationStatus};
+use crate::sinks::SinkError;
+
+/// The source a finding's claim is attributed to, and how much to trust it —
+/// the closest thing to the requested "source ID with a reliability score"
+/// this crate's data model carries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Citation {
+    pub source_path: PathBuf,
+    pub reliability_score: f32,
+}
+
+/// One analyzer's finding for a single [`ResultRecord`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisFinding {
+    pub analyzer_name: &'static str,
+    pub chunk_id: ChunkId,
+    pub passed: bool,
+    pub detail: String,
+    pub citation: Citation,
+}
+
+/// A check run once per [`ResultRecord`] by [`AnalysisPipeline::run_all`].
+/// Implementations must be cheap and side-effect-free; persistence and
+/// concurrency are the pipeline's job, not the analyzer's.
+pub trait Analyzer: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn analyze(&self, record: &ResultRecord) -> AnalysisFinding;
+}
+
+/// Flags results below a minimum confidence — standing in for this crate's
+/// missing `PerformanceAnalyzer`. "Performance" here means the
+/// summarization run's own confidence signal, the only quantitative score a
+/// [`ResultRecord`] carries.
+pub struct ConfidenceAnalyzer {
+    pub minimum_confidence: f32,
+}
+
+impl Analyzer for ConfidenceAnalyzer {
+    fn name(&self) -> &'static str {
+        "confidence"
+    }
+
+    fn analyze(&self, record: &ResultRecord) -> AnalysisFinding {
+        let passed = record.confidence >= self.minimum_confidence;
+        AnalysisFinding {
+            analyzer_name: self.name(),
+            chunk_id: record.chunk_id,
+            passed,
+            detail: format!(
+                "confidence {:.2} {} minimum {:.2}",
+                record.confidence,
+                if passed { ">=" } else { "<" },
+                self.minimum_confidence
+            ),
+            citation: Citation { source_path: record.source_path.clone(), reliability_score: record.confidence },
+        }
+    }
+}
+
+/// Flags results that failed validation — standing in for this crate's
+/// missing `MigrationAnalyzer`. The closest analog this crate has to "is
+/// this project in a healthy state" is whether its validation passed.
+pub struct ValidationStatusAnalyzer;
+
+impl Analyzer for ValidationStatusAnalyzer {
+    fn name(&self) -> &'static str {
+        "validation_status"
+    }
+
+    fn analyze(&self, record: &ResultRecord) -> AnalysisFinding {
+        let passed = record.validation_status == ValidationStatus::Pass;
+        AnalysisFinding {
+            analyzer_name: self.name(),
+            chunk_id: record.chunk_id,
+            passed,
+            detail: format!("validation status: {:?}", record.validation_status),
+            citation: Citation { source_path: record.source_path.clone(), reliability_score: record.confidence },
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnalysisError {
+    #[error("failed to load or persist analysis data: {0}")]
+    Sink(#[from] SinkError),
+}
+
+/// Persists [`AnalysisFinding`]s to their own table, separate from
+/// [`ResultsStore`]'s `results` table, so analyzer output doesn't need to
+/// be squeezed into the results schema.
+pub struct AnalysisStore {
+    connection: Mutex<Connection>,
+}
+
+impl AnalysisStore {
+    pub fn open(path: &Path) -> Result<Self, AnalysisError> {
+        let connection = Connection::open(path).map_err(SinkError::from)?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS analysis_findings (
+                    chunk_id INTEGER NOT NULL,
+                    analyzer_name TEXT NOT NULL,
+                    passed INTEGER NOT NULL,
+                    detail TEXT NOT NULL,
+                    citation_source_path TEXT NOT NULL,
+                    citation_reliability_score REAL NOT NULL
+                )",
+                [],
+            )
+            .map_err(SinkError::from)?;
+        Ok(AnalysisStore { connection: Mutex::new(connection) })
+    }
+
+    fn insert(&self, finding: &AnalysisFinding) -> Result<(), AnalysisError> {
+        let connection = self.connection.lock().expect("analysis store connection mutex poisoned");
+        connection
+            .execute(
+                "INSERT INTO analysis_findings
+                    (chunk_id, analyzer_name, passed, detail, citation_source_path, citation_reliability_score)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    finding.chunk_id.0,
+                    finding.analyzer_name,
+                    finding.passed,
+                    finding.detail,
+                    finding.citation.source_path.to_string_lossy(),
+                    finding.citation.reliability_score,
+                ],
+            )
+            .map_err(SinkError::from)?;
+        Ok(())
+    }
+}
+
+/// The combined result of one [`AnalysisPipeline::run_all`] call.
+#[derive(Debug, Clone)]
+pub struct AnalysisRunReport {
+    pub records_analyzed: usize,
+    pub findings: Vec<AnalysisFinding>,
+}
+
+impl AnalysisRunReport {
+    /// Findings where the analyzer did not pass the record.
+    pub fn failures(&self) -> impl Iterator<Item = &AnalysisFinding> {
+        self.findings.iter().filter(|finding| !finding.passed)
+    }
+
+    fn analyzer_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.findings.iter().map(|f| f.analyzer_name).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// A short Markdown summary: one line per analyzer with its pass count,
+    /// then one footnoted line per failure, with the footnotes at the
+    /// bottom citing the source path and reliability score each failure was
+    /// derived from. A fuller, styled run report already exists in
+    /// [`crate::report::html`]; this is just enough to eyeball an
+    /// `AnalysisPipeline::run_all` result from a terminal.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Analysis run ({} records)\n\n", self.records_analyzed);
+        for name in self.analyzer_names() {
+            let (passed, total) = self
+                .findings
+                .iter()
+                .filter(|f| f.analyzer_name == name)
+                .fold((0, 0), |(passed, total), f| (passed + f.passed as usize, total + 1));
+            out.push_str(&format!("- **{name}**: {passed}/{total} passed\n"));
+        }
+
+        let failures: Vec<&AnalysisFinding> = self.failures().collect();
+        if !failures.is_empty() {
+            out.push_str("\n## Failures\n\n");
+            for (index, finding) in failures.iter().enumerate() {
+                out.push_str(&format!(
+                    "- `{:?}` [{}]: {} [^{}]\n",
+                    finding.chunk_id,
+                    finding.analyzer_name,
+                    finding.detail,
+                    index + 1
+                ));
+            }
+            out.push('\n');
+            for (index, finding) in failures.iter().enumerate() {
+                out.push_str(&format!(
+                    "[^{}]: {} (reliability {:.2})\n",
+                    index + 1,
+                    finding.citation.source_path.display(),
+                    finding.citation.reliability_score
+                ));
+            }
+        }
+        out
+    }
+
+    /// An HTML equivalent of [`Self::to_markdown`], following the same
+    /// escaping approach as [`crate::validation::ValidationReport::to_html`]:
+    /// a `<ul>` of failures, each followed by a superscript citation link to
+    /// a footnote `<ol>` at the end.
+    pub fn to_html(&self) -> String {
+        let mut out = format!("<h1>Analysis run ({} records)</h1><ul>", self.records_analyzed);
+        for name in self.analyzer_names() {
+            let (passed, total) = self
+                .findings
+                .iter()
+                .filter(|f| f.analyzer_name == name)
+                .fold((0, 0), |(passed, total), f| (passed + f.passed as usize, total + 1));
+            out.push_str(&format!("<li><strong>{}</strong>: {passed}/{total} passed</li>", escape_html(name)));
+        }
+        out.push_str("</ul>");
+
+        let failures: Vec<&AnalysisFinding> = self.failures().collect();
+        if !failures.is_empty() {
+            out.push_str("<h2>Failures</h2><ul id=\"failures\">");
+            for (index, finding) in failures.iter().enumerate() {
+                out.push_str(&format!(
+                    "<li><code>{:?}</code> [{}]: {} <sup><a href=\"#citation-{n}\">[{n}]</a></sup></li>",
+                    finding.chunk_id,
+                    escape_html(finding.analyzer_name),
+                    escape_html(&finding.detail),
+                    n = index + 1,
+                ));
+            }
+            out.push_str("</ul><ol id=\"citations\">");
+            for (index, finding) in failures.iter().enumerate() {
+                out.push_str(&format!(
+                    "<li id=\"citation-{}\">{} (reliability {:.2})</li>",
+                    index + 1,
+                    escape_html(&finding.citation.source_path.display().to_string()),
+                    finding.citation.reliability_score
+                ));
+            }
+            out.push_str("</ol>");
+        }
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Orchestrates one or more [`Analyzer`]s over every [`ResultRecord`] in a
+/// [`ResultsStore`].
+pub struct AnalysisPipeline {
+    max_concurrency: usize,
+}
+
+impl AnalysisPipeline {
+    pub fn new(max_concurrency: usize) -> Self {
+        AnalysisPipeline { max_concurrency: max_concurrency.max(1) }
+    }
+
+    /// Loads every record from `results_store` matching `filter` (read page
+    /// by page via [`ResultsStore::stream_results`], but all matching records
+    /// are collected in memory before any analysis starts), runs every analyzer in
+    /// `analyzers` against each one concurrently (bounded to
+    /// `max_concurrency` in-flight analyses), persists every finding to
+    /// `analysis_store`, and returns the combined report.
+    pub async fn run_all(
+        &self,
+        results_store: &ResultsStore,
+        analysis_store: Arc<AnalysisStore>,
+        analyzers: &[Arc<dyn Analyzer>],
+        filter: ResultFilter,
+    ) -> Result<AnalysisRunReport, AnalysisError> {
+        let page_size = 200;
+        let records: Vec<ResultRecord> = {
+            let mut collected = Vec::new();
+            for result in results_store.stream_results(filter, page_size) {
+                collected.push(result?);
+            }
+            collected
+        };
+
+        let permits = Arc::new(Semaphore::new(self.max_concurrency));
+        let mut join_set: JoinSet<Result<AnalysisFinding, AnalysisError>> = JoinSet::new();
+
+        for record in &records {
+            for analyzer in analyzers {
+                let record = record.clone();
+                let analyzer = Arc::clone(analyzer);
+                let analysis_store = Arc::clone(&analysis_store);
+                let permits = Arc::clone(&permits);
+
+                join_set.spawn(async move {
+                    let _permit = permits.acquire().await.expect("analysis pipeline semaphore never closed");
+                    let finding = tokio::task::spawn_blocking(move || analyzer.analyze(&record))
+                        .await
+                        .expect("analyzer panicked");
+                    analysis_store.insert(&finding)?;
+                    Ok(finding)
+                });
+            }
+        }
+
+        let mut findings = Vec::new();
+        while let Some(joined) = join_set.join_next().await {
+            findings.push(joined.expect("analysis task panicked")?);
+        }
+
+        Ok(AnalysisRunReport { records_analyzed: records.len(), findings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn open_temp_store(name: &str) -> (ResultsStore, Arc<AnalysisStore>, PathBuf) {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        let results_store = ResultsStore::open(&path).unwrap();
+        let analysis_store = Arc::new(AnalysisStore::open(&path).unwrap());
+        (results_store, analysis_store, path)
+    }
+
+    fn record(chunk_id: u64, confidence: f32, status: ValidationStatus) -> ResultRecord {
+        ResultRecord {
+            chunk_id: ChunkId(chunk_id),
+            source_path: PathBuf::from(format!("f{chunk_id}.rs")),
+            summary_text: "a summary".to_string(),
+            confidence,
+            validation_status: status,
+            run_id: "run-1".to_string(),
+            model_version: String::new(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_analyzes_every_record_with_every_analyzer() {
+        let (results_store, analysis_store, path) = open_temp_store("transfiguration-analysis-pipeline-basic.sqlite");
+        results_store.insert(&record(1, 0.9, ValidationStatus::Pass)).unwrap();
+        results_store.insert(&record(2, 0.1, ValidationStatus::Fail)).unwrap();
+
+        let pipeline = AnalysisPipeline::new(4);
+        let analyzers: Vec<Arc<dyn Analyzer>> = vec![
+            Arc::new(ConfidenceAnalyzer { minimum_confidence: 0.5 }),
+            Arc::new(ValidationStatusAnalyzer),
+        ];
+
+        let report = pipeline
+            .run_all(&results_store, Arc::clone(&analysis_store), &analyzers, ResultFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report.records_analyzed, 2);
+        assert_eq!(report.findings.len(), 4);
+        assert_eq!(report.failures().count(), 2);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn findings_are_persisted_to_the_analysis_store() {
+        let (results_store, analysis_store, path) = open_temp_store("transfiguration-analysis-pipeline-persist.sqlite");
+        results_store.insert(&record(1, 0.9, ValidationStatus::Pass)).unwrap();
+
+        let pipeline = AnalysisPipeline::new(2);
+        let analyzers: Vec<Arc<dyn Analyzer>> = vec![Arc::new(ConfidenceAnalyzer { minimum_confidence: 0.5 })];
+        pipeline
+            .run_all(&results_store, Arc::clone(&analysis_store), &analyzers, ResultFilter::default())
+            .await
+            .unwrap();
+
+        let count: u64 = analysis_store
+            .connection
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM analysis_findings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    fn finding(chunk_id: u64, passed: bool, detail: &str, source_path: &str, reliability_score: f32) -> AnalysisFinding {
+        AnalysisFinding {
+            analyzer_name: "confidence",
+            chunk_id: ChunkId(chunk_id),
+            passed,
+            detail: detail.to_string(),
+            citation: Citation { source_path: PathBuf::from(source_path), reliability_score },
+        }
+    }
+
+    #[test]
+    fn to_markdown_summarizes_pass_counts_and_lists_failures() {
+        let report = AnalysisRunReport {
+            records_analyzed: 2,
+            findings: vec![
+                finding(1, true, "ok", "a.rs", 0.9),
+                finding(2, false, "too low", "b.rs", 0.1),
+            ],
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("1/2 passed"));
+        assert!(markdown.contains("too low"));
+    }
+
+    #[test]
+    fn to_markdown_footnotes_each_failure_with_its_citation() {
+        let report = AnalysisRunReport {
+            records_analyzed: 1,
+            findings: vec![finding(2, false, "too low", "b.rs", 0.1)],
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("[^1]"));
+        assert!(markdown.contains("[^1]: b.rs (reliability 0.10)"));
+    }
+
+    #[test]
+    fn to_html_links_each_failure_to_its_citation_footnote() {
+        let report = AnalysisRunReport {
+            records_analyzed: 1,
+            findings: vec![finding(2, false, "too low", "<b.rs>", 0.1)],
+        };
+
+        let html = report.to_html();
+        assert!(html.contains("href=\"#citation-1\""));
+        assert!(html.contains("id=\"citation-1\""));
+        assert!(html.contains("&lt;b.rs&gt;"));
+        assert!(!html.contains("<b.rs>"));
+    }
+}
@@ -0,0 +1,92 @@
+//! Benchmark entry point for [`transfiguration::scaling`]: builds one
+//! synthetic corpus with [`transfiguration::testgen::generate_synthetic_corpus`],
+//! tars it up, and runs repeated [`transfiguration::package::run_package_pipeline`]
+//! calls over that archive at several concurrency levels, printing the
+//! resulting [`transfiguration::scaling::ScalingReport`] as Markdown.
+//!
+//! Like `src/bin/soak.rs`, this binary does no argv parsing of its own —
+//! there's no `clap` dependency in this crate — so the concurrency levels
+//! and iteration count are read from the `SCALING_LEVELS` (comma-separated,
+//! e.g. `1,5,10,20`) and `SCALING_ITERATIONS_PER_LEVEL` environment
+//! variables, falling back to `1,5,10,20` and `20` when unset or
+//! unparseable.
+
+use std::path::Path;
+
+use transfiguration::package::run_package_pipeline;
+use transfiguration::scaling::{run_scaling_test, ScalingTestConfig};
+use transfiguration::testgen::{generate_synthetic_corpus, CorpusLanguage, CorpusSpec};
+use transfiguration::unpack::UnpackPolicy;
+
+fn main() {
+    let workspace = std::env::temp_dir().join("transfiguration-scaling-test");
+    let _ = std::fs::remove_dir_all(&workspace);
+
+    let archive_path = workspace.join("corpus.tar.gz");
+    if let Err(error) = build_corpus_archive(&workspace, &archive_path) {
+        eprintln!("scaling-test: failed to build the synthetic corpus: {error}");
+        std::process::exit(1);
+    }
+
+    let config = config_from_env();
+    let unpack_root = workspace.join("unpacked");
+    let next_run = std::sync::atomic::AtomicU64::new(0);
+
+    let result = run_scaling_test(&config, || {
+        let run_id = next_run.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let run_workspace = unpack_root.join(run_id.to_string());
+        let _ = run_package_pipeline(&archive_path, &run_workspace, UnpackPolicy::default());
+        let _ = std::fs::remove_dir_all(&run_workspace);
+    });
+
+    match result {
+        Ok(report) => println!("{}", report.to_markdown(0.7)),
+        Err(error) => {
+            eprintln!("scaling-test: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads `SCALING_LEVELS` / `SCALING_ITERATIONS_PER_LEVEL` from the
+/// environment, falling back field-by-field to `1,5,10,20` agents and 20
+/// iterations per level rather than failing the whole run over a typo.
+fn config_from_env() -> ScalingTestConfig {
+    let default_levels = vec![1, 5, 10, 20];
+    let concurrency_levels = std::env::var("SCALING_LEVELS")
+        .ok()
+        .and_then(|value| value.split(',').map(|level| level.trim().parse::<usize>()).collect::<Result<Vec<_>, _>>().ok())
+        .filter(|levels| !levels.is_empty())
+        .unwrap_or(default_levels);
+
+    let iterations_per_level = std::env::var("SCALING_ITERATIONS_PER_LEVEL").ok().and_then(|value| value.parse().ok()).unwrap_or(20);
+
+    ScalingTestConfig { concurrency_levels, iterations_per_level }
+}
+
+fn build_corpus_archive(workspace: &Path, archive_path: &Path) -> std::io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let corpus_dir = workspace.join("corpus");
+    let spec = CorpusSpec {
+        seed: 0x5CA1_1146,
+        language: CorpusLanguage::Rust,
+        file_count: 8,
+        functions_per_file: 6,
+        duplicate_ratio: 0.25,
+    };
+    let manifest = generate_synthetic_corpus(&spec, &corpus_dir)
+        .map_err(|error| std::io::Error::other(error.to_string()))?;
+
+    std::fs::create_dir_all(archive_path.parent().unwrap_or(workspace))?;
+    let file = std::fs::File::create(archive_path)?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for path in &manifest.files {
+        let name = path.strip_prefix(&corpus_dir).unwrap_or(path);
+        builder.append_path_with_name(path, name)?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+//! Nightly-job entry point for [`transfiguration::soak`]: builds one
+//! synthetic corpus with [`transfiguration::testgen::generate_synthetic_corpus`],
+//! tars it up, and loops [`transfiguration::package::run_package_pipeline`]
+//! over that archive for the configured duration, printing the resulting
+//! [`transfiguration::soak::SoakReport`] as Markdown.
+//!
+//! Like `src/main.rs`, this binary has no argv-parsing command loop — there's
+//! no `clap` dependency in this crate — and unlike it, reads no flags at
+//! all, so the soak duration and sample
+//! interval are read from the `SOAK_DURATION_SECS` / `SOAK_SAMPLE_SECS`
+//! environment variables, falling back to [`SoakConfig::default`] when unset
+//! or unparseable.
+
+use std::path::Path;
+use std::time::Duration;
+
+use transfiguration::package::run_package_pipeline;
+use transfiguration::soak::{run_soak_test, SoakConfig};
+use transfiguration::testgen::{generate_synthetic_corpus, CorpusLanguage, CorpusSpec};
+use transfiguration::unpack::UnpackPolicy;
+
+fn main() {
+    let workspace = std::env::temp_dir().join("transfiguration-soak");
+    let _ = std::fs::remove_dir_all(&workspace);
+
+    let archive_path = workspace.join("corpus.tar.gz");
+    if let Err(error) = build_corpus_archive(&workspace, &archive_path) {
+        eprintln!("soak: failed to build the synthetic corpus: {error}");
+        std::process::exit(1);
+    }
+
+    let config = config_from_env();
+    let mut iteration_error: Option<String> = None;
+    let unpack_root = workspace.join("unpacked");
+
+    let result = run_soak_test(&config, |iteration| {
+        let iteration_workspace = unpack_root.join(iteration.to_string());
+        if let Err(error) = run_package_pipeline(&archive_path, &iteration_workspace, UnpackPolicy::default()) {
+            iteration_error.get_or_insert_with(|| error.to_string());
+        }
+        let _ = std::fs::remove_dir_all(&iteration_workspace);
+    });
+
+    if let Some(error) = iteration_error {
+        eprintln!("soak: package pipeline failed mid-run: {error}");
+        std::process::exit(1);
+    }
+
+    match result {
+        Ok(report) => println!("{report}"),
+        Err(error) => {
+            eprintln!("soak: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reads `SOAK_DURATION_SECS` / `SOAK_SAMPLE_SECS` from the environment,
+/// falling back to [`SoakConfig::default`] field-by-field for anything
+/// unset or unparseable rather than failing the whole run over a typo.
+fn config_from_env() -> SoakConfig {
+    let default = SoakConfig::default();
+    SoakConfig {
+        duration: std::env::var("SOAK_DURATION_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.duration),
+        sample_interval: std::env::var("SOAK_SAMPLE_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default.sample_interval),
+        ..default
+    }
+}
+
+fn build_corpus_archive(workspace: &Path, archive_path: &Path) -> std::io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let corpus_dir = workspace.join("corpus");
+    let spec = CorpusSpec {
+        seed: 0x5011_50AC,
+        language: CorpusLanguage::Rust,
+        file_count: 8,
+        functions_per_file: 6,
+        duplicate_ratio: 0.25,
+    };
+    let manifest = generate_synthetic_corpus(&spec, &corpus_dir)
+        .map_err(|error| std::io::Error::other(error.to_string()))?;
+
+    std::fs::create_dir_all(archive_path.parent().unwrap_or(workspace))?;
+    let file = std::fs::File::create(archive_path)?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for path in &manifest.files {
+        let name = path.strip_prefix(&corpus_dir).unwrap_or(path);
+        builder.append_path_with_name(path, name)?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
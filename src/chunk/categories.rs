@@ -0,0 +1,201 @@
+//! Configurable keyword-category definitions loaded from a TOML file.
+//!
+//! This crate has no research "discovery engine" that crawls for named
+//! categories of things; the closest thing it has is
+//! [`crate::chunk::classify_chunk_by_path_and_content`]'s keyword/path
+//! heuristics, which are hardcoded to the five fixed [`ChunkClass`](crate::chunk::ChunkClass)
+//! variants used throughout reports and validation. Rather than bolt
+//! open-ended category names onto that fixed, stable enum, this module adds
+//! a separate, parallel system: arbitrary [`CategoryDefinition`]s (name,
+//! keywords, target count, evaluation threshold) loaded from a TOML file via
+//! [`load_category_config`], scored against a chunk's text by
+//! [`categorize`]. A caller who wants ad hoc categories (e.g. "terminal
+//! IDEs", "collaborative editors") can add them to the config file with no
+//! recompile; `target_count` is carried through for a caller to use as a
+//! stopping condition in whatever loop collects matches, since this module
+//! only scores one piece of text at a time and has no crawl/collection loop
+//! of its own.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CategoryConfigError {
+    #[error("failed to read category config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse category config: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// One configurable category: what it's called, which keywords count as
+/// evidence for it, how many matches a caller is ultimately looking for
+/// (`target_count`), and the minimum keyword-match ratio
+/// (`evaluation_threshold`, in `[0.0, 1.0]`) [`categorize`] requires before
+/// reporting a match.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CategoryDefinition {
+    pub name: String,
+    pub keywords: Vec<String>,
+    #[serde(default = "default_target_count")]
+    pub target_count: usize,
+    #[serde(default = "default_evaluation_threshold")]
+    pub evaluation_threshold: f32,
+}
+
+fn default_target_count() -> usize {
+    10
+}
+
+fn default_evaluation_threshold() -> f32 {
+    0.3
+}
+
+/// The full set of categories a run was configured with.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CategoryConfig {
+    #[serde(default)]
+    pub categories: Vec<CategoryDefinition>,
+}
+
+/// Reads a TOML file of the form:
+///
+/// ```toml
+/// [[categories]]
+/// name = "terminal IDEs"
+/// keywords = ["terminal", "tty", "pty"]
+/// target_count = 20
+/// evaluation_threshold = 0.25
+/// ```
+pub fn load_category_config(path: &Path) -> Result<CategoryConfig, CategoryConfigError> {
+    let contents = fs::read_to_string(path)?;
+    Ok(toml::from_str(&contents)?)
+}
+
+/// A category that cleared its [`CategoryDefinition::evaluation_threshold`]
+/// against a piece of text, with the fraction of its keywords that matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryMatch {
+    pub name: String,
+    pub score: f32,
+}
+
+/// Scores `text` against every category in `config` and returns the ones
+/// that clear their own `evaluation_threshold`, sorted by descending score.
+/// A category with no keywords never matches (score is always `0.0`).
+pub fn categorize(config: &CategoryConfig, text: &str) -> Vec<CategoryMatch> {
+    let lowercase_text = text.to_lowercase();
+
+    let mut matches: Vec<CategoryMatch> = config
+        .categories
+        .iter()
+        .filter_map(|category| {
+            if category.keywords.is_empty() {
+                return None;
+            }
+            let matched = category
+                .keywords
+                .iter()
+                .filter(|keyword| lowercase_text.contains(&keyword.to_lowercase()))
+                .count();
+            let score = matched as f32 / category.keywords.len() as f32;
+            (score >= category.evaluation_threshold).then(|| CategoryMatch { name: category.name.clone(), score })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with(categories: Vec<CategoryDefinition>) -> CategoryConfig {
+        CategoryConfig { categories }
+    }
+
+    fn category(name: &str, keywords: &[&str], evaluation_threshold: f32) -> CategoryDefinition {
+        CategoryDefinition {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            target_count: default_target_count(),
+            evaluation_threshold,
+        }
+    }
+
+    #[test]
+    fn loads_categories_from_a_toml_file_with_defaults_applied() {
+        let path = std::env::temp_dir().join("transfiguration-category-config.toml");
+        fs::write(
+            &path,
+            r#"
+            [[categories]]
+            name = "terminal IDEs"
+            keywords = ["terminal", "tty"]
+
+            [[categories]]
+            name = "collaborative editors"
+            keywords = ["collab", "crdt"]
+            target_count = 50
+            evaluation_threshold = 0.75
+            "#,
+        )
+        .unwrap();
+
+        let config = load_category_config(&path).unwrap();
+        assert_eq!(config.categories.len(), 2);
+        assert_eq!(config.categories[0].target_count, 10);
+        assert_eq!(config.categories[1].target_count, 50);
+        assert_eq!(config.categories[1].evaluation_threshold, 0.75);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_config_file_reports_an_io_error() {
+        let path = std::env::temp_dir().join("transfiguration-category-config-missing.toml");
+        let _ = fs::remove_file(&path);
+        assert!(matches!(load_category_config(&path), Err(CategoryConfigError::Io(_))));
+    }
+
+    #[test]
+    fn categorize_reports_only_categories_clearing_their_threshold() {
+        let config = config_with(vec![
+            category("terminal IDEs", &["terminal", "tty", "pty"], 0.5),
+            category("collaborative editors", &["collab", "crdt"], 0.5),
+        ]);
+
+        let matches = categorize(&config, "a fast terminal emulator with tty support");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].name, "terminal IDEs");
+    }
+
+    #[test]
+    fn categorize_sorts_by_descending_score() {
+        let config = config_with(vec![
+            category("low", &["a", "b", "c", "d"], 0.1),
+            category("high", &["a"], 0.1),
+        ]);
+
+        let matches = categorize(&config, "a");
+
+        assert_eq!(matches[0].name, "high");
+        assert_eq!(matches[1].name, "low");
+    }
+
+    #[test]
+    fn a_category_with_no_keywords_never_matches() {
+        let config = config_with(vec![category("empty", &[], 0.0)]);
+        assert!(categorize(&config, "anything").is_empty());
+    }
+
+    #[test]
+    fn keyword_matching_is_case_insensitive() {
+        let config = config_with(vec![category("terminal IDEs", &["TERMINAL"], 0.5)]);
+        let matches = categorize(&config, "a terminal app");
+        assert_eq!(matches.len(), 1);
+    }
+}
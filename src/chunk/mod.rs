@@ -0,0 +1,701 @@
+//! The unit of work handed to the summarization engine: a contiguous span of
+//! source text plus enough provenance to report back on where it came from.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::context::estimate_token_count;
+
+#[cfg(feature = "configurable-categories")]
+pub mod categories;
+pub mod packing;
+pub mod syntax_aware;
+
+/// Identifies a [`Chunk`] within a single analysis run. Stable for the
+/// lifetime of the run but not guaranteed to be stable across runs — two
+/// runs commonly assign the same `ChunkId` to unrelated chunks just because
+/// they landed at the same position in each run's walk order. Anything that
+/// persists keyed by chunk identity across runs (a cache, a dedup index, a
+/// diff) should key on [`StableChunkId`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ChunkId(pub u64);
+
+/// A chunk's identity that's stable across runs and across operating
+/// systems, unlike [`ChunkId`]. Derived from the chunk's source path, its
+/// `index` among chunks produced for that path, and a hash of its content
+/// with line endings normalized first — so a Windows checkout (CRLF) and a
+/// Unix checkout (LF) of the same logical file produce byte-identical input
+/// to the hash, and therefore the same id.
+///
+/// Deliberately not itself a hash of `(path, content)` alone: two chunks
+/// that happen to share a path and byte-for-byte content (e.g. two identical
+/// boilerplate functions chunked separately) would otherwise collide, so
+/// `index` — the chunk's position among same-path chunks, assigned by
+/// whatever chunks the file — disambiguates them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StableChunkId(pub String);
+
+impl StableChunkId {
+    /// Computes the stable id for a chunk at `index` (its position among
+    /// chunks produced for `source_path`) with content `content`.
+    pub fn compute(source_path: &Path, content: &str, index: usize) -> Self {
+        let normalized = normalize_line_endings(content);
+        let mut hash = fnv1a64(source_path.to_string_lossy().as_bytes());
+        hash = fnv1a64_continue(hash, normalized.as_bytes());
+        hash = fnv1a64_continue(hash, &index.to_le_bytes());
+        StableChunkId(format!("{hash:016x}"))
+    }
+}
+
+/// Replaces every `\r\n` with `\n`, so content that differs only in line
+/// ending — the same logical file checked out on Windows vs. Unix —
+/// normalizes to the same bytes before hashing or comparing.
+pub(crate) fn normalize_line_endings(content: &str) -> String {
+    content.replace("\r\n", "\n")
+}
+
+/// A small, dependency-free FNV-1a hash, used instead of
+/// `std::collections::hash_map::DefaultHasher` (as [`crate::dedup`] uses for
+/// its embedding buckets) because `DefaultHasher`'s algorithm is explicitly
+/// not guaranteed stable across Rust versions — fine for an in-memory
+/// bucketing hash, but it would silently invalidate every already-persisted
+/// [`StableChunkId`] on a toolchain bump.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    fnv1a64_continue(0xcbf29ce484222325, bytes)
+}
+
+fn fnv1a64_continue(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Byte offsets where each line of a file begins, computed with one
+/// SIMD-accelerated pass over the bytes (`memchr::memchr_iter`) instead of
+/// materializing a `Vec<&str>` of every line the way `str::lines` does.
+/// [`Chunk::from_line_span`] builds one of these per call, and
+/// [`crate::package::build_chunks`]'s `LineWindow` strategy builds one per
+/// file and reuses it for every window that file is split into, so a large
+/// file's newlines are scanned once no matter how many chunks it produces.
+pub(crate) struct LineIndex {
+    line_starts: Vec<usize>,
+    content_len: usize,
+}
+
+impl LineIndex {
+    pub(crate) fn build(content: &str) -> Self {
+        let bytes = content.as_bytes();
+        let newline_count = bytecount::count(bytes, b'\n');
+        let mut line_starts = Vec::with_capacity(newline_count + 1);
+        line_starts.push(0);
+        for newline_pos in memchr::memchr_iter(b'\n', bytes) {
+            let next_line_start = newline_pos + 1;
+            if next_line_start < bytes.len() {
+                line_starts.push(next_line_start);
+            }
+        }
+        LineIndex { line_starts, content_len: bytes.len() }
+    }
+
+    pub(crate) fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The text covering 1-indexed, inclusive lines `start_line..=end_line`
+    /// — the same per-line content `str::lines().join("\n")` would yield
+    /// (no trailing newline, every `\r\n` normalized to `\n`), but without
+    /// allocating a `Vec<&str>` entry for every line in `content`: only the
+    /// newline positions are scanned (once, in [`LineIndex::build`]), and
+    /// only the requested window is sliced out of `content` here. An
+    /// out-of-range or empty `start_line..=end_line` yields `""`, matching
+    /// how a `Vec<&str>` slice-and-join would behave for the same inputs.
+    pub(crate) fn slice<'a>(&self, content: &'a str, start_line: usize, end_line: usize) -> Cow<'a, str> {
+        if start_line == 0 || start_line > end_line || start_line > self.line_starts.len() {
+            return Cow::Borrowed("");
+        }
+        let start = self.line_starts[start_line - 1];
+        let end_index = end_line.min(self.line_starts.len());
+        let end = self.line_starts.get(end_index).copied().unwrap_or(self.content_len);
+
+        let raw = &content[start..end];
+        let trimmed = raw.strip_suffix('\n').map(|s| s.strip_suffix('\r').unwrap_or(s)).unwrap_or(raw);
+        if trimmed.as_bytes().contains(&b'\r') {
+            Cow::Owned(trimmed.replace("\r\n", "\n"))
+        } else {
+            Cow::Borrowed(trimmed)
+        }
+    }
+}
+
+/// A contiguous piece of source text to be summarized independently.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub id: ChunkId,
+    pub source_path: PathBuf,
+    pub content: String,
+    /// 1-indexed, inclusive line span `content` covers in `source_path`,
+    /// when known. Populated by [`Chunk::from_line_span`] and
+    /// [`Chunk::chunk_by_line_window`]; `None` for a chunk built via
+    /// [`Chunk::new`] or [`ChunkBuilder`] with no span supplied. Never
+    /// required for [`Chunk::classify`] or summarization.
+    pub line_range: Option<(usize, usize)>,
+    /// Overrides [`Chunk::classify`]'s path/content heuristic when a caller
+    /// already knows this chunk's kind better than the heuristic would
+    /// guess — see [`ChunkBuilder::kind`]. `None` for every chunk built
+    /// through this crate's own chunkers, which always defer to the
+    /// heuristic.
+    pub kind_override: Option<ChunkClass>,
+    /// Caller-supplied key/value bookkeeping, carried through untouched by
+    /// every chunker and the summarization engine — see [`ChunkBuilder::metadata`].
+    /// `BTreeMap` rather than `HashMap` so two chunks built with the same
+    /// entries compare and serialize identically regardless of insertion
+    /// order.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Chunk {
+    pub fn new(id: ChunkId, source_path: impl Into<PathBuf>, content: impl Into<String>) -> Self {
+        Chunk {
+            id,
+            source_path: source_path.into(),
+            content: content.into(),
+            line_range: None,
+            kind_override: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Starts a validated [`ChunkBuilder`] for a downstream tool that
+    /// already split its own input and wants to hand chunks to
+    /// [`crate::engine::SummaryRun::summarize_all_chunks`] directly, bypassing
+    /// this crate's own chunkers ([`Chunk::from_line_span`],
+    /// [`Chunk::chunk_by_line_window`]) while still getting the same
+    /// size/token validation [`ChunkBuilder::build`] enforces on everyone.
+    pub fn builder(id: ChunkId, source_path: impl Into<PathBuf>, content: impl Into<String>) -> ChunkBuilder {
+        ChunkBuilder::new(id, source_path, content)
+    }
+
+    /// Builds a chunk for an arbitrary line span inside `content`, padding
+    /// with `context_lines` of surrounding code on each side so the model
+    /// sees enclosing braces/signatures even for a span that starts mid-body.
+    /// `start_line`/`end_line` are 1-indexed and inclusive.
+    pub fn from_line_span(
+        id: ChunkId,
+        source_path: impl Into<PathBuf>,
+        content: &str,
+        start_line: usize,
+        end_line: usize,
+        context_lines: usize,
+    ) -> Self {
+        #[cfg(feature = "otel")]
+        let _span = tracing::trace_span!("from_line_span", chunk_id = id.0, start_line, end_line).entered();
+
+        let index = LineIndex::build(content);
+        let padded_start = start_line.saturating_sub(1).saturating_sub(context_lines) + 1;
+        let padded_end = (end_line + context_lines).min(index.line_count());
+        let span = index.slice(content, padded_start, padded_end).into_owned();
+        let mut chunk = Chunk::new(id, source_path, span);
+        chunk.line_range = Some((padded_start, padded_end));
+        chunk
+    }
+
+    /// Classifies this chunk using path and attribute heuristics, so the
+    /// engine can route it to a tailored prompt and reports can filter by
+    /// it — unless [`Chunk::kind_override`] is set, in which case that's
+    /// returned as-is and the heuristic never runs.
+    pub fn classify(&self) -> ChunkClass {
+        self.kind_override
+            .unwrap_or_else(|| classify_chunk_by_path_and_content(&self.source_path, &self.content))
+    }
+
+    /// This chunk's [`StableChunkId`], given its `index` among the chunks
+    /// produced for its `source_path`.
+    pub fn stable_id(&self, index: usize) -> StableChunkId {
+        StableChunkId::compute(&self.source_path, &self.content, index)
+    }
+
+    /// A truncated view of this chunk's source, for embedding alongside its
+    /// summary in run outputs (see [`crate::sinks::SinkRecord::source_excerpt`])
+    /// so a consumer of the JSONL doesn't need a second pass over the
+    /// original files just to see the code next to the summary.
+    ///
+    /// `content` no longer than `max_bytes` is returned in full. Longer
+    /// content keeps its first and last `edge_lines` lines — the enclosing
+    /// signature/braces a reader needs are almost always at the edges of a
+    /// chunk, not its middle — and collapses everything between them into a
+    /// one-line marker naming how many lines were dropped, so a consumer can
+    /// tell truncation happened instead of mistaking the excerpt for the
+    /// whole chunk.
+    pub fn excerpt(&self, edge_lines: usize, max_bytes: usize) -> String {
+        if self.content.len() <= max_bytes {
+            return self.content.clone();
+        }
+
+        let lines: Vec<&str> = self.content.lines().collect();
+        if lines.len() <= edge_lines * 2 {
+            return self.content.clone();
+        }
+
+        let omitted = lines.len() - edge_lines * 2;
+        let mut excerpt = lines[..edge_lines].join("\n");
+        excerpt.push_str(&format!("\n... ({omitted} line(s) omitted) ...\n"));
+        excerpt.push_str(&lines[lines.len() - edge_lines..].join("\n"));
+        excerpt
+    }
+
+    /// Splits `content` into non-overlapping `window_lines`-sized chunks,
+    /// assigning sequential [`ChunkId`]s starting at `first_id`. Builds one
+    /// [`LineIndex`] for the whole of `content` and slices every window out
+    /// of it, rather than calling [`Chunk::from_line_span`] once per window
+    /// — which would rescan `content`'s newlines from scratch for every
+    /// window it produced. [`crate::package::build_chunks`] is this
+    /// function's only library caller today, for its `LineWindow` strategy
+    /// and for non-Rust files under its `SyntaxAware` strategy.
+    pub fn chunk_by_line_window(first_id: u64, source_path: impl Into<PathBuf>, content: &str, window_lines: usize) -> Vec<Chunk> {
+        let source_path = source_path.into();
+        let index = LineIndex::build(content);
+        let total_lines = index.line_count().max(1);
+
+        let mut chunks = Vec::new();
+        let mut start_line = 1;
+        let mut next_id = first_id;
+        while start_line <= total_lines {
+            let end_line = (start_line + window_lines - 1).min(total_lines);
+            let span = index.slice(content, start_line, end_line).into_owned();
+            let mut chunk = Chunk::new(ChunkId(next_id), source_path.clone(), span);
+            chunk.line_range = Some((start_line, end_line));
+            chunks.push(chunk);
+            next_id += 1;
+            start_line = end_line + 1;
+        }
+        chunks
+    }
+
+    /// Splits `content` on Rust item boundaries instead of a fixed line
+    /// count, via [`syntax_aware::split_rust_by_item_boundaries`] — see
+    /// [`crate::fingerprint::ChunkingStrategy::SyntaxAware`]. Intended for
+    /// `.rs` files; a caller chunking a non-Rust file under that strategy
+    /// should fall back to [`Chunk::chunk_by_line_window`] instead, since
+    /// this splitter only recognizes Rust's item keywords.
+    pub fn chunk_by_rust_item_boundaries(first_id: u64, source_path: impl Into<PathBuf>, content: &str) -> Vec<Chunk> {
+        let source_path = source_path.into();
+        let index = LineIndex::build(content);
+        let spans = syntax_aware::split_rust_by_item_boundaries(content);
+
+        let mut chunks = Vec::with_capacity(spans.len());
+        for (next_id, (start_line, end_line)) in (first_id..).zip(spans) {
+            let span = index.slice(content, start_line, end_line).into_owned();
+            let mut chunk = Chunk::new(ChunkId(next_id), source_path.clone(), span);
+            chunk.line_range = Some((start_line, end_line));
+            chunks.push(chunk);
+        }
+        chunks
+    }
+}
+
+/// Errors [`ChunkBuilder::build`] rejects a chunk for, rather than handing
+/// the summarization engine something it would fail on anyway with a less
+/// specific message.
+#[derive(Debug, thiserror::Error)]
+pub enum ChunkBuildError {
+    #[error("chunk content must not be empty")]
+    EmptyContent,
+    #[error("line range start ({start}) must be at least 1 and not after end ({end})")]
+    InvalidLineRange { start: usize, end: usize },
+    #[error("chunk content needs an estimated {needed} tokens, exceeding the {budget} token budget")]
+    ContentExceedsTokenBudget { needed: usize, budget: usize },
+}
+
+/// Validated construction of a [`Chunk`] for a caller that already has its
+/// own splitter and just wants to hand chunks to the summarization engine
+/// directly — see [`Chunk::builder`]. Every setter consumes and returns
+/// `self` so calls chain; [`ChunkBuilder::build`] is the only step that can
+/// fail, and it fails the same way [`Chunk::from_line_span`] and
+/// [`Chunk::chunk_by_line_window`] would already be relied on to behave:
+/// never silently accepting something the engine can't actually summarize.
+#[derive(Debug, Clone)]
+pub struct ChunkBuilder {
+    id: ChunkId,
+    source_path: PathBuf,
+    content: String,
+    line_range: Option<(usize, usize)>,
+    kind: Option<ChunkClass>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl ChunkBuilder {
+    pub fn new(id: ChunkId, source_path: impl Into<PathBuf>, content: impl Into<String>) -> Self {
+        ChunkBuilder {
+            id,
+            source_path: source_path.into(),
+            content: content.into(),
+            line_range: None,
+            kind: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Records the 1-indexed, inclusive line span this chunk's content came
+    /// from in its source file. Purely informational — [`ChunkBuilder::build`]
+    /// checks `start <= end` and `start >= 1` but never checks the span
+    /// against `content`'s own line count, since a caller supplying its own
+    /// chunks may be slicing from a file this process never read in full.
+    pub fn line_range(mut self, start_line: usize, end_line: usize) -> Self {
+        self.line_range = Some((start_line, end_line));
+        self
+    }
+
+    /// Sets [`Chunk::kind_override`], so [`Chunk::classify`] returns `kind`
+    /// unconditionally instead of running the path/content heuristic.
+    pub fn kind(mut self, kind: ChunkClass) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Attaches one key/value pair of caller bookkeeping to
+    /// [`Chunk::metadata`]. Call repeatedly to attach more than one.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Validates and builds the [`Chunk`]. Rejects empty content, an
+    /// inverted or zero-indexed [`ChunkBuilder::line_range`], and content
+    /// whose [`estimate_token_count`] exceeds `max_tokens` — the same rough
+    /// estimate [`crate::model::context::enforce_prompt_budget`] checks a
+    /// whole prompt against, applied here to just the chunk's own content so
+    /// an oversized chunk is rejected at construction instead of surfacing
+    /// as an opaque context-window error once it's already deep inside a run.
+    pub fn build(self, max_tokens: usize) -> Result<Chunk, ChunkBuildError> {
+        if self.content.trim().is_empty() {
+            return Err(ChunkBuildError::EmptyContent);
+        }
+        if let Some((start, end)) = self.line_range {
+            if start == 0 || start > end {
+                return Err(ChunkBuildError::InvalidLineRange { start, end });
+            }
+        }
+        let needed = estimate_token_count(&self.content);
+        if needed > max_tokens {
+            return Err(ChunkBuildError::ContentExceedsTokenBudget { needed, budget: max_tokens });
+        }
+
+        let mut chunk = Chunk::new(self.id, self.source_path, self.content);
+        chunk.line_range = self.line_range;
+        chunk.kind_override = self.kind;
+        chunk.metadata = self.metadata;
+        Ok(chunk)
+    }
+}
+
+/// What kind of code a chunk holds, as distinct from production logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkClass {
+    Test,
+    Bench,
+    Example,
+    BuildScript,
+    Production,
+}
+
+impl ChunkClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChunkClass::Test => "test",
+            ChunkClass::Bench => "bench",
+            ChunkClass::Example => "example",
+            ChunkClass::BuildScript => "build-script",
+            ChunkClass::Production => "production",
+        }
+    }
+}
+
+fn classify_chunk_by_path_and_content(path: &Path, content: &str) -> ChunkClass {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let has_dir_component =
+        |name: &str| path.components().any(|c| c.as_os_str() == name);
+
+    if file_name == "build.rs" {
+        ChunkClass::BuildScript
+    } else if has_dir_component("benches") || content.contains("#[bench]") {
+        ChunkClass::Bench
+    } else if has_dir_component("examples") {
+        ChunkClass::Example
+    } else if has_dir_component("tests")
+        || file_name.ends_with("_test.rs")
+        || file_name.ends_with("_tests.rs")
+        || content.contains("#[test]")
+        || content.contains("#[cfg(test)]")
+    {
+        ChunkClass::Test
+    } else {
+        ChunkClass::Production
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        /// The requested line range must always survive in the padded span
+        /// verbatim; padding may add lines around it but must never eat into
+        /// or reorder the lines the caller asked for.
+        #[test]
+        fn from_line_span_never_loses_the_requested_lines(
+            lines in proptest::collection::vec("[a-zA-Z0-9 {}()_;]{0,20}", 1..20),
+            context_lines in 0usize..5,
+            seed in any::<u64>(),
+        ) {
+            let content = lines.join("\n");
+            // Re-split via `lines()` rather than reusing the input vector: a
+            // trailing empty element can collapse into the final line ending
+            // when joined back together, so this is the only count that
+            // agrees with what `from_line_span` itself sees.
+            let split: Vec<&str> = content.lines().collect();
+            let line_count = split.len();
+            // An all-empty-lines input joins down to the empty string, which
+            // `lines()` reports as zero lines; nothing to request in that case.
+            prop_assume!(line_count > 0);
+            let start_line = 1 + (seed as usize % line_count);
+            let end_line = start_line + (seed as usize / 7) % (line_count - start_line + 1);
+
+            let chunk = Chunk::from_line_span(ChunkId(seed), "f.rs", &content, start_line, end_line, context_lines);
+
+            let requested = split[start_line - 1..end_line].join("\n");
+            prop_assert!(chunk.content.contains(&requested));
+            prop_assert_eq!(chunk.id, ChunkId(seed));
+        }
+
+        /// Arbitrary (including multi-byte) content and out-of-range line
+        /// numbers must never panic; `from_line_span` should just clamp to
+        /// whatever lines actually exist.
+        #[test]
+        fn from_line_span_never_panics_on_unicode_or_out_of_range_input(
+            content in ".{0,200}",
+            line_a in 1usize..50,
+            line_b in 1usize..50,
+            context_lines in 0usize..10,
+        ) {
+            let (start_line, end_line) = if line_a <= line_b { (line_a, line_b) } else { (line_b, line_a) };
+            let _ = Chunk::from_line_span(ChunkId(1), "f.rs", &content, start_line, end_line, context_lines);
+        }
+    }
+
+    #[test]
+    fn stable_id_is_identical_for_lf_and_crlf_variants_of_the_same_content() {
+        let lf = Chunk::new(ChunkId(0), "f.rs", "fn a() {}\nfn b() {}\n");
+        let crlf = Chunk::new(ChunkId(0), "f.rs", "fn a() {}\r\nfn b() {}\r\n");
+
+        assert_eq!(lf.stable_id(0), crlf.stable_id(0));
+    }
+
+    #[test]
+    fn stable_id_differs_by_source_path() {
+        let a = Chunk::new(ChunkId(0), "a.rs", "fn f() {}");
+        let b = Chunk::new(ChunkId(0), "b.rs", "fn f() {}");
+
+        assert_ne!(a.stable_id(0), b.stable_id(0));
+    }
+
+    #[test]
+    fn stable_id_differs_by_index_for_identical_content_and_path() {
+        let chunk = Chunk::new(ChunkId(0), "f.rs", "fn f() {}");
+
+        assert_ne!(chunk.stable_id(0), chunk.stable_id(1));
+    }
+
+    #[test]
+    fn stable_id_is_deterministic_across_repeated_calls() {
+        let chunk = Chunk::new(ChunkId(0), "f.rs", "fn f() {}");
+
+        assert_eq!(chunk.stable_id(3), chunk.stable_id(3));
+    }
+
+    #[test]
+    fn line_span_pads_with_surrounding_context() {
+        let content = "fn a() {}\nfn b() {\n    1\n}\nfn c() {}";
+        let chunk = Chunk::from_line_span(ChunkId(1), "f.rs", content, 3, 3, 1);
+        assert_eq!(chunk.content, "fn b() {\n    1\n}");
+    }
+
+    #[test]
+    fn from_line_span_normalizes_crlf_to_lf_like_lines_join_did() {
+        let content = "fn a() {}\r\nfn b() {}\r\nfn c() {}\r\n";
+        let chunk = Chunk::from_line_span(ChunkId(1), "f.rs", content, 1, 2, 0);
+        assert_eq!(chunk.content, "fn a() {}\nfn b() {}");
+    }
+
+    #[test]
+    fn excerpt_returns_content_in_full_when_under_the_byte_cap() {
+        let chunk = Chunk::new(ChunkId(0), "f.rs", "fn a() {}\nfn b() {}");
+        assert_eq!(chunk.excerpt(1, 1_000), "fn a() {}\nfn b() {}");
+    }
+
+    #[test]
+    fn excerpt_keeps_first_and_last_lines_and_notes_what_was_dropped() {
+        let content = (1..=20).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n");
+        let chunk = Chunk::new(ChunkId(0), "f.rs", content);
+
+        let excerpt = chunk.excerpt(2, 10);
+
+        assert!(excerpt.starts_with("line 1\nline 2\n"));
+        assert!(excerpt.ends_with("line 19\nline 20"));
+        assert!(excerpt.contains("16 line(s) omitted"));
+    }
+
+    #[test]
+    fn excerpt_returns_content_in_full_when_too_short_to_usefully_truncate() {
+        let chunk = Chunk::new(ChunkId(0), "f.rs", "a".repeat(500));
+        assert_eq!(chunk.excerpt(10, 1), "a".repeat(500));
+    }
+
+    #[test]
+    fn chunk_by_line_window_covers_every_line_in_non_overlapping_windows() {
+        let content = "1\n2\n3\n4\n5\n6\n7";
+        let chunks = Chunk::chunk_by_line_window(10, "f.rs", content, 3);
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].id, ChunkId(10));
+        assert_eq!(chunks[0].content, "1\n2\n3");
+        assert_eq!(chunks[1].id, ChunkId(11));
+        assert_eq!(chunks[1].content, "4\n5\n6");
+        assert_eq!(chunks[2].id, ChunkId(12));
+        assert_eq!(chunks[2].content, "7");
+    }
+
+    #[test]
+    fn chunk_by_line_window_matches_from_line_span_called_once_per_window() {
+        let content = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\nfn e() {}";
+        let window_lines = 2;
+
+        let via_window = Chunk::chunk_by_line_window(0, "f.rs", content, window_lines);
+
+        let total_lines = content.lines().count();
+        let mut via_from_line_span = Vec::new();
+        let mut start_line = 1;
+        while start_line <= total_lines {
+            let end_line = (start_line + window_lines - 1).min(total_lines);
+            via_from_line_span.push(Chunk::from_line_span(ChunkId(0), "f.rs", content, start_line, end_line, 0).content);
+            start_line = end_line + 1;
+        }
+
+        assert_eq!(
+            via_window.iter().map(|c| c.content.clone()).collect::<Vec<_>>(),
+            via_from_line_span
+        );
+    }
+
+    #[test]
+    fn classifies_by_directory_and_attribute() {
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("src/build.rs"), ""),
+            ChunkClass::BuildScript
+        );
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("tests/foo.rs"), ""),
+            ChunkClass::Test
+        );
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("src/lib.rs"), "#[test]\nfn it_works() {}"),
+            ChunkClass::Test
+        );
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("benches/bench_main.rs"), ""),
+            ChunkClass::Bench
+        );
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("examples/demo.rs"), ""),
+            ChunkClass::Example
+        );
+        assert_eq!(
+            classify_chunk_by_path_and_content(Path::new("src/lib.rs"), "fn real_logic() {}"),
+            ChunkClass::Production
+        );
+    }
+
+    #[test]
+    fn builder_rejects_empty_content() {
+        let err = Chunk::builder(ChunkId(0), "f.rs", "   ").build(100).unwrap_err();
+        assert!(matches!(err, ChunkBuildError::EmptyContent));
+    }
+
+    #[test]
+    fn builder_rejects_an_inverted_line_range() {
+        let err = Chunk::builder(ChunkId(0), "f.rs", "fn f() {}")
+            .line_range(5, 2)
+            .build(100)
+            .unwrap_err();
+        assert!(matches!(err, ChunkBuildError::InvalidLineRange { start: 5, end: 2 }));
+    }
+
+    #[test]
+    fn builder_rejects_a_zero_line_range_start() {
+        let err = Chunk::builder(ChunkId(0), "f.rs", "fn f() {}")
+            .line_range(0, 1)
+            .build(100)
+            .unwrap_err();
+        assert!(matches!(err, ChunkBuildError::InvalidLineRange { start: 0, end: 1 }));
+    }
+
+    #[test]
+    fn builder_rejects_content_over_the_token_budget() {
+        let content = "one two three four five";
+        let err = Chunk::builder(ChunkId(0), "f.rs", content).build(3).unwrap_err();
+        assert!(matches!(
+            err,
+            ChunkBuildError::ContentExceedsTokenBudget { needed: 5, budget: 3 }
+        ));
+    }
+
+    #[test]
+    fn builder_carries_line_range_kind_override_and_metadata_through() {
+        let chunk = Chunk::builder(ChunkId(7), "f.rs", "fn f() {}")
+            .line_range(10, 12)
+            .kind(ChunkClass::Test)
+            .metadata("origin", "external-splitter")
+            .build(100)
+            .unwrap();
+
+        assert_eq!(chunk.line_range, Some((10, 12)));
+        assert_eq!(chunk.classify(), ChunkClass::Test);
+        assert_eq!(chunk.metadata.get("origin").map(String::as_str), Some("external-splitter"));
+    }
+
+    #[test]
+    fn builder_without_a_kind_override_falls_back_to_the_usual_heuristic() {
+        let chunk = Chunk::builder(ChunkId(0), "tests/foo.rs", "fn it_works() {}").build(100).unwrap();
+        assert_eq!(chunk.kind_override, None);
+        assert_eq!(chunk.classify(), ChunkClass::Test);
+    }
+
+    #[test]
+    fn new_defaults_line_range_kind_override_and_metadata_to_empty() {
+        let chunk = Chunk::new(ChunkId(0), "f.rs", "fn f() {}");
+        assert_eq!(chunk.line_range, None);
+        assert_eq!(chunk.kind_override, None);
+        assert!(chunk.metadata.is_empty());
+    }
+
+    #[test]
+    fn from_line_span_records_its_padded_line_range() {
+        let content = "fn a() {}\nfn b() {\n    1\n}\nfn c() {}";
+        let chunk = Chunk::from_line_span(ChunkId(1), "f.rs", content, 3, 3, 1);
+        assert_eq!(chunk.line_range, Some((2, 4)));
+    }
+
+    #[test]
+    fn chunk_by_line_window_records_each_windows_line_range() {
+        let content = "1\n2\n3\n4\n5\n6\n7";
+        let chunks = Chunk::chunk_by_line_window(0, "f.rs", content, 3);
+        assert_eq!(chunks[0].line_range, Some((1, 3)));
+        assert_eq!(chunks[1].line_range, Some((4, 6)));
+        assert_eq!(chunks[2].line_range, Some((7, 7)));
+    }
+}
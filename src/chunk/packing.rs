@@ -0,0 +1,202 @@
+//! Combines several small files into one chunk so a repo of thousands of
+//! tiny files (a typical microservice monorepo) doesn't pay one inference
+//! call's fixed overhead per file.
+//!
+//! Packed files are joined with an explicit `===== FILE: <path> =====`
+//! separator that also doubles as the contract for the response: the prompt
+//! asks the model to echo the same separator before each file's summary, so
+//! [`split_packed_summary`] can attribute each piece of the combined output
+//! back to the file it describes without any structured-output support from
+//! the backend.
+
+use std::path::{Path, PathBuf};
+
+use crate::chunk::{Chunk, ChunkId};
+
+/// A single marker line, reused both when building the packed prompt and
+/// when parsing the packed response, so the two halves can't drift apart.
+const FILE_SEPARATOR_PREFIX: &str = "===== FILE: ";
+const FILE_SEPARATOR_SUFFIX: &str = " =====";
+
+/// Caps how large a packed chunk is allowed to grow, so packing small files
+/// together doesn't just recreate the "one huge prompt" problem it's meant
+/// to avoid.
+#[derive(Debug, Clone, Copy)]
+pub struct PackingBudget {
+    /// Only files at or under this size are eligible for packing; larger
+    /// files keep getting their own chunk via the normal per-file path.
+    pub max_file_bytes: usize,
+    /// Combined content size a single packed chunk may reach.
+    pub max_total_bytes: usize,
+    /// How many files a single packed chunk may combine, regardless of how
+    /// much byte budget is left, so a response with hundreds of per-file
+    /// summaries doesn't blow past the model's own output limit.
+    pub max_files_per_chunk: usize,
+}
+
+impl Default for PackingBudget {
+    fn default() -> Self {
+        PackingBudget {
+            max_file_bytes: 2_000,
+            max_total_bytes: 12_000,
+            max_files_per_chunk: 20,
+        }
+    }
+}
+
+/// Greedily bins `files` into packed chunks under `budget`, preserving
+/// input order within each bin. Files over `budget.max_file_bytes` are
+/// returned untouched in `oversized` for the caller to chunk individually
+/// instead of being silently dropped.
+pub fn pack_small_files(files: Vec<(PathBuf, String)>, budget: PackingBudget) -> PackingResult {
+    let mut packed_chunks = Vec::new();
+    let mut oversized = Vec::new();
+
+    let mut current_bin: Vec<(PathBuf, String)> = Vec::new();
+    let mut current_bytes = 0usize;
+
+    for (path, content) in files {
+        if content.len() > budget.max_file_bytes {
+            oversized.push((path, content));
+            continue;
+        }
+
+        let would_overflow_bytes = current_bytes + content.len() > budget.max_total_bytes;
+        let would_overflow_count = current_bin.len() >= budget.max_files_per_chunk;
+        if !current_bin.is_empty() && (would_overflow_bytes || would_overflow_count) {
+            packed_chunks.push(build_packed_chunk(ChunkId(packed_chunks.len() as u64), std::mem::take(&mut current_bin)));
+            current_bytes = 0;
+        }
+
+        current_bytes += content.len();
+        current_bin.push((path, content));
+    }
+
+    if !current_bin.is_empty() {
+        packed_chunks.push(build_packed_chunk(ChunkId(packed_chunks.len() as u64), current_bin));
+    }
+
+    PackingResult { packed_chunks, oversized }
+}
+
+/// What [`pack_small_files`] produced: chunks ready for inference, plus any
+/// files that didn't fit the packing budget.
+#[derive(Debug, Clone)]
+pub struct PackingResult {
+    pub packed_chunks: Vec<Chunk>,
+    pub oversized: Vec<(PathBuf, String)>,
+}
+
+fn build_packed_chunk(id: ChunkId, files: Vec<(PathBuf, String)>) -> Chunk {
+    let display_path = files
+        .first()
+        .map(|(path, _)| format!("{} (+{} more)", path.display(), files.len().saturating_sub(1)))
+        .unwrap_or_default();
+
+    let content = files
+        .iter()
+        .map(|(path, content)| format!("{FILE_SEPARATOR_PREFIX}{}{FILE_SEPARATOR_SUFFIX}\n{content}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Chunk::new(id, display_path, content)
+}
+
+/// Splits a model's response to a packed prompt back into one summary per
+/// file, keyed by the same paths that were packed in. A file the model
+/// didn't echo a separator for (a truncated or malformed response) is
+/// simply absent from the result rather than guessed at.
+pub fn split_packed_summary(packed_paths: &[&Path], response: &str) -> Vec<(PathBuf, String)> {
+    let mut summaries = Vec::new();
+    let mut current_path: Option<PathBuf> = None;
+    let mut current_text = String::new();
+
+    for line in response.lines() {
+        if let Some(path) = parse_separator_line(line) {
+            if let Some(previous_path) = current_path.take() {
+                summaries.push((previous_path, current_text.trim().to_string()));
+            }
+            current_text.clear();
+            current_path = Some(path);
+            continue;
+        }
+        if current_path.is_some() {
+            current_text.push_str(line);
+            current_text.push('\n');
+        }
+    }
+    if let Some(path) = current_path {
+        summaries.push((path, current_text.trim().to_string()));
+    }
+
+    let known_paths: std::collections::HashSet<&Path> = packed_paths.iter().copied().collect();
+    summaries.retain(|(path, _)| known_paths.contains(path.as_path()));
+    summaries
+}
+
+fn parse_separator_line(line: &str) -> Option<PathBuf> {
+    let line = line.trim();
+    let path = line.strip_prefix(FILE_SEPARATOR_PREFIX)?.strip_suffix(FILE_SEPARATOR_SUFFIX)?;
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(specs: &[(&str, &str)]) -> Vec<(PathBuf, String)> {
+        specs.iter().map(|(path, content)| (PathBuf::from(path), content.to_string())).collect()
+    }
+
+    #[test]
+    fn packs_multiple_small_files_into_one_chunk() {
+        let result = pack_small_files(files(&[("a.rs", "fn a() {}"), ("b.rs", "fn b() {}")]), PackingBudget::default());
+        assert_eq!(result.packed_chunks.len(), 1);
+        assert!(result.packed_chunks[0].content.contains("===== FILE: a.rs ====="));
+        assert!(result.packed_chunks[0].content.contains("===== FILE: b.rs ====="));
+        assert!(result.oversized.is_empty());
+    }
+
+    #[test]
+    fn splits_across_chunks_once_byte_budget_is_exceeded() {
+        let budget = PackingBudget { max_file_bytes: 100, max_total_bytes: 15, max_files_per_chunk: 10 };
+        let result = pack_small_files(files(&[("a.rs", "0123456789"), ("b.rs", "0123456789")]), budget);
+        assert_eq!(result.packed_chunks.len(), 2);
+    }
+
+    #[test]
+    fn splits_across_chunks_once_file_count_budget_is_exceeded() {
+        let budget = PackingBudget { max_file_bytes: 100, max_total_bytes: 10_000, max_files_per_chunk: 1 };
+        let result = pack_small_files(files(&[("a.rs", "x"), ("b.rs", "y")]), budget);
+        assert_eq!(result.packed_chunks.len(), 2);
+    }
+
+    #[test]
+    fn files_over_the_size_cap_are_returned_as_oversized_instead_of_packed() {
+        let budget = PackingBudget { max_file_bytes: 5, max_total_bytes: 10_000, max_files_per_chunk: 10 };
+        let result = pack_small_files(files(&[("a.rs", "short"), ("big.rs", "this is way too long")]), budget);
+        assert_eq!(result.packed_chunks.len(), 1);
+        assert_eq!(result.oversized, vec![(PathBuf::from("big.rs"), "this is way too long".to_string())]);
+    }
+
+    #[test]
+    fn round_trips_per_file_attribution_through_a_packed_response() {
+        let response = "===== FILE: a.rs =====\nSummary of a.\n\n===== FILE: b.rs =====\nSummary of b.\n";
+        let paths = [Path::new("a.rs"), Path::new("b.rs")];
+        let summaries = split_packed_summary(&paths, response);
+        assert_eq!(
+            summaries,
+            vec![
+                (PathBuf::from("a.rs"), "Summary of a.".to_string()),
+                (PathBuf::from("b.rs"), "Summary of b.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_summaries_for_paths_that_were_not_actually_packed() {
+        let response = "===== FILE: unexpected.rs =====\nHallucinated.\n";
+        let summaries = split_packed_summary(&[Path::new("a.rs")], response);
+        assert!(summaries.is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+//! Lightweight line-prefix boundary detection for
+//! [`crate::fingerprint::ChunkingStrategy::SyntaxAware`] Rust chunking.
+//!
+//! This is not a real parser: it takes on no `syn` or `tree-sitter`
+//! dependency and does not understand Rust's grammar, so a multi-line
+//! string literal that happens to contain a line starting with `fn ` at
+//! column zero would fool it. What it does do reliably is find the same
+//! top-level item keywords [`crate::engine::heuristic::HeuristicBackend`]
+//! already counts (`fn`, `struct`, `enum`, `trait`, plus `impl` and `mod`)
+//! at column zero and split there — enough to stop a fixed-size window
+//! from cutting a real function or impl block in half on most source
+//! files, without the cost of hand-rolling a full parser for one chunking
+//! mode.
+
+const ITEM_PREFIXES: &[&str] = &[
+    "fn ", "pub fn ", "pub(crate) fn ", "async fn ", "pub async fn ", "pub(crate) async fn ", "unsafe fn ",
+    "pub unsafe fn ", "struct ", "pub struct ", "enum ", "pub enum ", "trait ", "pub trait ", "impl ", "impl<", "mod ",
+    "pub mod ",
+];
+
+fn is_item_boundary(line: &str) -> bool {
+    ITEM_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+}
+
+fn is_attribute_or_doc_comment(line: &str) -> bool {
+    line.starts_with("#[") || line.starts_with("///") || line.starts_with("//!")
+}
+
+/// Splits `content` into 1-indexed, inclusive `(start_line, end_line)`
+/// spans: a new span starts at each line matching [`is_item_boundary`],
+/// pulling any immediately preceding `#[...]` attribute or `///`/`//!` doc
+/// comment lines into the item that follows them rather than leaving them
+/// as trailing lines of the previous span. Lines before the first detected
+/// boundary (e.g. a file's leading `use` statements) form their own
+/// leading span, and a span runs up to (but not including) the next
+/// boundary, so it carries any blank lines separating two items. Returns a
+/// single span covering the whole file if no boundary is found at all.
+pub fn split_rust_by_item_boundaries(content: &str) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.is_empty() {
+        return vec![(1, 1)];
+    }
+
+    let mut boundaries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if is_item_boundary(line) {
+            let mut start = index;
+            while start > 0 && is_attribute_or_doc_comment(lines[start - 1]) {
+                start -= 1;
+            }
+            if boundaries.last() != Some(&start) {
+                boundaries.push(start);
+            }
+        }
+    }
+
+    if boundaries.is_empty() {
+        return vec![(1, lines.len())];
+    }
+
+    let mut spans = Vec::new();
+    let mut cursor = 0usize;
+    for &boundary in &boundaries {
+        if boundary > cursor {
+            spans.push((cursor + 1, boundary));
+        }
+        cursor = boundary;
+    }
+    spans.push((cursor + 1, lines.len()));
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_each_top_level_fn() {
+        let content = "use std::fmt;\n\nfn a() {\n    1\n}\n\nfn b() {\n    2\n}\n";
+        let spans = split_rust_by_item_boundaries(content);
+        assert_eq!(spans, vec![(1, 2), (3, 6), (7, 9)]);
+    }
+
+    #[test]
+    fn pulls_a_preceding_doc_comment_and_attribute_into_the_item_they_document() {
+        let content = "/// Doc.\n#[test]\nfn t() {\n    assert!(true);\n}\n";
+        let spans = split_rust_by_item_boundaries(content);
+        assert_eq!(spans, vec![(1, 5)]);
+    }
+
+    #[test]
+    fn a_file_with_no_detected_boundary_is_one_span() {
+        let content = "let x = 1;\nlet y = 2;\n";
+        let spans = split_rust_by_item_boundaries(content);
+        assert_eq!(spans, vec![(1, 2)]);
+    }
+
+    #[test]
+    fn does_not_split_on_an_indented_fn_inside_an_impl_block() {
+        let content = "impl Thing {\n    fn a() {\n        1\n    }\n\n    fn b() {\n        2\n    }\n}\n";
+        let spans = split_rust_by_item_boundaries(content);
+        assert_eq!(spans, vec![(1, 9)]);
+    }
+
+    #[test]
+    fn an_empty_file_is_a_single_degenerate_span() {
+        let spans = split_rust_by_item_boundaries("");
+        assert_eq!(spans, vec![(1, 1)]);
+    }
+}
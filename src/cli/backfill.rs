@@ -0,0 +1,210 @@
+//! Regenerating stale summaries after a model swap.
+//!
+//! This crate has no notion of a model's identity anywhere: concrete
+//! backends ([`crate::engine::heuristic::HeuristicBackend`], the ONNX
+//! backend behind the `onnx` feature) don't report a version or hash, and
+//! [`crate::model::card::ModelCard`] is licensing/capability metadata, not
+//! identity. So [`ResultRecord::model_version`](crate::sinks::query::ResultRecord::model_version)
+//! is an opaque string the caller supplies — a weights file hash, a version
+//! tag, whatever the caller's backend is keyed by — and [`backfill_stale_summaries`]
+//! trusts it rather than deriving it.
+//!
+//! A model hash has no natural ordering the way a version number might, so
+//! "regenerate summaries older than a given model version" is implemented
+//! as [`ResultsStore::stale_entries`](crate::sinks::query::ResultsStore::stale_entries):
+//! every record whose `model_version` isn't the one passed in. The results
+//! store also doesn't persist each record's original chunk boundaries (just
+//! `chunk_id` and `source_path`), so "schedules just those chunks" means
+//! re-reading each stale record's whole source file and re-summarizing it
+//! in one [`crate::engine::summarize_span`] call over every line, rather than
+//! reproducing whatever line span originally produced it.
+//!
+//! Re-scoring `confidence`/`validation_status` is
+//! [`crate::analysis`](crate::analysis)'s job, not this one — backfill
+//! carries both over from the stale record unchanged and only overwrites
+//! `summary_text` and `model_version`, the two things a model swap actually
+//! invalidates.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::chunk::ChunkId;
+use crate::engine::{summarize_span, EngineError, InferenceBackend};
+use crate::sinks::query::ResultsStore;
+use crate::sinks::SinkError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackfillError {
+    #[error("failed to read a source file to re-summarize: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+    #[error(transparent)]
+    Sink(#[from] SinkError),
+}
+
+/// One record [`backfill_stale_summaries`] regenerated and wrote back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackfillOutcome {
+    pub chunk_id: ChunkId,
+    pub source_path: PathBuf,
+    /// The `model_version` this record carried before the backfill, so a
+    /// caller building an audit trail can show what changed.
+    pub previous_model_version: String,
+}
+
+/// What [`backfill_stale_summaries`] did across every stale record it found.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackfillReport {
+    pub updated: Vec<BackfillOutcome>,
+    /// Stale records whose `source_path` no longer exists under
+    /// `workspace_root` (the file was deleted or moved since the run that
+    /// produced them), left untouched in the store rather than erroring the
+    /// whole backfill out over one missing file.
+    pub skipped_missing_source: Vec<PathBuf>,
+}
+
+/// Regenerates every record in `store` whose `model_version` isn't
+/// `current_model_version`: re-reads each one's source file from under
+/// `workspace_root`, re-summarizes it with `backend`, and writes the new
+/// text and `current_model_version` back in place via
+/// [`ResultsStore::update_in_place`](crate::sinks::query::ResultsStore::update_in_place).
+pub fn backfill_stale_summaries(
+    store: &ResultsStore,
+    backend: &impl InferenceBackend,
+    workspace_root: &Path,
+    current_model_version: &str,
+) -> Result<BackfillReport, BackfillError> {
+    let stale = store.stale_entries(current_model_version)?;
+    let mut report = BackfillReport::default();
+
+    for record in stale {
+        let full_path = workspace_root.join(&record.source_path);
+        let Ok(content) = fs::read_to_string(&full_path) else {
+            report.skipped_missing_source.push(record.source_path);
+            continue;
+        };
+
+        let line_count = content.lines().count().max(1);
+        let result = summarize_span(backend, &record.source_path, &content, 1, line_count)?;
+
+        store.update_in_place(
+            record.chunk_id,
+            &record.source_path,
+            &result.summary.text,
+            record.confidence,
+            record.validation_status,
+            current_model_version,
+        )?;
+
+        report.updated.push(BackfillOutcome {
+            chunk_id: record.chunk_id,
+            source_path: record.source_path,
+            previous_model_version: record.model_version,
+        });
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::query::{ResultRecord, ValidationStatus};
+    use std::path::PathBuf;
+
+    struct StaticBackend;
+    impl InferenceBackend for StaticBackend {
+        fn generate_completion_text(&self, _prompt: &str) -> Result<String, EngineError> {
+            Ok("regenerated by the new model".to_string())
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn open_temp_store(name: &str) -> ResultsStore {
+        let path = std::env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+        ResultsStore::open(&path).unwrap()
+    }
+
+    #[test]
+    fn regenerates_only_records_from_an_older_model_and_records_provenance() {
+        let workspace = temp_dir("transfiguration-backfill-workspace");
+        fs::write(workspace.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(workspace.join("b.rs"), "fn b() {}\n").unwrap();
+
+        let store = open_temp_store("transfiguration-backfill-store.sqlite");
+        store
+            .insert(&ResultRecord {
+                chunk_id: ChunkId(1),
+                source_path: PathBuf::from("a.rs"),
+                summary_text: "old summary of a".to_string(),
+                confidence: 0.8,
+                validation_status: ValidationStatus::Pass,
+                run_id: "run-1".to_string(),
+                model_version: "model-v1".to_string(),
+            })
+            .unwrap();
+        store
+            .insert(&ResultRecord {
+                chunk_id: ChunkId(2),
+                source_path: PathBuf::from("b.rs"),
+                summary_text: "already current".to_string(),
+                confidence: 0.9,
+                validation_status: ValidationStatus::Pass,
+                run_id: "run-1".to_string(),
+                model_version: "model-v2".to_string(),
+            })
+            .unwrap();
+
+        let report = backfill_stale_summaries(&store, &StaticBackend, &workspace, "model-v2").unwrap();
+
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].chunk_id, ChunkId(1));
+        assert_eq!(report.updated[0].previous_model_version, "model-v1");
+        assert!(report.skipped_missing_source.is_empty());
+
+        let rows = store
+            .query(
+                &Default::default(),
+                crate::sinks::query::SortColumn::SourcePath,
+                crate::sinks::query::SortOrder::Ascending,
+                crate::sinks::query::Page { limit: 10, offset: 0 },
+            )
+            .unwrap();
+        let a_row = rows.iter().find(|r| r.source_path == Path::new("a.rs")).unwrap();
+        assert_eq!(a_row.summary_text, "regenerated by the new model");
+        assert_eq!(a_row.model_version, "model-v2");
+        let b_row = rows.iter().find(|r| r.source_path == Path::new("b.rs")).unwrap();
+        assert_eq!(b_row.summary_text, "already current");
+    }
+
+    #[test]
+    fn a_stale_record_whose_source_file_is_gone_is_skipped_not_errored() {
+        let workspace = temp_dir("transfiguration-backfill-missing-source");
+
+        let store = open_temp_store("transfiguration-backfill-missing-store.sqlite");
+        store
+            .insert(&ResultRecord {
+                chunk_id: ChunkId(1),
+                source_path: PathBuf::from("deleted.rs"),
+                summary_text: "old summary".to_string(),
+                confidence: 0.8,
+                validation_status: ValidationStatus::Pass,
+                run_id: "run-1".to_string(),
+                model_version: "model-v1".to_string(),
+            })
+            .unwrap();
+
+        let report = backfill_stale_summaries(&store, &StaticBackend, &workspace, "model-v2").unwrap();
+
+        assert!(report.updated.is_empty());
+        assert_eq!(report.skipped_missing_source, vec![PathBuf::from("deleted.rs")]);
+    }
+}
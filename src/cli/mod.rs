@@ -0,0 +1,15 @@
+//! Interactive terminal commands, as opposed to the one-shot `summarize`
+//! flow the rest of this crate supports. So far this is [`triage`],
+//! [`backfill`], [`overview`], and [`batch`], all built on the
+//! long-reserved `cli` feature flag ([`batch`] additionally needs
+//! `batch-cli`, since it composes `package-pipeline`). [`schema`] is the
+//! exception: it's a data description of the CLI surface (including
+//! `triage` and the one-shot `summarize` flow), not a command itself, used
+//! by `main.rs` to serve `--help-json` and `--completions`.
+
+pub mod backfill;
+#[cfg(feature = "batch-cli")]
+pub mod batch;
+pub mod overview;
+pub mod schema;
+pub mod triage;
@@ -0,0 +1,283 @@
+//! `summarize overview`: a time-boxed, exploratory "what is this repository"
+//! pass over a handful of representative files, rather than a full
+//! chunk-by-chunk run.
+//!
+//! This crate has no notion of sampling "representative chunks" anywhere
+//! else — every other entry point ([`crate::package::run_package_pipeline`],
+//! [`run_with_deadline`](crate::engine::deadline::run_with_deadline))
+//! processes every discovered file/chunk, only degrading *how* a chunk is
+//! summarized once behind schedule, never skipping whole files up front by
+//! design. [`build_overview`] instead picks a small, fixed-priority sample —
+//! entry points, then READMEs, then the largest remaining files, the same
+//! three categories the request names — and stops sampling the moment the
+//! budget runs out, the same "never block past the deadline" rule
+//! [`run_with_deadline`] follows, just applied to whole files instead of one
+//! chunk's in-flight call. Each [`OverviewStatement`] records the single file
+//! that produced it, which [`RepoOverview::to_markdown`] renders as an
+//! inline citation, so a reader can tell which statement to trust less if
+//! they already know a sampled file is unrepresentative.
+//!
+//! Nothing here is [`crate::validation`]-checked the way a chunk summary
+//! would be: a time-boxed overview is explicitly a best-effort sketch, not a
+//! validated [`crate::sinks::query::ResultRecord`].
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::engine::{EngineError, InferenceBackend};
+use crate::fingerprint::{self, FingerprintError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum OverviewError {
+    #[error("failed to walk repository at {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error(transparent)]
+    Fingerprint(#[from] FingerprintError),
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+}
+
+/// Filenames [`collect_sample_files`] treats as entry points, sampled before
+/// anything else.
+const ENTRY_POINT_FILE_NAMES: &[&str] = &["main.rs", "lib.rs", "main.py", "main.go", "index.js", "index.ts"];
+
+/// How many files [`collect_sample_files`] samples at most, across all three
+/// categories combined.
+const DEFAULT_SAMPLE_SIZE: usize = 12;
+
+/// One model-generated statement about the repository, attributed to the
+/// single file that produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverviewStatement {
+    pub source_path: PathBuf,
+    pub text: String,
+}
+
+/// What [`build_overview`] produced: every statement it managed to generate
+/// before running out of budget, and whether it ran out before sampling
+/// every file it had picked out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoOverview {
+    pub statements: Vec<OverviewStatement>,
+    /// Files [`collect_sample_files`] picked but the budget ran out before
+    /// reaching, in the order they would have been sampled.
+    pub skipped_for_budget: Vec<PathBuf>,
+}
+
+impl RepoOverview {
+    /// Renders a one-page, citation-annotated overview: one bullet per
+    /// [`OverviewStatement`], each followed by the file that informed it.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Repository overview\n\n");
+
+        if self.statements.is_empty() {
+            markdown.push_str("_no statements were generated before the budget ran out_\n");
+        }
+        for statement in &self.statements {
+            markdown.push_str(&format!("- {} _(source: {})_\n", statement.text.trim(), statement.source_path.display()));
+        }
+
+        if !self.skipped_for_budget.is_empty() {
+            markdown.push_str(&format!(
+                "\n_{} file(s) sampled but not reached before the time budget ran out: {}_\n",
+                self.skipped_for_budget.len(),
+                self.skipped_for_budget.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "),
+            ));
+        }
+
+        markdown
+    }
+}
+
+/// Samples up to `max_files` representative files under `root`: every
+/// [`ENTRY_POINT_FILE_NAMES`] match first, then every `README*` file
+/// (case-insensitive), then the largest remaining files by byte size —
+/// skipping [`fingerprint::ALWAYS_SKIPPED_DIRS`] the same way every other
+/// tree walk in this crate does.
+pub fn collect_sample_files(root: &Path, max_files: usize) -> Result<Vec<PathBuf>, OverviewError> {
+    let mut all_files = Vec::new();
+    walk_for_candidates(root, root, &mut all_files)?;
+
+    let mut entry_points: Vec<(PathBuf, u64)> = Vec::new();
+    let mut readmes: Vec<(PathBuf, u64)> = Vec::new();
+    let mut rest: Vec<(PathBuf, u64)> = Vec::new();
+
+    for (relative_path, size) in all_files {
+        let file_name = relative_path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_ascii_lowercase();
+        if ENTRY_POINT_FILE_NAMES.contains(&file_name.as_str()) {
+            entry_points.push((relative_path, size));
+        } else if file_name.starts_with("readme") {
+            readmes.push((relative_path, size));
+        } else {
+            rest.push((relative_path, size));
+        }
+    }
+
+    rest.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    let sampled = entry_points.into_iter().chain(readmes).chain(rest).map(|(path, _)| path).take(max_files).collect();
+    Ok(sampled)
+}
+
+fn walk_for_candidates(root: &Path, dir: &Path, out: &mut Vec<(PathBuf, u64)>) -> Result<(), OverviewError> {
+    let entries = fs::read_dir(dir).map_err(|source| OverviewError::Io { path: dir.to_path_buf(), source })?;
+
+    for entry in entries {
+        let entry = entry.map_err(|source| OverviewError::Io { path: dir.to_path_buf(), source })?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if path.is_dir() {
+            if fingerprint::ALWAYS_SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                continue;
+            }
+            walk_for_candidates(root, &path, out)?;
+            continue;
+        }
+
+        let metadata = entry.metadata().map_err(|source| OverviewError::Io { path: path.clone(), source })?;
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        out.push((relative, metadata.len()));
+    }
+
+    Ok(())
+}
+
+/// Samples up to [`DEFAULT_SAMPLE_SIZE`] representative files under `root`
+/// (see [`collect_sample_files`]) and asks `backend` for a one-sentence
+/// architectural note about each in turn, stopping — without starting the
+/// next file's call — the moment `started_at.elapsed() >= budget`. A file
+/// that isn't valid UTF-8 is treated as a binary asset and skipped, the same
+/// convention [`crate::package::run_package_pipeline`] follows.
+pub fn build_overview(backend: &impl InferenceBackend, root: &Path, budget: Duration) -> Result<RepoOverview, OverviewError> {
+    let started_at = Instant::now();
+    let sample = collect_sample_files(root, DEFAULT_SAMPLE_SIZE)?;
+
+    let mut overview = RepoOverview { statements: Vec::new(), skipped_for_budget: Vec::new() };
+
+    for (index, relative_path) in sample.iter().enumerate() {
+        if started_at.elapsed() >= budget {
+            overview.skipped_for_budget.extend(sample[index..].iter().cloned());
+            break;
+        }
+
+        let Ok(content) = fs::read_to_string(root.join(relative_path)) else {
+            continue;
+        };
+
+        let prompt = format!(
+            "In one sentence, describe the architectural role this file plays within the repository it belongs to.\n\nFile: {}\n\n{content}",
+            relative_path.display(),
+        );
+        let text = backend.generate_completion_text(&prompt)?;
+
+        overview.statements.push(OverviewStatement { source_path: relative_path.clone(), text });
+    }
+
+    Ok(overview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::heuristic::HeuristicBackend;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A backend whose calls take `delay` each and count how many times they
+    /// ran, so tests can drive [`build_overview`] past its budget
+    /// deterministically instead of racing a real model.
+    struct SlowBackend {
+        delay: Duration,
+        calls: AtomicUsize,
+    }
+
+    impl InferenceBackend for SlowBackend {
+        fn generate_completion_text(&self, _prompt: &str) -> Result<String, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(self.delay);
+            Ok("a statement".to_string())
+        }
+    }
+
+    #[test]
+    fn collect_sample_files_prioritizes_entry_points_then_readmes_then_largest_files() {
+        let root = temp_dir("transfiguration-overview-sampling");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(root.join("README.md"), "# hello\n").unwrap();
+        fs::write(root.join("small.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.join("big.rs"), "fn a() {}\n".repeat(100)).unwrap();
+
+        let sampled = collect_sample_files(&root, 4).unwrap();
+
+        assert_eq!(sampled[0], PathBuf::from("src/lib.rs"));
+        assert_eq!(sampled[1], PathBuf::from("README.md"));
+        assert_eq!(sampled[2], PathBuf::from("big.rs"));
+        assert_eq!(sampled[3], PathBuf::from("small.rs"));
+    }
+
+    #[test]
+    fn collect_sample_files_caps_at_max_files() {
+        let root = temp_dir("transfiguration-overview-cap");
+        for i in 0..5 {
+            fs::write(root.join(format!("f{i}.rs")), "fn a() {}\n").unwrap();
+        }
+
+        let sampled = collect_sample_files(&root, 2).unwrap();
+        assert_eq!(sampled.len(), 2);
+    }
+
+    #[test]
+    fn build_overview_cites_the_file_that_produced_each_statement() {
+        let root = temp_dir("transfiguration-overview-citation");
+        fs::write(root.join("lib.rs"), "pub fn go() {}\n").unwrap();
+
+        let overview = build_overview(&HeuristicBackend, &root, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(overview.statements.len(), 1);
+        assert_eq!(overview.statements[0].source_path, PathBuf::from("lib.rs"));
+        assert!(overview.skipped_for_budget.is_empty());
+
+        let markdown = overview.to_markdown();
+        assert!(markdown.contains("source: lib.rs"));
+    }
+
+    #[test]
+    fn an_exhausted_budget_stops_sampling_and_records_what_was_skipped() {
+        let root = temp_dir("transfiguration-overview-budget");
+        for i in 0..4 {
+            fs::write(root.join(format!("f{i}.rs")), "fn a() {}\n").unwrap();
+        }
+        let backend = SlowBackend { delay: Duration::from_millis(20), calls: AtomicUsize::new(0) };
+
+        let overview = build_overview(&backend, &root, Duration::from_nanos(1)).unwrap();
+
+        assert!(overview.statements.is_empty());
+        assert_eq!(overview.skipped_for_budget.len(), 4);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn a_generous_budget_produces_a_statement_per_sampled_file() {
+        let root = temp_dir("transfiguration-overview-generous");
+        fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(root.join("b.rs"), "fn b() {}\n").unwrap();
+        let backend = SlowBackend { delay: Duration::from_millis(1), calls: AtomicUsize::new(0) };
+
+        let overview = build_overview(&backend, &root, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(overview.statements.len(), 2);
+        assert!(overview.skipped_for_budget.is_empty());
+    }
+}
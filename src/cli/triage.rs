@@ -0,0 +1,397 @@
+//! `research triage`: an interactive pass over newly produced results,
+//! letting an analyst accept, reject, or categorize each one.
+//!
+//! This crate has no "newly discovered projects" table or research pipeline
+//! — the closest real dataset is [`crate::sinks::query::ResultsStore`], and
+//! the closest thing to an "auto-evaluation score" a [`ResultRecord`]
+//! carries is its `confidence` and `validation_status`, the same two
+//! signals [`crate::analysis::ConfidenceAnalyzer`] and
+//! [`crate::analysis::ValidationStatusAnalyzer`] check when the
+//! `analysis-pipeline` feature is enabled (triage does not depend on that
+//! feature; it computes the same summary inline to stay usable with just
+//! `cli` + `sqlite-sink`). There is also no raw-terminal/keystroke-capture
+//! dependency in this crate (no `crossterm`/`termion`), so [`TriageTerminal`]
+//! reads one line at a time rather than single keystrokes — still
+//! keyboard-driven (`a`/`r`/`c <category>`/`q`), just Enter-terminated.
+//! Decisions and an append-only audit trail are both persisted by
+//! [`TriageStore`], in its own table, following the same "additive new
+//! table" convention as [`crate::sinks::query::ResultsStore`] and
+//! [`crate::analysis::AnalysisStore`].
+
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::chunk::ChunkId;
+use crate::sinks::query::ResultRecord;
+use crate::sinks::SinkError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TriageError {
+    #[error("failed to read a triage command: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to persist a triage decision: {0}")]
+    Sink(#[from] SinkError),
+}
+
+/// One analyst decision about a [`ResultRecord`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriageDecision {
+    Accept,
+    Reject,
+    Categorize(String),
+}
+
+impl TriageDecision {
+    fn kind_str(&self) -> &'static str {
+        match self {
+            TriageDecision::Accept => "accept",
+            TriageDecision::Reject => "reject",
+            TriageDecision::Categorize(_) => "categorize",
+        }
+    }
+
+    fn category(&self) -> Option<&str> {
+        match self {
+            TriageDecision::Categorize(category) => Some(category.as_str()),
+            _ => None,
+        }
+    }
+
+    fn parse(kind: &str, category: Option<String>) -> Option<Self> {
+        match kind {
+            "accept" => Some(TriageDecision::Accept),
+            "reject" => Some(TriageDecision::Reject),
+            "categorize" => category.map(TriageDecision::Categorize),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed keystroke command read by a [`TriageTerminal`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriageCommand {
+    Decide(TriageDecision),
+    Quit,
+}
+
+/// Parses one line of triage input: `a` (accept), `r` (reject), `c <name>`
+/// (categorize), or `q` (quit). Returns `None` for anything else, so the
+/// caller can re-prompt rather than silently misinterpreting a typo.
+fn parse_command(line: &str) -> Option<TriageCommand> {
+    let line = line.trim();
+    if line.eq_ignore_ascii_case("a") {
+        return Some(TriageCommand::Decide(TriageDecision::Accept));
+    }
+    if line.eq_ignore_ascii_case("r") {
+        return Some(TriageCommand::Decide(TriageDecision::Reject));
+    }
+    if line.eq_ignore_ascii_case("q") {
+        return Some(TriageCommand::Quit);
+    }
+    if let Some(rest) = line.strip_prefix('c').or_else(|| line.strip_prefix('C')) {
+        let category = rest.trim();
+        if rest.starts_with(char::is_whitespace) && !category.is_empty() {
+            return Some(TriageCommand::Decide(TriageDecision::Categorize(category.to_string())));
+        }
+    }
+    None
+}
+
+/// The analyst-facing side of a triage session: showing each record and
+/// reading back a command. Abstracted behind a trait so [`run_triage_session`]
+/// is testable without a real terminal; see [`ScriptedTerminal`] in this
+/// module's tests and [`StdioTerminal`] for the real implementation.
+pub trait TriageTerminal {
+    fn display(&mut self, record: &ResultRecord, evaluation: &str) -> Result<(), TriageError>;
+    fn read_command(&mut self) -> Result<TriageCommand, TriageError>;
+}
+
+/// Summarizes a [`ResultRecord`]'s auto-evaluation signals the way
+/// [`crate::analysis::ConfidenceAnalyzer`]/[`crate::analysis::ValidationStatusAnalyzer`]
+/// would score it, for display alongside each record during triage.
+pub fn format_evaluation(record: &ResultRecord) -> String {
+    format!(
+        "confidence={:.2} validation={:?}",
+        record.confidence, record.validation_status
+    )
+}
+
+/// Reads commands from `stdin`/writes prompts to `stdout`, re-prompting on
+/// an unparseable line rather than erroring the whole session.
+pub struct StdioTerminal {
+    stdin: io::Stdin,
+    stdout: io::Stdout,
+}
+
+impl Default for StdioTerminal {
+    fn default() -> Self {
+        StdioTerminal { stdin: io::stdin(), stdout: io::stdout() }
+    }
+}
+
+impl TriageTerminal for StdioTerminal {
+    fn display(&mut self, record: &ResultRecord, evaluation: &str) -> Result<(), TriageError> {
+        writeln!(self.stdout, "\n{}", record.source_path.display())?;
+        writeln!(self.stdout, "  {}", record.summary_text)?;
+        writeln!(self.stdout, "  [{evaluation}]")?;
+        write!(self.stdout, "(a)ccept / (r)eject / (c)ategorize <name> / (q)uit > ")?;
+        self.stdout.flush()?;
+        Ok(())
+    }
+
+    fn read_command(&mut self) -> Result<TriageCommand, TriageError> {
+        loop {
+            let mut line = String::new();
+            if self.stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(TriageCommand::Quit);
+            }
+            if let Some(command) = parse_command(&line) {
+                return Ok(command);
+            }
+            write!(self.stdout, "unrecognized command, try again > ")?;
+            self.stdout.flush()?;
+        }
+    }
+}
+
+/// Persists triage decisions and their full audit trail, separate from
+/// [`crate::sinks::query::ResultsStore`]'s `results` table: every decision
+/// is appended as its own row (the audit log), and the most recent row for
+/// a chunk is its current decision.
+pub struct TriageStore {
+    connection: Connection,
+}
+
+impl TriageStore {
+    pub fn open(path: &Path) -> Result<Self, TriageError> {
+        let connection = Connection::open(path).map_err(SinkError::from)?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS triage_audit_log (
+                    chunk_id INTEGER NOT NULL,
+                    decision_kind TEXT NOT NULL,
+                    category TEXT,
+                    decided_at_unix_ms INTEGER NOT NULL
+                )",
+                [],
+            )
+            .map_err(SinkError::from)?;
+        Ok(TriageStore { connection })
+    }
+
+    /// Appends `decision` for `chunk_id` to the audit log; this also becomes
+    /// the chunk's current decision, since [`Self::latest_decision`] always
+    /// reads the most recently appended row.
+    pub fn record_decision(&self, chunk_id: ChunkId, decision: &TriageDecision) -> Result<(), TriageError> {
+        let decided_at_unix_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        self.connection
+            .execute(
+                "INSERT INTO triage_audit_log (chunk_id, decision_kind, category, decided_at_unix_ms)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![chunk_id.0, decision.kind_str(), decision.category(), decided_at_unix_ms],
+            )
+            .map_err(SinkError::from)?;
+        Ok(())
+    }
+
+    /// The most recently recorded decision for `chunk_id`, or `None` if it
+    /// has never been triaged.
+    ///
+    /// Ordered by `decided_at_unix_ms DESC, rowid DESC` rather than
+    /// `decided_at_unix_ms` alone: two decisions for the same chunk can land
+    /// in the same millisecond on ordinary hardware, and SQLite resolves a
+    /// tie on the `ORDER BY` column arbitrarily. `rowid` — SQLite's own
+    /// implicit, monotonically increasing insertion order for this table —
+    /// is a tiebreaker that always agrees with insertion order, even when
+    /// the clock doesn't.
+    pub fn latest_decision(&self, chunk_id: ChunkId) -> Result<Option<TriageDecision>, TriageError> {
+        let row: Option<(String, Option<String>)> = self
+            .connection
+            .query_row(
+                "SELECT decision_kind, category FROM triage_audit_log
+                 WHERE chunk_id = ?1 ORDER BY decided_at_unix_ms DESC, rowid DESC LIMIT 1",
+                rusqlite::params![chunk_id.0],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(SinkError::from)?;
+
+        Ok(row.and_then(|(kind, category)| TriageDecision::parse(&kind, category)))
+    }
+
+    /// How many audit log rows (not distinct chunks) have been recorded for
+    /// `chunk_id`, for callers that want the full history depth.
+    pub fn audit_entry_count(&self, chunk_id: ChunkId) -> Result<u64, TriageError> {
+        let count = self
+            .connection
+            .query_row("SELECT COUNT(*) FROM triage_audit_log WHERE chunk_id = ?1", rusqlite::params![chunk_id.0], |row| {
+                row.get(0)
+            })
+            .map_err(SinkError::from)?;
+        Ok(count)
+    }
+}
+
+/// Tallies of what a [`run_triage_session`] call decided.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TriageSessionSummary {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub categorized: usize,
+    pub quit_early: bool,
+}
+
+/// Steps through `records` one at a time, showing each one plus its
+/// auto-evaluation summary via `terminal`, persisting every decision to
+/// `store`, and stopping early if the analyst quits.
+pub fn run_triage_session(
+    records: &[ResultRecord],
+    store: &TriageStore,
+    terminal: &mut dyn TriageTerminal,
+) -> Result<TriageSessionSummary, TriageError> {
+    let mut summary = TriageSessionSummary::default();
+
+    for record in records {
+        let evaluation = format_evaluation(record);
+        terminal.display(record, &evaluation)?;
+
+        let decision = match terminal.read_command()? {
+            TriageCommand::Decide(decision) => Some(decision),
+            TriageCommand::Quit => None,
+        };
+
+        let Some(decision) = decision else {
+            summary.quit_early = true;
+            return Ok(summary);
+        };
+
+        store.record_decision(record.chunk_id, &decision)?;
+        match decision {
+            TriageDecision::Accept => summary.accepted += 1,
+            TriageDecision::Reject => summary.rejected += 1,
+            TriageDecision::Categorize(_) => summary.categorized += 1,
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::query::ValidationStatus;
+    use std::collections::VecDeque;
+    use std::path::PathBuf;
+
+    fn record(chunk_id: u64, confidence: f32, status: ValidationStatus) -> ResultRecord {
+        ResultRecord {
+            chunk_id: ChunkId(chunk_id),
+            source_path: PathBuf::from(format!("f{chunk_id}.rs")),
+            summary_text: "a summary".to_string(),
+            confidence,
+            validation_status: status,
+            run_id: "run-1".to_string(),
+            model_version: String::new(),
+        }
+    }
+
+    fn open_temp_store(name: &str) -> (TriageStore, PathBuf) {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        (TriageStore::open(&path).unwrap(), path)
+    }
+
+    /// A scripted stand-in for a real terminal: replays a fixed queue of
+    /// commands and records every `display` call it was asked to show.
+    struct ScriptedTerminal {
+        commands: VecDeque<TriageCommand>,
+        shown: Vec<ChunkId>,
+    }
+
+    impl TriageTerminal for ScriptedTerminal {
+        fn display(&mut self, record: &ResultRecord, _evaluation: &str) -> Result<(), TriageError> {
+            self.shown.push(record.chunk_id);
+            Ok(())
+        }
+
+        fn read_command(&mut self) -> Result<TriageCommand, TriageError> {
+            Ok(self.commands.pop_front().unwrap_or(TriageCommand::Quit))
+        }
+    }
+
+    #[test]
+    fn parse_command_recognizes_every_keystroke_and_rejects_garbage() {
+        assert_eq!(parse_command("a"), Some(TriageCommand::Decide(TriageDecision::Accept)));
+        assert_eq!(parse_command("R"), Some(TriageCommand::Decide(TriageDecision::Reject)));
+        assert_eq!(parse_command("q"), Some(TriageCommand::Quit));
+        assert_eq!(
+            parse_command("c infra"),
+            Some(TriageCommand::Decide(TriageDecision::Categorize("infra".to_string())))
+        );
+        assert_eq!(parse_command("c"), None, "categorize with no name is not a valid command");
+        assert_eq!(parse_command("xyz"), None);
+    }
+
+    #[test]
+    fn run_triage_session_persists_every_decision_and_tallies_them() {
+        let (store, path) = open_temp_store("transfiguration-triage-tally.sqlite");
+        let records = vec![
+            record(1, 0.9, ValidationStatus::Pass),
+            record(2, 0.1, ValidationStatus::Fail),
+            record(3, 0.5, ValidationStatus::Pass),
+        ];
+
+        let mut terminal = ScriptedTerminal {
+            commands: VecDeque::from(vec![
+                TriageCommand::Decide(TriageDecision::Accept),
+                TriageCommand::Decide(TriageDecision::Reject),
+                TriageCommand::Decide(TriageDecision::Categorize("needs-review".to_string())),
+            ]),
+            shown: Vec::new(),
+        };
+
+        let summary = run_triage_session(&records, &store, &mut terminal).unwrap();
+
+        assert_eq!(summary, TriageSessionSummary { accepted: 1, rejected: 1, categorized: 1, quit_early: false });
+        assert_eq!(terminal.shown, vec![ChunkId(1), ChunkId(2), ChunkId(3)]);
+        assert_eq!(store.latest_decision(ChunkId(1)).unwrap(), Some(TriageDecision::Accept));
+        assert_eq!(store.latest_decision(ChunkId(2)).unwrap(), Some(TriageDecision::Reject));
+        assert_eq!(
+            store.latest_decision(ChunkId(3)).unwrap(),
+            Some(TriageDecision::Categorize("needs-review".to_string()))
+        );
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn quitting_early_stops_the_session_without_showing_remaining_records() {
+        let (store, path) = open_temp_store("transfiguration-triage-quit.sqlite");
+        let records = vec![record(1, 0.9, ValidationStatus::Pass), record(2, 0.9, ValidationStatus::Pass)];
+
+        let mut terminal = ScriptedTerminal { commands: VecDeque::from(vec![TriageCommand::Quit]), shown: Vec::new() };
+        let summary = run_triage_session(&records, &store, &mut terminal).unwrap();
+
+        assert!(summary.quit_early);
+        assert_eq!(terminal.shown, vec![ChunkId(1)], "only the record shown before quitting is recorded");
+        assert_eq!(store.latest_decision(ChunkId(1)).unwrap(), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn re_triaging_a_chunk_appends_to_the_audit_log_rather_than_overwriting() {
+        let (store, path) = open_temp_store("transfiguration-triage-audit.sqlite");
+
+        store.record_decision(ChunkId(1), &TriageDecision::Reject).unwrap();
+        store.record_decision(ChunkId(1), &TriageDecision::Accept).unwrap();
+
+        assert_eq!(store.audit_entry_count(ChunkId(1)).unwrap(), 2);
+        assert_eq!(store.latest_decision(ChunkId(1)).unwrap(), Some(TriageDecision::Accept));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
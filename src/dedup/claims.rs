@@ -0,0 +1,223 @@
+//! Cross-source duplicate-claim detection, so five sources repeating the
+//! same sentence don't inflate a corroboration score to "5 independent
+//! sources confirm this".
+//!
+//! This crate has no multi-source research pipeline (no notion of a
+//! "source" beyond a chunk's `source_path`, and no `key_insights` field);
+//! the closest fit is [`crate::dedup`]'s existing summary-clustering, which
+//! already groups near-identical text but does so over one run's
+//! [`Summary`](crate::engine::Summary)s via a hashing-trick embedding, not
+//! over claims attributed to distinct sources. This module instead works
+//! over arbitrary [`ClaimRecord`]s (a piece of text plus whichever source it
+//! came from) using k-shingling + MinHash, the similarity technique the
+//! request asks for, and reports [`corroboration_count`] as the number of
+//! distinct *clusters* rather than the number of (possibly duplicated)
+//! claims, so near-duplicate claims from different sources count as one
+//! independent confirmation rather than several.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// One claim as attributed to a single source, the unit [`cluster_claims`]
+/// groups.
+#[derive(Debug, Clone)]
+pub struct ClaimRecord {
+    pub source_path: PathBuf,
+    pub text: String,
+}
+
+/// Splits `text` into `k`-word shingles, hashed to `u64` rather than kept as
+/// owned substrings, since only set membership (for Jaccard/MinHash) is
+/// needed, not the text itself.
+fn shingles(text: &str, k: usize) -> HashSet<u64> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() < k {
+        return HashSet::from([hash_shingle(&words.join(" "))]);
+    }
+    words.windows(k).map(|window| hash_shingle(&window.join(" "))).collect()
+}
+
+fn hash_shingle(shingle: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    shingle.to_ascii_lowercase().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A fixed-size MinHash signature: the minimum hash value seen for each of
+/// `num_hashes` independent hash functions, over a shingle set. Two
+/// signatures' fraction of matching slots estimates the sets' Jaccard
+/// similarity without ever comparing the (potentially large) sets directly.
+#[derive(Debug, Clone, PartialEq)]
+struct MinHashSignature {
+    slots: Vec<u64>,
+}
+
+/// Deterministic, seeded hash functions used to build a [`MinHashSignature`]
+/// slot, in the same spirit as [`crate::testgen`]'s `SplitMix64`: no
+/// external dependency, reproducible across platforms. Function `i` maps
+/// `value` to `splitmix64(value ^ seed_for(i))`.
+fn permuted_hash(value: u64, function_index: u64) -> u64 {
+    let seed = value ^ function_index.wrapping_mul(0x9E3779B97F4A7C15);
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn compute_signature(shingles: &HashSet<u64>, num_hashes: usize) -> MinHashSignature {
+    let mut slots = vec![u64::MAX; num_hashes.max(1)];
+    for &shingle in shingles {
+        for (function_index, slot) in slots.iter_mut().enumerate() {
+            let hashed = permuted_hash(shingle, function_index as u64);
+            if hashed < *slot {
+                *slot = hashed;
+            }
+        }
+    }
+    MinHashSignature { slots }
+}
+
+fn estimate_jaccard(a: &MinHashSignature, b: &MinHashSignature) -> f32 {
+    let matching = a.slots.iter().zip(&b.slots).filter(|(x, y)| x == y).count();
+    matching as f32 / a.slots.len().max(1) as f32
+}
+
+/// One cluster of near-duplicate claims: the first-seen claim plus every
+/// other source found to repeat it, within `similarity_threshold`.
+#[derive(Debug, Clone)]
+pub struct ClaimCluster {
+    pub representative: ClaimRecord,
+    pub derivative_sources: Vec<PathBuf>,
+}
+
+impl ClaimCluster {
+    /// Every source (the representative's plus every derivative) that
+    /// attests to this claim, for callers that want the full list rather
+    /// than just the count.
+    pub fn all_sources(&self) -> Vec<&Path> {
+        std::iter::once(self.representative.source_path.as_path())
+            .chain(self.derivative_sources.iter().map(PathBuf::as_path))
+            .collect()
+    }
+}
+
+const SHINGLE_SIZE: usize = 3;
+const NUM_MINHASH_FUNCTIONS: usize = 32;
+
+/// Greedily clusters `claims` by MinHash-estimated Jaccard similarity: each
+/// claim joins the first existing cluster whose representative scores at
+/// least `similarity_threshold`, or starts a new cluster otherwise. Claims
+/// already attributed to the same `source_path` as a cluster's
+/// representative are never folded into it — a source repeating its own
+/// claim isn't a second, independent source repeating it.
+pub fn cluster_claims(claims: Vec<ClaimRecord>, similarity_threshold: f32) -> Vec<ClaimCluster> {
+    let mut clusters: Vec<(MinHashSignature, ClaimCluster)> = Vec::new();
+
+    for claim in claims {
+        let signature = compute_signature(&shingles(&claim.text, SHINGLE_SIZE), NUM_MINHASH_FUNCTIONS);
+        let existing = clusters.iter_mut().find(|(rep_signature, cluster)| {
+            cluster.representative.source_path != claim.source_path && estimate_jaccard(rep_signature, &signature) >= similarity_threshold
+        });
+
+        match existing {
+            Some((_, cluster)) => cluster.derivative_sources.push(claim.source_path),
+            None => clusters.push((
+                signature,
+                ClaimCluster {
+                    representative: claim,
+                    derivative_sources: Vec::new(),
+                },
+            )),
+        }
+    }
+
+    clusters.into_iter().map(|(_, cluster)| cluster).collect()
+}
+
+/// The corroboration score [`cluster_claims`] is meant to feed: the number
+/// of independent clusters, not the number of claims — five near-duplicate
+/// claims from five sources count as one independent confirmation, not
+/// five.
+pub fn corroboration_count(clusters: &[ClaimCluster]) -> usize {
+    clusters.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(source: &str, text: &str) -> ClaimRecord {
+        ClaimRecord {
+            source_path: PathBuf::from(source),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn near_identical_claims_from_different_sources_cluster_together() {
+        let claims = vec![
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-b.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-c.html", "the weather today is sunny with a light breeze"),
+        ];
+
+        let clusters = cluster_claims(claims, 0.8);
+
+        assert_eq!(clusters.len(), 2);
+        assert!(clusters.iter().any(|c| c.derivative_sources.len() == 1));
+        assert!(clusters.iter().any(|c| c.derivative_sources.is_empty()));
+    }
+
+    #[test]
+    fn corroboration_count_counts_clusters_not_raw_claims() {
+        let claims = vec![
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-b.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-c.html", "our new caching layer delivers a 2-5x improvement in latency"),
+        ];
+
+        let clusters = cluster_claims(claims, 0.8);
+
+        assert_eq!(corroboration_count(&clusters), 1, "three duplicated claims are one independent confirmation");
+    }
+
+    #[test]
+    fn a_source_repeating_itself_does_not_inflate_its_own_cluster() {
+        let claims = vec![
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+        ];
+
+        let clusters = cluster_claims(claims, 0.8);
+
+        assert_eq!(clusters.len(), 2, "the same source repeating its own claim is not a second independent source");
+    }
+
+    #[test]
+    fn distinct_claims_do_not_merge_even_at_a_low_threshold() {
+        let claims = vec![
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-b.html", "the quarterly earnings report exceeded analyst expectations"),
+        ];
+
+        let clusters = cluster_claims(claims, 0.1);
+
+        assert_eq!(clusters.len(), 2);
+    }
+
+    #[test]
+    fn all_sources_lists_the_representative_and_every_derivative() {
+        let claims = vec![
+            claim("blog-a.html", "our new caching layer delivers a 2-5x improvement in latency"),
+            claim("blog-b.html", "our new caching layer delivers a 2-5x improvement in latency"),
+        ];
+
+        let clusters = cluster_claims(claims, 0.8);
+        let sources = clusters[0].all_sources();
+
+        assert_eq!(sources.len(), 2);
+        assert!(sources.contains(&Path::new("blog-a.html")));
+        assert!(sources.contains(&Path::new("blog-b.html")));
+    }
+}
@@ -0,0 +1,116 @@
+//! Clusters near-identical summaries so reports over large repositories
+//! don't drown in hundreds of copies of "getter for the `id` field".
+//!
+//! There's no embedding model wired in yet, so [`embed_summary_text`] uses a
+//! deterministic hashing-trick bag-of-words vector. It's a real embedding in
+//! the sense that cosine similarity over it tracks lexical overlap; swapping
+//! in a learned embedding later only touches this one function.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::chunk::ChunkId;
+use crate::engine::Summary;
+
+pub mod claims;
+#[cfg(feature = "sqlite-sink")]
+pub mod store;
+
+/// A cluster of summaries judged similar enough to report once.
+#[derive(Debug, Clone)]
+pub struct SummaryCluster {
+    pub representative: Summary,
+    pub member_chunk_ids: Vec<ChunkId>,
+}
+
+impl SummaryCluster {
+    pub fn count(&self) -> usize {
+        self.member_chunk_ids.len()
+    }
+}
+
+/// Embeds `text` into a fixed-size, L2-normalized vector via feature hashing.
+pub fn embed_summary_text(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector = vec![0f32; dimensions.max(1)];
+    for token in text.split_whitespace() {
+        let mut hasher = DefaultHasher::new();
+        token.to_ascii_lowercase().hash(&mut hasher);
+        let index = (hasher.finish() as usize) % vector.len();
+        vector[index] += 1.0;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in &mut vector {
+            *v /= norm;
+        }
+    }
+    vector
+}
+
+/// Cosine similarity of two already-normalized embeddings, so callers that
+/// only need comparison (not clustering) can reuse [`embed_summary_text`]'s
+/// vectors without pulling in the clustering pass; see
+/// [`crate::engine::near_duplicate`].
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Greedily clusters `summaries` by embedding similarity: each summary joins
+/// the first existing cluster whose representative is within `threshold`
+/// cosine similarity, or starts a new cluster otherwise.
+pub fn cluster_summaries_by_similarity(summaries: Vec<Summary>, threshold: f32) -> Vec<SummaryCluster> {
+    const DIMENSIONS: usize = 64;
+    let mut clusters: Vec<(Vec<f32>, SummaryCluster)> = Vec::new();
+
+    for summary in summaries {
+        let embedding = embed_summary_text(&summary.text, DIMENSIONS);
+        let chunk_id = summary.chunk_id;
+        let existing = clusters
+            .iter_mut()
+            .find(|(rep_embedding, _)| cosine_similarity(rep_embedding, &embedding) >= threshold);
+
+        match existing {
+            Some((_, cluster)) => cluster.member_chunk_ids.push(chunk_id),
+            None => clusters.push((
+                embedding,
+                SummaryCluster {
+                    representative: summary,
+                    member_chunk_ids: vec![chunk_id],
+                },
+            )),
+        }
+    }
+
+    clusters.into_iter().map(|(_, cluster)| cluster).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkClass;
+
+    fn summary(id: u64, text: &str) -> Summary {
+        Summary {
+            chunk_id: ChunkId(id),
+            class: ChunkClass::Production,
+            text: text.to_string(),
+            instruction: None,
+            source: crate::engine::SummarySource::Model,
+            language: crate::validation::Language::default(),
+        }
+    }
+
+    #[test]
+    fn near_identical_summaries_merge_into_one_cluster() {
+        let summaries = vec![
+            summary(1, "returns the value of the id field"),
+            summary(2, "returns the value of the id field"),
+            summary(3, "parses a TOML config file into a struct"),
+        ];
+
+        let clusters = cluster_summaries_by_similarity(summaries, 0.99);
+        assert_eq!(clusters.len(), 2);
+        assert!(clusters.iter().any(|c| c.count() == 2));
+        assert!(clusters.iter().any(|c| c.count() == 1));
+    }
+}
@@ -0,0 +1,282 @@
+//! Persists [`embed_summary_text`](crate::dedup::embed_summary_text) vectors
+//! across runs, so semantic search/dedup doesn't start from nothing every
+//! time a repo is re-summarized.
+//!
+//! Metadata and vectors both live in one SQLite database (vectors as a flat
+//! `BLOB` of little-endian `f32`s) rather than splitting vectors into a
+//! separate memory-mapped file: this crate's embeddings are small
+//! (tens of KB per run even for a large repo), so a second storage engine
+//! just for vectors isn't worth the added failure mode of the two files
+//! drifting out of sync.
+//!
+//! [`EmbeddingIndex::nearest`] does an exact brute-force scan rather than a
+//! true ANN structure (HNSW etc.): at the corpus sizes one repo's chunks
+//! produce, a linear scan over a few thousand vectors is microseconds, and
+//! an exact result is strictly better than an approximate one when it's
+//! this cheap. [`EmbeddingStore::rebuild_index`] is the seam to swap in a
+//! real ANN index later without touching the persistence layer.
+//!
+//! Rows are keyed by [`crate::chunk::StableChunkId`], not
+//! [`crate::chunk::ChunkId`]: this store outlives a single run, and
+//! `ChunkId` is only guaranteed unique within the run that produced it, so
+//! keying by it would let a later run's unrelated chunk silently inherit a
+//! stale vector just because it landed at the same position in that run's
+//! walk order.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::chunk::{ChunkId, StableChunkId};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DedupStoreError {
+    #[error("embedding store error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("stored vector for chunk {0:?} has a corrupt byte length")]
+    CorruptVector(StableChunkId),
+}
+
+/// One chunk's embedding as persisted to (or loaded from) the store, keyed
+/// by [`StableChunkId`] rather than [`ChunkId`]: this store is read back in
+/// later runs, and `ChunkId` is only guaranteed unique within the run that
+/// produced it, so keying by it would let a later run's unrelated chunk
+/// silently inherit a stale vector. `chunk_id` is still recorded alongside,
+/// for correlating a row back to the run that wrote it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingRecord {
+    pub stable_id: StableChunkId,
+    pub chunk_id: ChunkId,
+    pub source_path: String,
+    /// Hash of the summarized content this vector was derived from, so a
+    /// caller can tell "unchanged, skip re-embedding" from "stale, replace"
+    /// on a re-run without re-embedding just to compare.
+    pub content_hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// SQLite-backed persistence for [`EmbeddingRecord`]s, supporting incremental
+/// upserts and deletion of vectors for chunks that no longer exist.
+pub struct EmbeddingStore {
+    connection: Connection,
+}
+
+impl EmbeddingStore {
+    pub fn open(path: &Path) -> Result<Self, DedupStoreError> {
+        let connection = Connection::open(path)?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS embeddings (
+                stable_id TEXT PRIMARY KEY,
+                chunk_id INTEGER NOT NULL,
+                source_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                vector BLOB NOT NULL
+            )",
+            [],
+        )?;
+        Ok(EmbeddingStore { connection })
+    }
+
+    /// Inserts `record`, replacing any existing row for the same
+    /// [`StableChunkId`].
+    pub fn upsert(&self, record: &EmbeddingRecord) -> Result<(), DedupStoreError> {
+        self.connection.execute(
+            "INSERT INTO embeddings (stable_id, chunk_id, source_path, content_hash, vector)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(stable_id) DO UPDATE SET
+                chunk_id = excluded.chunk_id,
+                source_path = excluded.source_path,
+                content_hash = excluded.content_hash,
+                vector = excluded.vector",
+            params![
+                record.stable_id.0,
+                record.chunk_id.0,
+                record.source_path,
+                record.content_hash,
+                encode_vector(&record.vector)
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Deletes every row whose [`StableChunkId`] is not in `live_stable_ids`
+    /// (e.g. a file was deleted, edited, or no longer chunks the same way),
+    /// returning how many rows were removed.
+    pub fn delete_stale(&self, live_stable_ids: &HashSet<StableChunkId>) -> Result<usize, DedupStoreError> {
+        let mut statement = self.connection.prepare("SELECT stable_id FROM embeddings")?;
+        let stored_ids: Vec<String> = statement.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        drop(statement);
+
+        let mut deleted = 0usize;
+        for stored_id in stored_ids {
+            if !live_stable_ids.contains(&StableChunkId(stored_id.clone())) {
+                self.connection.execute("DELETE FROM embeddings WHERE stable_id = ?1", params![stored_id])?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    pub fn get(&self, stable_id: &StableChunkId) -> Result<Option<EmbeddingRecord>, DedupStoreError> {
+        let mut statement = self.connection.prepare(
+            "SELECT stable_id, chunk_id, source_path, content_hash, vector FROM embeddings WHERE stable_id = ?1",
+        )?;
+        let mut rows = statement.query(params![stable_id.0])?;
+        let Some(row) = rows.next()? else { return Ok(None) };
+        Ok(Some(row_to_record(row)?))
+    }
+
+    /// Loads every stored vector into an in-memory [`EmbeddingIndex`] ready
+    /// for nearest-neighbor queries. Cheap enough to call after every batch
+    /// of upserts/deletes rather than maintaining the index incrementally.
+    pub fn rebuild_index(&self) -> Result<EmbeddingIndex, DedupStoreError> {
+        let mut statement =
+            self.connection.prepare("SELECT stable_id, chunk_id, source_path, content_hash, vector FROM embeddings")?;
+        let rows: Vec<RawRow> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+            .collect::<Result<_, rusqlite::Error>>()?;
+        let entries = rows.into_iter().map(raw_row_to_record).collect::<Result<Vec<_>, _>>()?;
+        Ok(EmbeddingIndex { entries })
+    }
+}
+
+type RawRow = (String, i64, String, String, Vec<u8>);
+
+fn row_to_record(row: &rusqlite::Row) -> Result<EmbeddingRecord, DedupStoreError> {
+    let raw: RawRow = (row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?);
+    raw_row_to_record(raw)
+}
+
+fn raw_row_to_record(
+    (stable_id, chunk_id, source_path, content_hash, vector_bytes): RawRow,
+) -> Result<EmbeddingRecord, DedupStoreError> {
+    let stable_id = StableChunkId(stable_id);
+    let vector = decode_vector(&vector_bytes).ok_or_else(|| DedupStoreError::CorruptVector(stable_id.clone()))?;
+    Ok(EmbeddingRecord { stable_id, chunk_id: ChunkId(chunk_id as u64), source_path, content_hash, vector })
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Option<Vec<f32>> {
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    Some(bytes.chunks_exact(4).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap())).collect())
+}
+
+/// An in-memory snapshot of every stored embedding, rebuilt on demand via
+/// [`EmbeddingStore::rebuild_index`].
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingIndex {
+    entries: Vec<EmbeddingRecord>,
+}
+
+impl EmbeddingIndex {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns up to `k` entries ranked by cosine similarity to `query`,
+    /// most similar first.
+    pub fn nearest(&self, query: &[f32], k: usize) -> Vec<(StableChunkId, f32)> {
+        let mut scored: Vec<(StableChunkId, f32)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.stable_id.clone(), cosine_similarity(&entry.vector, query)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(k);
+        scored
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: u64, vector: Vec<f32>) -> EmbeddingRecord {
+        EmbeddingRecord {
+            stable_id: StableChunkId(format!("stable-{id}")),
+            chunk_id: ChunkId(id),
+            source_path: format!("f{id}.rs"),
+            content_hash: format!("hash{id}"),
+            vector,
+        }
+    }
+
+    fn open_temp_store(name: &str) -> EmbeddingStore {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        EmbeddingStore::open(&path).unwrap()
+    }
+
+    #[test]
+    fn upsert_then_get_round_trips_the_vector() {
+        let store = open_temp_store("transfiguration-embedding-store-roundtrip.sqlite");
+        store.upsert(&record(1, vec![0.1, 0.2, 0.3])).unwrap();
+
+        let loaded = store.get(&StableChunkId("stable-1".into())).unwrap().unwrap();
+        assert_eq!(loaded.vector, vec![0.1, 0.2, 0.3]);
+        assert_eq!(loaded.content_hash, "hash1");
+    }
+
+    #[test]
+    fn upsert_replaces_the_existing_row_for_the_same_stable_id() {
+        let store = open_temp_store("transfiguration-embedding-store-upsert.sqlite");
+        store.upsert(&record(1, vec![1.0, 0.0])).unwrap();
+        store.upsert(&EmbeddingRecord { content_hash: "hash1-v2".into(), ..record(1, vec![0.0, 1.0]) }).unwrap();
+
+        let loaded = store.get(&StableChunkId("stable-1".into())).unwrap().unwrap();
+        assert_eq!(loaded.vector, vec![0.0, 1.0]);
+        assert_eq!(loaded.content_hash, "hash1-v2");
+    }
+
+    #[test]
+    fn delete_stale_removes_only_vectors_absent_from_the_live_set() {
+        let store = open_temp_store("transfiguration-embedding-store-stale.sqlite");
+        store.upsert(&record(1, vec![1.0])).unwrap();
+        store.upsert(&record(2, vec![2.0])).unwrap();
+        store.upsert(&record(3, vec![3.0])).unwrap();
+
+        let live: HashSet<StableChunkId> =
+            [StableChunkId("stable-1".into()), StableChunkId("stable-3".into())].into_iter().collect();
+        let deleted = store.delete_stale(&live).unwrap();
+
+        assert_eq!(deleted, 1);
+        assert!(store.get(&StableChunkId("stable-2".into())).unwrap().is_none());
+        assert!(store.get(&StableChunkId("stable-1".into())).unwrap().is_some());
+    }
+
+    #[test]
+    fn rebuilt_index_finds_the_most_similar_vector() {
+        let store = open_temp_store("transfiguration-embedding-store-index.sqlite");
+        store.upsert(&record(1, vec![1.0, 0.0])).unwrap();
+        store.upsert(&record(2, vec![0.0, 1.0])).unwrap();
+        store.upsert(&record(3, vec![0.9, 0.1])).unwrap();
+
+        let index = store.rebuild_index().unwrap();
+        let nearest = index.nearest(&[1.0, 0.0], 2);
+
+        assert_eq!(nearest.len(), 2);
+        assert_eq!(nearest[0].0, StableChunkId("stable-1".into()));
+        assert_eq!(nearest[1].0, StableChunkId("stable-3".into()));
+    }
+}
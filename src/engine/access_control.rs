@@ -0,0 +1,310 @@
+//! API-key authorization, per-key quotas, and audit logging for service
+//! mode.
+//!
+//! There is no HTTP/gRPC listener in this crate yet — `src/main.rs` is
+//! still a placeholder, and [`crate::engine::http_backend`] is an *outbound*
+//! backend (calling a remote inference API), not an inbound server — so
+//! this module is the authorization layer a future server would call
+//! into before handing a request to [`super::jobs::MultiJobScheduler`] or
+//! [`super::agents::ParallelAgentSystem`], not a server itself. Likewise,
+//! mTLS needs a TLS stack this crate has no dependency on (the same
+//! "can't depend on it yet" situation [`super::ort_compat`] documents for
+//! `ort` 1.x); [`ApiKey`] is the half of "API-key or mTLS" this crate can
+//! actually implement today, with [`AccessControl::authorize`]'s `&str`
+//! key taken deliberately as an opaque credential string so a future mTLS
+//! front end can feed it the certificate's subject name instead without
+//! changing this module.
+//!
+//! [`Scope`] covers the three capabilities service mode exposes. Quotas are
+//! enforced with the same token-bucket shape [`super::http_backend::RateLimiter`]
+//! uses for outbound rate limiting, just per-key instead of per-backend.
+//! Every [`AccessControl::authorize`] call — allowed or denied — appends one
+//! [`AuditEntry`] to the in-memory log, which [`AccessControl::flush_audit_log_to`]
+//! appends to a JSONL file the same way [`crate::sinks::FileSink`] appends
+//! result records, so "who requested summaries of which paths" survives a
+//! process restart.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// One capability an [`ApiKey`] can be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    /// Submit new summarization jobs.
+    SubmitJobs,
+    /// Read back results of already-submitted jobs.
+    ReadResults,
+    /// Change pool sizing/limits (e.g. [`super::jobs::SchedulerLimits`]).
+    AdminPool,
+}
+
+/// How many requests a key may make in a rolling window, enforced as a
+/// token bucket: `max_requests` tokens, refilled continuously over
+/// `window` so a key that has been idle can burst back up to the full
+/// allowance rather than waiting out a fixed-size window boundary.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestQuota {
+    pub max_requests: u32,
+    pub window: Duration,
+}
+
+impl RequestQuota {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        RequestQuota { max_requests, window }
+    }
+}
+
+/// A token bucket sized by a [`RequestQuota`]; same refill-on-acquire shape
+/// as the internal `Bucket` in [`super::http_backend`], just tracking
+/// request counts instead of tokens-per-minute.
+struct QuotaBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl QuotaBucket {
+    fn new(quota: RequestQuota) -> Self {
+        let capacity = quota.max_requests.max(1) as f64;
+        let refill_per_sec = capacity / quota.window.as_secs_f64().max(f64::EPSILON);
+        QuotaBucket { capacity, refill_per_sec, available: capacity, last_refill: Instant::now() }
+    }
+
+    /// Tries to spend one request's worth of quota; `true` if there was
+    /// enough left, `false` (and no change) if the key is over budget.
+    fn try_acquire_one(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.available >= 1.0 {
+            self.available -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The scopes and quota an [`ApiKey`] was registered with.
+struct ApiKeyRecord {
+    scopes: std::collections::HashSet<Scope>,
+    quota: QuotaBucket,
+}
+
+pub type ApiKey = str;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AccessControlError {
+    #[error("unrecognized API key")]
+    UnknownKey,
+    #[error("API key is not granted the {0:?} scope")]
+    ScopeDenied(Scope),
+    #[error("API key has exceeded its request quota")]
+    QuotaExceeded,
+    #[error("failed to write audit log: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to serialize audit log entry: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// What [`AccessControl::authorize`] decided, recorded on every
+/// [`AuditEntry`] regardless of whether the request was allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditDecision {
+    Allowed,
+    DeniedUnknownKey,
+    DeniedScope,
+    DeniedQuota,
+}
+
+/// One audit log row: who asked, for what scope, over which path, and what
+/// was decided. `key_label` is caller-supplied at [`AccessControl::register_key`]
+/// time (e.g. a key id or owning team name) rather than the raw key itself,
+/// so an audit log exported for review never carries a live credential.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub key_label: String,
+    pub scope: Scope,
+    pub path: Option<PathBuf>,
+    pub decision: AuditDecision,
+    pub at_unix_ms: u64,
+}
+
+/// Authorizes requests against registered [`ApiKey`]s, enforces their
+/// [`RequestQuota`], and accumulates an [`AuditEntry`] per request; see the
+/// module docs for how this composes with a future HTTP/mTLS front end.
+pub struct AccessControl {
+    keys: HashMap<String, (String, ApiKeyRecord)>,
+    audit_log: Mutex<Vec<AuditEntry>>,
+    flushed: Mutex<usize>,
+}
+
+impl Default for AccessControl {
+    fn default() -> Self {
+        AccessControl { keys: HashMap::new(), audit_log: Mutex::new(Vec::new()), flushed: Mutex::new(0) }
+    }
+}
+
+impl AccessControl {
+    pub fn new() -> Self {
+        AccessControl::default()
+    }
+
+    /// Grants `key` the given `scopes` under `quota`. `label` is what shows
+    /// up in the audit log in place of the key itself — see
+    /// [`AuditEntry::key_label`].
+    pub fn register_key(&mut self, key: impl Into<String>, label: impl Into<String>, scopes: impl IntoIterator<Item = Scope>, quota: RequestQuota) {
+        self.keys.insert(key.into(), (label.into(), ApiKeyRecord { scopes: scopes.into_iter().collect(), quota: QuotaBucket::new(quota) }));
+    }
+
+    /// Checks `key` is known, is granted `scope`, and still has quota left
+    /// for one more request against `path` — in that order, so a denial
+    /// always reports the first reason a request would have failed. Every
+    /// outcome, allowed or denied, is appended to the audit log.
+    pub fn authorize(&mut self, key: &ApiKey, scope: Scope, path: Option<&Path>) -> Result<(), AccessControlError> {
+        let Some((label, record)) = self.keys.get_mut(key) else {
+            self.record_audit("<unknown>", scope, path, AuditDecision::DeniedUnknownKey);
+            return Err(AccessControlError::UnknownKey);
+        };
+
+        if !record.scopes.contains(&scope) {
+            let label = label.clone();
+            self.record_audit(&label, scope, path, AuditDecision::DeniedScope);
+            return Err(AccessControlError::ScopeDenied(scope));
+        }
+
+        if !record.quota.try_acquire_one() {
+            let label = label.clone();
+            self.record_audit(&label, scope, path, AuditDecision::DeniedQuota);
+            return Err(AccessControlError::QuotaExceeded);
+        }
+
+        let label = label.clone();
+        self.record_audit(&label, scope, path, AuditDecision::Allowed);
+        Ok(())
+    }
+
+    fn record_audit(&self, key_label: &str, scope: Scope, path: Option<&Path>, decision: AuditDecision) {
+        let at_unix_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        self.audit_log.lock().expect("audit log mutex is never poisoned").push(AuditEntry {
+            key_label: key_label.to_string(),
+            scope,
+            path: path.map(Path::to_path_buf),
+            decision,
+            at_unix_ms,
+        });
+    }
+
+    /// A snapshot of every [`AuditEntry`] recorded so far, oldest first.
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        self.audit_log.lock().expect("audit log mutex is never poisoned").clone()
+    }
+
+    /// Appends every [`AuditEntry`] recorded since the last call to this
+    /// method (or since construction) to `path` as one JSON line per
+    /// entry — the same append-one-line-per-record shape
+    /// [`crate::sinks::FileSink`] uses for results, so a long-running
+    /// service can flush periodically without re-writing lines already on
+    /// disk.
+    pub fn flush_audit_log_to(&self, path: &Path) -> Result<(), AccessControlError> {
+        use std::io::Write;
+
+        let log = self.audit_log.lock().expect("audit log mutex is never poisoned");
+        let mut flushed = self.flushed.lock().expect("flushed-offset mutex is never poisoned");
+
+        if *flushed >= log.len() {
+            return Ok(());
+        }
+
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        for entry in &log[*flushed..] {
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+        *flushed = log.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("transfiguration-access-control-{name}.jsonl"))
+    }
+
+    #[test]
+    fn an_unregistered_key_is_denied_and_audited() {
+        let mut access = AccessControl::new();
+        let result = access.authorize("nope", Scope::SubmitJobs, None);
+        assert!(matches!(result, Err(AccessControlError::UnknownKey)));
+        assert_eq!(access.audit_log()[0].decision, AuditDecision::DeniedUnknownKey);
+    }
+
+    #[test]
+    fn a_key_missing_the_requested_scope_is_denied() {
+        let mut access = AccessControl::new();
+        access.register_key("key-1", "team-a", [Scope::ReadResults], RequestQuota::new(10, Duration::from_secs(60)));
+
+        let result = access.authorize("key-1", Scope::SubmitJobs, None);
+        assert!(matches!(result, Err(AccessControlError::ScopeDenied(Scope::SubmitJobs))));
+    }
+
+    #[test]
+    fn a_key_with_the_requested_scope_and_quota_is_allowed() {
+        let mut access = AccessControl::new();
+        access.register_key("key-1", "team-a", [Scope::SubmitJobs], RequestQuota::new(10, Duration::from_secs(60)));
+
+        let result = access.authorize("key-1", Scope::SubmitJobs, Some(Path::new("src/lib.rs")));
+        assert!(result.is_ok());
+        let entry = access.audit_log().into_iter().next().unwrap();
+        assert_eq!(entry.decision, AuditDecision::Allowed);
+        assert_eq!(entry.key_label, "team-a");
+        assert_eq!(entry.path, Some(PathBuf::from("src/lib.rs")));
+    }
+
+    #[test]
+    fn exhausting_the_quota_denies_further_requests_until_it_refills() {
+        let mut access = AccessControl::new();
+        access.register_key("key-1", "team-a", [Scope::SubmitJobs], RequestQuota::new(1, Duration::from_secs(60)));
+
+        assert!(access.authorize("key-1", Scope::SubmitJobs, None).is_ok());
+        let result = access.authorize("key-1", Scope::SubmitJobs, None);
+        assert!(matches!(result, Err(AccessControlError::QuotaExceeded)));
+    }
+
+    #[test]
+    fn flush_audit_log_to_appends_only_entries_not_yet_flushed() {
+        let path = scratch_path("flush");
+        let _ = std::fs::remove_file(&path);
+
+        let mut access = AccessControl::new();
+        access.register_key("key-1", "team-a", [Scope::SubmitJobs], RequestQuota::new(10, Duration::from_secs(60)));
+
+        access.authorize("key-1", Scope::SubmitJobs, None).unwrap();
+        access.flush_audit_log_to(&path).unwrap();
+        access.authorize("key-1", Scope::SubmitJobs, None).unwrap();
+        access.flush_audit_log_to(&path).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    #[test]
+    fn flush_audit_log_to_is_a_noop_when_nothing_new_happened() {
+        let path = scratch_path("noop");
+        let _ = std::fs::remove_file(&path);
+
+        let access = AccessControl::new();
+        access.flush_audit_log_to(&path).unwrap();
+
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,222 @@
+//! Dynamically sizing how many chunks a [`crate::engine::agents::ParallelAgentSystem`]
+//! runs concurrently, instead of a fixed number picked once up front.
+//!
+//! [`ParallelAgentSystem::run_all`](crate::engine::agents::ParallelAgentSystem::run_all)
+//! spawns every session in its batch at once — fine for a cheap,
+//! memory-bounded backend, but a poor fit once per-chunk latency, process
+//! memory, or the failure rate start climbing under load. The concurrency
+//! cap it needs at that point is itself a moving target: too low wastes
+//! throughput on an otherwise-healthy backend, too high risks the OOM a
+//! fixed cap was meant to prevent in the first place.
+//! [`AdaptiveConcurrencyController`] watches those three signals after each
+//! round of work and grows the next round's concurrency while all three
+//! stay within bounds, shrinking it the moment any one doesn't — within the
+//! [`ConcurrencyBounds`] a caller configures up front, so it never scales
+//! to zero or past a known-safe ceiling.
+
+use std::time::Duration;
+
+/// Hard floor and ceiling an [`AdaptiveConcurrencyController`] never scales
+/// outside of, regardless of how healthy or unhealthy recent rounds looked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyBounds {
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+}
+
+/// The thresholds a round's [`RoundObservation`] is checked against, and how
+/// aggressively to grow or shrink when it crosses one.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveConcurrencyConfig {
+    pub bounds: ConcurrencyBounds,
+    /// A round whose p95 chunk latency exceeds this is treated as
+    /// overloaded. `None` (from [`crate::metrics::streaming::LatencyStatsSnapshot::p95`]
+    /// needing at least a couple of samples) never counts as overloaded on
+    /// its own — there's nothing to compare yet.
+    pub max_p95_latency: Duration,
+    /// A round whose process RSS (see [`current_rss_bytes`]) exceeds this is
+    /// treated as overloaded.
+    pub max_rss_bytes: u64,
+    /// A round whose `failures / (failures + successes)` exceeds this is
+    /// treated as overloaded. `0.0` for an empty round never counts.
+    pub max_failure_rate: f64,
+    /// How many agents to add or remove per adjustment.
+    pub step: usize,
+}
+
+/// What one round of work looked like, for [`AdaptiveConcurrencyController::observe`]
+/// to judge against its [`AdaptiveConcurrencyConfig`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RoundObservation {
+    pub p95_latency: Option<Duration>,
+    pub rss_bytes: u64,
+    pub successes: u64,
+    pub failures: u64,
+}
+
+impl RoundObservation {
+    fn failure_rate(&self) -> f64 {
+        let total = self.successes + self.failures;
+        if total == 0 {
+            0.0
+        } else {
+            self.failures as f64 / total as f64
+        }
+    }
+}
+
+/// Tracks the current concurrency level and moves it up or down one
+/// [`AdaptiveConcurrencyConfig::step`] at a time as rounds get observed.
+pub struct AdaptiveConcurrencyController {
+    config: AdaptiveConcurrencyConfig,
+    current: usize,
+}
+
+impl AdaptiveConcurrencyController {
+    /// Clamps `initial_concurrency` into `config.bounds` rather than
+    /// panicking or trusting a caller-supplied value that's out of range.
+    pub fn new(config: AdaptiveConcurrencyConfig, initial_concurrency: usize) -> Self {
+        let current = initial_concurrency.clamp(config.bounds.min_concurrency, config.bounds.max_concurrency);
+        AdaptiveConcurrencyController { config, current }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Folds one round's observation in and returns the concurrency the
+    /// next round should run at. Any one signal over its configured bound
+    /// shrinks by `step`; a fully healthy round grows by `step`; both clamp
+    /// to [`AdaptiveConcurrencyConfig::bounds`].
+    pub fn observe(&mut self, observation: &RoundObservation) -> usize {
+        let over_latency = observation.p95_latency.is_some_and(|p95| p95 > self.config.max_p95_latency);
+        let over_memory = observation.rss_bytes > self.config.max_rss_bytes;
+        let over_failure_rate = observation.failure_rate() > self.config.max_failure_rate;
+
+        self.current = if over_latency || over_memory || over_failure_rate {
+            self.current.saturating_sub(self.config.step).max(self.config.bounds.min_concurrency)
+        } else {
+            (self.current + self.config.step).min(self.config.bounds.max_concurrency)
+        };
+        self.current
+    }
+}
+
+/// This process's current resident set size, in bytes — re-exported from
+/// [`crate::memory`] so existing callers of this module don't need to
+/// change their import. `0` (rather than a `Result`) on a platform or read
+/// failure [`crate::memory::current_rss_bytes`] can't handle: a concurrency
+/// controller that can't read memory pressure should degrade to ignoring
+/// that one signal rather than aborting the run over it.
+pub use crate::memory::current_rss_bytes;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AdaptiveConcurrencyConfig {
+        AdaptiveConcurrencyConfig {
+            bounds: ConcurrencyBounds {
+                min_concurrency: 2,
+                max_concurrency: 20,
+            },
+            max_p95_latency: Duration::from_secs(1),
+            max_rss_bytes: 1_000_000_000,
+            max_failure_rate: 0.1,
+            step: 4,
+        }
+    }
+
+    #[test]
+    fn a_healthy_round_grows_concurrency_by_one_step() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 10);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_millis(100)),
+            rss_bytes: 500_000_000,
+            successes: 10,
+            failures: 0,
+        });
+        assert_eq!(next, 14);
+    }
+
+    #[test]
+    fn an_overloaded_round_shrinks_concurrency_by_one_step() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 10);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_secs(5)),
+            rss_bytes: 500_000_000,
+            successes: 10,
+            failures: 0,
+        });
+        assert_eq!(next, 6);
+    }
+
+    #[test]
+    fn excess_memory_shrinks_concurrency_even_with_otherwise_healthy_signals() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 10);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_millis(1)),
+            rss_bytes: 2_000_000_000,
+            successes: 10,
+            failures: 0,
+        });
+        assert_eq!(next, 6);
+    }
+
+    #[test]
+    fn an_excess_failure_rate_shrinks_concurrency() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 10);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_millis(1)),
+            rss_bytes: 1,
+            successes: 8,
+            failures: 2,
+        });
+        assert_eq!(next, 6);
+    }
+
+    #[test]
+    fn concurrency_never_grows_past_the_configured_maximum() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 19);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_millis(1)),
+            rss_bytes: 1,
+            successes: 1,
+            failures: 0,
+        });
+        assert_eq!(next, 20);
+    }
+
+    #[test]
+    fn concurrency_never_shrinks_below_the_configured_minimum() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 3);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: Some(Duration::from_secs(10)),
+            rss_bytes: 1,
+            successes: 1,
+            failures: 0,
+        });
+        assert_eq!(next, 2);
+    }
+
+    #[test]
+    fn a_round_with_no_chunks_has_a_zero_failure_rate() {
+        let mut controller = AdaptiveConcurrencyController::new(config(), 10);
+        let next = controller.observe(&RoundObservation {
+            p95_latency: None,
+            rss_bytes: 1,
+            successes: 0,
+            failures: 0,
+        });
+        assert_eq!(next, 14);
+    }
+
+    #[test]
+    fn initial_concurrency_outside_the_bounds_is_clamped() {
+        let controller = AdaptiveConcurrencyController::new(config(), 1000);
+        assert_eq!(controller.current(), 20);
+
+        let controller = AdaptiveConcurrencyController::new(config(), 0);
+        assert_eq!(controller.current(), 2);
+    }
+}
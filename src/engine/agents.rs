@@ -0,0 +1,985 @@
+//! Structured-concurrency fan-out of many summarization sessions over a
+//! single [`tokio::task::JoinSet`].
+//!
+//! Sessions used to be spawned ad hoc with `tokio::spawn`, which left
+//! shutdown and error propagation up to whoever held the `JoinHandle`s (or
+//! didn't). [`ParallelAgentSystem`] owns every session it spawns in one
+//! `JoinSet`, so [`ParallelAgentSystem::run_all`] cannot return until every
+//! spawned task has been joined or aborted — no orphaned task keeps running
+//! past the call that spawned it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::task::JoinSet;
+
+use crate::chunk::{Chunk, ChunkId};
+use crate::engine::adaptive_concurrency::{current_rss_bytes, AdaptiveConcurrencyConfig, AdaptiveConcurrencyController, RoundObservation};
+use crate::engine::batching::BatchInferenceBackend;
+use crate::engine::checkpoint::{Checkpoint, CheckpointError};
+use crate::engine::retry_budget::RetryBudget;
+use crate::engine::{EngineError, InferenceBackend};
+use crate::metrics::ParallelMetrics;
+use crate::model::context::estimate_token_count;
+
+/// How a [`ParallelAgentSystem`] run reacts to a session failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Abort every still-running session as soon as one fails.
+    FailFast,
+    /// Let every session run to completion and report every failure at the
+    /// end.
+    CollectAll,
+}
+
+/// One chunk's worth of work for a session to summarize.
+///
+/// `Clone` so [`ParallelAgentSystem::run_all_with_retry_budget`] can hold
+/// onto a session across a failed attempt and respawn it for a retry
+/// without the original session having been consumed by the first attempt.
+#[derive(Clone)]
+pub struct AgentSession {
+    pub chunk: Chunk,
+}
+
+/// What came out of [`ParallelAgentSystem::run_all`]: every chunk that
+/// produced a summary, and every chunk that didn't. Under
+/// [`ErrorPolicy::FailFast`], `summaries` only holds sessions that finished
+/// before the first failure aborted the rest.
+#[derive(Debug, Default)]
+pub struct RunOutcome {
+    pub summaries: Vec<(ChunkId, String)>,
+    pub errors: Vec<(ChunkId, EngineError)>,
+}
+
+impl RunOutcome {
+    /// Groups `errors` by [`EngineError::category`] and resolves each failed
+    /// chunk's `source_path` from `chunks`. A failure whose chunk isn't in
+    /// `chunks` gets `source_path: None` rather than being dropped.
+    pub fn failure_report(&self, chunks: &[Chunk]) -> FailureReport {
+        let source_paths: HashMap<ChunkId, &Path> =
+            chunks.iter().map(|chunk| (chunk.id, chunk.source_path.as_path())).collect();
+
+        let mut report = FailureReport::default();
+        for (chunk_id, error) in &self.errors {
+            report.groups.entry(error.category()).or_default().push(FailedChunk {
+                chunk_id: *chunk_id,
+                source_path: source_paths.get(chunk_id).map(|path| path.to_path_buf()),
+                error_chain: error_chain(error),
+            });
+        }
+        report
+    }
+}
+
+/// One chunk a [`ParallelAgentSystem::run_all`] run failed to summarize,
+/// with enough context to show why without grepping logs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedChunk {
+    pub chunk_id: ChunkId,
+    /// `None` when [`RunOutcome::failure_report`] wasn't passed the chunk
+    /// this failure came from — it can only resolve a source path for
+    /// chunks present in the slice it was given.
+    pub source_path: Option<PathBuf>,
+    /// The failing [`EngineError`]'s message, followed by its
+    /// [`std::error::Error::source`] chain. [`ParallelAgentSystem::run_all`]
+    /// never retries a failed chunk, so for a report built from its
+    /// [`RunOutcome`] this is simply the one attempt's full error chain.
+    /// [`ParallelAgentSystem::run_all_with_retry_budget`] may have retried
+    /// the chunk first; in that case this is the *last* attempt's chain,
+    /// not every attempt's.
+    pub error_chain: Vec<String>,
+}
+
+/// Every failed chunk from a run, grouped by [`EngineError::category`].
+///
+/// This crate has no "agent id" distinct from [`ChunkId`]: every session
+/// [`ParallelAgentSystem::run_all`] spawns *is* one chunk's worth of work,
+/// so the chunk id already identifies which agent failed. Likewise, a
+/// [`Chunk`] only carries its `source_path`, not the line span it was built
+/// from, so a failure links to its file rather than a file/line range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FailureReport {
+    pub groups: BTreeMap<&'static str, Vec<FailedChunk>>,
+}
+
+impl FailureReport {
+    /// Up to `n` categories with the most failed chunks, most frequent
+    /// first; ties broken alphabetically by category name so the same set
+    /// of failures always produces the same ordering regardless of
+    /// discovery order. No runner in this module calls it:
+    /// [`ParallelAgentSystem::run_all_with_retry_budget`] returns its
+    /// failures in [`RetryRunOutcome::outcome`] with no category summary,
+    /// so a caller builds one from [`RunOutcome::failure_report`] and this
+    /// method.
+    pub fn top_categories(&self, n: usize) -> Vec<(&'static str, usize)> {
+        let mut counts: Vec<(&'static str, usize)> = self.groups.iter().map(|(category, chunks)| (*category, chunks.len())).collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        counts.truncate(n);
+        counts
+    }
+}
+
+fn error_chain(error: &EngineError) -> Vec<String> {
+    let mut chain = vec![error.to_string()];
+    let mut current: &dyn std::error::Error = error;
+    while let Some(source) = current.source() {
+        chain.push(source.to_string());
+        current = source;
+    }
+    chain
+}
+
+pub(crate) struct ActiveAgentGuard(Arc<ParallelMetrics>);
+
+impl Drop for ActiveAgentGuard {
+    fn drop(&mut self) {
+        self.0.agent_finished();
+    }
+}
+
+impl ActiveAgentGuard {
+    /// Must be constructed *before* the `async move` block it guards (and
+    /// moved in as a captured variable), never by a `let` statement inside
+    /// the block's own body. An `async move { ... ActiveAgentGuard::new(...) ... }`
+    /// only runs its body's statements once polled — `abort_all()` aborting
+    /// a task before its first poll drops the whole `Future` without ever
+    /// reaching a guard constructed that way, permanently leaking
+    /// `active_agents`. Capturing an already-constructed guard is evaluated
+    /// eagerly, at the point the `async move` block itself is written, so
+    /// it's part of the `Future`'s state from the moment it's spawned and
+    /// still drops correctly even if the task is cancelled pre-poll.
+    pub(crate) fn new(metrics: Arc<ParallelMetrics>) -> Self {
+        metrics.agent_started();
+        ActiveAgentGuard(metrics)
+    }
+}
+
+/// Owns a batch of concurrent summarization sessions. Every session spawned
+/// by [`ParallelAgentSystem::run_all`] lives in the `JoinSet` that call
+/// alone owns, giving the whole batch a single, scoped lifetime.
+pub struct ParallelAgentSystem<B: InferenceBackend + Send + Sync + 'static> {
+    backend: Arc<B>,
+    metrics: Arc<ParallelMetrics>,
+    error_policy: ErrorPolicy,
+}
+
+impl<B: InferenceBackend + Send + Sync + 'static> ParallelAgentSystem<B> {
+    pub fn new(backend: Arc<B>, error_policy: ErrorPolicy) -> Self {
+        ParallelAgentSystem {
+            backend,
+            metrics: Arc::new(ParallelMetrics::default()),
+            error_policy,
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<ParallelMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    fn spawn_all(&self, sessions: Vec<AgentSession>) -> JoinSet<(ChunkId, Result<String, EngineError>)> {
+        let mut join_set = JoinSet::new();
+
+        for session in sessions {
+            let backend = Arc::clone(&self.backend);
+            let metrics = Arc::clone(&self.metrics);
+            let chunk_id = session.chunk.id;
+            let prompt = session.chunk.content;
+
+            // Constructed (and `agent_started()` called) before the
+            // `async move` block, not inside it — see `ActiveAgentGuard::new`
+            // for why that ordering is what keeps `active_agents` accurate
+            // under `abort_all()`.
+            let active_guard = ActiveAgentGuard::new(Arc::clone(&metrics));
+            join_set.spawn(async move {
+                let _active_guard = active_guard;
+
+                let started_at = std::time::Instant::now();
+                let result = backend.generate_completion_text(&prompt);
+                match &result {
+                    Ok(text) => {
+                        metrics.record_chunk_completed(started_at.elapsed());
+                        metrics.record_tokens_generated(estimate_token_count(text) as u64);
+                    }
+                    Err(_) => metrics.record_chunk_failed(),
+                }
+                (chunk_id, result)
+            });
+        }
+
+        join_set
+    }
+
+    /// Runs every session concurrently. Under [`ErrorPolicy::FailFast`], the
+    /// first failure aborts every session still in flight and `run_all`
+    /// returns as soon as every task (finished, failed, or aborted) has been
+    /// joined — never before. Under [`ErrorPolicy::CollectAll`], every
+    /// session always runs to completion.
+    pub async fn run_all(&self, sessions: Vec<AgentSession>) -> RunOutcome {
+        let mut join_set = self.spawn_all(sessions);
+        let mut outcome = RunOutcome::default();
+        let mut abort_remaining = false;
+
+        while let Some(joined) = join_set.join_next().await {
+            if abort_remaining {
+                continue;
+            }
+            match joined {
+                Ok((chunk_id, Ok(text))) => outcome.summaries.push((chunk_id, text)),
+                Ok((chunk_id, Err(error))) => {
+                    outcome.errors.push((chunk_id, error));
+                    if self.error_policy == ErrorPolicy::FailFast {
+                        join_set.abort_all();
+                        abort_remaining = true;
+                    }
+                }
+                Err(join_error) if join_error.is_cancelled() => {
+                    // Our own abort_all(); not a new failure to report.
+                }
+                Err(join_error) => {
+                    outcome
+                        .errors
+                        .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                    if self.error_policy == ErrorPolicy::FailFast {
+                        join_set.abort_all();
+                        abort_remaining = true;
+                    }
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// Like [`ParallelAgentSystem::run_all`], but rewrites a
+    /// [`Checkpoint`] at `checkpoint_path` every time a chunk finishes
+    /// successfully, so a crash partway through this call leaves behind
+    /// exactly which chunks are already summarized and which are still
+    /// pending. Doesn't itself consult an existing checkpoint to skip
+    /// work that's already done — pair this with
+    /// [`ParallelAgentSystem::resume_from_checkpoint`], which splits a
+    /// session list into what a prior checkpointed run already finished
+    /// and what still needs to be passed in here.
+    ///
+    /// A session that fails stays in [`Checkpoint::pending`] rather than
+    /// being dropped, so resuming retries it instead of silently losing
+    /// it.
+    pub async fn run_all_checkpointed(&self, sessions: Vec<AgentSession>, checkpoint_path: &Path) -> Result<RunOutcome, CheckpointError> {
+        let mut checkpoint = Checkpoint {
+            completed: BTreeMap::new(),
+            pending: sessions.iter().map(|session| session.chunk.id).collect(),
+        };
+        checkpoint.save_to_file(checkpoint_path)?;
+
+        let mut join_set = self.spawn_all(sessions);
+        let mut outcome = RunOutcome::default();
+        let mut abort_remaining = false;
+
+        while let Some(joined) = join_set.join_next().await {
+            if abort_remaining {
+                continue;
+            }
+            match joined {
+                Ok((chunk_id, Ok(text))) => {
+                    outcome.summaries.push((chunk_id, text.clone()));
+                    checkpoint.completed.insert(chunk_id, text);
+                    checkpoint.pending.retain(|pending_id| *pending_id != chunk_id);
+                    checkpoint.save_to_file(checkpoint_path)?;
+                }
+                Ok((chunk_id, Err(error))) => {
+                    outcome.errors.push((chunk_id, error));
+                    if self.error_policy == ErrorPolicy::FailFast {
+                        join_set.abort_all();
+                        abort_remaining = true;
+                    }
+                }
+                Err(join_error) if join_error.is_cancelled() => {
+                    // Our own abort_all(); not a new failure to report.
+                }
+                Err(join_error) => {
+                    outcome
+                        .errors
+                        .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                    if self.error_policy == ErrorPolicy::FailFast {
+                        join_set.abort_all();
+                        abort_remaining = true;
+                    }
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Loads the [`Checkpoint`] at `checkpoint_path` (a missing file
+    /// means no run has ever checkpointed here — nothing to resume),
+    /// splitting `all_sessions` into the ones it already has a summary
+    /// for (folded into the returned [`RunOutcome`]) and the ones still
+    /// pending (returned for the caller to pass to
+    /// [`ParallelAgentSystem::run_all_checkpointed`]).
+    pub fn resume_from_checkpoint(checkpoint_path: &Path, all_sessions: Vec<AgentSession>) -> Result<(Vec<AgentSession>, RunOutcome), CheckpointError> {
+        let checkpoint = Checkpoint::load_from_file_or_default(checkpoint_path)?;
+        let mut outcome = RunOutcome::default();
+        let mut remaining = Vec::with_capacity(all_sessions.len());
+
+        for session in all_sessions {
+            match checkpoint.completed.get(&session.chunk.id) {
+                Some(summary) => outcome.summaries.push((session.chunk.id, summary.clone())),
+                None => remaining.push(session),
+            }
+        }
+
+        Ok((remaining, outcome))
+    }
+
+    /// Like [`ParallelAgentSystem::run_all`], but runs `sessions` in
+    /// successive rounds sized by an [`AdaptiveConcurrencyController`]
+    /// instead of spawning everything at once. After each round, the run's
+    /// p95 chunk latency so far (the metrics' latency statistics cover every
+    /// chunk since they were created, not only this round), the round's
+    /// failure counts (from [`ParallelAgentSystem::metrics`]) and the
+    /// process's current RSS
+    /// (from [`current_rss_bytes`]) are fed into the controller, which
+    /// grows the next round's size while all three stay healthy and
+    /// shrinks it the moment any one doesn't, within the bounds `config`
+    /// sets.
+    ///
+    /// Like [`ParallelAgentSystem::run_all_batched`], this always behaves
+    /// like [`ErrorPolicy::CollectAll`]: a round has to finish in full for
+    /// the controller to judge it, so there's no "abort everything still in
+    /// flight" to fail fast into.
+    pub async fn run_all_adaptive(&self, sessions: Vec<AgentSession>, config: AdaptiveConcurrencyConfig, initial_concurrency: usize) -> RunOutcome {
+        let mut controller = AdaptiveConcurrencyController::new(config, initial_concurrency);
+        let mut remaining = sessions;
+        let mut outcome = RunOutcome::default();
+
+        while !remaining.is_empty() {
+            let round_size = controller.current().max(1).min(remaining.len());
+            let round: Vec<AgentSession> = remaining.drain(..round_size).collect();
+
+            let before = self.metrics.snapshot();
+            let mut join_set = self.spawn_all(round);
+
+            while let Some(joined) = join_set.join_next().await {
+                match joined {
+                    Ok((chunk_id, Ok(text))) => outcome.summaries.push((chunk_id, text)),
+                    Ok((chunk_id, Err(error))) => outcome.errors.push((chunk_id, error)),
+                    Err(join_error) => {
+                        outcome
+                            .errors
+                            .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                    }
+                }
+            }
+
+            let after = self.metrics.snapshot();
+            controller.observe(&RoundObservation {
+                p95_latency: after.latency_stats.p95,
+                rss_bytes: current_rss_bytes(),
+                successes: after.chunks_completed.saturating_sub(before.chunks_completed),
+                failures: after.chunks_failed.saturating_sub(before.chunks_failed),
+            });
+        }
+
+        outcome
+    }
+
+    /// Like [`ParallelAgentSystem::run_all`], but retries a failed session
+    /// instead of reporting it immediately, as long as a shared
+    /// [`RetryBudget`] (sized as `retry_fraction` of `sessions.len()`) still
+    /// has room. Once the budget is exhausted, every further failure is
+    /// fast-failed — reported right away with no further retry — so a
+    /// systematically failing backend can't turn this into an unbounded
+    /// number of repeated, doomed attempts.
+    ///
+    /// This always behaves like [`ErrorPolicy::CollectAll`] regardless of
+    /// `self`'s configured policy: deciding who gets a retry requires
+    /// seeing every failure from a round before moving on, which
+    /// [`ErrorPolicy::FailFast`]'s abort-on-first-failure doesn't allow.
+    pub async fn run_all_with_retry_budget(&self, sessions: Vec<AgentSession>, retry_fraction: f64) -> RetryRunOutcome {
+        let budget = RetryBudget::from_fraction(sessions.len(), retry_fraction);
+        let mut retryable: HashMap<ChunkId, AgentSession> = sessions.into_iter().map(|session| (session.chunk.id, session)).collect();
+        let mut outcome = RunOutcome::default();
+        let mut fast_failed = Vec::new();
+
+        loop {
+            let batch: Vec<AgentSession> = retryable.values().cloned().collect();
+            if batch.is_empty() {
+                break;
+            }
+
+            let mut join_set = self.spawn_all(batch);
+            let mut round_errors: HashMap<ChunkId, EngineError> = HashMap::new();
+
+            while let Some(joined) = join_set.join_next().await {
+                match joined {
+                    Ok((chunk_id, Ok(text))) => {
+                        outcome.summaries.push((chunk_id, text));
+                        retryable.remove(&chunk_id);
+                    }
+                    Ok((chunk_id, Err(error))) => {
+                        round_errors.insert(chunk_id, error);
+                    }
+                    Err(join_error) => {
+                        // No chunk id to retry against; report it directly
+                        // rather than feeding it into the retry loop.
+                        outcome
+                            .errors
+                            .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                    }
+                }
+            }
+
+            if round_errors.is_empty() {
+                break;
+            }
+
+            let mut any_retry_scheduled = false;
+            for (chunk_id, error) in round_errors {
+                if budget.try_consume() {
+                    any_retry_scheduled = true;
+                    // Stays in `retryable`; respawned next round.
+                } else {
+                    retryable.remove(&chunk_id);
+                    fast_failed.push(chunk_id);
+                    outcome.errors.push((chunk_id, error));
+                }
+            }
+
+            if !any_retry_scheduled {
+                break;
+            }
+        }
+
+        RetryRunOutcome {
+            outcome,
+            retries_used: budget.used(),
+            retry_budget: budget.max_retries(),
+            fast_failed,
+        }
+    }
+
+    /// Like [`ParallelAgentSystem::run_all`], but retries each session
+    /// in place against `policy` (see [`RetryPolicy::should_retry`] and
+    /// [`RetryPolicy::backoff_for_attempt`]) rather than reporting its
+    /// first failure immediately.
+    ///
+    /// Unlike [`ParallelAgentSystem::run_all_with_retry_budget`], retries
+    /// here are purely per-chunk — there's no shared budget capping the
+    /// run's total retry count, only `policy.max_attempts` per chunk — and
+    /// the backoff between attempts happens inside each chunk's own task,
+    /// not between synchronized rounds, so a slow-to-recover chunk never
+    /// holds up chunks that already succeeded.
+    pub async fn run_all_with_retry_policy(&self, sessions: Vec<AgentSession>, policy: crate::engine::retry_policy::RetryPolicy) -> RetryPolicyOutcome {
+        let mut join_set = JoinSet::new();
+
+        for session in sessions {
+            let backend = Arc::clone(&self.backend);
+            let metrics = Arc::clone(&self.metrics);
+            let policy = policy.clone();
+            let chunk_id = session.chunk.id;
+            let prompt = session.chunk.content;
+
+            let active_guard = ActiveAgentGuard::new(Arc::clone(&metrics));
+            join_set.spawn(async move {
+                let _active_guard = active_guard;
+
+                let mut attempt: u32 = 1;
+                loop {
+                    let started_at = std::time::Instant::now();
+                    let result = backend.generate_completion_text(&prompt);
+                    match result {
+                        Ok(text) => {
+                            metrics.record_chunk_completed(started_at.elapsed());
+                            metrics.record_tokens_generated(estimate_token_count(&text) as u64);
+                            return (chunk_id, Ok(text), attempt);
+                        }
+                        Err(error) => {
+                            if !policy.should_retry(attempt, &error) {
+                                metrics.record_chunk_failed();
+                                return (chunk_id, Err(error), attempt);
+                            }
+                            let backoff = policy.backoff_for_attempt(attempt, chunk_id.0 ^ attempt as u64);
+                            tokio::time::sleep(backoff).await;
+                            attempt += 1;
+                        }
+                    }
+                }
+            });
+        }
+
+        let mut outcome = RunOutcome::default();
+        let mut attempts = HashMap::new();
+
+        while let Some(joined) = join_set.join_next().await {
+            match joined {
+                Ok((chunk_id, Ok(text), used_attempts)) => {
+                    outcome.summaries.push((chunk_id, text));
+                    attempts.insert(chunk_id, used_attempts);
+                }
+                Ok((chunk_id, Err(error), used_attempts)) => {
+                    outcome.errors.push((chunk_id, error));
+                    attempts.insert(chunk_id, used_attempts);
+                }
+                Err(join_error) => {
+                    outcome
+                        .errors
+                        .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                }
+            }
+        }
+
+        RetryPolicyOutcome { outcome, attempts }
+    }
+}
+
+/// Result of [`ParallelAgentSystem::run_all_with_retry_policy`]: the usual
+/// [`RunOutcome`], plus how many attempts each chunk actually took.
+#[derive(Debug)]
+pub struct RetryPolicyOutcome {
+    pub outcome: RunOutcome,
+    pub attempts: HashMap<ChunkId, u32>,
+}
+
+impl<B: InferenceBackend + BatchInferenceBackend + Send + Sync + 'static> ParallelAgentSystem<B> {
+    /// Like [`ParallelAgentSystem::run_all`], but groups `sessions` into
+    /// batches of at most `max_batch_size` and dispatches one
+    /// [`BatchInferenceBackend::generate_completion_batch`] call per group
+    /// instead of one [`InferenceBackend::generate_completion_text`] call
+    /// per chunk — for a backend that runs faster per-prompt in a batch
+    /// than one at a time, per [`crate::engine::batching`]'s module docs.
+    ///
+    /// Groups run concurrently with each other in the same `JoinSet`
+    /// [`ParallelAgentSystem::run_all`] uses, but always behave like
+    /// [`ErrorPolicy::CollectAll`] regardless of `self`'s configured
+    /// policy: a failing batch call fails every chunk in that group
+    /// together (a [`BatchInferenceBackend`] has no way to report a
+    /// per-prompt result out of an otherwise-failed batch), so there's no
+    /// single "first failure" to fail fast on.
+    pub async fn run_all_batched(&self, sessions: Vec<AgentSession>, max_batch_size: usize) -> RunOutcome {
+        let max_batch_size = max_batch_size.max(1);
+        let mut remaining = sessions;
+        let mut join_set = JoinSet::new();
+
+        while !remaining.is_empty() {
+            let split_at = max_batch_size.min(remaining.len());
+            let group: Vec<AgentSession> = remaining.drain(..split_at).collect();
+
+            let backend = Arc::clone(&self.backend);
+            let metrics = Arc::clone(&self.metrics);
+            let chunk_ids: Vec<ChunkId> = group.iter().map(|session| session.chunk.id).collect();
+            let prompts: Vec<String> = group.into_iter().map(|session| session.chunk.content).collect();
+
+            let active_guards: Vec<ActiveAgentGuard> =
+                chunk_ids.iter().map(|_| ActiveAgentGuard::new(Arc::clone(&metrics))).collect();
+
+            join_set.spawn(async move {
+                let _active_guards = active_guards;
+
+                let started_at = std::time::Instant::now();
+                let result = backend.generate_completion_batch(&prompts);
+                match &result {
+                    Ok(texts) => {
+                        for text in texts {
+                            metrics.record_chunk_completed(started_at.elapsed());
+                            metrics.record_tokens_generated(estimate_token_count(text) as u64);
+                        }
+                    }
+                    Err(_) => {
+                        for _ in &chunk_ids {
+                            metrics.record_chunk_failed();
+                        }
+                    }
+                }
+                (chunk_ids, result)
+            });
+        }
+
+        let mut outcome = RunOutcome::default();
+        while let Some(joined) = join_set.join_next().await {
+            match joined {
+                Ok((chunk_ids, Ok(texts))) => {
+                    for (chunk_id, text) in chunk_ids.into_iter().zip(texts) {
+                        outcome.summaries.push((chunk_id, text));
+                    }
+                }
+                Ok((chunk_ids, Err(error))) => {
+                    let message = error.to_string();
+                    for chunk_id in chunk_ids {
+                        outcome.errors.push((chunk_id, EngineError::BackendFailed(message.clone())));
+                    }
+                }
+                Err(join_error) => {
+                    outcome
+                        .errors
+                        .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                }
+            }
+        }
+
+        outcome
+    }
+}
+
+/// What came out of [`ParallelAgentSystem::run_all_with_retry_budget`]: the
+/// usual [`RunOutcome`], plus how much of the shared [`RetryBudget`] the run
+/// actually spent and which chunks were given up on once that budget ran
+/// out rather than because they never failed.
+#[derive(Debug)]
+pub struct RetryRunOutcome {
+    pub outcome: RunOutcome,
+    pub retries_used: usize,
+    pub retry_budget: usize,
+    pub fast_failed: Vec<ChunkId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::Chunk;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingBackend {
+        calls: AtomicUsize,
+        fail_on: Option<&'static str>,
+    }
+
+    impl InferenceBackend for CountingBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail_on == Some(prompt) {
+                return Err(EngineError::BackendFailed(format!("refused: {prompt}")));
+            }
+            Ok(format!("summary of {prompt}"))
+        }
+    }
+
+    fn session(id: u64, content: &str) -> AgentSession {
+        AgentSession {
+            chunk: Chunk::new(ChunkId(id), "f.rs", content),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn collect_all_runs_every_session_despite_a_failure() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: Some("bad"),
+        });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        let outcome = system
+            .run_all(vec![session(1, "good-a"), session(2, "bad"), session(3, "good-b")])
+            .await;
+
+        assert_eq!(outcome.summaries.len(), 2);
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(system.metrics().snapshot().active_agents, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn fail_fast_stops_reporting_once_a_session_fails() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: Some("bad"),
+        });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::FailFast);
+
+        let outcome = system
+            .run_all(vec![session(1, "bad"), session(2, "good-a"), session(3, "good-b")])
+            .await;
+
+        assert_eq!(outcome.errors.len(), 1);
+        assert!(outcome.summaries.len() <= 2);
+        // run_all only returns once every spawned task has been joined or
+        // aborted, so the metrics it leaves behind are always consistent.
+        assert_eq!(system.metrics().snapshot().active_agents, 0);
+    }
+
+    fn checkpoint_scratch_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("transfiguration-agents-checkpoint-{name}.json"))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_checkpointed_records_every_completed_chunk_as_pending_clears() {
+        let path = checkpoint_scratch_path("completes");
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: None });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        let outcome = system
+            .run_all_checkpointed(vec![session(1, "a"), session(2, "b")], &path)
+            .await
+            .unwrap();
+        assert_eq!(outcome.summaries.len(), 2);
+
+        let checkpoint = Checkpoint::load_from_file(&path).unwrap();
+        assert_eq!(checkpoint.completed.len(), 2);
+        assert!(checkpoint.pending.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_checkpointed_leaves_a_failed_chunk_pending() {
+        let path = checkpoint_scratch_path("failure");
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: Some("bad") });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        system.run_all_checkpointed(vec![session(1, "bad")], &path).await.unwrap();
+
+        let checkpoint = Checkpoint::load_from_file(&path).unwrap();
+        assert!(checkpoint.completed.is_empty());
+        assert_eq!(checkpoint.pending, vec![ChunkId(1)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn resume_from_checkpoint_skips_already_completed_chunks() {
+        let path = checkpoint_scratch_path("resume");
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: None });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        system.run_all_checkpointed(vec![session(1, "a"), session(2, "b")], &path).await.unwrap();
+
+        let all_sessions = vec![session(1, "a"), session(2, "b"), session(3, "c")];
+        let (remaining, partial_outcome) = ParallelAgentSystem::<CountingBackend>::resume_from_checkpoint(&path, all_sessions).unwrap();
+
+        assert_eq!(partial_outcome.summaries.len(), 2);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].chunk.id, ChunkId(3));
+    }
+
+    #[test]
+    fn resume_from_checkpoint_with_no_existing_file_returns_every_session_unchanged() {
+        let path = checkpoint_scratch_path("never-run");
+        let _ = std::fs::remove_file(&path);
+
+        let all_sessions = vec![session(1, "a"), session(2, "b")];
+        let (remaining, partial_outcome) = ParallelAgentSystem::<CountingBackend>::resume_from_checkpoint(&path, all_sessions).unwrap();
+
+        assert!(partial_outcome.summaries.is_empty());
+        assert_eq!(remaining.len(), 2);
+    }
+
+    struct FlakyBackend {
+        attempts_for_bad: AtomicUsize,
+        fail_attempts: usize,
+    }
+
+    impl InferenceBackend for FlakyBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            if prompt == "bad" {
+                let attempt = self.attempts_for_bad.fetch_add(1, Ordering::SeqCst);
+                if attempt < self.fail_attempts {
+                    return Err(EngineError::BackendFailed("flaky".to_string()));
+                }
+            }
+            Ok(format!("summary of {prompt}"))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_with_retry_budget_retries_a_failure_until_it_succeeds() {
+        let backend = Arc::new(FlakyBackend {
+            attempts_for_bad: AtomicUsize::new(0),
+            fail_attempts: 1,
+        });
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let result = system.run_all_with_retry_budget(vec![session(1, "bad")], 1.0).await;
+
+        assert_eq!(result.outcome.summaries.len(), 1);
+        assert!(result.outcome.errors.is_empty());
+        assert!(result.fast_failed.is_empty());
+        assert_eq!(result.retries_used, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_with_retry_budget_fast_fails_once_the_budget_is_exhausted() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: Some("bad"),
+        });
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let sessions = vec![session(1, "bad"), session(2, "bad"), session(3, "bad"), session(4, "bad")];
+        let result = system.run_all_with_retry_budget(sessions, 0.25).await;
+
+        assert_eq!(result.retry_budget, 1);
+        assert_eq!(result.retries_used, 1);
+        assert_eq!(result.fast_failed.len(), 4);
+        assert_eq!(result.outcome.errors.len(), 4);
+        assert!(result.outcome.summaries.is_empty());
+    }
+
+    struct CountingBatchBackend {
+        batch_calls: AtomicUsize,
+        fail_on_batch_containing: Option<&'static str>,
+    }
+
+    impl InferenceBackend for CountingBatchBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("summary of {prompt}"))
+        }
+    }
+
+    impl BatchInferenceBackend for CountingBatchBackend {
+        fn generate_completion_batch(&self, prompts: &[String]) -> Result<Vec<String>, EngineError> {
+            self.batch_calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(needle) = self.fail_on_batch_containing {
+                if prompts.iter().any(|prompt| prompt == needle) {
+                    return Err(EngineError::BackendFailed(format!("batch refused: {needle}")));
+                }
+            }
+            Ok(prompts.iter().map(|prompt| format!("summary of {prompt}")).collect())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_batched_groups_sessions_into_batches_of_the_configured_size() {
+        let backend = Arc::new(CountingBatchBackend {
+            batch_calls: AtomicUsize::new(0),
+            fail_on_batch_containing: None,
+        });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        let sessions = vec![session(1, "a"), session(2, "b"), session(3, "c"), session(4, "d"), session(5, "e")];
+        let outcome = system.run_all_batched(sessions, 2).await;
+
+        assert_eq!(outcome.summaries.len(), 5);
+        assert!(outcome.errors.is_empty());
+        // 5 sessions at batch size 2 means 3 batch calls (2 + 2 + 1).
+        assert_eq!(backend.batch_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(system.metrics().snapshot().active_agents, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_batched_fails_every_chunk_in_a_group_whose_batch_call_fails() {
+        let backend = Arc::new(CountingBatchBackend {
+            batch_calls: AtomicUsize::new(0),
+            fail_on_batch_containing: Some("bad"),
+        });
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let sessions = vec![session(1, "good"), session(2, "bad")];
+        let outcome = system.run_all_batched(sessions, 2).await;
+
+        assert!(outcome.summaries.is_empty());
+        assert_eq!(outcome.errors.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_adaptive_processes_every_session_across_several_rounds() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: None,
+        });
+        let system = ParallelAgentSystem::new(Arc::clone(&backend), ErrorPolicy::CollectAll);
+
+        let config = crate::engine::adaptive_concurrency::AdaptiveConcurrencyConfig {
+            bounds: crate::engine::adaptive_concurrency::ConcurrencyBounds {
+                min_concurrency: 1,
+                max_concurrency: 2,
+            },
+            max_p95_latency: std::time::Duration::from_secs(60),
+            max_rss_bytes: u64::MAX,
+            max_failure_rate: 1.0,
+            step: 1,
+        };
+
+        let sessions = vec![session(1, "a"), session(2, "b"), session(3, "c"), session(4, "d"), session(5, "e")];
+        let outcome = system.run_all_adaptive(sessions, config, 2).await;
+
+        assert_eq!(outcome.summaries.len(), 5);
+        assert!(outcome.errors.is_empty());
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn top_categories_sorts_by_count_then_breaks_ties_alphabetically() {
+        let mut report = FailureReport::default();
+        report.groups.insert(
+            "unknown_chunk",
+            vec![FailedChunk {
+                chunk_id: ChunkId(1),
+                source_path: None,
+                error_chain: vec!["x".to_string()],
+            }],
+        );
+        report.groups.insert(
+            "backend_failed",
+            vec![
+                FailedChunk {
+                    chunk_id: ChunkId(2),
+                    source_path: None,
+                    error_chain: vec!["y".to_string()],
+                },
+                FailedChunk {
+                    chunk_id: ChunkId(3),
+                    source_path: None,
+                    error_chain: vec!["z".to_string()],
+                },
+            ],
+        );
+
+        assert_eq!(report.top_categories(5), vec![("backend_failed", 2), ("unknown_chunk", 1)]);
+        assert_eq!(report.top_categories(1), vec![("backend_failed", 2)]);
+    }
+
+    fn no_jitter_policy(max_attempts: u32) -> crate::engine::retry_policy::RetryPolicy {
+        crate::engine::retry_policy::RetryPolicy {
+            max_attempts,
+            base_backoff: std::time::Duration::from_millis(1),
+            max_backoff: std::time::Duration::from_millis(10),
+            jitter_fraction: 0.0,
+            retryable_categories: vec!["backend_failed"],
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_with_retry_policy_retries_until_it_succeeds_and_records_attempts_used() {
+        let backend = Arc::new(FlakyBackend {
+            attempts_for_bad: AtomicUsize::new(0),
+            fail_attempts: 2,
+        });
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let result = system.run_all_with_retry_policy(vec![session(1, "bad")], no_jitter_policy(5)).await;
+
+        assert_eq!(result.outcome.summaries.len(), 1);
+        assert!(result.outcome.errors.is_empty());
+        assert_eq!(result.attempts.get(&ChunkId(1)), Some(&3));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_with_retry_policy_reports_failure_once_max_attempts_is_exhausted() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: Some("bad"),
+        });
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let result = system.run_all_with_retry_policy(vec![session(1, "bad")], no_jitter_policy(3)).await;
+
+        assert!(result.outcome.summaries.is_empty());
+        assert_eq!(result.outcome.errors.len(), 1);
+        assert_eq!(result.attempts.get(&ChunkId(1)), Some(&3));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn run_all_with_retry_policy_never_retries_a_non_retryable_category() {
+        let backend = Arc::new(CountingBackend {
+            calls: AtomicUsize::new(0),
+            fail_on: Some("bad"),
+        });
+        let backend_for_assertions = Arc::clone(&backend);
+        let system = ParallelAgentSystem::new(backend, ErrorPolicy::CollectAll);
+
+        let mut policy = no_jitter_policy(5);
+        policy.retryable_categories = vec![];
+        let result = system.run_all_with_retry_policy(vec![session(1, "bad")], policy).await;
+
+        assert_eq!(result.outcome.errors.len(), 1);
+        assert_eq!(result.attempts.get(&ChunkId(1)), Some(&1));
+        assert_eq!(backend_for_assertions.calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,229 @@
+//! Latency-budgeted micro-batching for backends that run faster per-prompt
+//! in a batch than one at a time (e.g. a GPU forward pass).
+//!
+//! [`MicroBatcher`] accumulates up to `max_batch_size` prompts, but never
+//! waits longer than `max_wait` for a batch to fill before sending whatever
+//! it has, trading a bounded latency increase for throughput instead of an
+//! unbounded one.
+
+use std::sync::mpsc::{sync_channel, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::engine::EngineError;
+
+/// A backend that can process several prompts in one forward pass.
+pub trait BatchInferenceBackend: Send + Sync {
+    fn generate_completion_batch(&self, prompts: &[String]) -> Result<Vec<String>, EngineError>;
+}
+
+/// `T` and `B` from the batching policy: wait up to `max_wait` to accumulate
+/// up to `max_batch_size` prompts before running a batch.
+#[derive(Debug, Clone, Copy)]
+pub struct MicroBatchConfig {
+    pub max_wait: Duration,
+    pub max_batch_size: usize,
+}
+
+/// Counts how many prompts actually ended up in each dispatched batch, so an
+/// operator can tell whether `max_wait`/`max_batch_size` are tuned well for
+/// real traffic.
+#[derive(Default)]
+pub struct BatchSizeDistribution {
+    counts_by_size: Mutex<Vec<u64>>,
+}
+
+impl BatchSizeDistribution {
+    fn record(&self, batch_size: usize) {
+        let mut counts = self.counts_by_size.lock().expect("batch size distribution lock poisoned");
+        if counts.len() <= batch_size {
+            counts.resize(batch_size + 1, 0);
+        }
+        counts[batch_size] += 1;
+    }
+
+    /// Returns `(batch_size, times_dispatched_at_that_size)` pairs for every
+    /// size that was actually observed.
+    pub fn snapshot(&self) -> Vec<(usize, u64)> {
+        self.counts_by_size
+            .lock()
+            .expect("batch size distribution lock poisoned")
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(size, count)| (size, *count))
+            .collect()
+    }
+}
+
+struct BatchRequest {
+    prompt: String,
+    reply_to: Sender<Result<String, EngineError>>,
+}
+
+/// Runs a background worker that groups submitted prompts into micro-batches
+/// and dispatches them to a [`BatchInferenceBackend`].
+pub struct MicroBatcher {
+    // `Option` so `Drop` can close the channel before joining the worker;
+    // otherwise the worker would block forever on a channel this struct
+    // itself is still holding open.
+    request_sender: Option<SyncSender<BatchRequest>>,
+    worker: Option<JoinHandle<()>>,
+    distribution: Arc<BatchSizeDistribution>,
+}
+
+impl MicroBatcher {
+    pub fn spawn(backend: Arc<dyn BatchInferenceBackend>, config: MicroBatchConfig) -> Self {
+        let (request_sender, request_receiver) = sync_channel(config.max_batch_size.max(1) * 4);
+        let distribution = Arc::new(BatchSizeDistribution::default());
+
+        let worker_distribution = Arc::clone(&distribution);
+        let worker = thread::spawn(move || run_batching_loop(request_receiver, backend, config, worker_distribution));
+
+        MicroBatcher {
+            request_sender: Some(request_sender),
+            worker: Some(worker),
+            distribution,
+        }
+    }
+
+    /// Submits `prompt`, blocking until its batch has been dispatched and a
+    /// result is available.
+    pub fn submit(&self, prompt: impl Into<String>) -> Result<String, EngineError> {
+        let (reply_to, reply_from) = std::sync::mpsc::channel();
+        self.request_sender
+            .as_ref()
+            .expect("request_sender is only taken in Drop")
+            .send(BatchRequest {
+                prompt: prompt.into(),
+                reply_to,
+            })
+            .map_err(|_| EngineError::BackendFailed("micro-batch worker is no longer running".into()))?;
+        reply_from
+            .recv()
+            .map_err(|_| EngineError::BackendFailed("micro-batch worker dropped the reply channel".into()))?
+    }
+
+    pub fn distribution(&self) -> Arc<BatchSizeDistribution> {
+        Arc::clone(&self.distribution)
+    }
+}
+
+impl Drop for MicroBatcher {
+    fn drop(&mut self) {
+        // Dropping the sender disconnects the worker's receiver, which is
+        // what lets its blocking `recv()` return and the loop exit.
+        self.request_sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_batching_loop(
+    receiver: Receiver<BatchRequest>,
+    backend: Arc<dyn BatchInferenceBackend>,
+    config: MicroBatchConfig,
+    distribution: Arc<BatchSizeDistribution>,
+) {
+    loop {
+        let Ok(first) = receiver.recv() else { break };
+        let mut batch = vec![first];
+        let deadline = Instant::now() + config.max_wait;
+
+        while batch.len() < config.max_batch_size.max(1) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match receiver.recv_timeout(remaining) {
+                Ok(request) => batch.push(request),
+                Err(_) => break,
+            }
+        }
+
+        distribution.record(batch.len());
+
+        let prompts: Vec<String> = batch.iter().map(|request| request.prompt.clone()).collect();
+        match backend.generate_completion_batch(&prompts) {
+            Ok(texts) => {
+                for (request, text) in batch.into_iter().zip(texts) {
+                    let _ = request.reply_to.send(Ok(text));
+                }
+            }
+            Err(error) => {
+                let message = error.to_string();
+                for request in batch {
+                    let _ = request
+                        .reply_to
+                        .send(Err(EngineError::BackendFailed(message.clone())));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct EchoBatchBackend {
+        calls: AtomicUsize,
+    }
+
+    impl BatchInferenceBackend for EchoBatchBackend {
+        fn generate_completion_batch(&self, prompts: &[String]) -> Result<Vec<String>, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(prompts.iter().map(|p| format!("echo: {p}")).collect())
+        }
+    }
+
+    #[test]
+    fn fills_batch_to_capacity_before_waiting_out_the_timer() {
+        let backend = Arc::new(EchoBatchBackend {
+            calls: AtomicUsize::new(0),
+        });
+        let batcher = Arc::new(MicroBatcher::spawn(
+            Arc::clone(&backend) as Arc<dyn BatchInferenceBackend>,
+            MicroBatchConfig {
+                max_wait: Duration::from_secs(5),
+                max_batch_size: 4,
+            },
+        ));
+
+        let results: Vec<String> = (0..4)
+            .map(|i| {
+                let batcher = Arc::clone(&batcher);
+                thread::spawn(move || batcher.submit(format!("prompt-{i}")).unwrap())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.starts_with("echo: ")));
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(batcher.distribution().snapshot(), vec![(4, 1)]);
+    }
+
+    #[test]
+    fn flushes_a_partial_batch_once_max_wait_elapses() {
+        let backend = Arc::new(EchoBatchBackend {
+            calls: AtomicUsize::new(0),
+        });
+        let batcher = MicroBatcher::spawn(
+            Arc::clone(&backend) as Arc<dyn BatchInferenceBackend>,
+            MicroBatchConfig {
+                max_wait: Duration::from_millis(20),
+                max_batch_size: 100,
+            },
+        );
+
+        let result = batcher.submit("solo prompt").unwrap();
+        assert_eq!(result, "echo: solo prompt");
+        assert_eq!(batcher.distribution().snapshot(), vec![(1, 1)]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Machine-readable report of what a running instance of this engine can
+//! actually do, for ops tooling that needs to know before it routes a job
+//! here rather than discovering it mid-run.
+//!
+//! There is no `Engine` type and no service-mode HTTP server in this crate
+//! — `src/main.rs` is still a placeholder and [`crate::cli::schema`]'s
+//! module doc explains why there's no argv-parsing CLI yet either — so
+//! there's no literal `/capabilities` route for [`EngineCapabilities`] to
+//! be served from today. [`EngineCapabilities::detect`] is the honest stand
+//! in: the same structured data a future route would serve, available now
+//! as a plain function any caller (a CLI flag, a health-check script, a
+//! future HTTP handler) can call and render as JSON.
+//!
+//! "Devices detected" has no GPU-enumeration code anywhere in this crate —
+//! [`crate::engine::ort_compat`]'s ONNX Runtime backend can in principle run
+//! on a GPU execution provider, but nothing here probes for one — so this
+//! report is honest about that gap and only reports CPU parallelism, which
+//! `std::thread::available_parallelism` can answer truthfully everywhere.
+
+use serde::Serialize;
+
+use crate::model::card::ModelCard;
+use crate::model::context::ModelConfig;
+use crate::model::memory_planner::SessionPoolPlan;
+
+/// Bumped whenever a field is added, removed, or changes meaning, so a
+/// consumer that's cached an older shape can tell it needs to re-check
+/// instead of silently misreading a renamed field.
+pub const CAPABILITIES_SCHEMA_VERSION: u32 = 1;
+
+/// What's known about the model currently loaded, carried into
+/// [`EngineCapabilities`] when a caller has one — see
+/// [`EngineCapabilities::detect`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedModelCapabilities {
+    /// The model's real context window, from `config.json` — see
+    /// [`ModelConfig::max_context_tokens`].
+    pub context_window_tokens: usize,
+    /// A deterministic fingerprint of the *discovered model config*
+    /// (context window and special token ids), not the model's weight
+    /// bytes — nothing in this crate hashes raw weights. Two loads that
+    /// report the same fingerprint parsed the same `config.json`/
+    /// `generation_config.json` values; it is not a cryptographic
+    /// integrity check, just enough to notice a config change between runs.
+    pub config_fingerprint: String,
+    /// [`ModelCard::license`], when a `model_card.json` was found.
+    pub license: Option<String>,
+}
+
+fn fingerprint_model_config(config: &ModelConfig) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+    feed(&config.max_context_tokens.to_le_bytes());
+    feed(&config.bos_token_id.unwrap_or(u32::MAX).to_le_bytes());
+    for eos_token_id in &config.eos_token_ids {
+        feed(&eos_token_id.to_le_bytes());
+    }
+    format!("{hash:016x}")
+}
+
+/// What this running instance can do: which [`crate::engine::InferenceBackend`]
+/// implementations were compiled in, which Cargo feature flags are on, the
+/// currently-loaded model's capabilities (if any), how much CPU parallelism
+/// was detected, and how many concurrent sessions are safe to run.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineCapabilities {
+    pub schema_version: u32,
+    /// Every [`crate::engine::InferenceBackend`] implementation compiled
+    /// into this binary, by name.
+    pub backends_compiled_in: Vec<&'static str>,
+    /// Cargo feature flags this binary was built with, restricted to the
+    /// ones that change what the engine itself can do (not every flag in
+    /// `Cargo.toml` — e.g. `db`/`cli` gate tooling around the engine, not
+    /// the engine's own capabilities, so they're omitted here).
+    pub feature_flags: Vec<&'static str>,
+    pub detected_cpu_cores: usize,
+    /// How many inference sessions can safely run concurrently.
+    /// [`EngineCapabilities::detect`]'s `session_pool_plan` argument, when
+    /// given, reports [`SessionPoolPlan::max_safe_sessions`] — a real
+    /// memory-budget-aware answer from [`crate::model::memory_planner`].
+    /// With no plan (no model memory profile known yet), this falls back to
+    /// [`EngineCapabilities::detected_cpu_cores`], the same conservative
+    /// proxy a CPU-bound backend's concurrency is bounded by regardless of
+    /// memory.
+    pub max_safe_concurrency: usize,
+    pub model: Option<LoadedModelCapabilities>,
+}
+
+impl EngineCapabilities {
+    /// Detects this instance's capabilities. `model_config`/`model_card`
+    /// (both from [`crate::model`]) describe the model currently loaded, if
+    /// any — pass `None` for either when nothing is loaded yet.
+    /// `session_pool_plan` is a [`crate::model::memory_planner::plan_session_pool`]
+    /// result computed for that model against this host's memory budget, if
+    /// the caller has already worked one out; see
+    /// [`EngineCapabilities::max_safe_concurrency`] for the fallback when it
+    /// hasn't.
+    pub fn detect(
+        model_config: Option<&ModelConfig>,
+        model_card: Option<&ModelCard>,
+        session_pool_plan: Option<&SessionPoolPlan>,
+    ) -> Self {
+        let detected_cpu_cores = std::thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1);
+
+        let mut backends_compiled_in = vec!["heuristic", "extractive", "replay"];
+        if cfg!(feature = "http-backend") {
+            backends_compiled_in.push("http");
+        }
+        if cfg!(feature = "ort-2") {
+            backends_compiled_in.push("onnx");
+        }
+
+        let mut feature_flags = Vec::new();
+        for (flag, enabled) in [
+            ("service", cfg!(feature = "service")),
+            ("http-backend", cfg!(feature = "http-backend")),
+            ("onnx", cfg!(feature = "onnx")),
+            ("sandbox", cfg!(feature = "sandbox")),
+            ("signing", cfg!(feature = "signing")),
+            ("otel", cfg!(feature = "otel")),
+            ("worker-isolation", cfg!(feature = "worker-isolation")),
+            ("configurable-categories", cfg!(feature = "configurable-categories")),
+            ("archive-unpacking", cfg!(feature = "archive-unpacking")),
+            ("package-pipeline", cfg!(feature = "package-pipeline")),
+            ("dylib-plugins", cfg!(feature = "dylib-plugins")),
+            ("soak-test", cfg!(feature = "soak-test")),
+        ] {
+            if enabled {
+                feature_flags.push(flag);
+            }
+        }
+
+        let max_safe_concurrency = session_pool_plan
+            .map(|plan| plan.max_safe_sessions)
+            .unwrap_or(detected_cpu_cores);
+
+        let model = model_config.map(|config| LoadedModelCapabilities {
+            context_window_tokens: config.max_context_tokens,
+            config_fingerprint: fingerprint_model_config(config),
+            license: model_card.and_then(|card| card.license.clone()),
+        });
+
+        EngineCapabilities {
+            schema_version: CAPABILITIES_SCHEMA_VERSION,
+            backends_compiled_in,
+            feature_flags,
+            detected_cpu_cores,
+            max_safe_concurrency,
+            model,
+        }
+    }
+
+    /// Renders as pretty-printed JSON, for a CLI flag or health-check
+    /// script to print directly — the same role [`crate::cli::schema::CliSchema::to_json`]
+    /// plays for the CLI surface.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("EngineCapabilities contains no non-serializable types")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_with_no_model_reports_no_model_capabilities() {
+        let capabilities = EngineCapabilities::detect(None, None, None);
+        assert!(capabilities.model.is_none());
+        assert!(capabilities.detected_cpu_cores >= 1);
+        assert_eq!(capabilities.max_safe_concurrency, capabilities.detected_cpu_cores);
+    }
+
+    #[test]
+    fn detect_with_a_model_reports_its_context_window_and_license() {
+        let config = ModelConfig { max_context_tokens: 4096, bos_token_id: Some(1), eos_token_ids: vec![2] };
+        let card = ModelCard { license: Some("Apache-2.0".to_string()), ..ModelCard::default() };
+
+        let capabilities = EngineCapabilities::detect(Some(&config), Some(&card), None);
+        let model = capabilities.model.expect("model_config was given");
+        assert_eq!(model.context_window_tokens, 4096);
+        assert_eq!(model.license, Some("Apache-2.0".to_string()));
+    }
+
+    #[test]
+    fn config_fingerprint_is_deterministic_and_sensitive_to_every_field() {
+        let a = ModelConfig { max_context_tokens: 4096, bos_token_id: Some(1), eos_token_ids: vec![2, 3] };
+        let b = ModelConfig { max_context_tokens: 4096, bos_token_id: Some(1), eos_token_ids: vec![2, 3] };
+        let different_context = ModelConfig { max_context_tokens: 2048, ..a.clone() };
+        let different_eos = ModelConfig { eos_token_ids: vec![2], ..a.clone() };
+        let different_bos = ModelConfig { bos_token_id: Some(7), ..a.clone() };
+
+        assert_eq!(fingerprint_model_config(&a), fingerprint_model_config(&b));
+        assert_ne!(fingerprint_model_config(&a), fingerprint_model_config(&different_context));
+        assert_ne!(fingerprint_model_config(&a), fingerprint_model_config(&different_eos));
+        assert_ne!(fingerprint_model_config(&a), fingerprint_model_config(&different_bos));
+    }
+
+    #[test]
+    fn session_pool_plan_overrides_the_cpu_core_fallback() {
+        let plan = SessionPoolPlan { weights_bytes: 0, per_session_kv_cache_bytes: 0, max_safe_sessions: 3 };
+        let capabilities = EngineCapabilities::detect(None, None, Some(&plan));
+        assert_eq!(capabilities.max_safe_concurrency, 3);
+    }
+
+    #[test]
+    fn to_json_round_trips_through_serde_json() {
+        let capabilities = EngineCapabilities::detect(None, None, None);
+        let json = capabilities.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], CAPABILITIES_SCHEMA_VERSION);
+    }
+}
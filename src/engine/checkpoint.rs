@@ -0,0 +1,118 @@
+//! Crash-recoverable checkpointing for long [`super::agents::ParallelAgentSystem`] runs.
+//!
+//! A multi-million-line codebase can take hours to summarize, and
+//! [`super::agents::ParallelAgentSystem::run_all`] had no way to resume if
+//! the process died partway through — a crash at hour three meant
+//! starting over from chunk zero. [`Checkpoint`] is a JSON sidecar file
+//! recording which chunks already have a summary and which chunk ids are
+//! still pending;
+//! [`super::agents::ParallelAgentSystem::run_all_checkpointed`] rewrites it
+//! after every chunk that finishes, and
+//! [`super::agents::ParallelAgentSystem::resume_from_checkpoint`] reads it
+//! back to split a session list into what's already done and what still
+//! needs to run.
+//!
+//! JSON rather than sqlite: one run's checkpoint is a single small flat
+//! map with no query need beyond "load the whole thing back on startup" —
+//! the same reasoning [`crate::report`] uses for `RunArtifacts`, not the
+//! indexed query surface [`crate::sinks::sqlite`] exists for.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkId;
+use crate::locking::{self, FileLock, LockError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("failed to read or write checkpoint file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse checkpoint file: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Lock(#[from] LockError),
+}
+
+/// Every chunk a checkpointed run has finished (with its summary text) or
+/// still has left to do, as of the last write.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub completed: BTreeMap<ChunkId, String>,
+    pub pending: Vec<ChunkId>,
+}
+
+impl Checkpoint {
+    pub fn load_from_file(path: &Path) -> Result<Self, CheckpointError> {
+        let bytes = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Like [`Checkpoint::load_from_file`], but a missing file means no
+    /// run has ever checkpointed here yet — an empty [`Checkpoint`]
+    /// rather than an error, so a first run and a resumed run can share
+    /// the same call site.
+    pub fn load_from_file_or_default(path: &Path) -> Result<Self, CheckpointError> {
+        match Self::load_from_file(path) {
+            Ok(checkpoint) => Ok(checkpoint),
+            Err(CheckpointError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => Ok(Checkpoint::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Locked and atomically renamed into place, like
+    /// [`crate::engine::replay::ReplayLog::save_to_file`] and
+    /// [`crate::report::RunArtifacts::save_to_file`] — a crash mid-write or
+    /// two concurrent runs checkpointing to the same path would otherwise
+    /// corrupt the very file the resume feature exists to trust.
+    pub fn save_to_file(&self, path: &Path) -> Result<(), CheckpointError> {
+        let lock = FileLock::try_acquire(FileLock::lock_path_for(path))?;
+
+        let json = serde_json::to_vec_pretty(self)?;
+        locking::atomic_write(path, &json)?;
+
+        drop(lock);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("transfiguration-checkpoint-{name}.json"))
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let path = scratch_path("round-trip");
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.completed.insert(ChunkId(1), "summary one".to_string());
+        checkpoint.pending.push(ChunkId(2));
+
+        checkpoint.save_to_file(&path).unwrap();
+        let loaded = Checkpoint::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded, checkpoint);
+    }
+
+    #[test]
+    fn load_from_file_or_default_treats_a_missing_file_as_empty() {
+        let path = scratch_path("missing");
+        let _ = std::fs::remove_file(&path);
+
+        let checkpoint = Checkpoint::load_from_file_or_default(&path).unwrap();
+        assert_eq!(checkpoint, Checkpoint::default());
+    }
+
+    #[test]
+    fn load_from_file_reports_a_missing_file_as_an_io_error() {
+        let path = scratch_path("missing-strict");
+        let _ = std::fs::remove_file(&path);
+
+        let result = Checkpoint::load_from_file(&path);
+        assert!(matches!(result, Err(CheckpointError::Io(_))));
+    }
+}
@@ -0,0 +1,249 @@
+//! Deadline-aware degradation ladder over a sequential summarization pass.
+//!
+//! [`InferenceBackend::generate_completion_text`] takes a prompt and returns
+//! text — there's no `max_new_tokens`, sampling, or per-call priority knob a
+//! caller can turn down mid-run (`engine::decode::GenerationConfig` has a
+//! `max_new_tokens` field, but it's only reachable by the token-level decode
+//! loop, not through this trait). So the three degradations the request
+//! asks for map onto what a caller of this trait, plus [`ChunkClass`],
+//! actually has to turn:
+//!
+//!   1. "smaller max_new_tokens" → append a directive asking for a shorter
+//!      summary, the same mechanism [`crate::validation::Language::prompt_directive`]
+//!      already uses to steer output without a token-count parameter.
+//!   2. "skip low-priority chunks" → chunks are processed
+//!      [`ChunkClass::Production`] first, and once behind schedule the
+//!      remaining non-`Production` chunks are dropped entirely rather than
+//!      summarized at all.
+//!   3. "switch to heuristic backend for the tail" → [`HeuristicBackend`] is
+//!      already this crate's zero-dependency fallback backend; the run
+//!      switches every remaining chunk to it instead of the caller-supplied
+//!      backend.
+//!
+//! [`run_with_deadline`] re-estimates completion after every chunk from the
+//! mean per-chunk latency observed so far times the chunks still queued, and
+//! climbs one more rung of the ladder each time that estimate would miss
+//! `deadline` and a rung is still available, so the run only gets as coarse
+//! as it needs to in order to finish on time. Every rung actually climbed is
+//! recorded on [`DeadlineRunReport::degradations_applied`], in climb order,
+//! so a reader can see why a report came out coarser than usual.
+
+use std::time::{Duration, Instant};
+
+use crate::chunk::{Chunk, ChunkClass, ChunkId};
+use crate::engine::heuristic::HeuristicBackend;
+use crate::engine::{InferenceBackend, Summary, SummarySource};
+use crate::validation::Language;
+
+/// One rung of the degradation ladder, in the order [`run_with_deadline`]
+/// climbs them — cheapest/least-damaging first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degradation {
+    TersenPrompts,
+    SkipLowPriorityChunks,
+    HeuristicBackendForTail,
+}
+
+impl Degradation {
+    /// A one-sentence, past-tense description suitable for a run report, so
+    /// a reader doesn't have to know what each variant name means.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Degradation::TersenPrompts => "asked for shorter summaries to cut per-chunk latency",
+            Degradation::SkipLowPriorityChunks => {
+                "stopped summarizing non-production chunks (tests/benches/examples/build scripts) to protect the deadline"
+            }
+            Degradation::HeuristicBackendForTail => {
+                "switched the remaining chunks to the zero-dependency heuristic backend"
+            }
+        }
+    }
+}
+
+/// What [`run_with_deadline`] produced: every summary it managed to
+/// generate (possibly terser, or from the heuristic backend, if the run
+/// fell behind), every chunk it chose not to summarize at all, and which
+/// rungs of the ladder it had to climb to make the deadline.
+#[derive(Debug, Default)]
+pub struct DeadlineRunReport {
+    pub summaries: Vec<Summary>,
+    pub skipped: Vec<ChunkId>,
+    pub degradations_applied: Vec<Degradation>,
+}
+
+/// Runs `chunks` through `backend` one at a time, reordering so every
+/// [`ChunkClass::Production`] chunk is attempted before any other class, and
+/// climbing [`Degradation`]'s rungs in order as soon as the projected finish
+/// time (mean per-chunk latency so far × chunks still queued) would miss
+/// `deadline`. Does not stop at `deadline`: every chunk is still either
+/// summarized or skipped, and a backend call already in flight is always
+/// waited for — a single chunk's own latency isn't bounded by this
+/// function; the deadline only decides which rungs get climbed.
+pub fn run_with_deadline(backend: &impl InferenceBackend, chunks: Vec<Chunk>, deadline: Duration) -> DeadlineRunReport {
+    let started_at = Instant::now();
+    let mut report = DeadlineRunReport::default();
+
+    let mut ordered = chunks;
+    ordered.sort_by_key(|chunk| chunk.classify() != ChunkClass::Production);
+    let total = ordered.len();
+
+    let mut terser_prompts = false;
+    let mut skip_low_priority = false;
+    let mut use_heuristic_tail = false;
+    let mut latencies: Vec<Duration> = Vec::with_capacity(total);
+
+    for (index, chunk) in ordered.into_iter().enumerate() {
+        let class = chunk.classify();
+        let chunks_remaining_including_this_one = total - index;
+
+        if let Some(mean_latency) = mean(&latencies) {
+            let projected_finish = started_at.elapsed() + mean_latency * chunks_remaining_including_this_one as u32;
+            if projected_finish > deadline {
+                if !terser_prompts {
+                    terser_prompts = true;
+                    report.degradations_applied.push(Degradation::TersenPrompts);
+                } else if !skip_low_priority {
+                    skip_low_priority = true;
+                    report.degradations_applied.push(Degradation::SkipLowPriorityChunks);
+                } else if !use_heuristic_tail {
+                    use_heuristic_tail = true;
+                    report.degradations_applied.push(Degradation::HeuristicBackendForTail);
+                }
+            }
+        }
+
+        if skip_low_priority && class != ChunkClass::Production {
+            report.skipped.push(chunk.id);
+            continue;
+        }
+
+        let prompt = build_prompt(&chunk, class, terser_prompts);
+        let call_started = Instant::now();
+        let text = if use_heuristic_tail {
+            HeuristicBackend.generate_completion_text(&prompt)
+        } else {
+            backend.generate_completion_text(&prompt)
+        };
+        latencies.push(call_started.elapsed());
+
+        match text {
+            Ok(text) => report.summaries.push(Summary {
+                chunk_id: chunk.id,
+                class,
+                text,
+                instruction: None,
+                source: SummarySource::Model,
+                language: Language::default(),
+            }),
+            Err(_) => report.skipped.push(chunk.id),
+        }
+    }
+
+    report
+}
+
+fn build_prompt(chunk: &Chunk, class: ChunkClass, terse: bool) -> String {
+    let mut prompt = super::initial_summary_prompt(chunk, class, Language::default());
+    if terse {
+        prompt.push_str(" Answer in one short sentence of at most 12 words.");
+    }
+    prompt
+}
+
+fn mean(latencies: &[Duration]) -> Option<Duration> {
+    if latencies.is_empty() {
+        return None;
+    }
+    let total = latencies.iter().fold(Duration::ZERO, |acc, latency| acc + *latency);
+    Some(total / latencies.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    /// A backend whose calls take `delay` each, so tests can drive
+    /// `run_with_deadline` past its deadline deterministically instead of
+    /// racing a real model.
+    struct SlowBackend {
+        delay: Duration,
+        calls: AtomicUsize,
+    }
+
+    impl InferenceBackend for SlowBackend {
+        fn generate_completion_text(&self, _prompt: &str) -> Result<String, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(self.delay);
+            Ok("a summary".to_string())
+        }
+    }
+
+    fn chunk(id: u64, path: &str, content: &str) -> Chunk {
+        Chunk::new(ChunkId(id), path, content)
+    }
+
+    #[test]
+    fn a_generous_deadline_needs_no_degradation() {
+        let backend = SlowBackend { delay: Duration::from_millis(1), calls: AtomicUsize::new(0) };
+        let chunks = vec![chunk(1, "a.rs", "fn a() {}"), chunk(2, "b.rs", "fn b() {}")];
+
+        let report = run_with_deadline(&backend, chunks, Duration::from_secs(60));
+
+        assert_eq!(report.summaries.len(), 2);
+        assert!(report.skipped.is_empty());
+        assert!(report.degradations_applied.is_empty());
+    }
+
+    #[test]
+    fn production_chunks_are_attempted_before_non_production_chunks() {
+        let backend = SlowBackend { delay: Duration::from_millis(0), calls: AtomicUsize::new(0) };
+        let chunks = vec![
+            chunk(1, "tests/a.rs", "#[test]\nfn t() {}"),
+            chunk(2, "src/lib.rs", "pub fn go() {}"),
+        ];
+
+        let report = run_with_deadline(&backend, chunks, Duration::from_secs(60));
+
+        assert_eq!(report.summaries[0].chunk_id, ChunkId(2));
+        assert_eq!(report.summaries[1].chunk_id, ChunkId(1));
+    }
+
+    #[test]
+    fn an_impossible_deadline_climbs_every_rung_and_still_finishes() {
+        let backend = SlowBackend { delay: Duration::from_millis(20), calls: AtomicUsize::new(0) };
+        let chunks: Vec<Chunk> = (0..8)
+            .map(|i| {
+                if i % 2 == 0 {
+                    chunk(i, &format!("src/f{i}.rs"), "pub fn go() {}")
+                } else {
+                    chunk(i, &format!("tests/f{i}.rs"), "#[test]\nfn t() {}")
+                }
+            })
+            .collect();
+
+        let report = run_with_deadline(&backend, chunks, Duration::from_nanos(1));
+
+        assert!(!report.degradations_applied.is_empty());
+        assert!(report.degradations_applied.contains(&Degradation::SkipLowPriorityChunks));
+        assert!(!report.skipped.is_empty());
+        // Every chunk is either summarized or explicitly skipped - none vanish.
+        assert_eq!(report.summaries.len() + report.skipped.len(), 8);
+    }
+
+    #[test]
+    fn falling_behind_eventually_switches_the_tail_to_the_heuristic_backend() {
+        let backend = SlowBackend { delay: Duration::from_millis(20), calls: AtomicUsize::new(0) };
+        // All production so `SkipLowPriorityChunks` can't help - forces the
+        // ladder to climb to the heuristic-backend rung instead.
+        let chunks: Vec<Chunk> = (0..6).map(|i| chunk(i, &format!("src/f{i}.rs"), "pub fn go() {}")).collect();
+
+        let report = run_with_deadline(&backend, chunks, Duration::from_nanos(1));
+
+        assert!(report.degradations_applied.contains(&Degradation::HeuristicBackendForTail));
+        assert_eq!(report.summaries.len(), 6);
+        assert!(backend.calls.load(Ordering::SeqCst) < 6);
+    }
+}
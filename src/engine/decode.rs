@@ -0,0 +1,416 @@
+//! Decode-loop algorithms: turn per-step next-token logits into a finished
+//! token sequence under a chosen [`DecodingStrategy`].
+//!
+//! [`InferenceBackend`](crate::engine::InferenceBackend) only exposes whole
+//! completions (text in, text out), so nothing here is wired into the
+//! current heuristic or ONNX backends yet — neither surfaces per-token
+//! logits to decode against. This module is the seam for when one does: a
+//! backend-agnostic [`decode`] that drives greedy, beam search, or nucleus
+//! sampling against anything implementing [`NextTokenLogits`], independent
+//! of whatever produces the logits.
+
+/// A source of next-token logits over the model's vocabulary, given the
+/// tokens generated so far, so [`decode`] never needs to know how logits are
+/// actually produced (ONNX forward pass, a test fixture, anything else).
+pub trait NextTokenLogits {
+    fn next_token_logits(&mut self, tokens_so_far: &[u32]) -> Vec<f32>;
+}
+
+/// Which decoding algorithm [`decode`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DecodingStrategy {
+    /// Always takes the highest-probability token. Deterministic.
+    #[default]
+    Greedy,
+    /// Keeps `beam_width` candidate sequences per step, scored by summed
+    /// log-probability, and returns the highest-scoring one once every beam
+    /// has finished or `max_new_tokens` is reached. Deterministic.
+    BeamSearch { beam_width: usize },
+    /// Samples from the smallest set of tokens whose cumulative probability
+    /// (after `temperature` scaling) reaches `top_p`, seeded by `seed` for
+    /// reproducibility.
+    NucleusSampling { top_p: f32, temperature: f32, seed: u64 },
+}
+
+/// Settings for one [`decode`] call.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationConfig {
+    pub strategy: DecodingStrategy,
+    pub max_new_tokens: usize,
+    /// Token ids masked out of every step's logits before a token is chosen,
+    /// so the model is steered away from them rather than repaired
+    /// afterwards. [`one_line_forbidden_token_ids`] builds this list for
+    /// `SummaryStyle::OneLine`-style single-line constraints; this crate
+    /// doesn't own a tokenizer, so the caller is responsible for resolving
+    /// "newline" and "code fence" to actual vocabulary ids.
+    pub forbidden_token_ids: Vec<u32>,
+}
+
+/// Builds the forbidden-token-id list for a single-line generation
+/// constraint: newline tokens, plus optional code-fence tokens, masked out
+/// at every decode step so the model can't produce a line break or drop into
+/// a fenced code block.
+pub fn one_line_forbidden_token_ids(newline_token_ids: &[u32], code_fence_token_ids: &[u32]) -> Vec<u32> {
+    let mut forbidden: Vec<u32> = newline_token_ids.iter().chain(code_fence_token_ids).copied().collect();
+    forbidden.sort_unstable();
+    forbidden.dedup();
+    forbidden
+}
+
+fn mask_forbidden(logits: &mut [f32], forbidden_token_ids: &[u32]) {
+    for &token_id in forbidden_token_ids {
+        if let Some(slot) = logits.get_mut(token_id as usize) {
+            *slot = f32::NEG_INFINITY;
+        }
+    }
+}
+
+/// Runs `config.strategy`'s decode loop against `logits_source`, stopping at
+/// `max_new_tokens` or as soon as a generated token is in `eos_token_ids`.
+/// Any token id in `config.forbidden_token_ids` is masked out of every
+/// step's logits before a token is chosen.
+pub fn decode(logits_source: &mut impl NextTokenLogits, config: &GenerationConfig, eos_token_ids: &[u32]) -> Vec<u32> {
+    match config.strategy {
+        DecodingStrategy::Greedy => greedy_decode(logits_source, config.max_new_tokens, eos_token_ids, &config.forbidden_token_ids),
+        DecodingStrategy::BeamSearch { beam_width } => {
+            beam_search_decode(logits_source, config.max_new_tokens, eos_token_ids, beam_width, &config.forbidden_token_ids)
+        }
+        DecodingStrategy::NucleusSampling { top_p, temperature, seed } => {
+            nucleus_sampling_decode(logits_source, config.max_new_tokens, eos_token_ids, top_p, temperature, seed, &config.forbidden_token_ids)
+        }
+    }
+}
+
+fn greedy_decode(logits_source: &mut impl NextTokenLogits, max_new_tokens: usize, eos_token_ids: &[u32], forbidden_token_ids: &[u32]) -> Vec<u32> {
+    let mut tokens = Vec::new();
+    for _ in 0..max_new_tokens {
+        let mut logits = logits_source.next_token_logits(&tokens);
+        mask_forbidden(&mut logits, forbidden_token_ids);
+        let Some(next) = argmax(&logits) else { break };
+        tokens.push(next);
+        if eos_token_ids.contains(&next) {
+            break;
+        }
+    }
+    tokens
+}
+
+fn argmax(logits: &[f32]) -> Option<u32> {
+    logits
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index as u32)
+}
+
+fn softmax(logits: &[f32], temperature: f32) -> Vec<f32> {
+    let scaled: Vec<f32> = logits.iter().map(|l| l / temperature.max(f32::EPSILON)).collect();
+    let max = scaled.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = scaled.iter().map(|l| (l - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    if sum <= 0.0 {
+        return vec![0.0; logits.len()];
+    }
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+#[derive(Debug, Clone)]
+struct Beam {
+    tokens: Vec<u32>,
+    log_prob: f32,
+    finished: bool,
+}
+
+/// Standard beam search: at each step every live beam is expanded by its top
+/// `beam_width` next tokens (by probability), and only the `beam_width`
+/// highest summed-log-probability candidates survive into the next step.
+fn beam_search_decode(
+    logits_source: &mut impl NextTokenLogits,
+    max_new_tokens: usize,
+    eos_token_ids: &[u32],
+    beam_width: usize,
+    forbidden_token_ids: &[u32],
+) -> Vec<u32> {
+    let beam_width = beam_width.max(1);
+    let mut beams = vec![Beam {
+        tokens: Vec::new(),
+        log_prob: 0.0,
+        finished: false,
+    }];
+
+    for _ in 0..max_new_tokens {
+        if beams.iter().all(|beam| beam.finished) {
+            break;
+        }
+
+        let mut candidates = Vec::new();
+        for beam in &beams {
+            if beam.finished {
+                candidates.push(beam.clone());
+                continue;
+            }
+            let mut logits = logits_source.next_token_logits(&beam.tokens);
+            mask_forbidden(&mut logits, forbidden_token_ids);
+            let probs = softmax(&logits, 1.0);
+            let mut ranked: Vec<(u32, f32)> = probs.into_iter().enumerate().map(|(id, p)| (id as u32, p)).collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            for (token_id, probability) in ranked.into_iter().take(beam_width) {
+                let mut tokens = beam.tokens.clone();
+                tokens.push(token_id);
+                candidates.push(Beam {
+                    tokens,
+                    log_prob: beam.log_prob + probability.max(f32::MIN_POSITIVE).ln(),
+                    finished: eos_token_ids.contains(&token_id),
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| b.log_prob.total_cmp(&a.log_prob));
+        candidates.truncate(beam_width);
+        beams = candidates;
+    }
+
+    beams
+        .into_iter()
+        .max_by(|a, b| a.log_prob.total_cmp(&b.log_prob))
+        .map(|beam| beam.tokens)
+        .unwrap_or_default()
+}
+
+/// Minimal deterministic PRNG (SplitMix64) so sampling has no external
+/// dependency and reproduces byte-for-byte across platforms, matching
+/// [`crate::testgen`]'s approach.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn seeded_from_value(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_raw_value(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `[0.0, 1.0)`.
+    fn next_unit_f64(&mut self) -> f64 {
+        (self.next_raw_value() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn nucleus_sampling_decode(
+    logits_source: &mut impl NextTokenLogits,
+    max_new_tokens: usize,
+    eos_token_ids: &[u32],
+    top_p: f32,
+    temperature: f32,
+    seed: u64,
+    forbidden_token_ids: &[u32],
+) -> Vec<u32> {
+    let mut rng = SplitMix64::seeded_from_value(seed);
+    let mut tokens = Vec::new();
+
+    for _ in 0..max_new_tokens {
+        let mut logits = logits_source.next_token_logits(&tokens);
+        mask_forbidden(&mut logits, forbidden_token_ids);
+        let probs = softmax(&logits, temperature);
+        let mut ranked: Vec<(u32, f32)> = probs.into_iter().enumerate().map(|(id, p)| (id as u32, p)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let mut cumulative = 0.0;
+        let mut nucleus = Vec::new();
+        for (token_id, probability) in ranked {
+            nucleus.push((token_id, probability));
+            cumulative += probability;
+            if cumulative >= top_p {
+                break;
+            }
+        }
+
+        let nucleus_total: f32 = nucleus.iter().map(|(_, p)| p).sum();
+        let Some(next) = sample_from(&nucleus, nucleus_total, &mut rng) else { break };
+        tokens.push(next);
+        if eos_token_ids.contains(&next) {
+            break;
+        }
+    }
+    tokens
+}
+
+fn sample_from(candidates: &[(u32, f32)], total: f32, rng: &mut SplitMix64) -> Option<u32> {
+    if candidates.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let draw = rng.next_unit_f64() as f32 * total;
+    let mut cumulative = 0.0;
+    for (token_id, probability) in candidates {
+        cumulative += probability;
+        if draw <= cumulative {
+            return Some(*token_id);
+        }
+    }
+    candidates.last().map(|(token_id, _)| *token_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic logits source: token `i` always scores `scores[i]` for
+    /// the first step, then every further step strongly favors the eos
+    /// token, so tests terminate without relying on a real vocabulary.
+    struct FixedLogits {
+        first_step: Vec<f32>,
+        eos_token: u32,
+    }
+
+    impl NextTokenLogits for FixedLogits {
+        fn next_token_logits(&mut self, tokens_so_far: &[u32]) -> Vec<f32> {
+            if tokens_so_far.is_empty() {
+                self.first_step.clone()
+            } else {
+                let mut logits = vec![-10.0; self.first_step.len()];
+                if let Some(slot) = logits.get_mut(self.eos_token as usize) {
+                    *slot = 10.0;
+                }
+                logits
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_is_deterministic_and_picks_the_highest_scoring_token() {
+        let mut source = FixedLogits {
+            first_step: vec![0.1, 5.0, 0.2],
+            eos_token: 1,
+        };
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::Greedy,
+            max_new_tokens: 5,
+            forbidden_token_ids: Vec::new(),
+        };
+        let first_run = decode(&mut source, &config, &[1]);
+
+        let mut source = FixedLogits {
+            first_step: vec![0.1, 5.0, 0.2],
+            eos_token: 1,
+        };
+        let second_run = decode(&mut source, &config, &[1]);
+
+        assert_eq!(first_run, vec![1]);
+        assert_eq!(first_run, second_run);
+    }
+
+    #[test]
+    fn beam_search_is_deterministic_and_finds_the_same_high_probability_token() {
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::BeamSearch { beam_width: 3 },
+            max_new_tokens: 5,
+            forbidden_token_ids: Vec::new(),
+        };
+        let mut source = FixedLogits {
+            first_step: vec![0.1, 5.0, 4.9],
+            eos_token: 1,
+        };
+        let first_run = decode(&mut source, &config, &[1]);
+
+        let mut source = FixedLogits {
+            first_step: vec![0.1, 5.0, 4.9],
+            eos_token: 1,
+        };
+        let second_run = decode(&mut source, &config, &[1]);
+
+        assert_eq!(first_run, second_run);
+        assert_eq!(first_run[0], 1);
+    }
+
+    #[test]
+    fn nucleus_sampling_reproduces_the_same_sequence_for_the_same_seed() {
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::NucleusSampling {
+                top_p: 0.9,
+                temperature: 1.0,
+                seed: 42,
+            },
+            max_new_tokens: 3,
+            forbidden_token_ids: Vec::new(),
+        };
+        let mut source = FixedLogits {
+            first_step: vec![1.0, 2.0, 0.5, 1.5],
+            eos_token: 0,
+        };
+        let first_run = decode(&mut source, &config, &[0]);
+
+        let mut source = FixedLogits {
+            first_step: vec![1.0, 2.0, 0.5, 1.5],
+            eos_token: 0,
+        };
+        let second_run = decode(&mut source, &config, &[0]);
+
+        assert_eq!(first_run, second_run);
+    }
+
+    #[test]
+    fn nucleus_sampling_only_ever_draws_from_the_nucleus_set() {
+        // With top_p this small, only the single highest-probability token
+        // (index 1) should ever be sampled.
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::NucleusSampling {
+                top_p: 0.01,
+                temperature: 1.0,
+                seed: 7,
+            },
+            max_new_tokens: 1,
+            forbidden_token_ids: Vec::new(),
+        };
+        let mut source = FixedLogits {
+            first_step: vec![0.0, 100.0, 0.0],
+            eos_token: 1,
+        };
+        let tokens = decode(&mut source, &config, &[]);
+        assert_eq!(tokens, vec![1]);
+    }
+
+    #[test]
+    fn decode_stops_at_max_new_tokens_even_without_eos() {
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::Greedy,
+            max_new_tokens: 4,
+            forbidden_token_ids: Vec::new(),
+        };
+        let mut source = FixedLogits {
+            first_step: vec![5.0, 0.0],
+            eos_token: 99, // never matches, so eos never fires
+        };
+        let tokens = decode(&mut source, &config, &[99]);
+        assert_eq!(tokens.len(), 4);
+    }
+
+    #[test]
+    fn forbidden_token_ids_are_never_chosen_by_greedy_decoding() {
+        // Token 1 scores highest, but it's forbidden, so token 2 should win.
+        let config = GenerationConfig {
+            strategy: DecodingStrategy::Greedy,
+            max_new_tokens: 1,
+            forbidden_token_ids: vec![1],
+        };
+        let mut source = FixedLogits {
+            first_step: vec![0.1, 5.0, 4.9],
+            eos_token: 1,
+        };
+        let tokens = decode(&mut source, &config, &[]);
+        assert_eq!(tokens, vec![2]);
+    }
+
+    #[test]
+    fn one_line_forbidden_token_ids_merges_and_dedupes_newline_and_fence_ids() {
+        let forbidden = one_line_forbidden_token_ids(&[10, 11], &[11, 12]);
+        assert_eq!(forbidden, vec![10, 11, 12]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Generation-drift detection for long-lived inference sessions.
+//!
+//! [`crate::engine::InferenceBackend::generate_completion_text`] is a single
+//! stateless call — this crate has no persistent in-process notion of "a
+//! session" that could itself degrade over many calls the way a long-lived
+//! ORT session occasionally does (repetition loops as token-level state
+//! drifts). The closest real analog is [`crate::engine::worker_pool::WorkerPool`]:
+//! each [`crate::engine::worker_pool::WorkerTransport`] slot is a
+//! long-lived child process holding exactly the kind of session this
+//! request is about, dispatched to repeatedly over a run's lifetime. So
+//! [`DriftMonitor`] is keyed by worker index (the same index
+//! [`crate::engine::worker_pool::WorkerPool`] round-robins dispatch over),
+//! scores every [`crate::engine::worker_pool::WorkerResponse::Summary`] text
+//! by [`repetition_ratio`] and [`shannon_entropy_bits_per_char`], and once a
+//! worker has produced [`DriftThresholds::consecutive_degraded_outputs_before_recycle`]
+//! degraded outputs in a row, [`DriftMonitor::record`] reports it — letting
+//! the pool respawn that worker and re-dispatch the chunk exactly the way it
+//! already does for a crashed one, rather than inventing a second recovery
+//! path.
+
+use std::collections::HashMap;
+
+/// Thresholds [`DriftMonitor`] scores every generated text against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriftThresholds {
+    /// Above this fraction of repeated word-bigrams, a text is considered a
+    /// repetition loop.
+    pub max_repetition_ratio: f32,
+    /// Below this many bits of per-character Shannon entropy, a text is
+    /// considered too degenerate (e.g. stuck repeating one token) to trust.
+    pub min_entropy_bits_per_char: f32,
+    /// How many degraded outputs in a row from the same session trigger a
+    /// recycle. A single bad output is tolerated as noise; a streak isn't.
+    pub consecutive_degraded_outputs_before_recycle: usize,
+}
+
+impl Default for DriftThresholds {
+    /// Loose enough that ordinary short, varied text (the kind every
+    /// existing [`crate::engine::heuristic::HeuristicBackend`] summary and
+    /// test fixture in this crate produces) never trips it, while still
+    /// catching genuine repetition loops ("the the the the...") or
+    /// single-character degenerate runs.
+    fn default() -> Self {
+        DriftThresholds {
+            max_repetition_ratio: 0.5,
+            min_entropy_bits_per_char: 2.0,
+            consecutive_degraded_outputs_before_recycle: 3,
+        }
+    }
+}
+
+/// The fraction of consecutive word-bigrams in `text` that repeat an
+/// earlier bigram — `0.0` for text with no repeated two-word sequence,
+/// climbing toward `1.0` for a tight repetition loop. Texts shorter than
+/// two words score `0.0`: too little signal to call them a loop.
+pub fn repetition_ratio(text: &str) -> f32 {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() < 2 {
+        return 0.0;
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut repeated = 0usize;
+    for pair in words.windows(2) {
+        if !seen.insert((pair[0], pair[1])) {
+            repeated += 1;
+        }
+    }
+
+    repeated as f32 / (words.len() - 1) as f32
+}
+
+/// Shannon entropy of `text`'s character distribution, in bits per
+/// character. Empty text scores `0.0`. Degenerate output that repeats one
+/// character or a short cycle scores low; varied natural-language text
+/// scores several bits per character.
+pub fn shannon_entropy_bits_per_char(text: &str) -> f32 {
+    let total_chars = text.chars().count();
+    if total_chars == 0 {
+        return 0.0;
+    }
+
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+
+    -counts
+        .values()
+        .map(|&count| {
+            let probability = count as f32 / total_chars as f32;
+            probability * probability.log2()
+        })
+        .sum::<f32>()
+}
+
+/// Whether a single piece of generated text crosses either
+/// [`DriftThresholds`] on its own.
+fn is_degraded(text: &str, thresholds: &DriftThresholds) -> bool {
+    repetition_ratio(text) > thresholds.max_repetition_ratio
+        || shannon_entropy_bits_per_char(text) < thresholds.min_entropy_bits_per_char
+}
+
+/// Tracks consecutive degraded outputs per session key (a
+/// [`crate::engine::worker_pool::WorkerPool`] worker index, or any other
+/// caller-chosen identifier for a long-lived session), and reports once a
+/// session crosses [`DriftThresholds::consecutive_degraded_outputs_before_recycle`]
+/// in a row.
+#[derive(Debug, Clone)]
+pub struct DriftMonitor {
+    thresholds: DriftThresholds,
+    consecutive_degraded: HashMap<usize, usize>,
+}
+
+impl DriftMonitor {
+    pub fn new(thresholds: DriftThresholds) -> Self {
+        DriftMonitor { thresholds, consecutive_degraded: HashMap::new() }
+    }
+
+    /// Scores `text` for `session_key`. A healthy output resets that
+    /// session's streak to zero. A degraded output extends the streak and,
+    /// once it reaches [`DriftThresholds::consecutive_degraded_outputs_before_recycle`],
+    /// resets the streak and returns `true` so the caller recycles the
+    /// session exactly once per streak rather than every call after.
+    pub fn record(&mut self, session_key: usize, text: &str) -> bool {
+        if !is_degraded(text, &self.thresholds) {
+            self.consecutive_degraded.remove(&session_key);
+            return false;
+        }
+
+        let streak = self.consecutive_degraded.entry(session_key).or_insert(0);
+        *streak += 1;
+        if *streak >= self.thresholds.consecutive_degraded_outputs_before_recycle {
+            self.consecutive_degraded.remove(&session_key);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repetition_ratio_is_zero_for_text_with_no_repeated_bigram() {
+        assert_eq!(repetition_ratio("the quick brown fox jumps"), 0.0);
+    }
+
+    #[test]
+    fn repetition_ratio_is_high_for_a_tight_repetition_loop() {
+        assert!(repetition_ratio("go go go go go go") > 0.5);
+    }
+
+    #[test]
+    fn entropy_is_zero_for_empty_text() {
+        assert_eq!(shannon_entropy_bits_per_char(""), 0.0);
+    }
+
+    #[test]
+    fn entropy_is_low_for_a_single_repeated_character() {
+        assert!(shannon_entropy_bits_per_char(&"a".repeat(50)) < 0.5);
+    }
+
+    #[test]
+    fn entropy_is_higher_for_varied_natural_language_text() {
+        let varied = shannon_entropy_bits_per_char("the quick brown fox jumps over the lazy dog");
+        let degenerate = shannon_entropy_bits_per_char(&"a".repeat(50));
+        assert!(varied > degenerate);
+    }
+
+    #[test]
+    fn a_single_degraded_output_does_not_trigger_a_recycle() {
+        let mut monitor = DriftMonitor::new(DriftThresholds::default());
+        assert!(!monitor.record(0, "go go go go go go"));
+    }
+
+    #[test]
+    fn a_streak_of_degraded_outputs_triggers_exactly_one_recycle() {
+        let mut monitor = DriftMonitor::new(DriftThresholds::default());
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(monitor.record(0, "go go go go go go"));
+        // The streak reset after triggering, so a fourth degraded call in a
+        // row doesn't immediately trigger again.
+        assert!(!monitor.record(0, "go go go go go go"));
+    }
+
+    #[test]
+    fn a_healthy_output_resets_the_streak() {
+        let mut monitor = DriftMonitor::new(DriftThresholds::default());
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(0, "a varied and perfectly healthy summary of the code"));
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(0, "go go go go go go"));
+    }
+
+    #[test]
+    fn different_session_keys_track_independent_streaks() {
+        let mut monitor = DriftMonitor::new(DriftThresholds::default());
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(0, "go go go go go go"));
+        assert!(!monitor.record(1, "go go go go go go"));
+        assert!(monitor.record(0, "go go go go go go"));
+    }
+}
@@ -0,0 +1,120 @@
+//! Promptless extractive summarization from existing doc comments.
+//!
+//! When a chunk already opens with a solid `///`/`//!` doc comment, asking a
+//! model to re-describe it spends an inference call restating what the
+//! author already wrote. [`extract_doc_comment_summary`] looks for such a
+//! comment at the top of a chunk and, if it clears a minimum quality bar,
+//! returns it (trimmed) as the summary instead; [`summarize_extractively`]
+//! wraps that into a [`Summary`] tagged [`SummarySource::Extractive`] so
+//! downstream reports can tell which summaries skipped inference entirely.
+
+use crate::chunk::Chunk;
+use crate::engine::{Summary, SummarySource};
+use crate::validation::Language;
+
+/// Doc comments shorter than this (in whitespace-delimited words) read as
+/// boilerplate (`/// TODO`, `/// Foo.`) rather than a real description, so
+/// they're not worth extracting in place of an actual summarization call.
+const MIN_QUALITY_WORDS: usize = 6;
+
+/// Finds a leading `///` or `//!` doc comment block at the top of
+/// `chunk_content` (skipping leading blank lines and attributes) and returns
+/// its text, with comment markers stripped and lines joined with spaces, if
+/// it has at least [`MIN_QUALITY_WORDS`] words.
+pub fn extract_doc_comment_summary(chunk_content: &str) -> Option<String> {
+    let mut doc_lines = Vec::new();
+
+    for line in chunk_content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            if doc_lines.is_empty() {
+                continue; // still skipping leading blank lines/attributes
+            }
+            break; // a doc block ended by a blank line or attribute
+        }
+        let Some(text) = strip_doc_comment_marker(trimmed) else {
+            break; // first non-doc, non-blank line ends the search
+        };
+        doc_lines.push(text);
+    }
+
+    if doc_lines.is_empty() {
+        return None;
+    }
+
+    let joined = doc_lines.join(" ").trim().to_string();
+    (joined.split_whitespace().count() >= MIN_QUALITY_WORDS).then_some(joined)
+}
+
+fn strip_doc_comment_marker(line: &str) -> Option<&str> {
+    line.strip_prefix("///").or_else(|| line.strip_prefix("//!")).map(str::trim)
+}
+
+/// Builds a [`Summary`] for `chunk` straight from its leading doc comment,
+/// with no inference call, or `None` if it has no doc comment good enough to
+/// stand in for one. `language` is recorded on the summary as the run's
+/// configured language, same as every other [`SummarySource`] — this
+/// function doesn't translate or detect the doc comment's actual language.
+pub fn summarize_extractively(chunk: &Chunk, language: Language) -> Option<Summary> {
+    let text = extract_doc_comment_summary(&chunk.content)?;
+    Some(Summary {
+        chunk_id: chunk.id,
+        class: chunk.classify(),
+        text,
+        instruction: None,
+        source: SummarySource::Extractive,
+        language,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+
+    #[test]
+    fn extracts_a_leading_doc_comment_block_with_markers_stripped() {
+        let content = "/// Parses a config file into a validated Settings struct.\n/// Returns an error for any unknown key.\nfn parse() {}";
+        let text = extract_doc_comment_summary(content).unwrap();
+        assert_eq!(text, "Parses a config file into a validated Settings struct. Returns an error for any unknown key.");
+    }
+
+    #[test]
+    fn module_level_doc_comments_are_also_extracted() {
+        let content = "//! Central dispatch table mapping request kinds to handlers.\n//! Keeps routing logic in one place instead of scattered matches.\n\nfn route() {}";
+        assert!(extract_doc_comment_summary(content).is_some());
+    }
+
+    #[test]
+    fn short_doc_comments_are_rejected_as_low_quality() {
+        let content = "/// TODO\nfn stub() {}";
+        assert!(extract_doc_comment_summary(content).is_none());
+    }
+
+    #[test]
+    fn no_leading_doc_comment_yields_nothing() {
+        let content = "fn helper() {\n    // not a doc comment\n}";
+        assert!(extract_doc_comment_summary(content).is_none());
+    }
+
+    #[test]
+    fn a_doc_comment_ended_by_a_blank_line_still_counts_but_a_plain_comment_does_not() {
+        let content = "/// Some description of something else entirely here.\n\nfn f() {}";
+        assert!(extract_doc_comment_summary(content).is_some());
+
+        let content = "// ordinary comment, not a doc comment at all here\nfn f() {}";
+        assert!(extract_doc_comment_summary(content).is_none());
+    }
+
+    #[test]
+    fn summarize_extractively_tags_the_source_and_skips_inference() {
+        let chunk = Chunk::new(
+            ChunkId(1),
+            "f.rs",
+            "/// Computes the shortest path between two nodes using Dijkstra's algorithm.\nfn shortest_path() {}",
+        );
+        let summary = summarize_extractively(&chunk, Language::default()).unwrap();
+        assert_eq!(summary.source, SummarySource::Extractive);
+        assert!(summary.text.contains("Dijkstra"));
+    }
+}
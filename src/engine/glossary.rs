@@ -0,0 +1,139 @@
+//! Domain glossary injection for org-specific terminology.
+//!
+//! A general-purpose model mangles internal acronyms and codebase-specific
+//! terms it's never seen. [`Glossary`] loads a term → definition table and
+//! [`Glossary::relevant_entries`] finds the entries whose term shows up in a
+//! chunk's identifiers, trimmed to fit a token budget so injecting it never
+//! crowds out the code itself.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GlossaryError {
+    #[error("failed to read glossary file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse glossary file: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Term → definition table for org-specific terminology a general-purpose
+/// model would otherwise mangle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Glossary {
+    terms: BTreeMap<String, String>,
+}
+
+impl Glossary {
+    pub fn load_from_file(path: &Path) -> Result<Self, GlossaryError> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Finds glossary entries whose term appears as one of `chunk_content`'s
+    /// identifiers, greedily filling `token_budget` whitespace-delimited
+    /// tokens (term plus definition) in alphabetical term order: an entry
+    /// that would overflow the budget is skipped whole rather than
+    /// truncated mid-definition, and matches are not ranked by relevance.
+    pub fn relevant_entries(&self, chunk_content: &str, token_budget: usize) -> Vec<(&str, &str)> {
+        let identifiers = extract_identifiers(chunk_content);
+        let mut used_tokens = 0;
+        let mut entries = Vec::new();
+
+        for (term, definition) in &self.terms {
+            if !identifiers.contains(term.as_str()) {
+                continue;
+            }
+            let cost = estimate_token_count(term) + estimate_token_count(definition);
+            if used_tokens + cost > token_budget {
+                continue;
+            }
+            used_tokens += cost;
+            entries.push((term.as_str(), definition.as_str()));
+        }
+        entries
+    }
+
+    /// Renders `entries` as a block meant to be prepended to a summarization
+    /// prompt. Returns an empty string for no entries, so a caller can
+    /// unconditionally splice the result into a prompt.
+    pub fn render_block(entries: &[(&str, &str)]) -> String {
+        if entries.is_empty() {
+            return String::new();
+        }
+        let lines: String = entries.iter().map(|(term, definition)| format!("- {term}: {definition}\n")).collect();
+        format!("Glossary of project-specific terms:\n{lines}\n")
+    }
+}
+
+/// Pulls identifier-shaped tokens (runs of alphanumerics/underscores) out of
+/// chunk content, as a cheap proxy for "names this chunk actually uses"
+/// without a real language-aware parser.
+fn extract_identifiers(content: &str) -> BTreeSet<&str> {
+    content
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn estimate_token_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary(pairs: &[(&str, &str)]) -> Glossary {
+        Glossary {
+            terms: pairs.iter().map(|(term, def)| (term.to_string(), def.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn matches_only_terms_present_as_identifiers() {
+        let glossary = glossary(&[("ACL", "access control list"), ("UNUSED", "not in this chunk")]);
+        let entries = glossary.relevant_entries("fn check(acl_entry: ACL) {}", 100);
+
+        assert_eq!(entries, vec![("ACL", "access control list")]);
+    }
+
+    #[test]
+    fn stops_injecting_once_token_budget_is_exhausted() {
+        let glossary = glossary(&[("ACL", "access control list"), ("MTU", "maximum transmission unit")]);
+        let entries = glossary.relevant_entries("uses ACL and MTU", 5);
+
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn render_block_is_empty_for_no_entries() {
+        assert_eq!(Glossary::render_block(&[]), "");
+        let block = Glossary::render_block(&[("ACL", "access control list")]);
+        assert!(block.contains("ACL: access control list"));
+    }
+
+    #[test]
+    fn load_from_file_round_trips_json() {
+        let dir = std::env::temp_dir().join("transfiguration-glossary");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("glossary.json");
+        fs::write(&path, r#"{"terms": {"ACL": "access control list"}}"#).unwrap();
+
+        let glossary = Glossary::load_from_file(&path).unwrap();
+        assert_eq!(glossary.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
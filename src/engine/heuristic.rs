@@ -0,0 +1,135 @@
+//! A zero-dependency [`InferenceBackend`] that pattern-matches syntax instead
+//! of running a model, for CI smoke tests and as the bottom rung of the
+//! degradation ladder when no real backend is configured or reachable.
+//!
+//! Output is deterministic and intentionally terse — it counts constructs
+//! (functions, structs, traits, tests) rather than attempting to describe
+//! what the code does, since it has no way to actually understand it.
+
+use crate::engine::{EngineError, InferenceBackend};
+
+/// Summarizes code by counting a handful of syntactic markers rather than
+/// understanding the code, so it never needs a model, a network call, or
+/// even much CPU.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicBackend;
+
+impl InferenceBackend for HeuristicBackend {
+    fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+        Ok(summarize_heuristically(prompt))
+    }
+}
+
+fn summarize_heuristically(code: &str) -> String {
+    let counts = SyntaxCounts::count(code);
+
+    if counts.is_empty() {
+        return "empty or non-code content".to_string();
+    }
+
+    let mut parts = Vec::new();
+    if counts.async_fns > 0 {
+        parts.push(format!("{} async fn{}", counts.async_fns, plural(counts.async_fns)));
+    }
+    if counts.fns > 0 {
+        parts.push(format!("{} fn{}", counts.fns, plural(counts.fns)));
+    }
+    if counts.structs > 0 {
+        parts.push(format!("{} struct{}", counts.structs, plural(counts.structs)));
+    }
+    if counts.enums > 0 {
+        parts.push(format!("{} enum{}", counts.enums, plural(counts.enums)));
+    }
+    if counts.traits > 0 {
+        parts.push(format!("{} trait{}", counts.traits, plural(counts.traits)));
+    }
+    if counts.tests > 0 {
+        parts.push(format!("{} test{}", counts.tests, plural(counts.tests)));
+    }
+
+    format!("Contains {}.", parts.join(", "))
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SyntaxCounts {
+    fns: usize,
+    async_fns: usize,
+    structs: usize,
+    enums: usize,
+    traits: usize,
+    tests: usize,
+}
+
+impl SyntaxCounts {
+    fn count(code: &str) -> Self {
+        let mut counts = SyntaxCounts::default();
+        for line in code.lines() {
+            let line = line.trim_start();
+            if line.starts_with("async fn ") || line.contains(" async fn ") {
+                counts.async_fns += 1;
+            } else if line.starts_with("fn ") || line.starts_with("pub fn ") || line.contains(" fn ") {
+                counts.fns += 1;
+            }
+            if line.starts_with("struct ") || line.starts_with("pub struct ") {
+                counts.structs += 1;
+            }
+            if line.starts_with("enum ") || line.starts_with("pub enum ") {
+                counts.enums += 1;
+            }
+            if line.starts_with("trait ") || line.starts_with("pub trait ") {
+                counts.traits += 1;
+            }
+            if line.starts_with("#[test]") || line.starts_with("#[tokio::test") {
+                counts.tests += 1;
+            }
+        }
+        counts
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fns == 0 && self.async_fns == 0 && self.structs == 0 && self.enums == 0 && self.traits == 0 && self.tests == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_mixed_constructs() {
+        let code = "struct Foo;\nasync fn go() {}\nfn helper() {}\n#[test]\nfn it_works() {}";
+        let summary = HeuristicBackend.generate_completion_text(code).unwrap();
+        assert!(summary.contains("1 async fn"));
+        assert!(summary.contains("1 struct"));
+        assert!(summary.contains("1 test"));
+    }
+
+    #[test]
+    fn pluralizes_counts_above_one() {
+        let code = "fn a() {}\nfn b() {}";
+        let summary = HeuristicBackend.generate_completion_text(code).unwrap();
+        assert!(summary.contains("2 fns"));
+    }
+
+    #[test]
+    fn empty_content_is_reported_explicitly() {
+        let summary = HeuristicBackend.generate_completion_text("").unwrap();
+        assert_eq!(summary, "empty or non-code content");
+    }
+
+    #[test]
+    fn is_deterministic_for_the_same_input() {
+        let code = "trait Speak { fn say(&self); }";
+        let first = HeuristicBackend.generate_completion_text(code).unwrap();
+        let second = HeuristicBackend.generate_completion_text(code).unwrap();
+        assert_eq!(first, second);
+    }
+}
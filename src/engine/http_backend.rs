@@ -0,0 +1,230 @@
+//! HTTP inference backend with shared token-bucket rate limiting.
+//!
+//! A remote inference API enforces its own rate limits; pushing every
+//! agent's requests straight through bursts past them into 429s.
+//! [`RateLimiter`] enforces a requests/sec cap and a tokens/min cap shared
+//! across every agent calling through the same [`HttpInferenceBackend`], and
+//! the backend itself retries a 429 using the response's `Retry-After`
+//! header instead of guessing a backoff.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{EngineError, InferenceBackend};
+
+/// Requests/sec and tokens/min limits a [`RateLimiter`] enforces.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    pub requests_per_sec: f64,
+    pub tokens_per_min: f64,
+}
+
+/// A single token bucket: refills continuously at `refill_per_sec`, capped
+/// at `capacity`, and reports how long a caller must wait for `cost` more
+/// units rather than ever blocking internally.
+struct Bucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Bucket {
+            capacity,
+            refill_per_sec,
+            available: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills based on elapsed time since the last call, then reserves
+    /// `cost` units (even if that drives `available` negative-equivalent,
+    /// clamped to zero) and returns how long the caller must wait before
+    /// that reservation is actually honored.
+    fn acquire(&mut self, cost: f64) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.available >= cost {
+            self.available -= cost;
+            return Duration::ZERO;
+        }
+
+        let deficit = cost - self.available;
+        self.available = 0.0;
+        Duration::from_secs_f64(deficit / self.refill_per_sec)
+    }
+}
+
+/// Thread-safe, shareable token-bucket rate limiter enforcing both a
+/// requests/sec and a tokens/min cap. Every [`HttpInferenceBackend`] sharing
+/// one `Arc<RateLimiter>` draws from the same pair of buckets, so the
+/// aggregate request rate across every agent stays under what the remote API
+/// allows.
+pub struct RateLimiter {
+    requests: Mutex<Bucket>,
+    tokens: Mutex<Bucket>,
+    total_wait_nanos: AtomicU64,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        RateLimiter {
+            requests: Mutex::new(Bucket::new(limits.requests_per_sec, limits.requests_per_sec)),
+            tokens: Mutex::new(Bucket::new(limits.tokens_per_min, limits.tokens_per_min / 60.0)),
+            total_wait_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Blocks the calling thread until both buckets have capacity for one
+    /// more request of `estimated_tokens`, recording the time spent waiting.
+    pub fn acquire(&self, estimated_tokens: usize) {
+        let request_wait = self.requests.lock().expect("rate limiter mutex poisoned").acquire(1.0);
+        if !request_wait.is_zero() {
+            std::thread::sleep(request_wait);
+        }
+        let token_wait = self
+            .tokens
+            .lock()
+            .expect("rate limiter mutex poisoned")
+            .acquire(estimated_tokens as f64);
+        if !token_wait.is_zero() {
+            std::thread::sleep(token_wait);
+        }
+        self.total_wait_nanos
+            .fetch_add((request_wait + token_wait).as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Total time every caller has spent waiting on this limiter, for
+    /// reporting alongside a run's other metrics.
+    pub fn total_wait(&self) -> Duration {
+        Duration::from_nanos(self.total_wait_nanos.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CompletionRequest<'a> {
+    prompt: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionResponse {
+    text: String,
+}
+
+/// How many times [`HttpInferenceBackend`] retries a 429 before giving up.
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// Fallback wait when a 429 response carries no (or an unparseable)
+/// `Retry-After` header.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Calls a remote inference API over HTTP, rate-limited by a shared
+/// [`RateLimiter`] and automatically retrying a 429 using the response's
+/// `Retry-After` header.
+pub struct HttpInferenceBackend {
+    client: reqwest::blocking::Client,
+    endpoint: String,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl HttpInferenceBackend {
+    pub fn new(endpoint: impl Into<String>, rate_limiter: Arc<RateLimiter>) -> Self {
+        HttpInferenceBackend {
+            client: reqwest::blocking::Client::new(),
+            endpoint: endpoint.into(),
+            rate_limiter,
+        }
+    }
+}
+
+impl InferenceBackend for HttpInferenceBackend {
+    fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+        let estimated_tokens = prompt.split_whitespace().count();
+
+        for attempt in 0..=MAX_RATE_LIMIT_RETRIES {
+            self.rate_limiter.acquire(estimated_tokens);
+
+            let response = self
+                .client
+                .post(&self.endpoint)
+                .json(&CompletionRequest { prompt })
+                .send()
+                .map_err(|e| EngineError::BackendFailed(e.to_string()))?;
+
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                if attempt == MAX_RATE_LIMIT_RETRIES {
+                    return Err(EngineError::BackendFailed(format!(
+                        "rate limited after {MAX_RATE_LIMIT_RETRIES} retries"
+                    )));
+                }
+                std::thread::sleep(retry_after_duration(&response).unwrap_or(DEFAULT_RETRY_AFTER));
+                continue;
+            }
+
+            let response = response.error_for_status().map_err(|e| EngineError::BackendFailed(e.to_string()))?;
+            let parsed: CompletionResponse = response.json().map_err(|e| EngineError::BackendFailed(e.to_string()))?;
+            return Ok(parsed.text);
+        }
+
+        unreachable!("the loop above always returns on its final iteration")
+    }
+}
+
+fn retry_after_duration(response: &reqwest::blocking::Response) -> Option<Duration> {
+    let header = response.headers().get(reqwest::header::RETRY_AFTER)?;
+    let seconds: u64 = header.to_str().ok()?.parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_does_not_wait_while_capacity_remains() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_sec: 100.0,
+            tokens_per_min: 100_000.0,
+        });
+        let started_at = Instant::now();
+        limiter.acquire(10);
+        assert!(started_at.elapsed() < Duration::from_millis(50));
+        assert!(limiter.total_wait().is_zero());
+    }
+
+    #[test]
+    fn acquire_waits_once_the_request_bucket_is_exhausted() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_sec: 10.0,
+            tokens_per_min: 1_000_000.0,
+        });
+        // Drain the request bucket's full starting capacity immediately.
+        for _ in 0..10 {
+            limiter.acquire(1);
+        }
+        let started_at = Instant::now();
+        limiter.acquire(1);
+        assert!(started_at.elapsed() >= Duration::from_millis(50));
+        assert!(!limiter.total_wait().is_zero());
+    }
+
+    #[test]
+    fn acquire_waits_once_the_token_bucket_is_exhausted() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_sec: 1_000_000.0,
+            tokens_per_min: 600.0, // 10 tokens/sec refill
+        });
+        limiter.acquire(600); // drains the starting capacity exactly
+        let started_at = Instant::now();
+        limiter.acquire(1);
+        assert!(started_at.elapsed() >= Duration::from_millis(90));
+    }
+}
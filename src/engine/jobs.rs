@@ -0,0 +1,388 @@
+//! Concurrent scheduling of multiple summarization jobs (e.g. several
+//! repositories in service mode) over one shared session pool.
+//!
+//! Before this, service mode could only drive one [`ParallelAgentSystem`]
+//! run at a time, so a second repository queued behind however long the
+//! first one's sessions took. [`MultiJobScheduler`] instead runs up to
+//! `max_concurrent_jobs` jobs at once, all drawing sessions from one shared
+//! pool sized by `session_pool_size`.
+//!
+//! Fair share is approximate rather than a true weighted fair queue: each
+//! job caps its own in-flight sessions at its `weight` before those sessions
+//! ever compete for a shared pool permit, so a job with twice the weight of
+//! another keeps roughly twice as many requests queued for the shared pool
+//! at any moment — proportionally more of the pool's grants go to it without
+//! letting it claim every permit in the pool for itself. Failures are
+//! isolated per job: each job drives its own inner [`JoinSet`], so one job's
+//! [`ErrorPolicy::FailFast`] abort never touches another job's sessions.
+
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::chunk::ChunkId;
+use crate::engine::agents::{ActiveAgentGuard, AgentSession, ErrorPolicy, RunOutcome};
+use crate::engine::{EngineError, InferenceBackend};
+use crate::metrics::ParallelMetrics;
+use crate::model::context::estimate_token_count;
+
+/// Identifies one job within a [`MultiJobScheduler`] run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(pub u64);
+
+/// One job submitted to a [`MultiJobScheduler`]: the sessions to run, how
+/// much of the shared session pool it's entitled to relative to other
+/// concurrently active jobs, and how it reacts to its own sessions failing.
+pub struct JobSpec {
+    pub id: JobId,
+    pub weight: u32,
+    pub sessions: Vec<AgentSession>,
+    pub error_policy: ErrorPolicy,
+}
+
+/// One job's [`RunOutcome`] plus the id and live progress it belongs to.
+pub struct JobOutcome {
+    pub job_id: JobId,
+    pub outcome: RunOutcome,
+    pub progress: Arc<ParallelMetrics>,
+}
+
+/// Bounds on shared resources a [`MultiJobScheduler`] run may use.
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerLimits {
+    /// How many jobs may have sessions in flight at once; extra jobs wait
+    /// for a slot to free up before their first session starts.
+    pub max_concurrent_jobs: usize,
+    /// How many sessions across every active job may run inference at once.
+    pub session_pool_size: usize,
+}
+
+impl Default for SchedulerLimits {
+    fn default() -> Self {
+        SchedulerLimits {
+            max_concurrent_jobs: 4,
+            session_pool_size: 8,
+        }
+    }
+}
+
+impl SchedulerLimits {
+    /// Resolves a [`crate::validation::HardwarePreset`] to the
+    /// [`SchedulerLimits`] it stands for, the parallelism-side counterpart
+    /// to [`crate::validation::RunConfig::from_preset`].
+    pub fn from_preset(preset: crate::validation::HardwarePreset) -> Self {
+        use crate::validation::HardwarePreset;
+        match preset {
+            HardwarePreset::Laptop8Gb => SchedulerLimits::laptop_8gb(),
+            HardwarePreset::Workstation32GbGpu => SchedulerLimits::workstation_32gb_gpu(),
+            HardwarePreset::Ci2Core => SchedulerLimits::ci_2core(),
+        }
+    }
+
+    /// Matches [`crate::validation::RunConfig::laptop_8gb`]: one job at a
+    /// time over a small shared session pool, so a laptop CPU backend
+    /// never has more concurrent inference calls in flight than it has
+    /// cores to spare.
+    pub fn laptop_8gb() -> Self {
+        SchedulerLimits {
+            max_concurrent_jobs: 1,
+            session_pool_size: 2,
+        }
+    }
+
+    /// Matches [`crate::validation::RunConfig::workstation_32gb_gpu`]: several
+    /// jobs at once over a large shared session pool, since a single
+    /// GPU-backed backend can usually serve many concurrent requests without
+    /// each one fighting the others for a CPU core the way a CPU backend
+    /// would.
+    pub fn workstation_32gb_gpu() -> Self {
+        SchedulerLimits {
+            max_concurrent_jobs: 4,
+            session_pool_size: 16,
+        }
+    }
+
+    /// Matches [`crate::validation::RunConfig::ci_2core`]: one job, one
+    /// in-flight session — a 2-core runner has no spare capacity for
+    /// concurrent inference calls to make progress on at once.
+    pub fn ci_2core() -> Self {
+        SchedulerLimits {
+            max_concurrent_jobs: 1,
+            session_pool_size: 1,
+        }
+    }
+
+    /// Sizes `session_pool_size` from [`crate::model::memory_planner::plan_session_pool`]'s
+    /// `max_safe_sessions` instead of a fixed preset, so a differently-sized
+    /// or differently-quantized model than the ones [`Self::laptop_8gb`],
+    /// [`Self::workstation_32gb_gpu`], and [`Self::ci_2core`] were tuned for
+    /// gets a pool that actually fits its own memory footprint.
+    /// `max_concurrent_jobs` is left to the caller, since the memory planner
+    /// has no opinion on job concurrency — only on how many sessions can
+    /// safely share the pool.
+    pub fn from_memory_plan(plan: crate::model::memory_planner::SessionPoolPlan, max_concurrent_jobs: usize) -> Self {
+        SchedulerLimits {
+            max_concurrent_jobs,
+            session_pool_size: plan.max_safe_sessions,
+        }
+    }
+}
+
+/// Runs many [`JobSpec`]s concurrently over one shared session pool; see the
+/// module docs for the fairness and failure-isolation guarantees.
+pub struct MultiJobScheduler<B: InferenceBackend + Send + Sync + 'static> {
+    backend: Arc<B>,
+    limits: SchedulerLimits,
+}
+
+impl<B: InferenceBackend + Send + Sync + 'static> MultiJobScheduler<B> {
+    pub fn new(backend: Arc<B>, limits: SchedulerLimits) -> Self {
+        MultiJobScheduler { backend, limits }
+    }
+
+    /// Runs every job to completion. Returns once every job's sessions have
+    /// all been joined or aborted — never before — in whatever order jobs
+    /// happen to finish in.
+    pub async fn run_jobs(&self, jobs: Vec<JobSpec>) -> Vec<JobOutcome> {
+        let job_admission = Arc::new(Semaphore::new(self.limits.max_concurrent_jobs.max(1)));
+        let session_pool = Arc::new(Semaphore::new(self.limits.session_pool_size.max(1)));
+
+        let mut join_set: JoinSet<JobOutcome> = JoinSet::new();
+        for job in jobs {
+            let backend = Arc::clone(&self.backend);
+            let job_admission = Arc::clone(&job_admission);
+            let session_pool = Arc::clone(&session_pool);
+
+            join_set.spawn(async move {
+                let _job_permit = job_admission.acquire_owned().await.expect("job admission semaphore never closed");
+                let job_id = job.id;
+                let (outcome, progress) = run_single_job(backend, session_pool, job).await;
+                JobOutcome { job_id, outcome, progress }
+            });
+        }
+
+        let mut outcomes = Vec::with_capacity(join_set.len());
+        while let Some(joined) = join_set.join_next().await {
+            if let Ok(outcome) = joined {
+                outcomes.push(outcome);
+            }
+        }
+        outcomes
+    }
+}
+
+async fn run_single_job<B: InferenceBackend + Send + Sync + 'static>(
+    backend: Arc<B>,
+    session_pool: Arc<Semaphore>,
+    job: JobSpec,
+) -> (RunOutcome, Arc<ParallelMetrics>) {
+    let local_share = Arc::new(Semaphore::new(job.weight.max(1) as usize));
+    let progress = Arc::new(ParallelMetrics::default());
+    let error_policy = job.error_policy;
+
+    let mut inner: JoinSet<(ChunkId, Result<String, EngineError>)> = JoinSet::new();
+    for session in job.sessions {
+        let backend = Arc::clone(&backend);
+        let local_share = Arc::clone(&local_share);
+        let session_pool = Arc::clone(&session_pool);
+        let progress = Arc::clone(&progress);
+        let chunk_id = session.chunk.id;
+        let prompt = session.chunk.content;
+
+        // Constructed (and `agent_started()` called) before the `async move`
+        // block, not inside it — see `ActiveAgentGuard::new`'s doc comment
+        // for why that ordering is what keeps `active_agents` accurate under
+        // `abort_all()`.
+        let active_guard = ActiveAgentGuard::new(Arc::clone(&progress));
+        inner.spawn(async move {
+            let _active_guard = active_guard;
+
+            // Caps this job's own in-flight sessions at its weight before
+            // it ever competes for a shared pool permit; see the module
+            // docs for why this approximates weighted fair share.
+            let _local_permit = local_share.acquire().await.expect("job-local semaphore never closed");
+            let _pool_permit = session_pool.acquire().await.expect("session pool semaphore never closed");
+
+            let started_at = std::time::Instant::now();
+            let result = backend.generate_completion_text(&prompt);
+            match &result {
+                Ok(text) => {
+                    progress.record_chunk_completed(started_at.elapsed());
+                    progress.record_tokens_generated(estimate_token_count(text) as u64);
+                }
+                Err(_) => progress.record_chunk_failed(),
+            }
+            (chunk_id, result)
+        });
+    }
+
+    let mut outcome = RunOutcome::default();
+    let mut abort_remaining = false;
+
+    while let Some(joined) = inner.join_next().await {
+        if abort_remaining {
+            continue;
+        }
+        match joined {
+            Ok((chunk_id, Ok(text))) => outcome.summaries.push((chunk_id, text)),
+            Ok((chunk_id, Err(error))) => {
+                outcome.errors.push((chunk_id, error));
+                if error_policy == ErrorPolicy::FailFast {
+                    inner.abort_all();
+                    abort_remaining = true;
+                }
+            }
+            Err(join_error) if join_error.is_cancelled() => {
+                // Our own abort_all(); not a new failure to report.
+            }
+            Err(join_error) => {
+                outcome
+                    .errors
+                    .push((ChunkId(0), EngineError::BackendFailed(join_error.to_string())));
+                if error_policy == ErrorPolicy::FailFast {
+                    inner.abort_all();
+                    abort_remaining = true;
+                }
+            }
+        }
+    }
+
+    (outcome, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::Chunk;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingBackend {
+        calls: AtomicUsize,
+        fail_on: Option<&'static str>,
+    }
+
+    impl InferenceBackend for CountingBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail_on == Some(prompt) {
+                return Err(EngineError::BackendFailed(format!("refused: {prompt}")));
+            }
+            Ok(format!("summary of {prompt}"))
+        }
+    }
+
+    fn session(id: u64, content: &str) -> AgentSession {
+        AgentSession {
+            chunk: Chunk::new(ChunkId(id), "f.rs", content),
+        }
+    }
+
+    fn job(id: u64, weight: u32, error_policy: ErrorPolicy, sessions: Vec<AgentSession>) -> JobSpec {
+        JobSpec {
+            id: JobId(id),
+            weight,
+            sessions,
+            error_policy,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn every_job_completes_and_reports_its_own_outcome() {
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: None });
+        let scheduler = MultiJobScheduler::new(Arc::clone(&backend), SchedulerLimits::default());
+
+        let outcomes = scheduler
+            .run_jobs(vec![
+                job(1, 1, ErrorPolicy::CollectAll, vec![session(1, "a"), session(2, "b")]),
+                job(2, 1, ErrorPolicy::CollectAll, vec![session(3, "c")]),
+            ])
+            .await;
+
+        assert_eq!(outcomes.len(), 2);
+        let total_summaries: usize = outcomes.iter().map(|o| o.outcome.summaries.len()).sum();
+        assert_eq!(total_summaries, 3);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+        for outcome in &outcomes {
+            assert_eq!(outcome.progress.snapshot().active_agents, 0);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn a_fail_fast_job_never_aborts_another_jobs_sessions() {
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: Some("bad") });
+        let scheduler = MultiJobScheduler::new(Arc::clone(&backend), SchedulerLimits::default());
+
+        let outcomes = scheduler
+            .run_jobs(vec![
+                job(1, 1, ErrorPolicy::FailFast, vec![session(1, "bad"), session(2, "good-a")]),
+                job(2, 1, ErrorPolicy::CollectAll, vec![session(3, "good-b"), session(4, "good-c")]),
+            ])
+            .await;
+
+        let failing_job = outcomes.iter().find(|o| o.job_id == JobId(1)).unwrap();
+        let healthy_job = outcomes.iter().find(|o| o.job_id == JobId(2)).unwrap();
+
+        assert_eq!(failing_job.outcome.errors.len(), 1);
+        assert_eq!(healthy_job.outcome.summaries.len(), 2);
+        assert!(healthy_job.outcome.errors.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn extra_jobs_beyond_the_concurrency_limit_still_all_complete() {
+        let backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0), fail_on: None });
+        let limits = SchedulerLimits { max_concurrent_jobs: 1, session_pool_size: 2 };
+        let scheduler = MultiJobScheduler::new(Arc::clone(&backend), limits);
+
+        let jobs = (0..5).map(|i| job(i, 1, ErrorPolicy::CollectAll, vec![session(i, "ok")])).collect();
+        let outcomes = scheduler.run_jobs(jobs).await;
+
+        assert_eq!(outcomes.len(), 5);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 5);
+        assert!(outcomes.iter().all(|o| o.outcome.errors.is_empty()));
+    }
+
+    #[test]
+    fn scheduler_limits_from_preset_matches_the_named_constructors() {
+        use crate::validation::HardwarePreset;
+
+        assert_eq!(
+            SchedulerLimits::from_preset(HardwarePreset::Laptop8Gb).max_concurrent_jobs,
+            SchedulerLimits::laptop_8gb().max_concurrent_jobs
+        );
+        assert_eq!(
+            SchedulerLimits::from_preset(HardwarePreset::Workstation32GbGpu).session_pool_size,
+            SchedulerLimits::workstation_32gb_gpu().session_pool_size
+        );
+        assert_eq!(
+            SchedulerLimits::from_preset(HardwarePreset::Ci2Core).session_pool_size,
+            SchedulerLimits::ci_2core().session_pool_size
+        );
+    }
+
+    #[test]
+    fn ci_2core_is_the_most_conservative_scheduler_preset() {
+        let ci = SchedulerLimits::ci_2core();
+        let laptop = SchedulerLimits::laptop_8gb();
+        let workstation = SchedulerLimits::workstation_32gb_gpu();
+
+        assert!(ci.session_pool_size <= laptop.session_pool_size);
+        assert!(laptop.session_pool_size <= workstation.session_pool_size);
+    }
+
+    #[test]
+    fn from_memory_plan_sizes_the_pool_from_max_safe_sessions_and_keeps_the_caller_s_job_concurrency() {
+        use crate::model::memory_planner::SessionPoolPlan;
+
+        let plan = SessionPoolPlan {
+            weights_bytes: 250_000_000,
+            per_session_kv_cache_bytes: 1_000_000,
+            max_safe_sessions: 5,
+        };
+
+        let limits = SchedulerLimits::from_memory_plan(plan, 3);
+
+        assert_eq!(limits.session_pool_size, 5);
+        assert_eq!(limits.max_concurrent_jobs, 3);
+    }
+}
@@ -0,0 +1,90 @@
+//! Compatibility facade for the legacy `QwenSummarizer` API.
+//!
+//! Two call sites this crate absorbed, `rust-codebase` and
+//! `A02OSSToolsPOC`, each carried their own `QwenSummarizer` with a
+//! different method surface, and one of the two returned an error
+//! unconditionally rather than ever producing a summary. [`QwenSummarizer`]
+//! here keeps the old method names so those callers compile unchanged, but
+//! every method is a thin, `#[deprecated]` wrapper over [`SummaryRun`] and
+//! [`ParallelAgentSystem`], so callers now get a real summary back instead
+//! of the old stub's error.
+
+use std::sync::Arc;
+
+use crate::chunk::{Chunk, ChunkId};
+use crate::engine::agents::{AgentSession, ErrorPolicy, ParallelAgentSystem};
+use crate::engine::{EngineError, InferenceBackend, SummaryRun};
+
+/// Legacy single-file-or-batch entry point, now backed by the working
+/// engine pipeline instead of either diverging original implementation.
+pub struct QwenSummarizer<B: InferenceBackend + Send + Sync + 'static> {
+    backend: Arc<B>,
+}
+
+impl<B: InferenceBackend + Send + Sync + 'static> QwenSummarizer<B> {
+    pub fn new(backend: B) -> Self {
+        QwenSummarizer {
+            backend: Arc::new(backend),
+        }
+    }
+
+    /// Legacy single-file entry point. Runs [`SummaryRun::summarize_all_chunks`]
+    /// on one chunk and returns its text directly.
+    #[deprecated(note = "use `SummaryRun::summarize_all_chunks` directly")]
+    pub fn summarize(&self, source_path: &str, content: &str) -> Result<String, EngineError> {
+        let chunk = Chunk::new(ChunkId(0), source_path, content);
+        let run = SummaryRun::summarize_all_chunks(Arc::clone(&self.backend), vec![chunk])?;
+        Ok(run
+            .latest_summary_for(ChunkId(0))
+            .expect("chunk 0 was just summarized by summarize_all_chunks")
+            .text
+            .clone())
+    }
+
+    /// Legacy batch entry point. Runs every file concurrently through
+    /// [`ParallelAgentSystem::run_all`] under [`ErrorPolicy::CollectAll`],
+    /// so one failing file no longer takes the whole batch down with it.
+    #[deprecated(note = "use `engine::agents::ParallelAgentSystem::run_all` directly")]
+    pub async fn summarize_many(&self, files: Vec<(String, String)>) -> Vec<(ChunkId, String)> {
+        let system = ParallelAgentSystem::new(Arc::clone(&self.backend), ErrorPolicy::CollectAll);
+        let sessions = files
+            .into_iter()
+            .enumerate()
+            .map(|(index, (source_path, content))| AgentSession {
+                chunk: Chunk::new(ChunkId(index as u64), source_path, content),
+            })
+            .collect();
+        system.run_all(sessions).await.summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+
+    impl InferenceBackend for EchoBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("echo: {}", prompt.len()))
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn summarize_returns_a_real_summary_instead_of_the_old_stub_error() {
+        let summarizer = QwenSummarizer::new(EchoBackend);
+        let summary = summarizer.summarize("f.rs", "fn f() {}").unwrap();
+        assert!(summary.starts_with("echo: "));
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn summarize_many_runs_every_file() {
+        let summarizer = QwenSummarizer::new(EchoBackend);
+        let results = summarizer
+            .summarize_many(vec![("a.rs".to_string(), "a".to_string()), ("b.rs".to_string(), "bb".to_string())])
+            .await;
+        assert_eq!(results.len(), 2);
+    }
+}
@@ -0,0 +1,694 @@
+//! Summarization engine: turns [`Chunk`]s into natural-language summaries and
+//! supports interactive, multi-turn refinement of a given chunk's summary.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::chunk::{Chunk, ChunkClass, ChunkId};
+use crate::engine::glossary::Glossary;
+use crate::engine::near_duplicate::{ChangeDetectionPolicy, UNCHANGED_NEAR_DUPLICATE_TEXT};
+use crate::engine::transform::{TransformOutcome, TransformPipeline};
+use crate::model::card::ModelCard;
+use crate::validation::Language;
+
+#[cfg(feature = "service")]
+pub mod access_control;
+#[cfg(feature = "service")]
+pub mod adaptive_concurrency;
+#[cfg(feature = "service")]
+pub mod agents;
+pub mod batching;
+pub mod capabilities;
+#[cfg(feature = "service")]
+pub mod checkpoint;
+pub mod deadline;
+pub mod decode;
+pub mod drift;
+pub mod extractive;
+pub mod glossary;
+pub mod heuristic;
+#[cfg(feature = "http-backend")]
+pub mod http_backend;
+#[cfg(feature = "service")]
+pub mod jobs;
+#[cfg(feature = "service")]
+pub mod legacy;
+pub mod near_duplicate;
+pub mod ort_compat;
+pub mod packing;
+pub mod prompt_template;
+pub mod replay;
+#[cfg(feature = "service")]
+pub mod retry_budget;
+pub mod retry_policy;
+pub mod session_pool;
+pub mod tokenizer;
+pub mod transform;
+#[cfg(feature = "service")]
+pub mod scheduler;
+#[cfg(feature = "service")]
+pub mod spillover;
+#[cfg(all(feature = "worker-isolation", unix))]
+pub mod worker_pool;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EngineError {
+    #[error("inference backend failed: {0}")]
+    BackendFailed(String),
+    #[error("no summary exists yet for chunk {0:?}")]
+    UnknownChunk(ChunkId),
+}
+
+impl EngineError {
+    /// A short, stable label for grouping failures by kind — used by
+    /// [`agents::RunOutcome::failure_report`] to group failed chunks the way
+    /// a reader would otherwise grep log lines for.
+    pub fn category(&self) -> &'static str {
+        match self {
+            EngineError::BackendFailed(_) => "backend_failed",
+            EngineError::UnknownChunk(_) => "unknown_chunk",
+        }
+    }
+}
+
+/// A swappable text-generation backend. Concrete backends (ONNX-based, a
+/// heuristic zero-dependency fallback, etc.) implement this and plug into
+/// [`SummaryRun`] without the engine knowing which one it got.
+pub trait InferenceBackend {
+    fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError>;
+}
+
+/// Lets a shared, reference-counted backend (e.g. one also held by a
+/// [`agents::ParallelAgentSystem`]) be handed to [`SummaryRun`] as well,
+/// without cloning the backend itself.
+impl<T: InferenceBackend + ?Sized> InferenceBackend for Arc<T> {
+    fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+        (**self).generate_completion_text(prompt)
+    }
+}
+
+/// One summary produced for a chunk, either the initial pass or a refinement.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub chunk_id: ChunkId,
+    pub class: ChunkClass,
+    pub text: String,
+    pub instruction: Option<String>,
+    pub source: SummarySource,
+    /// The language this summary's run was configured to write in (see
+    /// [`SummaryRun::summarize_all_chunks_with_language`]), regardless of
+    /// which [`SummarySource`] actually produced the text — an extractive
+    /// or unchanged-near-duplicate summary still records the run's
+    /// configured language rather than attempting to detect the text's
+    /// actual language.
+    pub language: Language,
+}
+
+/// How a [`Summary`]'s text was produced, so a report can tell a real model
+/// call from a free one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummarySource {
+    /// Produced by a [`InferenceBackend::generate_completion_text`] call.
+    Model,
+    /// Lifted from an existing doc comment by
+    /// [`extractive::summarize_extractively`] with no inference call.
+    Extractive,
+    /// Content matched a prior run's content closely enough, per
+    /// [`near_duplicate::is_near_duplicate`], that re-summarizing was
+    /// skipped; the summary text is [`near_duplicate::UNCHANGED_NEAR_DUPLICATE_TEXT`]
+    /// rather than a real summary.
+    UnchangedNearDuplicate,
+}
+
+struct ChunkHistory {
+    chunk: Chunk,
+    summaries: Vec<Summary>,
+    transform_outcomes: Vec<TransformOutcome>,
+}
+
+/// Holds the warm backend session and the summary history for every chunk in
+/// a run, so a caller can ask for follow-up refinements without re-paying
+/// session startup cost or re-sending the whole chunk from scratch.
+pub struct SummaryRun<B: InferenceBackend> {
+    backend: B,
+    histories: HashMap<ChunkId, ChunkHistory>,
+    model_card: Option<ModelCard>,
+    language: Language,
+}
+
+impl<B: InferenceBackend> SummaryRun<B> {
+    /// Produces an initial summary for every chunk, keeping the backend warm
+    /// for any later [`SummaryRun::refine`] calls. Takes `chunks` directly —
+    /// a caller with its own splitter can hand this function chunks built
+    /// with [`Chunk::builder`] and never touch this crate's own chunkers at
+    /// all; [`ChunkBuilder::build`](crate::chunk::ChunkBuilder::build)'s
+    /// size/token validation already happened by the time `chunks` gets here.
+    pub fn summarize_all_chunks(backend: B, chunks: Vec<Chunk>) -> Result<Self, EngineError> {
+        Self::summarize_all_chunks_with_options(
+            backend,
+            chunks,
+            None,
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+            false,
+            None,
+            None,
+            Language::default(),
+        )
+    }
+
+    /// Like [`SummaryRun::summarize_all_chunks`], but asks the model to write
+    /// every summary in `language` instead of the unsteered default
+    /// (English) — see [`Language::prompt_directive`].
+    pub fn summarize_all_chunks_with_language(backend: B, chunks: Vec<Chunk>, language: Language) -> Result<Self, EngineError> {
+        Self::summarize_all_chunks_with_options(backend, chunks, None, DEFAULT_GLOSSARY_TOKEN_BUDGET, false, None, None, language)
+    }
+
+    /// Like [`SummaryRun::summarize_all_chunks`], but injects relevant
+    /// [`Glossary`] entries (trimmed to `glossary_token_budget` tokens) into
+    /// each chunk's initial prompt, so domain-specific terms the model would
+    /// otherwise mangle come with their definition attached.
+    pub fn summarize_all_chunks_with_glossary(
+        backend: B,
+        chunks: Vec<Chunk>,
+        glossary: Option<&Glossary>,
+        glossary_token_budget: usize,
+    ) -> Result<Self, EngineError> {
+        Self::summarize_all_chunks_with_options(
+            backend,
+            chunks,
+            glossary,
+            glossary_token_budget,
+            false,
+            None,
+            None,
+            Language::default(),
+        )
+    }
+
+    /// Like [`SummaryRun::summarize_all_chunks`], but runs `pipeline` over
+    /// each chunk's content before it's classified or prompted — see
+    /// [`transform::TransformPipeline`]. Per-chunk transform timings are
+    /// available afterwards via [`SummaryRun::transform_outcomes_for`].
+    pub fn summarize_all_chunks_with_transforms(
+        backend: B,
+        chunks: Vec<Chunk>,
+        pipeline: &TransformPipeline,
+    ) -> Result<Self, EngineError> {
+        Self::summarize_all_chunks_with_options(
+            backend,
+            chunks,
+            None,
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+            false,
+            None,
+            Some(pipeline),
+            Language::default(),
+        )
+    }
+
+    /// Like [`SummaryRun::summarize_all_chunks`], but skips re-summarizing a
+    /// chunk whose content is a near-duplicate (per
+    /// [`near_duplicate::is_near_duplicate`]) of `previous_contents`' entry
+    /// for its [`Chunk::source_path`], recording
+    /// [`near_duplicate::UNCHANGED_NEAR_DUPLICATE_TEXT`] as its summary
+    /// instead of paying for a model call. A path absent from
+    /// `previous_contents` (new file) is always summarized normally.
+    pub fn summarize_all_chunks_with_change_detection(
+        backend: B,
+        chunks: Vec<Chunk>,
+        previous_contents: &HashMap<PathBuf, String>,
+        policy: &ChangeDetectionPolicy,
+    ) -> Result<Self, EngineError> {
+        Self::summarize_all_chunks_with_options(
+            backend,
+            chunks,
+            None,
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+            false,
+            Some((previous_contents, policy)),
+            None,
+            Language::default(),
+        )
+    }
+
+    /// Full-option entry point the other `summarize_all_chunks*` constructors
+    /// delegate to. When `use_extractive_fallback` is set, a chunk whose
+    /// leading doc comment clears [`extractive::extract_doc_comment_summary`]'s
+    /// quality bar gets that text as its summary with no inference call at
+    /// all; every other chunk falls through to the usual backend call.
+    /// `change_detection`, when set, is checked before the extractive
+    /// fallback — see [`SummaryRun::summarize_all_chunks_with_change_detection`].
+    /// `transform_pipeline`, when set, rewrites each chunk's content — see
+    /// [`transform::TransformPipeline`] — before any of the above run, so
+    /// change detection and extractive fallback both see the rewritten
+    /// content rather than the original. `language` is asked for in every
+    /// model-generated prompt (see [`Language::prompt_directive`]) and
+    /// recorded on every produced [`Summary`], model-generated or not.
+    #[allow(clippy::too_many_arguments)]
+    pub fn summarize_all_chunks_with_options(
+        backend: B,
+        chunks: Vec<Chunk>,
+        glossary: Option<&Glossary>,
+        glossary_token_budget: usize,
+        use_extractive_fallback: bool,
+        change_detection: Option<(&HashMap<PathBuf, String>, &ChangeDetectionPolicy)>,
+        transform_pipeline: Option<&TransformPipeline>,
+        language: Language,
+    ) -> Result<Self, EngineError> {
+        let mut histories = HashMap::with_capacity(chunks.len());
+        for mut chunk in chunks {
+            let transform_outcomes = if let Some(pipeline) = transform_pipeline {
+                let (transformed_content, outcomes) = pipeline.apply(&chunk.content);
+                chunk.content = transformed_content;
+                outcomes
+            } else {
+                Vec::new()
+            };
+
+            if let Some((previous_contents, policy)) = change_detection {
+                if let Some(previous) = previous_contents.get(&chunk.source_path) {
+                    if near_duplicate::is_near_duplicate(previous, &chunk.content, policy) {
+                        let class = chunk.classify();
+                        let summary = Summary {
+                            chunk_id: chunk.id,
+                            class,
+                            text: UNCHANGED_NEAR_DUPLICATE_TEXT.to_string(),
+                            instruction: None,
+                            source: SummarySource::UnchangedNearDuplicate,
+                            language,
+                        };
+                        histories.insert(
+                            chunk.id,
+                            ChunkHistory {
+                                chunk,
+                                summaries: vec![summary],
+                                transform_outcomes,
+                            },
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            if use_extractive_fallback {
+                if let Some(summary) = extractive::summarize_extractively(&chunk, language) {
+                    histories.insert(
+                        chunk.id,
+                        ChunkHistory {
+                            chunk,
+                            summaries: vec![summary],
+                            transform_outcomes,
+                        },
+                    );
+                    continue;
+                }
+            }
+
+            let class = chunk.classify();
+            let glossary_block = glossary
+                .map(|glossary| Glossary::render_block(&glossary.relevant_entries(&chunk.content, glossary_token_budget)))
+                .unwrap_or_default();
+            let prompt = format!("{glossary_block}{}", initial_summary_prompt(&chunk, class, language));
+            let text = backend.generate_completion_text(&prompt)?;
+            let summary = Summary {
+                chunk_id: chunk.id,
+                class,
+                text,
+                instruction: None,
+                source: SummarySource::Model,
+                language,
+            };
+            histories.insert(
+                chunk.id,
+                ChunkHistory {
+                    chunk,
+                    summaries: vec![summary],
+                    transform_outcomes,
+                },
+            );
+        }
+        Ok(SummaryRun { backend, histories, model_card: None, language })
+    }
+
+    /// Attaches licensing/capability metadata discovered for the backend's
+    /// model, so [`SummaryRun::model_card`] can report it and a caller can
+    /// carry the license into a run's manifest. See
+    /// [`crate::model::card::load_model_card`].
+    pub fn with_model_card(mut self, model_card: ModelCard) -> Self {
+        self.model_card = Some(model_card);
+        self
+    }
+
+    /// The licensing/capability metadata attached via
+    /// [`SummaryRun::with_model_card`], if any.
+    pub fn model_card(&self) -> Option<&ModelCard> {
+        self.model_card.as_ref()
+    }
+
+    pub fn latest_summary_for(&self, chunk_id: ChunkId) -> Option<&Summary> {
+        self.histories.get(&chunk_id)?.summaries.last()
+    }
+
+    /// Per-transform timing/error outcomes recorded for `chunk_id` by the
+    /// [`transform::TransformPipeline`] passed to
+    /// [`SummaryRun::summarize_all_chunks_with_transforms`] (or
+    /// [`SummaryRun::summarize_all_chunks_with_options`]'s
+    /// `transform_pipeline` parameter), or an empty slice if no pipeline was
+    /// configured for this run.
+    pub fn transform_outcomes_for(&self, chunk_id: ChunkId) -> &[TransformOutcome] {
+        self.histories.get(&chunk_id).map(|history| history.transform_outcomes.as_slice()).unwrap_or(&[])
+    }
+
+    /// Re-prompts the backend for `chunk_id` with the prior summary plus
+    /// `instruction` (e.g. `"focus on error handling"`), reusing the same
+    /// warm backend session, and returns the newly refined summary.
+    pub fn refine(&mut self, chunk_id: ChunkId, instruction: &str) -> Result<&Summary, EngineError> {
+        let history = self
+            .histories
+            .get_mut(&chunk_id)
+            .ok_or(EngineError::UnknownChunk(chunk_id))?;
+        let prior = history
+            .summaries
+            .last()
+            .expect("every tracked chunk has at least its initial summary");
+        let prompt = refinement_prompt(&history.chunk, &prior.text, instruction, self.language);
+        let text = self.backend.generate_completion_text(&prompt)?;
+        history.summaries.push(Summary {
+            chunk_id,
+            class: prior.class,
+            text,
+            instruction: Some(instruction.to_string()),
+            source: SummarySource::Model,
+            language: self.language,
+        });
+        Ok(history.summaries.last().unwrap())
+    }
+}
+
+/// A single summary paired with how long it took to produce, so editor
+/// integrations can tell whether they stayed within budget.
+pub struct ChunkResult {
+    pub summary: Summary,
+    pub latency: Duration,
+    /// How many inference attempts produced this result. Always `1` for
+    /// [`summarize_span`]/[`summarize_span_with_language`], which make
+    /// exactly one attempt so latency stays predictable; only
+    /// [`summarize_span_with_retry_policy`] can retry and report more.
+    pub attempts: u32,
+}
+
+/// Target latency for [`summarize_span`]: editor integrations call this on
+/// keystroke-adjacent actions (e.g. hover, quick-fix preview) and a response
+/// slower than this reads as the editor hanging.
+pub const INTERACTIVE_LATENCY_TARGET: Duration = Duration::from_millis(2000);
+
+/// Default cap, in whitespace-delimited tokens, on how much glossary text
+/// [`SummaryRun::summarize_all_chunks_with_glossary`] injects into a single
+/// chunk's prompt, so a chunk that matches many terms doesn't crowd out the
+/// code itself.
+pub const DEFAULT_GLOSSARY_TOKEN_BUDGET: usize = 200;
+
+/// Summarizes one line span of `file` instead of a whole file, for editor
+/// integrations that only care about the function or block under the
+/// cursor. Builds a single enriched chunk with surrounding context and runs
+/// exactly one inference call, so latency stays predictable and close to
+/// [`INTERACTIVE_LATENCY_TARGET`].
+pub fn summarize_span(
+    backend: &impl InferenceBackend,
+    file: &Path,
+    full_content: &str,
+    start_line: usize,
+    end_line: usize,
+) -> Result<ChunkResult, EngineError> {
+    summarize_span_with_language(backend, file, full_content, start_line, end_line, Language::default())
+}
+
+/// Like [`summarize_span`], but asks the model to write the summary in
+/// `language` — see [`Language::prompt_directive`].
+pub fn summarize_span_with_language(
+    backend: &impl InferenceBackend,
+    file: &Path,
+    full_content: &str,
+    start_line: usize,
+    end_line: usize,
+    language: Language,
+) -> Result<ChunkResult, EngineError> {
+    #[cfg(feature = "otel")]
+    let _span = tracing::info_span!("summarize_span", file = %file.display(), start_line, end_line).entered();
+
+    const SPAN_CONTEXT_LINES: usize = 3;
+    let chunk = Chunk::from_line_span(ChunkId(0), file, full_content, start_line, end_line, SPAN_CONTEXT_LINES);
+    let class = chunk.classify();
+    let prompt = initial_summary_prompt(&chunk, class, language);
+
+    let started_at = Instant::now();
+    let text = backend.generate_completion_text(&prompt)?;
+    let latency = started_at.elapsed();
+
+    Ok(ChunkResult {
+        summary: Summary {
+            chunk_id: chunk.id,
+            class,
+            text,
+            instruction: None,
+            source: SummarySource::Model,
+            language,
+        },
+        latency,
+        attempts: 1,
+    })
+}
+
+/// Like [`summarize_span_with_language`], but retries a failing backend call
+/// per `policy` instead of giving up after one attempt.
+///
+/// This is a deliberately separate function rather than a flag on
+/// [`summarize_span`]: that one exists specifically to stay inside
+/// [`INTERACTIVE_LATENCY_TARGET`], and any retry with backoff risks blowing
+/// straight through it. Call this instead only where a caller has already
+/// decided a slower-but-more-reliable answer beats a fast failure — e.g. a
+/// batch re-summarization pass, not a hover tooltip.
+pub fn summarize_span_with_retry_policy(
+    backend: &impl InferenceBackend,
+    file: &Path,
+    full_content: &str,
+    start_line: usize,
+    end_line: usize,
+    language: Language,
+    policy: &crate::engine::retry_policy::RetryPolicy,
+) -> Result<ChunkResult, EngineError> {
+    const SPAN_CONTEXT_LINES: usize = 3;
+    let chunk = Chunk::from_line_span(ChunkId(0), file, full_content, start_line, end_line, SPAN_CONTEXT_LINES);
+    let class = chunk.classify();
+    let prompt = initial_summary_prompt(&chunk, class, language);
+
+    let started_at = Instant::now();
+    let mut attempt: u32 = 1;
+    loop {
+        match backend.generate_completion_text(&prompt) {
+            Ok(text) => {
+                return Ok(ChunkResult {
+                    summary: Summary {
+                        chunk_id: chunk.id,
+                        class,
+                        text,
+                        instruction: None,
+                        source: SummarySource::Model,
+                        language,
+                    },
+                    latency: started_at.elapsed(),
+                    attempts: attempt,
+                });
+            }
+            Err(error) => {
+                if !policy.should_retry(attempt, &error) {
+                    return Err(error);
+                }
+                std::thread::sleep(policy.backoff_for_attempt(attempt, chunk.id.0 ^ attempt as u64));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// `pub(crate)` rather than private so [`crate::routing::ChunkRouter`] can
+/// build the same prompt a plain [`SummaryRun`] would, rather than
+/// duplicating this formatting for routed chunks.
+pub(crate) fn initial_summary_prompt(chunk: &Chunk, class: ChunkClass, language: Language) -> String {
+    let instruction = match class {
+        ChunkClass::Test => "Describe what behavior this test module verifies, not its implementation.",
+        ChunkClass::Bench => "Describe what this benchmark measures and under what workload.",
+        ChunkClass::Example => "Describe what capability this example demonstrates to a new user.",
+        ChunkClass::BuildScript => "Describe what this build script generates or configures at build time.",
+        ChunkClass::Production => "Summarize what this code does and why it exists.",
+    };
+    let instruction = match language.prompt_directive() {
+        Some(directive) => format!("{instruction} {directive}"),
+        None => instruction.to_string(),
+    };
+    format!(
+        "{instruction}\n\nCode from {}:\n\n{}",
+        chunk.source_path.display(),
+        chunk.content
+    )
+}
+
+fn refinement_prompt(chunk: &Chunk, prior_summary: &str, instruction: &str, language: Language) -> String {
+    let directive = language.prompt_directive().map(|directive| format!(" {directive}")).unwrap_or_default();
+    format!(
+        "Prior summary of {}:\n{}\n\nRefine it with this instruction: {}{directive}",
+        chunk.source_path.display(),
+        prior_summary,
+        instruction
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+
+    impl InferenceBackend for EchoBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("echo: {}", prompt.len()))
+        }
+    }
+
+    #[test]
+    fn refine_appends_to_history_and_reuses_backend() {
+        let chunk = Chunk::new(ChunkId(1), "lib.rs", "fn f() {}");
+        let mut run = SummaryRun::summarize_all_chunks(EchoBackend, vec![chunk]).unwrap();
+        assert!(run.latest_summary_for(ChunkId(1)).is_some());
+
+        run.refine(ChunkId(1), "focus on error handling").unwrap();
+        let latest = run.latest_summary_for(ChunkId(1)).unwrap();
+        assert_eq!(latest.instruction.as_deref(), Some("focus on error handling"));
+    }
+
+    #[test]
+    fn glossary_entries_relevant_to_a_chunk_are_injected_into_its_prompt() {
+        struct CapturingBackend;
+        impl InferenceBackend for CapturingBackend {
+            fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+                Ok(prompt.to_string())
+            }
+        }
+
+        let glossary = serde_json::from_str::<crate::engine::glossary::Glossary>(
+            r#"{"terms": {"ACL": "access control list"}}"#,
+        )
+        .unwrap();
+
+        let chunk = Chunk::new(ChunkId(1), "f.rs", "fn check(acl: ACL) {}");
+        let run = SummaryRun::summarize_all_chunks_with_glossary(
+            CapturingBackend,
+            vec![chunk],
+            Some(&glossary),
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+        )
+        .unwrap();
+
+        let prompt = &run.latest_summary_for(ChunkId(1)).unwrap().text;
+        assert!(prompt.contains("ACL: access control list"));
+    }
+
+    #[test]
+    fn non_english_language_appends_a_directive_to_the_prompt_and_is_recorded_on_the_summary() {
+        struct CapturingBackend;
+        impl InferenceBackend for CapturingBackend {
+            fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+                Ok(prompt.to_string())
+            }
+        }
+
+        let chunk = Chunk::new(ChunkId(1), "f.rs", "fn f() {}");
+        let run = SummaryRun::summarize_all_chunks_with_language(CapturingBackend, vec![chunk], Language::Japanese).unwrap();
+
+        let summary = run.latest_summary_for(ChunkId(1)).unwrap();
+        assert_eq!(summary.language, Language::Japanese);
+        assert!(summary.text.contains("Write the summary in Japanese."));
+    }
+
+    #[test]
+    fn english_language_appends_no_directive_to_the_prompt() {
+        struct CapturingBackend;
+        impl InferenceBackend for CapturingBackend {
+            fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+                Ok(prompt.to_string())
+            }
+        }
+
+        let chunk = Chunk::new(ChunkId(1), "f.rs", "fn f() {}");
+        let run = SummaryRun::summarize_all_chunks(CapturingBackend, vec![chunk]).unwrap();
+
+        let summary = run.latest_summary_for(ChunkId(1)).unwrap();
+        assert_eq!(summary.language, Language::English);
+        assert!(!summary.text.contains("Write the summary"));
+    }
+
+    #[test]
+    fn extractive_fallback_skips_inference_for_chunks_with_a_good_doc_comment() {
+        struct PanickingBackend;
+        impl InferenceBackend for PanickingBackend {
+            fn generate_completion_text(&self, _prompt: &str) -> Result<String, EngineError> {
+                panic!("should not be called for a chunk with a quality doc comment");
+            }
+        }
+
+        let documented = Chunk::new(
+            ChunkId(1),
+            "f.rs",
+            "/// Walks the dependency graph and reports the first cycle it finds.\nfn find_cycle() {}",
+        );
+        let run = SummaryRun::summarize_all_chunks_with_options(
+            PanickingBackend,
+            vec![documented],
+            None,
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+            true,
+            None,
+            None,
+            Language::default(),
+        )
+        .unwrap();
+
+        let summary = run.latest_summary_for(ChunkId(1)).unwrap();
+        assert_eq!(summary.source, SummarySource::Extractive);
+        assert!(summary.text.contains("dependency graph"));
+    }
+
+    #[test]
+    fn extractive_fallback_still_calls_the_backend_for_undocumented_chunks() {
+        let undocumented = Chunk::new(ChunkId(1), "f.rs", "fn find_cycle() {}");
+        let run = SummaryRun::summarize_all_chunks_with_options(
+            EchoBackend,
+            vec![undocumented],
+            None,
+            DEFAULT_GLOSSARY_TOKEN_BUDGET,
+            true,
+            None,
+            None,
+            Language::default(),
+        )
+        .unwrap();
+
+        assert_eq!(run.latest_summary_for(ChunkId(1)).unwrap().source, SummarySource::Model);
+    }
+
+    #[test]
+    fn summarize_span_extracts_single_function() {
+        let content = "fn a() {}\nfn b() {\n    1\n}\nfn c() {}";
+        let result = summarize_span(&EchoBackend, Path::new("f.rs"), content, 2, 4).unwrap();
+        assert_eq!(result.summary.class, ChunkClass::Production);
+        assert!(result.latency < INTERACTIVE_LATENCY_TARGET);
+    }
+
+    #[test]
+    fn refine_unknown_chunk_errors() {
+        let mut run = SummaryRun::summarize_all_chunks(EchoBackend, vec![]).unwrap();
+        assert!(run.refine(ChunkId(99), "anything").is_err());
+    }
+}
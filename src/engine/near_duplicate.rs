@@ -0,0 +1,101 @@
+//! Detects a chunk whose content has barely changed since a prior run, so
+//! [`crate::engine::SummaryRun::summarize_all_chunks_with_options`] can skip
+//! re-summarizing it. Lockfiles and generated snapshots churn on every
+//! commit but the summary worth writing about them never meaningfully
+//! changes, so re-paying a model call for them run after run is wasted work.
+//!
+//! Reuses [`crate::dedup::embed_summary_text`]/[`crate::dedup::cosine_similarity`]
+//! — built for comparing summary text by lexical overlap — to compare raw
+//! chunk content instead; "turn text into a bag-of-words vector, compare by
+//! cosine" doesn't care whether the text is a summary or source code.
+
+use crate::chunk::normalize_line_endings;
+use crate::dedup::{cosine_similarity, embed_summary_text};
+
+const EMBEDDING_DIMENSIONS: usize = 64;
+
+/// The similarity threshold at or above which a chunk is considered
+/// unchanged. The prior run's content per path is not held here; it is
+/// passed alongside, keyed by the same [`std::path::PathBuf`] a
+/// [`crate::chunk::Chunk::source_path`] carries.
+#[derive(Debug, Clone, Copy)]
+pub struct ChangeDetectionPolicy {
+    /// Cosine similarity (0.0-1.0) above which `current` content is treated
+    /// as an unchanged near-duplicate of `previous` content. `1.0` only
+    /// skips content that is identical once line endings are normalized
+    /// (or whose hashed bag-of-words vector is identical, e.g. the same
+    /// words in another order); lower values tolerate drift like
+    /// reordered lockfile entries or regenerated timestamps.
+    pub similarity_threshold: f32,
+}
+
+impl Default for ChangeDetectionPolicy {
+    /// `0.98`: tolerant of line-ending and whitespace drift
+    /// ([`normalize_line_endings`] already handles line endings before
+    /// embedding, so this margin is for everything else) without treating a
+    /// genuinely edited file as unchanged.
+    fn default() -> Self {
+        ChangeDetectionPolicy { similarity_threshold: 0.98 }
+    }
+}
+
+/// True when `previous` and `current` are similar enough, under `policy`,
+/// that re-summarizing `current` would be wasted work. Content is normalized
+/// for line-ending differences before comparison, so a file that only
+/// changed CRLF/LF style is never mistaken for a real edit.
+pub fn is_near_duplicate(previous: &str, current: &str, policy: &ChangeDetectionPolicy) -> bool {
+    let previous = normalize_line_endings(previous);
+    let current = normalize_line_endings(current);
+    if previous == current {
+        return true;
+    }
+
+    let previous_embedding = embed_summary_text(&previous, EMBEDDING_DIMENSIONS);
+    let current_embedding = embed_summary_text(&current, EMBEDDING_DIMENSIONS);
+    cosine_similarity(&previous_embedding, &current_embedding) >= policy.similarity_threshold
+}
+
+/// Text recorded as a chunk's summary when [`is_near_duplicate`] determines
+/// re-summarization can be skipped, so a report reader sees why no real
+/// summary text is present rather than an empty field.
+pub const UNCHANGED_NEAR_DUPLICATE_TEXT: &str = "unchanged (near-duplicate)";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_identical_content_is_always_a_near_duplicate() {
+        let policy = ChangeDetectionPolicy { similarity_threshold: 1.0 };
+        assert!(is_near_duplicate("fn a() {}", "fn a() {}", &policy));
+    }
+
+    #[test]
+    fn content_differing_only_by_line_ending_is_a_near_duplicate() {
+        let policy = ChangeDetectionPolicy { similarity_threshold: 1.0 };
+        assert!(is_near_duplicate("fn a() {}\nfn b() {}\n", "fn a() {}\r\nfn b() {}\r\n", &policy));
+    }
+
+    #[test]
+    fn lexically_similar_content_clears_a_lenient_threshold() {
+        let policy = ChangeDetectionPolicy { similarity_threshold: 0.9 };
+        let previous = "name = \"lockfile\"\nversion = \"1.0.0\"\nchecksum = \"abc123\"\n";
+        let current = "name = \"lockfile\"\nversion = \"1.0.0\"\nchecksum = \"def456\"\n";
+        assert!(is_near_duplicate(previous, current, &policy));
+    }
+
+    #[test]
+    fn unrelated_content_fails_even_a_lenient_threshold() {
+        let policy = ChangeDetectionPolicy { similarity_threshold: 0.5 };
+        let previous = "fn compute_tax(income: f64) -> f64 { income * 0.2 }";
+        let current = "struct Widget { id: u64, label: String, color: Color }";
+        assert!(!is_near_duplicate(previous, current, &policy));
+    }
+
+    #[test]
+    fn default_policy_is_tolerant_but_not_trivially_permissive() {
+        let policy = ChangeDetectionPolicy::default();
+        assert!(policy.similarity_threshold > 0.9);
+        assert!(policy.similarity_threshold < 1.0);
+    }
+}
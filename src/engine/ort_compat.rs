@@ -0,0 +1,143 @@
+//! Compatibility layer over ONNX Runtime bindings.
+//!
+//! Roughly half the tree targeted `ort` 1.16 and half targeted `ort` 2.0,
+//! with whichever half didn't match the locked version commented out. Rather
+//! than pick a winner, this module defines our own minimal session/tensor
+//! traits so the rest of the engine depends on [`CompatSession`] /
+//! [`CompatTensor`] instead of either `ort` version directly, behind one
+//! feature flag per major line.
+//!
+//! The `ort-1` feature exists for API parity but currently has no backend:
+//! every `ort` 1.x release on our registry (1.13 through 1.16) is yanked
+//! upstream, so there is no version we could pin. Enabling it fails the
+//! build with an explanation instead of silently compiling nothing.
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrtCompatError {
+    #[error("onnx runtime backend failed: {0}")]
+    Backend(String),
+    #[error("no ort backend compiled in (enable the `ort-2` feature)")]
+    NoBackendCompiled,
+}
+
+/// A tensor as our engine cares about it: shape plus flat `f32` data.
+pub trait CompatTensor {
+    fn shape(&self) -> &[usize];
+    fn as_f32_slice(&self) -> &[f32];
+}
+
+/// A loaded inference session, abstracted over the `ort` major version.
+pub trait CompatSession {
+    fn run_single_input(&mut self, input_name: &str, input: &dyn CompatTensor) -> Result<Vec<f32>, OrtCompatError>;
+}
+
+/// Plain owned tensor used to cross the [`CompatTensor`] boundary without
+/// tying callers to either `ort` version's tensor type.
+pub struct OwnedTensor {
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+impl CompatTensor for OwnedTensor {
+    fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    fn as_f32_slice(&self) -> &[f32] {
+        &self.data
+    }
+}
+
+#[cfg(all(feature = "ort-1", not(feature = "ort-2")))]
+compile_error!(
+    "the `ort-1` feature has no backend: every ort 1.x release is yanked upstream; \
+     build with the `ort-2` feature instead"
+);
+
+#[cfg(feature = "ort-2")]
+mod backend_v2 {
+    use super::{CompatSession, CompatTensor, OrtCompatError};
+
+    pub struct OrtV2Session {
+        session: ort2::session::Session,
+    }
+
+    /// How [`OrtV2Session::load`] should get the model's bytes to ONNX
+    /// Runtime.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum OrtLoadMode {
+        /// Hands ORT the file path directly (`commit_from_file`). ORT
+        /// memory-maps the model's weights from disk rather than copying
+        /// them into a process-owned buffer, so startup isn't blocked on
+        /// reading every weight byte up front — pages fault in as the
+        /// session actually touches them — and a model larger than free RAM
+        /// can still load. This is what cold-start latency actually needs,
+        /// and is why [`OrtV2Session::load_from_file`] defaults to it.
+        MemoryMapped,
+        /// Reads the whole file into a `Vec<u8>` first and loads via
+        /// `commit_from_memory`. Slower to start and needs the model's full
+        /// size free as heap on top of whatever ORT allocates for it; only
+        /// worth it when the model's bytes come from something that isn't a
+        /// plain file path to begin with (an embedded asset, a buffer
+        /// decrypted in memory) and `commit_from_file` isn't an option.
+        InMemory,
+    }
+
+    impl OrtV2Session {
+        /// Loads via [`OrtLoadMode::MemoryMapped`] — see its docs for why
+        /// that, not [`OrtLoadMode::InMemory`], is the cold-start-friendly
+        /// default every caller should reach for first.
+        pub fn load_from_file(path: &std::path::Path) -> Result<Self, OrtCompatError> {
+            Self::load(path, OrtLoadMode::MemoryMapped)
+        }
+
+        pub fn load(path: &std::path::Path, mode: OrtLoadMode) -> Result<Self, OrtCompatError> {
+            let mut builder = ort2::session::Session::builder().map_err(|e| OrtCompatError::Backend(e.to_string()))?;
+            let session = match mode {
+                OrtLoadMode::MemoryMapped => builder.commit_from_file(path).map_err(|e| OrtCompatError::Backend(e.to_string()))?,
+                OrtLoadMode::InMemory => {
+                    let bytes = std::fs::read(path).map_err(|e| OrtCompatError::Backend(e.to_string()))?;
+                    builder.commit_from_memory(&bytes).map_err(|e| OrtCompatError::Backend(e.to_string()))?
+                }
+            };
+            Ok(OrtV2Session { session })
+        }
+    }
+
+    impl CompatSession for OrtV2Session {
+        fn run_single_input(&mut self, input_name: &str, input: &dyn CompatTensor) -> Result<Vec<f32>, OrtCompatError> {
+            let value = ort2::value::Tensor::from_array((input.shape().to_vec(), input.as_f32_slice().to_vec()))
+                .map_err(|e| OrtCompatError::Backend(e.to_string()))?;
+            let outputs = self
+                .session
+                .run(ort2::inputs![input_name => value])
+                .map_err(|e| OrtCompatError::Backend(e.to_string()))?;
+            let (_, first_output) = outputs
+                .iter()
+                .next()
+                .ok_or_else(|| OrtCompatError::Backend("no outputs produced".into()))?;
+            let (_, data) = first_output
+                .try_extract_tensor::<f32>()
+                .map_err(|e| OrtCompatError::Backend(e.to_string()))?;
+            Ok(data.to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "ort-2")]
+pub use backend_v2::{OrtLoadMode, OrtV2Session};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_tensor_exposes_shape_and_data() {
+        let tensor = OwnedTensor {
+            shape: vec![1, 3],
+            data: vec![1.0, 2.0, 3.0],
+        };
+        assert_eq!(tensor.shape(), &[1, 3]);
+        assert_eq!(tensor.as_f32_slice(), &[1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,279 @@
+//! Token-budget packing for prompt assembly.
+//!
+//! A summarization prompt is spliced together from several independently
+//! sized pieces: a fixed instruction template, optional few-shot examples,
+//! a [`crate::engine::glossary::Glossary`] block, context carried over from
+//! an earlier turn, and the chunk's own content. Concatenating all of them
+//! unconditionally risks exactly the two failure modes
+//! [`crate::model::context::enforce_prompt_budget`] exists to catch on the
+//! way out: overflowing the model's context window, or (less obviously)
+//! burning so much of the budget on examples/glossary that the chunk's own
+//! content gets starved of room. [`pack_prompt`] allocates one shared token
+//! budget across [`PromptComponent`]s by caller-assigned priority, keeping
+//! every higher-priority component whole and truncating or dropping
+//! lower-priority ones first when everything doesn't fit, then reports
+//! exactly what it kept via [`PackingReport`] so a caller can log the final
+//! per-chunk allocation for debugging.
+//!
+//! This crate has no few-shot example store or cross-turn context-carry
+//! buffer anywhere yet — [`ComponentKind::FewShot`] and
+//! [`ComponentKind::ContextCarry`] exist here so a caller that does have
+//! that content can hand it in as a [`PromptComponent`] alongside the
+//! template, glossary, and chunk pieces this crate already builds; an empty
+//! string for either costs zero tokens and is dropped like any other
+//! component that doesn't fit.
+
+use crate::model::context::estimate_token_count;
+
+/// Which part of an assembled prompt a [`PromptComponent`] represents. Purely
+/// a label for [`PackingReport`]'s output — [`pack_prompt`] only ever looks
+/// at [`PromptComponent::priority`], never at `kind`, when deciding what to
+/// keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ComponentKind {
+    Template,
+    FewShot,
+    Glossary,
+    ContextCarry,
+    Chunk,
+}
+
+/// One named, sized piece of a prompt handed to [`pack_prompt`].
+#[derive(Debug, Clone)]
+pub struct PromptComponent {
+    pub kind: ComponentKind,
+    pub content: String,
+    /// Higher runs first and keeps its full content as long as enough
+    /// budget remains for all of it; lower is truncated or dropped first when the budget runs
+    /// out. Ties keep the order `components` were given to [`pack_prompt`]
+    /// in.
+    pub priority: u8,
+}
+
+impl PromptComponent {
+    pub fn new(kind: ComponentKind, content: impl Into<String>, priority: u8) -> Self {
+        PromptComponent { kind, content: content.into(), priority }
+    }
+}
+
+/// What became of one [`PromptComponent`] after [`pack_prompt`] ran.
+#[derive(Debug, Clone)]
+pub struct PackedComponent {
+    pub kind: ComponentKind,
+    pub priority: u8,
+    /// [`estimate_token_count`] of the component's original content, before
+    /// any trimming.
+    pub requested_tokens: usize,
+    /// How many tokens of budget this component actually received —
+    /// `requested_tokens` unless trimming cut into it.
+    pub allocated_tokens: usize,
+    /// `content` after trimming — identical to the original when
+    /// `allocated_tokens == requested_tokens`.
+    pub content: String,
+    pub trimmed: bool,
+}
+
+/// The outcome of packing one prompt's components into a shared budget —
+/// what [`pack_prompt`] returns. [`PackingReport::assembled_prompt`] gives
+/// the final prompt text; [`PackingReport::to_log_line`] gives a one-line
+/// per-chunk allocation summary worth logging alongside a run.
+#[derive(Debug, Clone)]
+pub struct PackingReport {
+    pub total_budget: usize,
+    pub components: Vec<PackedComponent>,
+}
+
+impl PackingReport {
+    /// Total tokens actually allocated across every component, always
+    /// `<= total_budget`.
+    pub fn total_allocated(&self) -> usize {
+        self.components.iter().map(|component| component.allocated_tokens).sum()
+    }
+
+    /// Concatenates every packed component's (possibly trimmed) content, in
+    /// the order [`pack_prompt`] was given them — not priority order — so
+    /// the result reads the way the template/glossary/chunk layout expects.
+    pub fn assembled_prompt(&self) -> String {
+        self.components.iter().map(|component| component.content.as_str()).collect()
+    }
+
+    /// A one-line, human-readable record of how the budget was split, e.g.
+    /// `"template=12/12 glossary=8/20(trimmed) chunk=40/40 (60/72 tokens used of 60 budget)"` —
+    /// meant for a run's debug log, one line per chunk packed.
+    pub fn to_log_line(&self) -> String {
+        let per_component: Vec<String> = self
+            .components
+            .iter()
+            .map(|component| {
+                let label = component_label(component.kind);
+                let trimmed_suffix = if component.trimmed { "(trimmed)" } else { "" };
+                format!("{label}={}/{}{trimmed_suffix}", component.allocated_tokens, component.requested_tokens)
+            })
+            .collect();
+        format!(
+            "{} ({}/{} tokens used of {} budget)",
+            per_component.join(" "),
+            self.total_allocated(),
+            self.components.iter().map(|component| component.requested_tokens).sum::<usize>(),
+            self.total_budget,
+        )
+    }
+}
+
+fn component_label(kind: ComponentKind) -> &'static str {
+    match kind {
+        ComponentKind::Template => "template",
+        ComponentKind::FewShot => "few_shot",
+        ComponentKind::Glossary => "glossary",
+        ComponentKind::ContextCarry => "context_carry",
+        ComponentKind::Chunk => "chunk",
+    }
+}
+
+/// Allocates `total_budget` whitespace-delimited tokens (see
+/// [`estimate_token_count`]) across `components`, highest
+/// [`PromptComponent::priority`] first: each component in priority order
+/// keeps its full content while budget remains, and the first component
+/// that doesn't fully fit is truncated to exactly what's left — every
+/// lower-priority component after it gets nothing. Ties in priority keep
+/// `components`' given order.
+pub fn pack_prompt(components: Vec<PromptComponent>, total_budget: usize) -> PackingReport {
+    let mut order: Vec<usize> = (0..components.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(components[index].priority));
+
+    let mut packed: Vec<Option<PackedComponent>> = vec![None; components.len()];
+    let mut remaining_budget = total_budget;
+
+    for index in order {
+        let component = &components[index];
+        let requested_tokens = estimate_token_count(&component.content);
+
+        let packed_component = if requested_tokens <= remaining_budget {
+            remaining_budget -= requested_tokens;
+            PackedComponent {
+                kind: component.kind,
+                priority: component.priority,
+                requested_tokens,
+                allocated_tokens: requested_tokens,
+                content: component.content.clone(),
+                trimmed: false,
+            }
+        } else {
+            let content = truncate_to_token_count(&component.content, remaining_budget);
+            let allocated_tokens = remaining_budget;
+            remaining_budget = 0;
+            PackedComponent {
+                kind: component.kind,
+                priority: component.priority,
+                requested_tokens,
+                allocated_tokens,
+                content,
+                trimmed: true,
+            }
+        };
+
+        packed[index] = Some(packed_component);
+    }
+
+    PackingReport {
+        total_budget,
+        components: packed.into_iter().map(|component| component.expect("every index was visited exactly once")).collect(),
+    }
+}
+
+/// Keeps `text`'s first `max_tokens` whitespace-delimited words, matching
+/// the unit [`estimate_token_count`] counts in so a truncated component's
+/// `allocated_tokens` is exact rather than approximate.
+fn truncate_to_token_count(text: &str, max_tokens: usize) -> String {
+    text.split_whitespace().take(max_tokens).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_component_fits_when_the_budget_is_generous() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Template, "do the thing", 3),
+            PromptComponent::new(ComponentKind::Chunk, "fn go() {}", 2),
+        ];
+        let report = pack_prompt(components, 100);
+
+        assert!(report.components.iter().all(|component| !component.trimmed));
+        assert_eq!(report.assembled_prompt(), "do the thingfn go() {}");
+    }
+
+    #[test]
+    fn lowest_priority_component_is_trimmed_first() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Chunk, "one two three four five", 10),
+            PromptComponent::new(ComponentKind::Glossary, "six seven eight nine ten", 1),
+        ];
+        let report = pack_prompt(components, 7);
+
+        let chunk = report.components.iter().find(|c| c.kind == ComponentKind::Chunk).unwrap();
+        let glossary = report.components.iter().find(|c| c.kind == ComponentKind::Glossary).unwrap();
+
+        assert!(!chunk.trimmed);
+        assert_eq!(chunk.allocated_tokens, 5);
+        assert!(glossary.trimmed);
+        assert_eq!(glossary.allocated_tokens, 2);
+        assert_eq!(glossary.content, "six seven");
+    }
+
+    #[test]
+    fn a_component_that_gets_zero_budget_contributes_empty_content() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Chunk, "one two three four five", 10),
+            PromptComponent::new(ComponentKind::FewShot, "example one", 5),
+        ];
+        let report = pack_prompt(components, 5);
+
+        let few_shot = report.components.iter().find(|c| c.kind == ComponentKind::FewShot).unwrap();
+        assert!(few_shot.trimmed);
+        assert_eq!(few_shot.allocated_tokens, 0);
+        assert_eq!(few_shot.content, "");
+    }
+
+    #[test]
+    fn ties_in_priority_keep_the_given_order() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Template, "alpha beta", 5),
+            PromptComponent::new(ComponentKind::Chunk, "gamma delta", 5),
+        ];
+        let report = pack_prompt(components, 2);
+
+        assert!(!report.components[0].trimmed);
+        assert_eq!(report.components[0].allocated_tokens, 2);
+        assert!(report.components[1].trimmed);
+        assert_eq!(report.components[1].allocated_tokens, 0);
+    }
+
+    #[test]
+    fn total_allocated_never_exceeds_the_budget() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Template, "a b c d e", 9),
+            PromptComponent::new(ComponentKind::Glossary, "f g h i j", 5),
+            PromptComponent::new(ComponentKind::Chunk, "k l m n o", 1),
+        ];
+        let report = pack_prompt(components, 8);
+
+        assert_eq!(report.total_allocated(), 8);
+        assert!(report.total_allocated() <= report.total_budget);
+    }
+
+    #[test]
+    fn to_log_line_reports_every_component_and_the_trimmed_marker() {
+        let components = vec![
+            PromptComponent::new(ComponentKind::Template, "a b c", 9),
+            PromptComponent::new(ComponentKind::Chunk, "d e f g", 1),
+        ];
+        let report = pack_prompt(components, 4);
+
+        let line = report.to_log_line();
+        assert!(line.contains("template=3/3"));
+        assert!(line.contains("chunk=1/4(trimmed)"));
+        assert!(line.contains("4/7 tokens used of 4 budget"));
+    }
+}
@@ -0,0 +1,260 @@
+//! External, validatable prompt template files.
+//!
+//! Every prompt this crate builds today — [`super::initial_summary_prompt`],
+//! [`super::refinement_prompt`], [`super::deadline::build_prompt`] — is a
+//! hardcoded `format!` string; there is no file a caller can edit without a
+//! rebuild, so there is nothing here to extend. [`PromptTemplate`] is that
+//! missing piece: a template file using `{{placeholder}}` syntax, loaded
+//! the same way [`super::glossary::Glossary::load_from_file`] loads its term
+//! table, but validated at load time rather than failing hours into a run
+//! the first time an agent hits a chunk that exercises a broken line.
+//!
+//! [`PromptTemplate::load_from_file`] checks every `{{placeholder}}` in the
+//! file against [`KNOWN_PLACEHOLDERS`] and reports the exact line of a typo
+//! or renamed field, then [`PromptTemplate::dry_render`] substitutes a
+//! sample chunk's values and reports the rendered prompt's token footprint
+//! via [`crate::model::context::estimate_token_count`] — the same estimator
+//! [`super::packing::pack_prompt`] budgets real prompts against — so a
+//! caller can catch an oversized template before it ever reaches an agent.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::chunk::{Chunk, ChunkClass};
+use crate::model::context::estimate_token_count;
+use crate::validation::Language;
+
+/// Every field a [`PromptTemplate`] may reference as `{{name}}`, matching
+/// the pieces [`super::initial_summary_prompt`] already assembles by hand.
+pub const KNOWN_PLACEHOLDERS: &[&str] = &["instruction", "source_path", "content", "language_directive"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("failed to read template file {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("{path}:{line}: unknown placeholder `{{{{{placeholder}}}}}`; known placeholders are {known}")]
+    UnknownPlaceholder { path: PathBuf, line: usize, placeholder: String, known: String },
+}
+
+/// A loaded, placeholder-validated template. Construct with
+/// [`PromptTemplate::load_from_file`] — every instance that exists has
+/// already passed placeholder validation.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    source_path: PathBuf,
+    raw: String,
+}
+
+/// Values substituted for a [`PromptTemplate`]'s placeholders, one field per
+/// entry in [`KNOWN_PLACEHOLDERS`].
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    pub instruction: String,
+    pub source_path: String,
+    pub content: String,
+    pub language_directive: String,
+}
+
+impl TemplateValues {
+    /// Builds the same instruction-and-directive values
+    /// [`super::initial_summary_prompt`] computes by hand, from a real
+    /// [`Chunk`] — the "sample chunk" a config-load-time dry render checks
+    /// a template against. Mirrors, rather than calls into,
+    /// [`super::initial_summary_prompt`]'s per-class instruction table,
+    /// since that function builds a fixed prompt string directly and has
+    /// no placeholder seam to hook into.
+    pub fn from_chunk(chunk: &Chunk, class: ChunkClass, language: Language) -> Self {
+        TemplateValues {
+            instruction: class_instruction(class).to_string(),
+            source_path: chunk.source_path.display().to_string(),
+            content: chunk.content.clone(),
+            language_directive: language.prompt_directive().map(str::to_string).unwrap_or_default(),
+        }
+    }
+
+    fn get(&self, placeholder: &str) -> &str {
+        match placeholder {
+            "instruction" => &self.instruction,
+            "source_path" => &self.source_path,
+            "content" => &self.content,
+            "language_directive" => &self.language_directive,
+            _ => "",
+        }
+    }
+}
+
+fn class_instruction(class: ChunkClass) -> &'static str {
+    match class {
+        ChunkClass::Test => "Describe what behavior this test module verifies, not its implementation.",
+        ChunkClass::Bench => "Describe what this benchmark measures and under what workload.",
+        ChunkClass::Example => "Describe what capability this example demonstrates to a new user.",
+        ChunkClass::BuildScript => "Describe what this build script generates or configures at build time.",
+        ChunkClass::Production => "Summarize what this code does and why it exists.",
+    }
+}
+
+/// What [`PromptTemplate::dry_render`] reports for one sample chunk.
+#[derive(Debug, Clone)]
+pub struct DryRenderReport {
+    pub rendered_prompt: String,
+    pub estimated_tokens: usize,
+}
+
+impl PromptTemplate {
+    /// Reads `path` and validates every `{{placeholder}}` it contains
+    /// against [`KNOWN_PLACEHOLDERS`] before returning, so a typo'd or
+    /// renamed placeholder fails at config load with the exact line it's
+    /// on instead of surfacing as a silently-empty substitution deep into
+    /// a run.
+    pub fn load_from_file(path: &Path) -> Result<Self, TemplateError> {
+        let raw = fs::read_to_string(path).map_err(|source| TemplateError::Io { path: path.to_path_buf(), source })?;
+        let template = PromptTemplate { source_path: path.to_path_buf(), raw };
+        template.validate_placeholders()?;
+        Ok(template)
+    }
+
+    fn validate_placeholders(&self) -> Result<(), TemplateError> {
+        for (line_index, line) in self.raw.lines().enumerate() {
+            for placeholder in placeholders_in_line(line) {
+                if !KNOWN_PLACEHOLDERS.contains(&placeholder) {
+                    return Err(TemplateError::UnknownPlaceholder {
+                        path: self.source_path.clone(),
+                        line: line_index + 1,
+                        placeholder: placeholder.to_string(),
+                        known: KNOWN_PLACEHOLDERS.join(", "),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Substitutes every `{{placeholder}}` in the template with its value
+    /// from `values`; a placeholder `values` leaves at its `Default`
+    /// renders as an empty string rather than erroring, since an unused
+    /// field (e.g. a template with no `{{language_directive}}`) is not a
+    /// template bug.
+    pub fn render(&self, values: &TemplateValues) -> String {
+        let mut rendered = String::with_capacity(self.raw.len());
+        let mut rest = self.raw.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else {
+                rendered.push_str(rest);
+                return rendered;
+            };
+            let end = start + end;
+            rendered.push_str(&rest[..start]);
+            let placeholder = rest[start + 2..end].trim();
+            rendered.push_str(values.get(placeholder));
+            rest = &rest[end + 2..];
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Renders against `sample`'s values and reports the resulting prompt's
+    /// estimated token footprint, so a caller can catch an oversized
+    /// template at config load rather than at [`super::packing::pack_prompt`]
+    /// truncation time.
+    pub fn dry_render(&self, sample: &TemplateValues) -> DryRenderReport {
+        let rendered_prompt = self.render(sample);
+        let estimated_tokens = estimate_token_count(&rendered_prompt);
+        DryRenderReport { rendered_prompt, estimated_tokens }
+    }
+
+    pub fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+}
+
+/// Every `{{name}}` placeholder appearing in `line`, in order, trimmed of
+/// surrounding whitespace. Unterminated `{{` with no matching `}}` on the
+/// same line is not reported here — [`PromptTemplate::render`] handles that
+/// case by treating the rest of the file as literal text.
+fn placeholders_in_line(line: &str) -> Vec<&str> {
+    let mut placeholders = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else { break };
+        let end = start + end;
+        placeholders.push(rest[start + 2..end].trim());
+        rest = &rest[end + 2..];
+    }
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_template(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("transfiguration-prompt-template-{name}.txt"));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn sample_values() -> TemplateValues {
+        TemplateValues {
+            instruction: "Summarize what this code does and why it exists.".to_string(),
+            source_path: "src/lib.rs".to_string(),
+            content: "fn main() {}".to_string(),
+            language_directive: String::new(),
+        }
+    }
+
+    #[test]
+    fn a_template_using_only_known_placeholders_loads_successfully() {
+        let path = write_template("valid", "{{instruction}}\n\nCode from {{source_path}}:\n\n{{content}}");
+        assert!(PromptTemplate::load_from_file(&path).is_ok());
+    }
+
+    #[test]
+    fn an_unknown_placeholder_is_rejected_with_its_line_number() {
+        let path = write_template("typo", "{{instruction}}\n\n{{soruce_path}}\n\n{{content}}");
+        let error = PromptTemplate::load_from_file(&path).unwrap_err();
+        match error {
+            TemplateError::UnknownPlaceholder { line, placeholder, .. } => {
+                assert_eq!(line, 3);
+                assert_eq!(placeholder, "soruce_path");
+            }
+            other => panic!("expected UnknownPlaceholder, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn render_substitutes_every_placeholder() {
+        let path = write_template("render", "{{instruction}}\n\nCode from {{source_path}}:\n\n{{content}}");
+        let template = PromptTemplate::load_from_file(&path).unwrap();
+
+        let rendered = template.render(&sample_values());
+        assert_eq!(rendered, "Summarize what this code does and why it exists.\n\nCode from src/lib.rs:\n\nfn main() {}");
+    }
+
+    #[test]
+    fn render_leaves_unset_fields_at_an_empty_default() {
+        let path = write_template("optional", "{{instruction}}{{language_directive}}");
+        let template = PromptTemplate::load_from_file(&path).unwrap();
+
+        let rendered = template.render(&sample_values());
+        assert_eq!(rendered, "Summarize what this code does and why it exists.");
+    }
+
+    #[test]
+    fn dry_render_reports_the_rendered_prompts_estimated_token_footprint() {
+        let path = write_template("dry-run", "{{instruction}}\n\n{{content}}");
+        let template = PromptTemplate::load_from_file(&path).unwrap();
+
+        let report = template.dry_render(&sample_values());
+        assert!(report.rendered_prompt.contains("fn main() {}"));
+        assert_eq!(report.estimated_tokens, estimate_token_count(&report.rendered_prompt));
+    }
+
+    #[test]
+    fn template_values_from_chunk_matches_the_hand_built_initial_summary_prompt_instruction() {
+        let chunk = Chunk::new(crate::chunk::ChunkId(1), "src/lib.rs", "fn main() {}");
+        let values = TemplateValues::from_chunk(&chunk, ChunkClass::Production, Language::default());
+        assert_eq!(values.instruction, "Summarize what this code does and why it exists.");
+        assert_eq!(values.source_path, "src/lib.rs");
+    }
+}
@@ -0,0 +1,267 @@
+//! Record/replay of inference calls for deterministic debugging.
+//!
+//! A bad summary is hard to debug because re-running the same chunk rarely
+//! reproduces the same prompt (chunking, prompt templates, and model
+//! versions all drift between runs). [`RecordingBackend`] wraps any
+//! [`InferenceBackend`] and captures every prompt/output pair it sees to a
+//! [`ReplayLog`]; [`ReplayBackend`] plays that log back verbatim with no
+//! model call at all, and [`replay_against`] re-runs the same recorded
+//! prompts against a (possibly different) backend for an apples-to-apples
+//! comparison.
+//!
+//! The `InferenceBackend` trait only carries prompt text in and completion
+//! text out, so that's what gets recorded; it has no notion of token ids or
+//! sampling parameters to capture.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{EngineError, InferenceBackend};
+use crate::locking::{self, FileLock, LockError};
+
+/// One recorded prompt/output pair, in the order it was produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub sequence: usize,
+    pub prompt: String,
+    pub output: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    #[error("failed to read/write replay log: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to (de)serialize a recorded exchange: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("replay log is exhausted after {recorded} recorded exchanges")]
+    Exhausted { recorded: usize },
+    #[error(transparent)]
+    Lock(#[from] LockError),
+}
+
+/// An ordered sequence of [`RecordedExchange`]s, persisted as JSON Lines.
+/// [`ReplayLog::save_to_file`] replaces the file atomically, and
+/// [`ReplayLog::load_from_file`] rejects a log with any malformed line
+/// rather than reading up to it.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayLog {
+    exchanges: Vec<RecordedExchange>,
+}
+
+impl ReplayLog {
+    pub fn exchanges(&self) -> &[RecordedExchange] {
+        &self.exchanges
+    }
+
+    /// Acquires an advisory lock on a sidecar `.lock` file before writing, so
+    /// two CLI invocations recording against the same checkpoint path don't
+    /// interleave their writes, and writes via [`locking::atomic_write`] so a
+    /// reader never observes a partially-written log.
+    pub fn save_to_file(&self, path: &Path) -> Result<(), ReplayError> {
+        let lock = FileLock::try_acquire(FileLock::lock_path_for(path))?;
+
+        let mut buffer = Vec::new();
+        for exchange in &self.exchanges {
+            serde_json::to_writer(&mut buffer, exchange)?;
+            buffer.push(b'\n');
+        }
+        locking::atomic_write(path, &buffer)?;
+
+        drop(lock);
+        Ok(())
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path)?;
+        let exchanges = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect::<Result<Vec<RecordedExchange>, ReplayError>>()?;
+        Ok(ReplayLog { exchanges })
+    }
+}
+
+/// Wraps `inner` and records every prompt/output pair it produces, so the
+/// run can be saved for later [`ReplayBackend`] playback or
+/// [`replay_against`] comparison.
+pub struct RecordingBackend<B: InferenceBackend> {
+    inner: B,
+    exchanges: Mutex<Vec<RecordedExchange>>,
+}
+
+impl<B: InferenceBackend> RecordingBackend<B> {
+    pub fn new(inner: B) -> Self {
+        RecordingBackend {
+            inner,
+            exchanges: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Snapshots everything recorded so far into a [`ReplayLog`], ready to
+    /// be saved to disk.
+    pub fn replay_log(&self) -> ReplayLog {
+        let exchanges = self.exchanges.lock().expect("recording backend mutex poisoned").clone();
+        ReplayLog { exchanges }
+    }
+}
+
+impl<B: InferenceBackend> InferenceBackend for RecordingBackend<B> {
+    fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+        let output = self.inner.generate_completion_text(prompt)?;
+        let mut exchanges = self.exchanges.lock().expect("recording backend mutex poisoned");
+        let sequence = exchanges.len();
+        exchanges.push(RecordedExchange {
+            sequence,
+            prompt: prompt.to_string(),
+            output: output.clone(),
+        });
+        Ok(output)
+    }
+}
+
+/// Plays a [`ReplayLog`] back verbatim in recorded order, with no model call
+/// at all, for exact, zero-cost reproduction of a prior run's outputs.
+/// Ignores the prompt it's actually called with beyond counting calls, since
+/// the point is deterministic playback, not re-validating the caller's
+/// chunking against the recording.
+pub struct ReplayBackend {
+    log: ReplayLog,
+    cursor: AtomicUsize,
+}
+
+impl ReplayBackend {
+    pub fn new(log: ReplayLog) -> Self {
+        ReplayBackend {
+            log,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl InferenceBackend for ReplayBackend {
+    fn generate_completion_text(&self, _prompt: &str) -> Result<String, EngineError> {
+        let index = self.cursor.fetch_add(1, Ordering::SeqCst);
+        self.log
+            .exchanges()
+            .get(index)
+            .map(|exchange| exchange.output.clone())
+            .ok_or_else(|| {
+                EngineError::BackendFailed(
+                    ReplayError::Exhausted {
+                        recorded: self.log.exchanges().len(),
+                    }
+                    .to_string(),
+                )
+            })
+    }
+}
+
+/// One recorded exchange re-run against `backend`, with the new output
+/// alongside the one originally recorded (possibly from a different model)
+/// for apples-to-apples comparison.
+#[derive(Debug, Clone)]
+pub struct ReplayComparison {
+    pub prompt: String,
+    pub recorded_output: String,
+    pub new_output: String,
+}
+
+impl ReplayComparison {
+    pub fn outputs_match(&self) -> bool {
+        self.recorded_output == self.new_output
+    }
+}
+
+/// Re-runs every recorded prompt in `log` against `backend`, in order,
+/// returning each comparison even if `backend` is a different model than
+/// produced the original recording.
+pub fn replay_against(log: &ReplayLog, backend: &impl InferenceBackend) -> Result<Vec<ReplayComparison>, EngineError> {
+    log.exchanges()
+        .iter()
+        .map(|exchange| {
+            let new_output = backend.generate_completion_text(&exchange.prompt)?;
+            Ok(ReplayComparison {
+                prompt: exchange.prompt.clone(),
+                recorded_output: exchange.output.clone(),
+                new_output,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+    impl InferenceBackend for EchoBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("echo: {prompt}"))
+        }
+    }
+
+    struct UppercaseBackend;
+    impl InferenceBackend for UppercaseBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(prompt.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn recording_backend_captures_every_call_in_order() {
+        let recorder = RecordingBackend::new(EchoBackend);
+        recorder.generate_completion_text("a").unwrap();
+        recorder.generate_completion_text("b").unwrap();
+
+        let log = recorder.replay_log();
+        assert_eq!(log.exchanges().len(), 2);
+        assert_eq!(log.exchanges()[0].output, "echo: a");
+        assert_eq!(log.exchanges()[1].sequence, 1);
+    }
+
+    #[test]
+    fn replay_log_round_trips_through_disk() {
+        let recorder = RecordingBackend::new(EchoBackend);
+        recorder.generate_completion_text("hello").unwrap();
+        let log = recorder.replay_log();
+
+        let path = std::env::temp_dir().join("transfiguration-replay-roundtrip.jsonl");
+        log.save_to_file(&path).unwrap();
+        let loaded = ReplayLog::load_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.exchanges().len(), 1);
+        assert_eq!(loaded.exchanges()[0].output, "echo: hello");
+    }
+
+    #[test]
+    fn replay_backend_reproduces_recorded_outputs_without_calling_a_model() {
+        let recorder = RecordingBackend::new(EchoBackend);
+        recorder.generate_completion_text("x").unwrap();
+        recorder.generate_completion_text("y").unwrap();
+        let log = recorder.replay_log();
+
+        let replay = ReplayBackend::new(log);
+        assert_eq!(replay.generate_completion_text("ignored").unwrap(), "echo: x");
+        assert_eq!(replay.generate_completion_text("ignored").unwrap(), "echo: y");
+        assert!(replay.generate_completion_text("ignored").is_err());
+    }
+
+    #[test]
+    fn replay_against_a_different_model_flags_divergence() {
+        let recorder = RecordingBackend::new(EchoBackend);
+        recorder.generate_completion_text("hi").unwrap();
+        let log = recorder.replay_log();
+
+        let comparisons = replay_against(&log, &UppercaseBackend).unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert!(!comparisons[0].outputs_match());
+        assert_eq!(comparisons[0].recorded_output, "echo: hi");
+        assert_eq!(comparisons[0].new_output, "HI");
+    }
+}
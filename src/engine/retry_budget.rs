@@ -0,0 +1,112 @@
+//! A run-wide cap on retry attempts, shared across every chunk in a
+//! [`crate::engine::agents::ParallelAgentSystem::run_all_with_retry_budget`]
+//! run.
+//!
+//! Retrying a single stuck chunk a bounded number of times is cheap; doing
+//! that for every chunk when the backend itself is systematically failing
+//! is how a run turns into hours of repeated, doomed attempts. A
+//! [`RetryBudget`] caps the *total* number of retries the whole run may
+//! spend, not the number any one chunk gets, so a backend-wide outage
+//! exhausts the budget quickly and the run moves on to reporting instead of
+//! spinning.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A shared counter sized as a fraction of the run's total chunk count.
+///
+/// Every [`RetryBudget::try_consume`] call atomically claims one retry
+/// attempt; once [`RetryBudget::used`] reaches [`RetryBudget::max_retries`],
+/// every further call returns `false` and the caller is expected to give up
+/// on that chunk rather than retry it again.
+#[derive(Debug)]
+pub struct RetryBudget {
+    max_retries: usize,
+    used: AtomicUsize,
+}
+
+impl RetryBudget {
+    /// `max_retries` is `floor(total_chunks * fraction)`. A non-positive
+    /// `fraction` (or a run with zero chunks) produces a budget that never
+    /// allows a retry, rather than panicking or going negative.
+    pub fn from_fraction(total_chunks: usize, fraction: f64) -> Self {
+        let max_retries = if fraction <= 0.0 { 0 } else { ((total_chunks as f64) * fraction).floor() as usize };
+        RetryBudget {
+            max_retries,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    /// Atomically claims one retry attempt if the budget has room. Safe to
+    /// call from multiple chunks retrying concurrently — never lets `used`
+    /// overrun `max_retries` under contention.
+    pub fn try_consume(&self) -> bool {
+        let mut current = self.used.load(Ordering::SeqCst);
+        loop {
+            if current >= self.max_retries {
+                return false;
+            }
+            match self.used.compare_exchange(current, current + 1, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return true,
+                Err(observed) => current = observed,
+            }
+        }
+    }
+
+    pub fn used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.used() >= self.max_retries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_fraction_rounds_down_to_a_whole_retry_count() {
+        let budget = RetryBudget::from_fraction(10, 0.25);
+        assert_eq!(budget.max_retries(), 2);
+    }
+
+    #[test]
+    fn a_non_positive_fraction_never_allows_a_retry() {
+        let budget = RetryBudget::from_fraction(10, 0.0);
+        assert!(!budget.try_consume());
+        assert!(budget.exhausted());
+    }
+
+    #[test]
+    fn try_consume_stops_once_the_budget_is_exhausted() {
+        let budget = RetryBudget::from_fraction(4, 0.5); // max_retries = 2
+        assert!(budget.try_consume());
+        assert!(budget.try_consume());
+        assert!(!budget.try_consume());
+        assert_eq!(budget.used(), 2);
+        assert!(budget.exhausted());
+    }
+
+    #[test]
+    fn concurrent_try_consume_calls_never_overrun_the_budget() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let budget = Arc::new(RetryBudget::from_fraction(100, 0.1)); // max_retries = 10
+        let handles: Vec<_> = (0..50)
+            .map(|_| {
+                let budget = Arc::clone(&budget);
+                thread::spawn(move || budget.try_consume())
+            })
+            .collect();
+
+        let successes = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|succeeded| *succeeded).count();
+        assert_eq!(successes, 10);
+        assert!(budget.exhausted());
+    }
+}
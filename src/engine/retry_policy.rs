@@ -0,0 +1,141 @@
+//! Per-chunk retry policy: how many attempts a single chunk gets, how long
+//! to back off between them, and which [`EngineError::category`] a backend
+//! failure has to fall into before it's worth retrying at all.
+//!
+//! [`crate::engine::retry_budget::RetryBudget`] caps how many retries a
+//! *whole run* may spend in total; [`RetryPolicy`] is the complementary,
+//! per-chunk half — how a single chunk's own retries are paced once it's
+//! been granted one. A caller typically wants both: a budget to bound the
+//! run overall, and a policy so each individual retry isn't hammering the
+//! backend back-to-back.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use crate::engine::EngineError;
+
+/// Max attempts, backoff shape, and which failure categories are worth
+/// retrying at all for one chunk.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts allowed, including the first. `1` never retries.
+    pub max_attempts: u32,
+    /// Backoff before the second attempt; doubles each attempt after that.
+    pub base_backoff: Duration,
+    /// Backoff never grows past this, no matter how many attempts have
+    /// failed.
+    pub max_backoff: Duration,
+    /// Fraction (`0.0..=1.0`) of the computed backoff to randomize on top,
+    /// so many chunks backing off at the same moment don't all retry in
+    /// lockstep. `0.0` disables jitter entirely.
+    pub jitter_fraction: f64,
+    /// An [`EngineError::category`] not in this list is never retried,
+    /// regardless of `max_attempts` — e.g. a caller might retry
+    /// `"backend_failed"` (plausibly transient) but not `"unknown_chunk"`
+    /// (a programming error that will fail identically every time).
+    pub retryable_categories: Vec<&'static str>,
+}
+
+impl RetryPolicy {
+    pub fn is_retryable(&self, error: &EngineError) -> bool {
+        self.retryable_categories.contains(&error.category())
+    }
+
+    /// Whether attempt number `attempt` (1-indexed, the attempt that just
+    /// failed) should be followed by another, per `max_attempts` and
+    /// `error`'s category.
+    pub fn should_retry(&self, attempt: u32, error: &EngineError) -> bool {
+        attempt < self.max_attempts && self.is_retryable(error)
+    }
+
+    /// Backoff to wait before retrying after attempt number `attempt` (the
+    /// attempt that just failed), with `seed` choosing the jitter — callers
+    /// typically seed with something
+    /// that varies per chunk (e.g. the failing chunk's id) so two chunks
+    /// backing off on the same attempt number don't pick the same jitter.
+    ///
+    /// The jitter itself comes from hashing `seed`, not a random number
+    /// generator — enough to stagger concurrent retries without taking on
+    /// a `rand`-style dependency for it, the same tradeoff
+    /// [`crate::ordering::OrderedAggregator`] makes hashing spill file
+    /// names with [`DefaultHasher`].
+    pub fn backoff_for_attempt(&self, attempt: u32, seed: u64) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let multiplier = 1u64.checked_shl(exponent).unwrap_or(u64::MAX);
+        let scaled = self.base_backoff.saturating_mul(multiplier.min(u32::MAX as u64) as u32);
+        let capped = scaled.min(self.max_backoff);
+
+        let jitter_unit = pseudo_random_unit(seed);
+        let jitter = capped.mul_f64(self.jitter_fraction.clamp(0.0, 1.0) * jitter_unit);
+        capped + jitter
+    }
+}
+
+fn pseudo_random_unit(seed: u64) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    (hasher.finish() % 1_000_000) as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter_fraction: 0.0,
+            retryable_categories: vec!["backend_failed"],
+        }
+    }
+
+    #[test]
+    fn should_retry_stops_once_max_attempts_is_reached() {
+        let policy = policy();
+        let error = EngineError::BackendFailed("x".to_string());
+        assert!(policy.should_retry(1, &error));
+        assert!(policy.should_retry(2, &error));
+        assert!(!policy.should_retry(3, &error));
+    }
+
+    #[test]
+    fn should_retry_refuses_a_non_retryable_category() {
+        let policy = policy();
+        let error = EngineError::UnknownChunk(crate::chunk::ChunkId(1));
+        assert!(!policy.should_retry(1, &error));
+    }
+
+    #[test]
+    fn backoff_doubles_each_attempt_with_no_jitter() {
+        let policy = policy();
+        assert_eq!(policy.backoff_for_attempt(1, 0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for_attempt(2, 0), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for_attempt(3, 0), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn backoff_never_exceeds_the_configured_maximum() {
+        let mut policy = policy();
+        policy.max_backoff = Duration::from_millis(250);
+        assert_eq!(policy.backoff_for_attempt(5, 0), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn jitter_adds_up_to_the_configured_fraction_on_top_of_the_base_backoff() {
+        let mut policy = policy();
+        policy.jitter_fraction = 1.0;
+        let backoff = policy.backoff_for_attempt(1, 42);
+        assert!(backoff >= Duration::from_millis(100));
+        assert!(backoff <= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn the_same_seed_always_produces_the_same_jitter() {
+        let mut policy = policy();
+        policy.jitter_fraction = 1.0;
+        assert_eq!(policy.backoff_for_attempt(1, 7), policy.backoff_for_attempt(1, 7));
+    }
+}
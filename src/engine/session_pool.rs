@@ -0,0 +1,348 @@
+//! A real session pool for ONNX Runtime sessions.
+//!
+//! [`crate::engine::jobs::MultiJobScheduler`] already bounds concurrent
+//! session usage with a bare `tokio::sync::Semaphore` permit count
+//! (`session_pool_size`) — effective for *counting* how many sessions may
+//! run at once, but it never owns an actual session: whoever acquires a
+//! permit is still responsible for creating (or finding) the
+//! [`CompatSession`] it's about to use. [`SessionPool`] is the fuller
+//! primitive this crate didn't have yet: it owns a fixed set of
+//! [`CompatSession`] instances up front, hands one out per
+//! [`SessionPool::checkout`] call behind a [`SessionGuard`] that returns it
+//! automatically when the guard drops, and tracks how long callers waited
+//! and how full the pool is via [`SessionPool::metrics`].
+//!
+//! This is a new, standalone pool rather than a rewrite of
+//! [`crate::engine::jobs::MultiJobScheduler`]'s semaphore: that scheduler's
+//! tests already pin down the counting-permit behavior, and swapping its
+//! session bookkeeping for this pool is a larger, separate change than
+//! "implement a real pool" asks for on its own. `MultiJobScheduler` has no
+//! hook for a [`SessionPool`]: its jobs only ever acquire a bare permit, so
+//! bounded concurrent jobs and real session reuse are not combined by
+//! anything in this crate.
+//!
+//! Checkout blocks the calling thread rather than using `async`, matching
+//! [`CompatSession::run_single_input`] itself being a blocking call (ONNX
+//! Runtime's own session API is synchronous) rather than the `tokio`-based
+//! concurrency [`crate::engine::jobs`] uses for scheduling around it.
+
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::ort_compat::CompatSession;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionPoolError {
+    #[error("session pool must own at least one session, got 0")]
+    EmptyPool,
+}
+
+struct PoolState<S> {
+    idle: Vec<S>,
+    in_use: usize,
+}
+
+struct MetricsState {
+    total_checkouts: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+}
+
+/// A point-in-time snapshot of a [`SessionPool`]'s counts and cumulative
+/// wait figures, from [`SessionPool::metrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionPoolMetrics {
+    pub in_use: usize,
+    pub idle: usize,
+    pub total_checkouts: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+}
+
+impl SessionPoolMetrics {
+    /// `total_wait` divided evenly across `total_checkouts`, or
+    /// [`Duration::ZERO`] for a pool nothing has checked out of yet.
+    pub fn mean_wait(&self) -> Duration {
+        if self.total_checkouts == 0 {
+            Duration::ZERO
+        } else {
+            self.total_wait / self.total_checkouts as u32
+        }
+    }
+
+    /// `in_use / (in_use + idle)` — the fraction of this pool's fixed
+    /// capacity that's checked out right now. `0.0` for a pool with no
+    /// sessions at all, though [`SessionPool::new`] never actually
+    /// constructs one.
+    pub fn utilization(&self) -> f64 {
+        let capacity = self.in_use + self.idle;
+        if capacity == 0 {
+            0.0
+        } else {
+            self.in_use as f64 / capacity as f64
+        }
+    }
+
+    /// Renders this snapshot as Prometheus text exposition format, in the
+    /// same style as [`crate::metrics::MetricsSnapshot::to_prometheus_text`].
+    pub fn to_prometheus_text(&self) -> String {
+        format!(
+            "transfiguration_session_pool_in_use {}\n\
+             transfiguration_session_pool_idle {}\n\
+             transfiguration_session_pool_utilization {:.6}\n\
+             transfiguration_session_pool_total_checkouts {}\n\
+             transfiguration_session_pool_mean_wait_seconds {:.6}\n\
+             transfiguration_session_pool_max_wait_seconds {:.6}\n",
+            self.in_use,
+            self.idle,
+            self.utilization(),
+            self.total_checkouts,
+            self.mean_wait().as_secs_f64(),
+            self.max_wait.as_secs_f64(),
+        )
+    }
+}
+
+/// Owns a fixed-size pool of [`CompatSession`] instances, handing one out
+/// per [`checkout`](SessionPool::checkout) behind a [`SessionGuard`] that
+/// returns it to the pool automatically when the guard drops. Blocks the
+/// calling thread when every session is already checked out, rather than
+/// creating sessions beyond the pool's fixed size — the same bound
+/// [`crate::engine::jobs::SchedulerLimits::session_pool_size`] enforces with
+/// a semaphore, but backed here by real, reusable sessions instead of bare
+/// permits.
+pub struct SessionPool<S> {
+    state: Mutex<PoolState<S>>,
+    available: Condvar,
+    metrics: Mutex<MetricsState>,
+    per_session_memory_bytes: u64,
+}
+
+impl<S: CompatSession> SessionPool<S> {
+    /// Builds a pool that owns exactly `sessions` — its size is
+    /// `max_concurrent_sessions`, fixed for the pool's lifetime.
+    /// `per_session_memory_bytes` is the caller's own estimate of one
+    /// session's resident memory (e.g. from
+    /// [`crate::model::memory_planner::plan_session_pool`]) — this pool has
+    /// no way to measure ONNX Runtime's actual allocations itself, so it
+    /// trusts the caller's figure for
+    /// [`SessionPool::estimated_memory_bytes`].
+    pub fn new(sessions: Vec<S>, per_session_memory_bytes: u64) -> Result<Self, SessionPoolError> {
+        if sessions.is_empty() {
+            return Err(SessionPoolError::EmptyPool);
+        }
+        Ok(SessionPool {
+            state: Mutex::new(PoolState { idle: sessions, in_use: 0 }),
+            available: Condvar::new(),
+            metrics: Mutex::new(MetricsState {
+                total_checkouts: 0,
+                total_wait: Duration::ZERO,
+                max_wait: Duration::ZERO,
+            }),
+            per_session_memory_bytes,
+        })
+    }
+
+    /// The pool's fixed size: how many sessions it owns in total, checked
+    /// out or not.
+    pub fn max_concurrent_sessions(&self) -> usize {
+        let state = self.state.lock().expect("session pool mutex poisoned");
+        state.idle.len() + state.in_use
+    }
+
+    /// `per_session_memory_bytes` (given to [`SessionPool::new`]) times how
+    /// many sessions are currently checked out.
+    pub fn estimated_memory_bytes(&self) -> u64 {
+        let state = self.state.lock().expect("session pool mutex poisoned");
+        state.in_use as u64 * self.per_session_memory_bytes
+    }
+
+    /// Blocks the calling thread until a session is idle, then hands it out
+    /// behind a [`SessionGuard`] that returns it to the pool when dropped.
+    pub fn checkout(&self) -> SessionGuard<'_, S> {
+        let started = Instant::now();
+        let mut state = self.state.lock().expect("session pool mutex poisoned");
+        while state.idle.is_empty() {
+            state = self.available.wait(state).expect("session pool mutex poisoned");
+        }
+        let session = state.idle.pop().expect("loop only exits once an idle session is available");
+        state.in_use += 1;
+        drop(state);
+
+        let wait = started.elapsed();
+        let mut metrics = self.metrics.lock().expect("session pool metrics mutex poisoned");
+        metrics.total_checkouts += 1;
+        metrics.total_wait += wait;
+        metrics.max_wait = metrics.max_wait.max(wait);
+        drop(metrics);
+
+        SessionGuard { pool: self, session: Some(session) }
+    }
+
+    fn check_in(&self, session: S) {
+        let mut state = self.state.lock().expect("session pool mutex poisoned");
+        state.in_use -= 1;
+        state.idle.push(session);
+        drop(state);
+        self.available.notify_one();
+    }
+
+    /// A snapshot of this pool's current in-use/idle counts and cumulative
+    /// checkout wait figures.
+    pub fn metrics(&self) -> SessionPoolMetrics {
+        let state = self.state.lock().expect("session pool mutex poisoned");
+        let metrics = self.metrics.lock().expect("session pool metrics mutex poisoned");
+        SessionPoolMetrics {
+            in_use: state.in_use,
+            idle: state.idle.len(),
+            total_checkouts: metrics.total_checkouts,
+            total_wait: metrics.total_wait,
+            max_wait: metrics.max_wait,
+        }
+    }
+}
+
+/// Holds one checked-out [`CompatSession`] for as long as it lives; returns
+/// the session to its [`SessionPool`] automatically on drop, so a caller
+/// can't forget to check a session back in even if it returns early or
+/// panics.
+pub struct SessionGuard<'a, S: CompatSession> {
+    pool: &'a SessionPool<S>,
+    session: Option<S>,
+}
+
+impl<S: CompatSession> std::ops::Deref for SessionGuard<'_, S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        self.session.as_ref().expect("session is only ever taken by Drop")
+    }
+}
+
+impl<S: CompatSession> std::ops::DerefMut for SessionGuard<'_, S> {
+    fn deref_mut(&mut self) -> &mut S {
+        self.session.as_mut().expect("session is only ever taken by Drop")
+    }
+}
+
+impl<S: CompatSession> Drop for SessionGuard<'_, S> {
+    fn drop(&mut self) {
+        if let Some(session) = self.session.take() {
+            self.pool.check_in(session);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ort_compat::{CompatTensor, OrtCompatError};
+
+    struct FakeSession {
+        id: usize,
+    }
+
+    impl CompatSession for FakeSession {
+        fn run_single_input(&mut self, _input_name: &str, _input: &dyn CompatTensor) -> Result<Vec<f32>, OrtCompatError> {
+            Ok(vec![self.id as f32])
+        }
+    }
+
+    #[test]
+    fn new_rejects_an_empty_session_list() {
+        let result = SessionPool::<FakeSession>::new(Vec::new(), 0);
+        assert!(matches!(result, Err(SessionPoolError::EmptyPool)));
+    }
+
+    #[test]
+    fn max_concurrent_sessions_is_the_fixed_pool_size() {
+        let pool = SessionPool::new(vec![FakeSession { id: 0 }, FakeSession { id: 1 }], 1024).unwrap();
+        assert_eq!(pool.max_concurrent_sessions(), 2);
+    }
+
+    #[test]
+    fn checkout_reports_in_use_and_idle_counts() {
+        let pool = SessionPool::new(vec![FakeSession { id: 0 }, FakeSession { id: 1 }], 1024).unwrap();
+
+        let guard = pool.checkout();
+        let metrics = pool.metrics();
+        assert_eq!(metrics.in_use, 1);
+        assert_eq!(metrics.idle, 1);
+        assert_eq!(pool.estimated_memory_bytes(), 1024);
+
+        drop(guard);
+        let metrics = pool.metrics();
+        assert_eq!(metrics.in_use, 0);
+        assert_eq!(metrics.idle, 2);
+        assert_eq!(pool.estimated_memory_bytes(), 0);
+    }
+
+    #[test]
+    fn dropping_a_guard_returns_the_session_for_reuse() {
+        let pool = SessionPool::new(vec![FakeSession { id: 7 }], 0).unwrap();
+
+        {
+            let mut guard = pool.checkout();
+            assert_eq!(guard.run_single_input("x", &crate::engine::ort_compat::OwnedTensor { shape: vec![1], data: vec![0.0] }).unwrap(), vec![7.0]);
+        }
+
+        let guard = pool.checkout();
+        assert_eq!(guard.id, 7);
+    }
+
+    #[test]
+    fn utilization_reflects_the_fraction_of_the_pool_checked_out() {
+        let pool = SessionPool::new(vec![FakeSession { id: 0 }, FakeSession { id: 1 }], 0).unwrap();
+        assert_eq!(pool.metrics().utilization(), 0.0);
+
+        let guard = pool.checkout();
+        assert_eq!(pool.metrics().utilization(), 0.5);
+
+        drop(guard);
+        assert_eq!(pool.metrics().utilization(), 0.0);
+    }
+
+    #[test]
+    fn prometheus_text_includes_utilization_and_checkout_counts() {
+        let pool = SessionPool::new(vec![FakeSession { id: 0 }], 0).unwrap();
+        let guard = pool.checkout();
+
+        let text = pool.metrics().to_prometheus_text();
+        assert!(text.contains("transfiguration_session_pool_in_use 1"));
+        assert!(text.contains("transfiguration_session_pool_idle 0"));
+        assert!(text.contains("transfiguration_session_pool_utilization 1.000000"));
+        assert!(text.contains("transfiguration_session_pool_total_checkouts 1"));
+
+        drop(guard);
+    }
+
+    #[test]
+    fn metrics_tracks_total_checkouts() {
+        let pool = SessionPool::new(vec![FakeSession { id: 0 }], 0).unwrap();
+
+        drop(pool.checkout());
+        drop(pool.checkout());
+
+        assert_eq!(pool.metrics().total_checkouts, 2);
+    }
+
+    #[test]
+    fn a_second_checkout_blocks_until_the_first_is_returned() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let pool = Arc::new(SessionPool::new(vec![FakeSession { id: 0 }], 0).unwrap());
+        let first_guard = pool.checkout();
+
+        let pool_for_thread = Arc::clone(&pool);
+        let handle = thread::spawn(move || {
+            let _second_guard = pool_for_thread.checkout();
+        });
+
+        thread::sleep(Duration::from_millis(50));
+        assert!(!handle.is_finished(), "second checkout should still be blocked while the first guard is held");
+
+        drop(first_guard);
+        handle.join().unwrap();
+    }
+}
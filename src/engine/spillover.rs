@@ -0,0 +1,274 @@
+//! Mixed GPU/CPU session pool with automatic spillover.
+//!
+//! A GPU backend has a small, fixed concurrency before sessions start
+//! queueing; the CPU backend is slower per-chunk but has far more headroom.
+//! [`SpilloverPool`] always sends latency-sensitive sessions to the GPU, but
+//! once the GPU's in-flight queue depth passes [`SpilloverConfig::spillover_threshold`]
+//! it starts routing [`LatencyTolerance::Tolerant`] sessions to the CPU
+//! backend instead of piling them up behind the saturated GPU queue.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::task::JoinSet;
+
+use crate::chunk::ChunkId;
+use crate::engine::agents::ActiveAgentGuard;
+use crate::engine::{EngineError, InferenceBackend};
+use crate::metrics::ParallelMetrics;
+use crate::model::context::estimate_token_count;
+
+/// Which backend actually executed a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Device {
+    Gpu,
+    Cpu,
+}
+
+/// Whether a session may be diverted to the CPU backend when the GPU queue
+/// is saturated. Interactive sessions always wait for the GPU instead, since
+/// spillover trades latency for throughput and an interactive caller is
+/// already budgeted against [`crate::engine::INTERACTIVE_LATENCY_TARGET`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyTolerance {
+    Interactive,
+    Tolerant,
+}
+
+/// One chunk's worth of work for a [`SpilloverPool`] to route and run.
+pub struct SpilloverSession {
+    pub chunk_id: ChunkId,
+    pub prompt: String,
+    pub tolerance: LatencyTolerance,
+}
+
+/// Queue-depth threshold past which tolerant sessions spill over to CPU.
+#[derive(Debug, Clone, Copy)]
+pub struct SpilloverConfig {
+    pub spillover_threshold: usize,
+}
+
+impl Default for SpilloverConfig {
+    fn default() -> Self {
+        SpilloverConfig { spillover_threshold: 4 }
+    }
+}
+
+/// What came out of a [`SpilloverPool::run_all`] call, with each result
+/// tagged by the device that produced it so a caller can tell spillover
+/// actually happened.
+#[derive(Debug, Default)]
+pub struct SpilloverOutcome {
+    pub summaries: Vec<(ChunkId, Device, String)>,
+    pub errors: Vec<(ChunkId, Device, EngineError)>,
+}
+
+/// Runs sessions over a GPU backend and a CPU backend, routing overflow from
+/// a saturated GPU queue to CPU, and tracking per-device throughput
+/// separately so a caller can tell whether spillover is actually earning its
+/// keep.
+pub struct SpilloverPool<G, C>
+where
+    G: InferenceBackend + Send + Sync + 'static,
+    C: InferenceBackend + Send + Sync + 'static,
+{
+    gpu_backend: Arc<G>,
+    cpu_backend: Arc<C>,
+    config: SpilloverConfig,
+    gpu_queue_depth: Arc<AtomicUsize>,
+    gpu_metrics: Arc<ParallelMetrics>,
+    cpu_metrics: Arc<ParallelMetrics>,
+}
+
+impl<G, C> SpilloverPool<G, C>
+where
+    G: InferenceBackend + Send + Sync + 'static,
+    C: InferenceBackend + Send + Sync + 'static,
+{
+    pub fn new(gpu_backend: Arc<G>, cpu_backend: Arc<C>, config: SpilloverConfig) -> Self {
+        SpilloverPool {
+            gpu_backend,
+            cpu_backend,
+            config,
+            gpu_queue_depth: Arc::new(AtomicUsize::new(0)),
+            gpu_metrics: Arc::new(ParallelMetrics::default()),
+            cpu_metrics: Arc::new(ParallelMetrics::default()),
+        }
+    }
+
+    /// Per-device throughput and latency for the GPU backend.
+    pub fn gpu_metrics(&self) -> Arc<ParallelMetrics> {
+        Arc::clone(&self.gpu_metrics)
+    }
+
+    /// Per-device throughput and latency for the CPU backend.
+    pub fn cpu_metrics(&self) -> Arc<ParallelMetrics> {
+        Arc::clone(&self.cpu_metrics)
+    }
+
+    /// Routes and runs every session concurrently. A [`LatencyTolerance::Tolerant`]
+    /// session spills over to CPU as soon as the GPU queue depth (sessions
+    /// dispatched to GPU but not yet finished) reaches
+    /// [`SpilloverConfig::spillover_threshold`] at the moment it is
+    /// dispatched; an [`LatencyTolerance::Interactive`] session always waits
+    /// for the GPU.
+    pub async fn run_all(&self, sessions: Vec<SpilloverSession>) -> SpilloverOutcome {
+        let mut join_set: JoinSet<(ChunkId, Device, Result<String, EngineError>)> = JoinSet::new();
+
+        for session in sessions {
+            let device = self.route(session.tolerance);
+            match device {
+                Device::Gpu => {
+                    let backend = Arc::clone(&self.gpu_backend);
+                    let metrics = Arc::clone(&self.gpu_metrics);
+                    let queue_depth = Arc::clone(&self.gpu_queue_depth);
+                    let chunk_id = session.chunk_id;
+                    let prompt = session.prompt;
+
+                    // Constructed before the `async move` block, not inside
+                    // it — see `ActiveAgentGuard::new`'s doc comment.
+                    let active_guard = ActiveAgentGuard::new(Arc::clone(&metrics));
+                    queue_depth.fetch_add(1, Ordering::SeqCst);
+                    join_set.spawn(async move {
+                        let _active_guard = active_guard;
+                        let _depth_guard = QueueDepthGuard(queue_depth);
+                        let result = run_one(&*backend, &prompt, &metrics);
+                        (chunk_id, Device::Gpu, result)
+                    });
+                }
+                Device::Cpu => {
+                    let backend = Arc::clone(&self.cpu_backend);
+                    let metrics = Arc::clone(&self.cpu_metrics);
+                    let chunk_id = session.chunk_id;
+                    let prompt = session.prompt;
+
+                    // Constructed before the `async move` block, not inside
+                    // it — see `ActiveAgentGuard::new`'s doc comment.
+                    let active_guard = ActiveAgentGuard::new(Arc::clone(&metrics));
+                    join_set.spawn(async move {
+                        let _active_guard = active_guard;
+                        let result = run_one(&*backend, &prompt, &metrics);
+                        (chunk_id, Device::Cpu, result)
+                    });
+                }
+            }
+        }
+
+        let mut outcome = SpilloverOutcome::default();
+        while let Some(joined) = join_set.join_next().await {
+            if let Ok((chunk_id, device, result)) = joined {
+                match result {
+                    Ok(text) => outcome.summaries.push((chunk_id, device, text)),
+                    Err(error) => outcome.errors.push((chunk_id, device, error)),
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Picks the device for one session without touching shared state beyond
+    /// reading the current GPU queue depth. Dispatching the session still has
+    /// to bump that counter itself, right before spawning, so two sessions
+    /// routed concurrently don't both see the depth from before either one
+    /// was dispatched.
+    fn route(&self, tolerance: LatencyTolerance) -> Device {
+        if tolerance == LatencyTolerance::Interactive {
+            return Device::Gpu;
+        }
+        if self.gpu_queue_depth.load(Ordering::SeqCst) >= self.config.spillover_threshold {
+            Device::Cpu
+        } else {
+            Device::Gpu
+        }
+    }
+}
+
+struct QueueDepthGuard(Arc<AtomicUsize>);
+
+impl Drop for QueueDepthGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn run_one(backend: &impl InferenceBackend, prompt: &str, metrics: &ParallelMetrics) -> Result<String, EngineError> {
+    let started_at = std::time::Instant::now();
+    let result = backend.generate_completion_text(prompt);
+    match &result {
+        Ok(text) => {
+            metrics.record_chunk_completed(started_at.elapsed());
+            metrics.record_tokens_generated(estimate_token_count(text) as u64);
+        }
+        Err(_) => metrics.record_chunk_failed(),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+
+    impl InferenceBackend for EchoBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("echo: {prompt}"))
+        }
+    }
+
+    fn session(id: u64, tolerance: LatencyTolerance) -> SpilloverSession {
+        SpilloverSession {
+            chunk_id: ChunkId(id),
+            prompt: format!("chunk-{id}"),
+            tolerance,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn interactive_sessions_never_spill_over() {
+        let pool = SpilloverPool::new(
+            Arc::new(EchoBackend),
+            Arc::new(EchoBackend),
+            SpilloverConfig { spillover_threshold: 0 },
+        );
+
+        let outcome = pool
+            .run_all(vec![session(1, LatencyTolerance::Interactive), session(2, LatencyTolerance::Interactive)])
+            .await;
+
+        assert!(outcome.summaries.iter().all(|(_, device, _)| *device == Device::Gpu));
+        assert_eq!(pool.cpu_metrics().snapshot().chunks_completed, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn tolerant_sessions_spill_to_cpu_once_threshold_is_zero() {
+        let pool = SpilloverPool::new(
+            Arc::new(EchoBackend),
+            Arc::new(EchoBackend),
+            SpilloverConfig { spillover_threshold: 0 },
+        );
+
+        let outcome = pool
+            .run_all(vec![session(1, LatencyTolerance::Tolerant), session(2, LatencyTolerance::Tolerant)])
+            .await;
+
+        assert_eq!(outcome.summaries.len(), 2);
+        assert!(outcome.summaries.iter().all(|(_, device, _)| *device == Device::Cpu));
+        assert_eq!(pool.gpu_metrics().snapshot().chunks_completed, 0);
+        assert_eq!(pool.cpu_metrics().snapshot().chunks_completed, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn tolerant_sessions_stay_on_gpu_under_the_threshold() {
+        let pool = SpilloverPool::new(
+            Arc::new(EchoBackend),
+            Arc::new(EchoBackend),
+            SpilloverConfig { spillover_threshold: 10 },
+        );
+
+        let outcome = pool.run_all(vec![session(1, LatencyTolerance::Tolerant)]).await;
+
+        assert_eq!(outcome.summaries.len(), 1);
+        assert_eq!(outcome.summaries[0].1, Device::Gpu);
+    }
+}
@@ -0,0 +1,351 @@
+//! Pluggable tokenization for ONNX-exported causal language models.
+//!
+//! [`crate::engine::ort_compat::CompatSession`] /
+//! [`crate::engine::ort_compat::CompatTensor`] abstract over the ONNX
+//! Runtime backend, but every caller still has to turn text into token ids
+//! and back by hand, one model family at a time. [`Tokenizer`] is the seam:
+//! encode/decode plus vocabulary and special-token introspection, so a
+//! caller that has a `CompatSession` for some exported causal LM can pair
+//! it with whichever [`Tokenizer`] matches that model without the rest of
+//! the engine caring which one it is.
+//!
+//! Two implementations ship here. [`VocabTokenizer`] loads a HuggingFace
+//! `tokenizer.json`'s `model.vocab` table and does greedy
+//! longest-prefix-match encoding against it — not the real BPE merge
+//! algorithm those files are meant to drive, which would mean either
+//! hand-rolling a full BPE engine or taking on the `tokenizers` crate as a
+//! dependency for this one seam. Until a caller needs exact parity with the
+//! reference tokenizer, greedy matching against the same vocabulary gets
+//! usable-but-approximate results from the same file with no new dependency
+//! — a documented, honest limitation rather than a silent one.
+//! [`ByteLevelTokenizer`] is the dependency-free fallback: one token per
+//! UTF-8 byte, always available, and always round-trips exactly.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenizerError {
+    #[error("failed to parse tokenizer vocab file: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("io error reading tokenizer vocab file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("token id {0} is not in the vocabulary")]
+    UnknownTokenId(u32),
+}
+
+/// Turns text into model input ids and back, for whichever ONNX-exported
+/// causal LM a [`crate::engine::ort_compat::CompatSession`] was loaded for.
+pub trait Tokenizer {
+    fn encode(&self, text: &str) -> Vec<u32>;
+    fn decode(&self, ids: &[u32]) -> Result<String, TokenizerError>;
+    fn vocab_size(&self) -> usize;
+    fn bos_token_id(&self) -> Option<u32>;
+    fn eos_token_id(&self) -> Option<u32>;
+}
+
+/// Dependency-free fallback: one token per UTF-8 byte. Always round-trips
+/// exactly, since every `u32` produced by [`encode`](Tokenizer::encode) is a
+/// valid byte value, and works for any model without a matching vocabulary
+/// file on hand — at the cost of a much longer sequence than a real subword
+/// tokenizer would produce.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteLevelTokenizer;
+
+impl Tokenizer for ByteLevelTokenizer {
+    fn encode(&self, text: &str) -> Vec<u32> {
+        text.bytes().map(u32::from).collect()
+    }
+
+    fn decode(&self, ids: &[u32]) -> Result<String, TokenizerError> {
+        let bytes: Vec<u8> = ids
+            .iter()
+            .map(|&id| u8::try_from(id).map_err(|_| TokenizerError::UnknownTokenId(id)))
+            .collect::<Result<_, _>>()?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn vocab_size(&self) -> usize {
+        256
+    }
+
+    fn bos_token_id(&self) -> Option<u32> {
+        None
+    }
+
+    fn eos_token_id(&self) -> Option<u32> {
+        None
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenizerJson {
+    model: TokenizerModel,
+    #[serde(default)]
+    added_tokens: Vec<AddedToken>,
+}
+
+#[derive(Deserialize)]
+struct TokenizerModel {
+    vocab: HashMap<String, u32>,
+}
+
+#[derive(Deserialize)]
+struct AddedToken {
+    id: u32,
+    content: String,
+    #[serde(default)]
+    special: bool,
+}
+
+/// Greedy longest-prefix-match tokenizer loaded from a HuggingFace
+/// `tokenizer.json`'s `model.vocab` table, plus whatever `added_tokens`
+/// entries it declares. See the module docs for why this is greedy matching
+/// rather than the real BPE merge algorithm.
+pub struct VocabTokenizer {
+    token_to_id: HashMap<String, u32>,
+    id_to_token: HashMap<u32, String>,
+    bos_token_id: Option<u32>,
+    eos_token_id: Option<u32>,
+    max_token_len: usize,
+}
+
+impl VocabTokenizer {
+    pub fn load_from_file(path: &Path) -> Result<Self, TokenizerError> {
+        let bytes = std::fs::read(path)?;
+        Self::load_from_json(&bytes)
+    }
+
+    pub fn load_from_json(bytes: &[u8]) -> Result<Self, TokenizerError> {
+        let parsed: TokenizerJson = serde_json::from_slice(bytes)?;
+
+        let mut token_to_id = parsed.model.vocab;
+        let mut bos_token_id = None;
+        let mut eos_token_id = None;
+
+        for added in &parsed.added_tokens {
+            token_to_id.insert(added.content.clone(), added.id);
+            if added.special {
+                match added.content.as_str() {
+                    "<s>" | "<|startoftext|>" | "<bos>" => bos_token_id = Some(added.id),
+                    "</s>" | "<|endoftext|>" | "<eos>" => eos_token_id = Some(added.id),
+                    _ => {}
+                }
+            }
+        }
+
+        let max_token_len = token_to_id.keys().map(|token| token.len()).max().unwrap_or(1).max(1);
+        let id_to_token = token_to_id.iter().map(|(token, &id)| (id, token.clone())).collect();
+
+        Ok(VocabTokenizer {
+            token_to_id,
+            id_to_token,
+            bos_token_id,
+            eos_token_id,
+            max_token_len,
+        })
+    }
+}
+
+impl Tokenizer for VocabTokenizer {
+    fn encode(&self, text: &str) -> Vec<u32> {
+        let bytes = text.as_bytes();
+        let mut ids = Vec::new();
+        let mut start = 0;
+
+        while start < bytes.len() {
+            let max_len = self.max_token_len.min(bytes.len() - start);
+            let matched = (1..=max_len).rev().find_map(|len| {
+                let candidate = std::str::from_utf8(&bytes[start..start + len]).ok()?;
+                self.token_to_id.get(candidate).map(|&id| (id, len))
+            });
+
+            match matched {
+                Some((id, len)) => {
+                    ids.push(id);
+                    start += len;
+                }
+                None => {
+                    // No vocab entry covers even a single byte here — skip it
+                    // rather than silently dropping the rest of the text.
+                    start += 1;
+                }
+            }
+        }
+
+        ids
+    }
+
+    fn decode(&self, ids: &[u32]) -> Result<String, TokenizerError> {
+        let mut text = String::new();
+        for &id in ids {
+            let token = self.id_to_token.get(&id).ok_or(TokenizerError::UnknownTokenId(id))?;
+            text.push_str(token);
+        }
+        Ok(text)
+    }
+
+    fn vocab_size(&self) -> usize {
+        self.token_to_id.len()
+    }
+
+    fn bos_token_id(&self) -> Option<u32> {
+        self.bos_token_id
+    }
+
+    fn eos_token_id(&self) -> Option<u32> {
+        self.eos_token_id
+    }
+}
+
+/// Right-pads `sequences` to a common length with `pad_token_id`, for
+/// stacking several chunk prompts' token ids into one `[batch, max_len]`
+/// tensor ahead of a single ONNX forward pass — see
+/// [`crate::engine::batching`]'s module docs for why running several
+/// prompts through one forward pass beats one-by-one. Returns the
+/// flattened, row-major ids alongside the common length every row was
+/// padded to, so a caller can hand `(ids, vec![sequences.len(), max_len])`
+/// straight to [`crate::engine::ort_compat::OwnedTensor`].
+///
+/// This crate has no ONNX-backed [`crate::engine::InferenceBackend`] wired
+/// up yet — `Tokenizer` and [`crate::engine::ort_compat::CompatSession`]
+/// exist as separate seams with nothing joining them into one backend — so
+/// this is the forward-pass-level batching mechanism such a backend would
+/// call into, not a batching backend in its own right. Chunk-level batching
+/// (grouping chunks before they ever reach a backend) is
+/// [`crate::engine::agents::ParallelAgentSystem::run_all_batched`]'s job
+/// instead.
+pub fn pad_batch(sequences: &[Vec<u32>], pad_token_id: u32) -> (Vec<u32>, usize) {
+    let max_len = sequences.iter().map(|sequence| sequence.len()).max().unwrap_or(0);
+    let mut flat = Vec::with_capacity(sequences.len() * max_len);
+    for sequence in sequences {
+        flat.extend_from_slice(sequence);
+        flat.extend(std::iter::repeat_n(pad_token_id, max_len - sequence.len()));
+    }
+    (flat, max_len)
+}
+
+/// Splits `outputs` — the flat result of one [`pad_batch`]-built forward
+/// pass — back into one slice per sequence, assuming every sequence
+/// produced the same number of output values (true for, say, a fixed-size
+/// pooled embedding; not true for per-token logits, where a caller still
+/// has to trim each sequence's own padding using the length it passed to
+/// [`pad_batch`]).
+pub fn split_batch_outputs(outputs: &[f32], batch_size: usize) -> Vec<&[f32]> {
+    if batch_size == 0 {
+        return Vec::new();
+    }
+    let per_sequence = outputs.len() / batch_size;
+    outputs.chunks(per_sequence).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_level_tokenizer_round_trips_unicode_text() {
+        let tokenizer = ByteLevelTokenizer;
+        let ids = tokenizer.encode("fn go() -> \u{2713} {}");
+        assert_eq!(ids.len(), "fn go() -> \u{2713} {}".len());
+        assert_eq!(tokenizer.decode(&ids).unwrap(), "fn go() -> \u{2713} {}");
+    }
+
+    #[test]
+    fn byte_level_tokenizer_reports_a_256_entry_vocab_with_no_special_tokens() {
+        let tokenizer = ByteLevelTokenizer;
+        assert_eq!(tokenizer.vocab_size(), 256);
+        assert_eq!(tokenizer.bos_token_id(), None);
+        assert_eq!(tokenizer.eos_token_id(), None);
+    }
+
+    fn sample_vocab_json() -> &'static str {
+        r#"{
+            "model": {
+                "vocab": {
+                    "fn": 0,
+                    "go": 1,
+                    "(": 2,
+                    ")": 3,
+                    " ": 4,
+                    "f": 5,
+                    "n": 6
+                }
+            },
+            "added_tokens": [
+                {"id": 7, "content": "<s>", "special": true},
+                {"id": 8, "content": "</s>", "special": true}
+            ]
+        }"#
+    }
+
+    #[test]
+    fn vocab_tokenizer_greedily_matches_the_longest_known_token() {
+        let tokenizer = VocabTokenizer::load_from_json(sample_vocab_json().as_bytes()).unwrap();
+        let ids = tokenizer.encode("fngo");
+        assert_eq!(ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn vocab_tokenizer_falls_back_to_shorter_matches_when_no_longer_one_exists() {
+        let tokenizer = VocabTokenizer::load_from_json(sample_vocab_json().as_bytes()).unwrap();
+        let ids = tokenizer.encode("fn()");
+        assert_eq!(ids, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn vocab_tokenizer_round_trips_through_decode() {
+        let tokenizer = VocabTokenizer::load_from_json(sample_vocab_json().as_bytes()).unwrap();
+        let ids = tokenizer.encode("fn go()");
+        assert_eq!(tokenizer.decode(&ids).unwrap(), "fn go()");
+    }
+
+    #[test]
+    fn vocab_tokenizer_picks_up_added_tokens_as_bos_and_eos() {
+        let tokenizer = VocabTokenizer::load_from_json(sample_vocab_json().as_bytes()).unwrap();
+        assert_eq!(tokenizer.bos_token_id(), Some(7));
+        assert_eq!(tokenizer.eos_token_id(), Some(8));
+        assert_eq!(tokenizer.vocab_size(), 9);
+    }
+
+    #[test]
+    fn vocab_tokenizer_decode_rejects_an_id_outside_the_vocabulary() {
+        let tokenizer = VocabTokenizer::load_from_json(sample_vocab_json().as_bytes()).unwrap();
+        assert!(matches!(tokenizer.decode(&[999]), Err(TokenizerError::UnknownTokenId(999))));
+    }
+
+    #[test]
+    fn pad_batch_right_pads_every_sequence_to_the_longest_one() {
+        let (flat, max_len) = pad_batch(&[vec![1, 2, 3], vec![4, 5]], 0);
+        assert_eq!(max_len, 3);
+        assert_eq!(flat, vec![1, 2, 3, 4, 5, 0]);
+    }
+
+    #[test]
+    fn pad_batch_of_equal_length_sequences_adds_no_padding() {
+        let (flat, max_len) = pad_batch(&[vec![1, 2], vec![3, 4]], 9);
+        assert_eq!(max_len, 2);
+        assert_eq!(flat, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn pad_batch_of_no_sequences_is_empty() {
+        let (flat, max_len) = pad_batch(&[], 0);
+        assert_eq!(max_len, 0);
+        assert!(flat.is_empty());
+    }
+
+    #[test]
+    fn split_batch_outputs_divides_evenly_by_batch_size() {
+        let outputs = vec![1.0, 2.0, 3.0, 4.0];
+        let slices = split_batch_outputs(&outputs, 2);
+        assert_eq!(slices, vec![&[1.0, 2.0][..], &[3.0, 4.0][..]]);
+    }
+
+    #[test]
+    fn split_batch_outputs_of_a_zero_batch_size_is_empty() {
+        assert!(split_batch_outputs(&[1.0, 2.0], 0).is_empty());
+    }
+}
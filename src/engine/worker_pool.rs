@@ -0,0 +1,543 @@
+//! Worker-process isolation for inference sessions, so a crash in native
+//! inference code (an ORT segfault, say) takes down one worker instead of
+//! the whole process and every chunk in flight with it.
+//!
+//! [`InferenceBackend`](crate::engine::InferenceBackend) runs in-process
+//! today — nothing currently routes a session through a child process.
+//! [`WorkerPool`] is the seam for when something does: it drives
+//! round-robin dispatch, crash detection, bounded respawn, and chunk
+//! re-dispatch against anything implementing [`WorkerTransport`], and
+//! [`UnixSocketWorker`] is a real bincode-over-Unix-socket transport a
+//! caller can spawn a worker binary with. A standalone worker entrypoint
+//! (a small binary that loads an ONNX model and answers
+//! [`WorkerRequest::Summarize`] over its socket) is out of scope here —
+//! this module owns the pool and transport machinery, not a worker binary,
+//! the same way [`decode`](crate::engine::decode) owns decode algorithms
+//! without being wired into a concrete backend.
+//!
+//! A long-lived worker can also degrade without crashing: its underlying
+//! ORT session starts producing repetition loops instead of dying outright.
+//! [`WorkerPool`] scores every [`WorkerResponse::Summary`] with a
+//! [`crate::engine::drift::DriftMonitor`] keyed by worker index, and treats
+//! a worker [`crate::engine::drift::DriftMonitor::record`] flags as degraded
+//! exactly like a crash: `respawn` replaces it and the chunk that exposed
+//! the drift is re-dispatched to a (hopefully healthy) worker, consuming one
+//! attempt of the same `max_attempts_per_chunk` budget a real crash would.
+//!
+//! [`WorkerPool::new`] spawns every worker up front, which is fine for a
+//! pool sized to what a run actually needs but pays the full cold-start
+//! cost (every seat's model load) before the very first chunk can dispatch.
+//! [`WorkerPool::new_lazy`] reserves seats without spawning into them,
+//! filling each one in (via the same `respawn` closure `dispatch` already
+//! uses to replace a crashed worker) only the first time dispatch actually
+//! rotates onto it — so a burst of the first few chunks pays for only as
+//! many workers as it needed, not the pool's full capacity.
+
+use std::io::{self, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkId;
+use crate::engine::drift::{DriftMonitor, DriftThresholds};
+
+/// One request sent to a worker over a [`WorkerTransport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorkerRequest {
+    Summarize { chunk_id: ChunkId, prompt: String },
+    Shutdown,
+}
+
+/// One response read back from a worker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorkerResponse {
+    Summary { chunk_id: ChunkId, text: String },
+    Failed { chunk_id: ChunkId, error: String },
+}
+
+/// A channel to one worker, abstracted so [`WorkerPool`]'s dispatch,
+/// crash-detection, and respawn logic can be tested against an in-memory
+/// double instead of a real child process; see [`UnixSocketWorker`] for the
+/// production implementation.
+pub trait WorkerTransport {
+    fn send(&mut self, request: &WorkerRequest) -> io::Result<()>;
+    fn recv(&mut self) -> io::Result<WorkerResponse>;
+    /// Whether the worker has already exited, without blocking to find out.
+    /// Checked before every dispatch so a worker that died between requests
+    /// is respawned instead of being written to and only then discovered
+    /// dead.
+    fn has_exited(&mut self) -> bool;
+}
+
+/// How to launch a worker process: the executable and the arguments it's
+/// invoked with, plus (appended by [`spawn_unix_socket_worker`]) the Unix
+/// socket path it should connect to.
+#[derive(Debug, Clone)]
+pub struct WorkerProgram {
+    pub executable: PathBuf,
+    pub args: Vec<String>,
+}
+
+/// A real worker: a child process connected over a Unix domain socket,
+/// speaking bincode-framed [`WorkerRequest`]/[`WorkerResponse`] messages
+/// length-prefixed by a 4-byte big-endian length.
+pub struct UnixSocketWorker {
+    child: Child,
+    stream: UnixStream,
+}
+
+fn write_framed(stream: &mut UnixStream, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    stream.write_all(&len.to_be_bytes())?;
+    stream.write_all(bytes)?;
+    stream.flush()
+}
+
+fn read_framed(stream: &mut UnixStream) -> io::Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    let mut buffer = vec![0u8; len];
+    stream.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+impl WorkerTransport for UnixSocketWorker {
+    fn send(&mut self, request: &WorkerRequest) -> io::Result<()> {
+        let bytes = bincode::serialize(request).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        write_framed(&mut self.stream, &bytes)
+    }
+
+    fn recv(&mut self) -> io::Result<WorkerResponse> {
+        let bytes = read_framed(&mut self.stream)?;
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn has_exited(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(Some(_)))
+    }
+}
+
+impl Drop for UnixSocketWorker {
+    fn drop(&mut self) {
+        let _ = self.send(&WorkerRequest::Shutdown);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Spawns `program` with `socket_path` appended as its final argument,
+/// binds a Unix listener at `socket_path` first so there's no race between
+/// the child starting and it having somewhere to connect to, and blocks
+/// (up to `accept_timeout`) for the child's connection.
+pub fn spawn_unix_socket_worker(program: &WorkerProgram, socket_path: &Path, accept_timeout: Duration) -> io::Result<UnixSocketWorker> {
+    let _ = std::fs::remove_file(socket_path);
+    let listener = UnixListener::bind(socket_path)?;
+    listener.set_nonblocking(true)?;
+
+    let mut args = program.args.clone();
+    args.push(socket_path.to_string_lossy().into_owned());
+    let child = Command::new(&program.executable).args(&args).spawn()?;
+
+    let deadline = Instant::now() + accept_timeout;
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(UnixSocketWorker { child, stream });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "worker never connected to its socket"));
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A worker's answer to one dispatched chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DispatchOutcome {
+    Summary(String),
+    /// The worker itself reported failure for this chunk (not a transport
+    /// or crash error).
+    WorkerError(String),
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum WorkerPoolError {
+    #[error("chunk {0:?} exhausted its retry budget across worker crashes")]
+    RetryBudgetExhausted(ChunkId),
+}
+
+/// Drives dispatch over a fixed set of workers, round-robin, with crash
+/// detection and bounded respawn: a worker found dead (or that errors
+/// mid-request) is replaced via `respawn` and the chunk it was holding is
+/// re-dispatched to the next live worker, up to `max_attempts_per_chunk`
+/// total tries.
+pub struct WorkerPool<T: WorkerTransport> {
+    /// One slot per pool seat. `None` means the seat has never been filled
+    /// yet — see [`Self::new_lazy`] — and is filled by `respawn` the first
+    /// time `dispatch` rotates onto it, exactly the same way a crashed
+    /// worker's slot is refilled.
+    workers: Vec<Option<T>>,
+    next_worker: usize,
+    max_attempts_per_chunk: usize,
+    drift_monitor: DriftMonitor,
+    /// Chunks whose summary was discarded and re-dispatched because the
+    /// worker that produced it had drifted, in the order it happened.
+    drift_recycles: Vec<ChunkId>,
+}
+
+impl<T: WorkerTransport> WorkerPool<T> {
+    pub fn new(workers: Vec<T>, max_attempts_per_chunk: usize) -> Self {
+        WorkerPool {
+            workers: workers.into_iter().map(Some).collect(),
+            next_worker: 0,
+            max_attempts_per_chunk: max_attempts_per_chunk.max(1),
+            drift_monitor: DriftMonitor::new(DriftThresholds::default()),
+            drift_recycles: Vec::new(),
+        }
+    }
+
+    /// Reserves `seat_count` pool seats without spawning anything into
+    /// them. Start-up used to pay for every worker's full model load before
+    /// the first chunk could dispatch at all; a lazy pool instead spawns a
+    /// seat's worker (via `dispatch`'s `respawn`) the first time dispatch
+    /// actually rotates onto that seat, so the Nth seat's cost is only ever
+    /// paid if a run is busy enough to need N workers at once.
+    pub fn new_lazy(seat_count: usize, max_attempts_per_chunk: usize) -> Self {
+        WorkerPool {
+            workers: std::iter::repeat_with(|| None).take(seat_count.max(1)).collect(),
+            next_worker: 0,
+            max_attempts_per_chunk: max_attempts_per_chunk.max(1),
+            drift_monitor: DriftMonitor::new(DriftThresholds::default()),
+            drift_recycles: Vec::new(),
+        }
+    }
+
+    /// Replaces the default [`DriftThresholds`] generation-drift outputs are
+    /// scored against.
+    pub fn with_drift_thresholds(mut self, thresholds: DriftThresholds) -> Self {
+        self.drift_monitor = DriftMonitor::new(thresholds);
+        self
+    }
+
+    /// Every chunk whose first-received summary was discarded and
+    /// re-dispatched because the worker that produced it had drifted,
+    /// across this pool's lifetime, in the order it happened.
+    pub fn drift_recycles(&self) -> &[ChunkId] {
+        &self.drift_recycles
+    }
+
+    /// How many of this pool's seats have actually spawned a worker so
+    /// far — always every seat for a pool built with [`Self::new`], and
+    /// only as many as have been needed for one built with
+    /// [`Self::new_lazy`].
+    pub fn workers_spawned(&self) -> usize {
+        self.workers.iter().filter(|worker| worker.is_some()).count()
+    }
+
+    /// Dispatches one chunk, retrying against a different worker (after
+    /// respawning any dead or not-yet-spawned one via `respawn`) until it
+    /// succeeds, the error is reported by a live worker (not a crash), or
+    /// the retry budget is exhausted.
+    pub fn dispatch(&mut self, chunk_id: ChunkId, prompt: &str, mut respawn: impl FnMut() -> T) -> Result<DispatchOutcome, WorkerPoolError> {
+        for _ in 0..self.max_attempts_per_chunk {
+            let index = self.next_worker;
+            self.next_worker = (self.next_worker + 1) % self.workers.len().max(1);
+
+            let needs_spawn = match &mut self.workers[index] {
+                Some(worker) => worker.has_exited(),
+                None => true,
+            };
+            if needs_spawn {
+                self.workers[index] = Some(respawn());
+            }
+
+            let worker = self.workers[index].as_mut().expect("just spawned above if it was missing");
+            let request = WorkerRequest::Summarize { chunk_id, prompt: prompt.to_string() };
+            if worker.send(&request).is_err() {
+                self.workers[index] = Some(respawn());
+                continue;
+            }
+
+            match self.workers[index].as_mut().expect("just spawned above").recv() {
+                Ok(WorkerResponse::Summary { text, .. }) => {
+                    if self.drift_monitor.record(index, &text) {
+                        // The worker answered, so this isn't a crash, but a
+                        // streak of degenerate output means its session has
+                        // drifted - discard this summary, recycle the
+                        // worker, and re-dispatch the same chunk exactly the
+                        // way a crash would.
+                        self.drift_recycles.push(chunk_id);
+                        self.workers[index] = Some(respawn());
+                        continue;
+                    }
+                    return Ok(DispatchOutcome::Summary(text));
+                }
+                Ok(WorkerResponse::Failed { error, .. }) => return Ok(DispatchOutcome::WorkerError(error)),
+                Err(_) => {
+                    // The worker died (or the pipe broke) mid-request; replace
+                    // it and re-dispatch this same chunk to the next worker.
+                    self.workers[index] = Some(respawn());
+                }
+            }
+        }
+        Err(WorkerPoolError::RetryBudgetExhausted(chunk_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// In-memory worker double: a scripted sequence of behaviors
+    /// (`Respond`, `Crash`) consumed one per dispatch, so pool logic is
+    /// testable without a real child process.
+    enum ScriptedStep {
+        Respond(WorkerResponse),
+        Crash,
+    }
+
+    struct ScriptedWorker {
+        steps: VecDeque<ScriptedStep>,
+        exited: bool,
+    }
+
+    impl ScriptedWorker {
+        fn new(steps: Vec<ScriptedStep>) -> Self {
+            ScriptedWorker { steps: steps.into(), exited: false }
+        }
+    }
+
+    impl WorkerTransport for ScriptedWorker {
+        fn send(&mut self, _request: &WorkerRequest) -> io::Result<()> {
+            if self.exited {
+                return Err(io::Error::other("worker already exited"));
+            }
+            Ok(())
+        }
+
+        fn recv(&mut self) -> io::Result<WorkerResponse> {
+            match self.steps.pop_front() {
+                Some(ScriptedStep::Respond(response)) => Ok(response),
+                Some(ScriptedStep::Crash) | None => {
+                    self.exited = true;
+                    Err(io::Error::other("worker crashed"))
+                }
+            }
+        }
+
+        fn has_exited(&mut self) -> bool {
+            self.exited
+        }
+    }
+
+    fn healthy_worker(chunk_id: ChunkId, text: &str) -> ScriptedWorker {
+        ScriptedWorker::new(vec![ScriptedStep::Respond(WorkerResponse::Summary { chunk_id, text: text.to_string() })])
+    }
+
+    #[test]
+    fn dispatch_returns_the_workers_summary_on_success() {
+        let mut pool = WorkerPool::new(vec![healthy_worker(ChunkId(1), "a summary")], 3);
+        let outcome = pool.dispatch(ChunkId(1), "prompt", || panic!("should not need to respawn")).unwrap();
+        assert_eq!(outcome, DispatchOutcome::Summary("a summary".to_string()));
+    }
+
+    #[test]
+    fn a_crashed_worker_is_respawned_and_the_chunk_is_redispatched() {
+        let crashed = ScriptedWorker::new(vec![ScriptedStep::Crash]);
+        let mut pool = WorkerPool::new(vec![crashed], 3);
+
+        let mut respawn_calls = 0;
+        let outcome = pool
+            .dispatch(ChunkId(7), "prompt", || {
+                respawn_calls += 1;
+                healthy_worker(ChunkId(7), "recovered")
+            })
+            .unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::Summary("recovered".to_string()));
+        assert_eq!(respawn_calls, 1);
+    }
+
+    #[test]
+    fn a_worker_reported_failure_is_not_treated_as_a_crash() {
+        let mut pool = WorkerPool::new(
+            vec![ScriptedWorker::new(vec![ScriptedStep::Respond(WorkerResponse::Failed {
+                chunk_id: ChunkId(1),
+                error: "bad prompt".to_string(),
+            })])],
+            3,
+        );
+
+        let outcome = pool.dispatch(ChunkId(1), "prompt", || panic!("should not respawn on a reported failure")).unwrap();
+        assert_eq!(outcome, DispatchOutcome::WorkerError("bad prompt".to_string()));
+    }
+
+    #[test]
+    fn repeated_crashes_eventually_exhaust_the_retry_budget() {
+        let always_crashes = ScriptedWorker::new(vec![]);
+        let mut pool = WorkerPool::new(vec![always_crashes], 3);
+
+        let result = pool.dispatch(ChunkId(1), "prompt", || ScriptedWorker::new(vec![]));
+        assert_eq!(result, Err(WorkerPoolError::RetryBudgetExhausted(ChunkId(1))));
+    }
+
+    #[test]
+    fn dead_worker_found_before_sending_is_respawned_first() {
+        let mut dead = ScriptedWorker::new(vec![]);
+        dead.exited = true;
+        let mut pool = WorkerPool::new(vec![dead], 3);
+
+        let outcome = pool
+            .dispatch(ChunkId(3), "prompt", || healthy_worker(ChunkId(3), "fresh worker"))
+            .unwrap();
+        assert_eq!(outcome, DispatchOutcome::Summary("fresh worker".to_string()));
+    }
+
+    #[test]
+    fn a_lazy_pool_spawns_nothing_until_its_first_dispatch() {
+        let pool = WorkerPool::<ScriptedWorker>::new_lazy(3, 3);
+        assert_eq!(pool.workers_spawned(), 0);
+    }
+
+    #[test]
+    fn a_lazy_pool_only_spawns_as_many_seats_as_dispatch_has_actually_needed() {
+        let mut pool = WorkerPool::new_lazy(3, 3);
+        let mut spawn_calls = 0;
+
+        let outcome = pool
+            .dispatch(ChunkId(1), "prompt", || {
+                spawn_calls += 1;
+                healthy_worker(ChunkId(1), "summary")
+            })
+            .unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::Summary("summary".to_string()));
+        assert_eq!(spawn_calls, 1);
+        assert_eq!(pool.workers_spawned(), 1);
+    }
+
+    #[test]
+    fn a_lazy_pool_fills_in_more_seats_as_more_dispatches_rotate_onto_them() {
+        let mut pool = WorkerPool::new_lazy(3, 3);
+
+        for chunk_id in 1..=3u64 {
+            pool.dispatch(ChunkId(chunk_id), "prompt", || healthy_worker(ChunkId(chunk_id), "summary"))
+                .unwrap();
+        }
+
+        assert_eq!(pool.workers_spawned(), 3);
+    }
+
+    #[test]
+    fn an_eager_pool_reports_every_worker_already_spawned() {
+        let pool = WorkerPool::new(vec![healthy_worker(ChunkId(1), "a"), healthy_worker(ChunkId(2), "b")], 3);
+        assert_eq!(pool.workers_spawned(), 2);
+    }
+
+    #[test]
+    fn a_drifting_worker_is_recycled_and_the_chunk_is_redispatched() {
+        // The first two dispatches against the same worker only extend its
+        // drift streak - each still returns its (degraded) summary
+        // normally, the same way a single bad output is tolerated as noise.
+        let drifting = ScriptedWorker::new(vec![
+            ScriptedStep::Respond(WorkerResponse::Summary { chunk_id: ChunkId(1), text: "go go go go go go".to_string() }),
+            ScriptedStep::Respond(WorkerResponse::Summary { chunk_id: ChunkId(2), text: "go go go go go go".to_string() }),
+            ScriptedStep::Respond(WorkerResponse::Summary { chunk_id: ChunkId(3), text: "go go go go go go".to_string() }),
+        ]);
+        let mut pool = WorkerPool::new(vec![drifting], 5);
+
+        let first = pool.dispatch(ChunkId(1), "prompt", || panic!("streak of 1 should not recycle")).unwrap();
+        let second = pool.dispatch(ChunkId(2), "prompt", || panic!("streak of 2 should not recycle")).unwrap();
+        assert_eq!(first, DispatchOutcome::Summary("go go go go go go".to_string()));
+        assert_eq!(second, DispatchOutcome::Summary("go go go go go go".to_string()));
+        assert!(pool.drift_recycles().is_empty());
+
+        // The third consecutive degraded output from the same worker
+        // completes the streak: it's discarded and the chunk is
+        // re-dispatched to a freshly respawned worker instead.
+        let mut respawn_calls = 0;
+        let third = pool
+            .dispatch(ChunkId(3), "prompt", || {
+                respawn_calls += 1;
+                healthy_worker(ChunkId(3), "a healthy, varied summary of the chunk")
+            })
+            .unwrap();
+
+        assert_eq!(third, DispatchOutcome::Summary("a healthy, varied summary of the chunk".to_string()));
+        assert_eq!(respawn_calls, 1);
+        assert_eq!(pool.drift_recycles(), &[ChunkId(3)]);
+    }
+
+    #[test]
+    fn an_occasional_degraded_output_without_a_streak_is_not_recycled() {
+        let mostly_healthy = ScriptedWorker::new(vec![ScriptedStep::Respond(WorkerResponse::Summary {
+            chunk_id: ChunkId(1),
+            text: "go go go go go go".to_string(),
+        })]);
+        let mut pool = WorkerPool::new(vec![mostly_healthy], 3);
+
+        let outcome = pool.dispatch(ChunkId(1), "prompt", || panic!("a single degraded output should not recycle")).unwrap();
+
+        assert_eq!(outcome, DispatchOutcome::Summary("go go go go go go".to_string()));
+        assert!(pool.drift_recycles().is_empty());
+    }
+
+    #[test]
+    fn round_robin_rotates_across_multiple_workers() {
+        let mut pool = WorkerPool::new(
+            vec![healthy_worker(ChunkId(1), "from worker 0"), healthy_worker(ChunkId(2), "from worker 1")],
+            1,
+        );
+
+        let first = pool.dispatch(ChunkId(1), "prompt", || panic!("no crash expected")).unwrap();
+        let second = pool.dispatch(ChunkId(2), "prompt", || panic!("no crash expected")).unwrap();
+
+        assert_eq!(first, DispatchOutcome::Summary("from worker 0".to_string()));
+        assert_eq!(second, DispatchOutcome::Summary("from worker 1".to_string()));
+    }
+
+    #[test]
+    fn real_unix_socket_round_trip_frames_and_decodes_a_request_and_response() {
+        // Exercises the framing + bincode encoding `UnixSocketWorker` uses
+        // over a real Unix socket, against a tiny inline echo "worker" run on a thread
+        // standing in for a child process, since this crate has no
+        // standalone worker binary to spawn; see the module docs.
+        let socket_path = std::env::temp_dir().join("transfiguration-worker-pool-test.sock");
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let worker_thread = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let bytes = read_framed(&mut stream).unwrap();
+            let request: WorkerRequest = bincode::deserialize(&bytes).unwrap();
+            let WorkerRequest::Summarize { chunk_id, .. } = request else {
+                panic!("expected a Summarize request");
+            };
+            let response = WorkerResponse::Summary { chunk_id, text: "echoed".to_string() };
+            write_framed(&mut stream, &bincode::serialize(&response).unwrap()).unwrap();
+        });
+
+        let mut client = UnixStream::connect(&socket_path).unwrap();
+        write_framed(
+            &mut client,
+            &bincode::serialize(&WorkerRequest::Summarize { chunk_id: ChunkId(1), prompt: "hi".to_string() }).unwrap(),
+        )
+        .unwrap();
+        let bytes = read_framed(&mut client).unwrap();
+        let response: WorkerResponse = bincode::deserialize(&bytes).unwrap();
+
+        assert_eq!(response, WorkerResponse::Summary { chunk_id: ChunkId(1), text: "echoed".to_string() });
+        worker_thread.join().unwrap();
+        let _ = std::fs::remove_file(&socket_path);
+    }
+}
@@ -0,0 +1,192 @@
+//! A shared error classification that this crate's many per-subsystem
+//! error enums (see e.g. [`crate::engine::EngineError`],
+//! [`crate::chunk::ChunkBuildError`], [`crate::unpack::ArchiveError`],
+//! [`crate::locking::LockError`]) can opt into without giving up their own
+//! shape.
+//!
+//! There is no single `ProcessingError`/`ResearchError`/`ChunkError`/
+//! `ExtractionError` hierarchy in this crate, and no separate
+//! `transfiguration-errors` crate — this is one crate, not a workspace of
+//! several, and every subsystem already defines its own `thiserror`
+//! enum scoped to exactly what it does, chained into its callers' errors
+//! via `#[from]` (e.g. [`crate::report::ReportError`] wraps both
+//! [`crate::engine::EngineError`] and [`LockError`](crate::locking::LockError)
+//! this way already). Collapsing forty-odd enums into one flat type would
+//! ripple through every public function signature in the crate for no
+//! real benefit over what `#[from]` chaining already gives callers.
+//!
+//! What a caller that wants to match on one type and pick a consistent
+//! exit code is actually missing is a shared *classification*, not a
+//! shared *type* — that's [`ErrorKind`]. An error enum implements
+//! [`Categorized`] to report which kind it falls into; a caller that only
+//! has a `Box<dyn Categorized>` (or a concrete error behind a `dyn` in a
+//! CLI's `main`) can still get a stable [`ErrorKind::exit_code`] without
+//! knowing which of this crate's error enums actually produced it.
+
+use std::fmt;
+
+/// A coarse category an error falls into, independent of which subsystem
+/// raised it. Stable across every [`Categorized`] implementor, so a
+/// caller matching on this instead of a concrete error type keeps working
+/// as subsystems add variants to their own enums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Input (a chunk, a config file, a CLI argument) was malformed or
+    /// failed validation.
+    InvalidInput,
+    /// Something the caller referenced doesn't exist.
+    NotFound,
+    /// A filesystem or network I/O operation failed.
+    Io,
+    /// An external process, model backend, or service call failed.
+    BackendFailure,
+    /// A concurrency or resource conflict — a lock already held, a budget
+    /// exhausted, a checkpoint that doesn't match what's being resumed.
+    Conflict,
+    /// A requested capability isn't supported on this platform or build.
+    Unsupported,
+    /// A bug in this crate's own bookkeeping rather than anything external.
+    Internal,
+}
+
+impl ErrorKind {
+    /// The process exit code a CLI entry point should use for an error of
+    /// this kind. Follows the `sysexits.h` convention this family of codes
+    /// comes from, rather than inventing a new numbering: `65` for bad
+    /// input, `66` for a missing input, `69`/`76` for an unavailable
+    /// service or capability, `70` for this crate's own bugs, `74` for I/O,
+    /// `75` for a conflict worth retrying.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::InvalidInput => 65,
+            ErrorKind::NotFound => 66,
+            ErrorKind::Internal => 70,
+            ErrorKind::Io => 74,
+            ErrorKind::Conflict => 75,
+            ErrorKind::BackendFailure => 69,
+            ErrorKind::Unsupported => 76,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Io => "io",
+            ErrorKind::BackendFailure => "backend_failure",
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Internal => "internal",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Implemented by this crate's per-subsystem error enums so a caller can
+/// get a stable [`ErrorKind`] — and the [`ErrorKind::exit_code`] that comes
+/// with it — without matching on every concrete error type individually.
+pub trait Categorized {
+    fn kind(&self) -> ErrorKind;
+
+    /// The exit code a CLI entry point should use for this error.
+    /// Defaults to `self.kind()`'s code; override only if a specific
+    /// variant genuinely warrants a different one than its kind implies.
+    fn exit_code(&self) -> i32 {
+        self.kind().exit_code()
+    }
+}
+
+impl Categorized for crate::engine::EngineError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            crate::engine::EngineError::BackendFailed(_) => ErrorKind::BackendFailure,
+            crate::engine::EngineError::UnknownChunk(_) => ErrorKind::NotFound,
+        }
+    }
+}
+
+impl Categorized for crate::chunk::ChunkBuildError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::InvalidInput
+    }
+}
+
+impl Categorized for crate::locking::LockError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            crate::locking::LockError::Io(_) => ErrorKind::Io,
+            crate::locking::LockError::Contended { .. } => ErrorKind::Conflict,
+        }
+    }
+}
+
+#[cfg(feature = "archive-unpacking")]
+impl Categorized for crate::unpack::ArchiveError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            crate::unpack::ArchiveError::Io(_) => ErrorKind::Io,
+            crate::unpack::ArchiveError::UnsupportedFormat(_) => ErrorKind::Unsupported,
+            crate::unpack::ArchiveError::PathTraversal(_) => ErrorKind::InvalidInput,
+            crate::unpack::ArchiveError::UnsafeSymlinkTarget(_, _) => ErrorKind::InvalidInput,
+            crate::unpack::ArchiveError::DepthLimitExceeded { .. } => ErrorKind::InvalidInput,
+            crate::unpack::ArchiveError::EntryNotFound(_) => ErrorKind::NotFound,
+        }
+    }
+}
+
+#[cfg(feature = "analysis-pipeline")]
+impl Categorized for crate::analysis::AnalysisError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            crate::analysis::AnalysisError::Sink(_) => ErrorKind::Io,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_all_distinct() {
+        let kinds = [
+            ErrorKind::InvalidInput,
+            ErrorKind::NotFound,
+            ErrorKind::Io,
+            ErrorKind::BackendFailure,
+            ErrorKind::Conflict,
+            ErrorKind::Unsupported,
+            ErrorKind::Internal,
+        ];
+        let mut codes: Vec<i32> = kinds.iter().map(|kind| kind.exit_code()).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+    }
+
+    #[test]
+    fn engine_error_variants_map_to_the_expected_kind() {
+        assert_eq!(crate::engine::EngineError::BackendFailed("x".to_string()).kind(), ErrorKind::BackendFailure);
+        assert_eq!(crate::engine::EngineError::UnknownChunk(crate::chunk::ChunkId(1)).kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn a_categorized_error_s_exit_code_defaults_to_its_kind_s_exit_code() {
+        let error = crate::engine::EngineError::BackendFailed("x".to_string());
+        assert_eq!(error.exit_code(), ErrorKind::BackendFailure.exit_code());
+    }
+
+    #[test]
+    fn lock_error_distinguishes_io_from_contention() {
+        let io_error = crate::locking::LockError::Io(std::io::Error::other("disk full"));
+        assert_eq!(io_error.kind(), ErrorKind::Io);
+
+        let contended = crate::locking::LockError::Contended {
+            path: std::path::PathBuf::from("f.lock"),
+            holder_pid: Some(123),
+        };
+        assert_eq!(contended.kind(), ErrorKind::Conflict);
+    }
+}
@@ -0,0 +1,240 @@
+//! Repository fingerprinting for default selection of chunking strategy and
+//! prompts.
+//!
+//! A Rust library, a JS monorepo, and a docs site all want different
+//! defaults: how big a chunk should be, what tone the summarization prompt
+//! should take, and what to skip entirely. [`fingerprint_repository`] walks
+//! a tree once to characterize it, and [`select_chunking_profile`] turns
+//! that characterization into a starting [`ChunkingProfile`] that the caller
+//! is free to override before a run.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FingerprintError {
+    #[error("failed to walk repository at {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+}
+
+/// Filenames that mark a repository as belonging to a particular ecosystem.
+const BUILD_FILE_NAMES: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml", "go.mod", "pom.xml"];
+
+/// Directories that are never worth chunking regardless of profile.
+pub(crate) const ALWAYS_SKIPPED_DIRS: &[&str] = &["node_modules", "target", ".git", "vendor", "dist"];
+
+/// A characterization of a repository's contents, used to pick sensible
+/// chunking defaults without the caller having to inspect the tree itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoFingerprint {
+    pub total_files: usize,
+    pub total_bytes: u64,
+    /// File extension (without the dot) to file count, e.g. `"rs" -> 120`.
+    pub language_mix: BTreeMap<String, usize>,
+    /// Fraction of files whose path or name looks test-related.
+    pub test_file_ratio: f64,
+    /// Build/manifest files found at any depth, e.g. `"Cargo.toml"`.
+    pub build_files_present: Vec<String>,
+}
+
+impl RepoFingerprint {
+    /// The file extension with the most files, if any were scanned.
+    pub fn dominant_language(&self) -> Option<&str> {
+        self.language_mix
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(extension, _)| extension.as_str())
+    }
+}
+
+/// How chunks should be carved out of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkingStrategy {
+    /// Treat each file as a single chunk.
+    WholeFile,
+    /// Slide a fixed-size window of lines over each file.
+    LineWindow { window_lines: usize },
+    /// Break Rust source on item boundaries (`fn`/`impl`/`struct`/`enum`/
+    /// `trait`/`mod`) via
+    /// [`crate::chunk::Chunk::chunk_by_rust_item_boundaries`], instead of
+    /// [`ChunkingStrategy::LineWindow`]'s fixed line count cutting a
+    /// function or impl block in half. `fallback_window_lines` is the
+    /// window size [`crate::package::build_chunks`] falls back to
+    /// ([`crate::chunk::Chunk::chunk_by_line_window`]) for a file it
+    /// doesn't recognize as Rust by extension.
+    SyntaxAware { fallback_window_lines: usize },
+}
+
+/// How much detail the summarization prompt should ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptStyle {
+    Concise,
+    Detailed,
+}
+
+/// The chunking/prompt defaults chosen for a run. Every field is public and
+/// safe to overwrite after [`select_chunking_profile`] returns, so a caller
+/// with stronger opinions than the fingerprint heuristics is never stuck.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkingProfile {
+    pub strategy: ChunkingStrategy,
+    pub prompt_style: PromptStyle,
+    pub skip_globs: Vec<String>,
+}
+
+/// Walks `root` once, tallying file extensions, sizes, test-likeness, and
+/// build manifests, skipping directories in [`ALWAYS_SKIPPED_DIRS`].
+pub fn fingerprint_repository(root: &Path) -> Result<RepoFingerprint, FingerprintError> {
+    let mut fingerprint = RepoFingerprint::default();
+    let mut test_files = 0usize;
+    walk_directory(root, &mut fingerprint, &mut test_files)?;
+
+    if fingerprint.total_files > 0 {
+        fingerprint.test_file_ratio = test_files as f64 / fingerprint.total_files as f64;
+    }
+
+    Ok(fingerprint)
+}
+
+fn walk_directory(dir: &Path, fingerprint: &mut RepoFingerprint, test_files: &mut usize) -> Result<(), FingerprintError> {
+    let entries = fs::read_dir(dir).map_err(|source| FingerprintError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    for entry in entries {
+        let entry = entry.map_err(|source| FingerprintError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if path.is_dir() {
+            if ALWAYS_SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                continue;
+            }
+            walk_directory(&path, fingerprint, test_files)?;
+            continue;
+        }
+
+        if BUILD_FILE_NAMES.contains(&file_name.as_ref()) && !fingerprint.build_files_present.contains(&file_name.to_string()) {
+            fingerprint.build_files_present.push(file_name.to_string());
+        }
+
+        let metadata = entry.metadata().map_err(|source| FingerprintError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        fingerprint.total_files += 1;
+        fingerprint.total_bytes += metadata.len();
+
+        if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+            *fingerprint.language_mix.entry(extension.to_string()).or_insert(0) += 1;
+        }
+
+        if looks_like_test_file(&path) {
+            *test_files += 1;
+        }
+    }
+
+    Ok(())
+}
+
+fn looks_like_test_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    path.components().any(|c| c.as_os_str() == "tests")
+        || file_name.ends_with("_test.rs")
+        || file_name.ends_with("_tests.rs")
+        || file_name.starts_with("test_")
+}
+
+/// Picks a starting [`ChunkingProfile`] from `fingerprint`'s shape. Large
+/// repositories get a sliding line window instead of whole-file chunks so a
+/// single enormous file doesn't dominate run time; a high test ratio nudges
+/// prompts toward detailed mode since tests double as behavioral spec.
+pub fn select_chunking_profile(fingerprint: &RepoFingerprint) -> ChunkingProfile {
+    const LARGE_REPO_FILE_THRESHOLD: usize = 500;
+    const HIGH_TEST_RATIO_THRESHOLD: f64 = 0.3;
+
+    let strategy = if fingerprint.total_files > LARGE_REPO_FILE_THRESHOLD {
+        ChunkingStrategy::LineWindow { window_lines: 200 }
+    } else {
+        ChunkingStrategy::WholeFile
+    };
+
+    let prompt_style = if fingerprint.test_file_ratio >= HIGH_TEST_RATIO_THRESHOLD {
+        PromptStyle::Detailed
+    } else {
+        PromptStyle::Concise
+    };
+
+    let mut skip_globs: Vec<String> = ALWAYS_SKIPPED_DIRS.iter().map(|dir| format!("{dir}/**")).collect();
+    if fingerprint.build_files_present.iter().any(|f| f == "package.json") {
+        skip_globs.push("**/*.min.js".to_string());
+    }
+
+    ChunkingProfile {
+        strategy,
+        prompt_style,
+        skip_globs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("transfiguration-fingerprint-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn tallies_language_mix_and_build_files_while_skipping_target() {
+        let dir = scratch_dir("basic");
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"").unwrap();
+        fs::write(dir.join("lib.rs"), "fn main() {}").unwrap();
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::write(dir.join("tests").join("smoke.rs"), "#[test]\nfn t() {}").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target").join("ignored.rs"), "should not be scanned").unwrap();
+
+        let fingerprint = fingerprint_repository(&dir).unwrap();
+        assert_eq!(fingerprint.total_files, 3);
+        assert_eq!(fingerprint.language_mix.get("rs"), Some(&2));
+        assert_eq!(fingerprint.build_files_present, vec!["Cargo.toml".to_string()]);
+        assert!(fingerprint.test_file_ratio > 0.0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn large_repos_get_a_line_window_strategy() {
+        let fingerprint = RepoFingerprint {
+            total_files: 1000,
+            ..Default::default()
+        };
+        let profile = select_chunking_profile(&fingerprint);
+        assert_eq!(profile.strategy, ChunkingStrategy::LineWindow { window_lines: 200 });
+    }
+
+    #[test]
+    fn high_test_ratio_selects_detailed_prompts() {
+        let fingerprint = RepoFingerprint {
+            total_files: 10,
+            test_file_ratio: 0.5,
+            ..Default::default()
+        };
+        let profile = select_chunking_profile(&fingerprint);
+        assert_eq!(profile.prompt_style, PromptStyle::Detailed);
+    }
+}
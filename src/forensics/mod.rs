@@ -0,0 +1,235 @@
+//! Crash forensics: when a run aborts, collecting everything needed for a
+//! bug report by hand is slow and someone always forgets the config. On a
+//! fatal error, [`write_forensics_bundle`] gathers recent log events, the
+//! config, model/tokenizer fingerprints, the offending chunk, and a
+//! backtrace into one `.tar.gz` with a layout stable enough to script
+//! against.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use tar::{Builder, Header};
+
+use crate::chunk::Chunk;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForensicsError {
+    #[error("failed to write forensics bundle: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to serialize forensics bundle entry: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// One log line captured into a [`RecentLogBuffer`].
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEvent {
+    pub timestamp_unix_ms: u64,
+    pub level: String,
+    pub message: String,
+}
+
+/// A fixed-capacity ring buffer of the most recent log events, cheap enough
+/// to update on every log line so a crash bundle always has context.
+#[derive(Debug, Clone)]
+pub struct RecentLogBuffer {
+    capacity: usize,
+    events: VecDeque<LogEvent>,
+}
+
+impl RecentLogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        RecentLogBuffer {
+            capacity: capacity.max(1),
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, event: LogEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Returns the buffered events oldest-first.
+    pub fn snapshot(&self) -> Vec<LogEvent> {
+        self.events.iter().cloned().collect()
+    }
+}
+
+/// Everything [`write_forensics_bundle`] needs to assemble a crash report.
+pub struct ForensicsBundle {
+    pub recent_log_events: Vec<LogEvent>,
+    pub config_snapshot: serde_json::Value,
+    pub model_hash: String,
+    pub tokenizer_hash: String,
+    pub offending_chunk: Option<Chunk>,
+    pub redact_offending_chunk: bool,
+    pub backtrace_text: String,
+}
+
+/// Deterministically fingerprints `bytes` for inclusion in a bundle, so two
+/// bundles from the same model/tokenizer asset can be compared without
+/// shipping the asset itself.
+pub fn fingerprint_bytes(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Writes `bundle` to `output_path` as a gzip-compressed tar with a stable
+/// layout: `logs.json`, `config.json`, `model_fingerprint.json`,
+/// `chunk.txt` (redacted to a fingerprint when `redact_offending_chunk` is
+/// set), and `backtrace.txt`.
+pub fn write_forensics_bundle(bundle: &ForensicsBundle, output_path: &Path) -> Result<(), ForensicsError> {
+    let file = File::create(output_path)?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut archive = Builder::new(encoder);
+
+    append_json_entry(&mut archive, "logs.json", &bundle.recent_log_events)?;
+    append_json_entry(&mut archive, "config.json", &bundle.config_snapshot)?;
+    append_json_entry(
+        &mut archive,
+        "model_fingerprint.json",
+        &serde_json::json!({
+            "model_hash": bundle.model_hash,
+            "tokenizer_hash": bundle.tokenizer_hash,
+        }),
+    )?;
+    append_text_entry(&mut archive, "chunk.txt", &render_offending_chunk(bundle))?;
+    append_text_entry(&mut archive, "backtrace.txt", &bundle.backtrace_text)?;
+
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn render_offending_chunk(bundle: &ForensicsBundle) -> String {
+    match &bundle.offending_chunk {
+        None => "(no offending chunk captured)".to_string(),
+        Some(chunk) if bundle.redact_offending_chunk => format!(
+            "source_path: {}\ncontent_len: {}\ncontent_fingerprint: {}\n(content redacted)",
+            chunk.source_path.display(),
+            chunk.content.len(),
+            fingerprint_bytes(chunk.content.as_bytes()),
+        ),
+        Some(chunk) => format!(
+            "source_path: {}\n\n{}",
+            chunk.source_path.display(),
+            chunk.content
+        ),
+    }
+}
+
+fn append_json_entry<W: io::Write, T: Serialize>(
+    archive: &mut Builder<W>,
+    name: &str,
+    value: &T,
+) -> Result<(), ForensicsError> {
+    append_text_entry(archive, name, &serde_json::to_string_pretty(value)?)
+}
+
+fn append_text_entry<W: io::Write>(archive: &mut Builder<W>, name: &str, text: &str) -> Result<(), ForensicsError> {
+    let bytes = text.as_bytes();
+    let mut header = Header::new_gnu();
+    header.set_path(name)?;
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive.append(&header, bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+    use std::io::Read;
+
+    fn sample_bundle(redact: bool) -> ForensicsBundle {
+        let mut logs = RecentLogBuffer::with_capacity(2);
+        logs.push(LogEvent {
+            timestamp_unix_ms: 1,
+            level: "info".into(),
+            message: "starting run".into(),
+        });
+        logs.push(LogEvent {
+            timestamp_unix_ms: 2,
+            level: "error".into(),
+            message: "decode failed".into(),
+        });
+
+        ForensicsBundle {
+            recent_log_events: logs.snapshot(),
+            config_snapshot: serde_json::json!({"agents": 4}),
+            model_hash: fingerprint_bytes(b"model-bytes"),
+            tokenizer_hash: fingerprint_bytes(b"tokenizer-bytes"),
+            offending_chunk: Some(Chunk::new(ChunkId(7), "src/lib.rs", "secret_key = \"abc\";")),
+            redact_offending_chunk: redact,
+            backtrace_text: "at frame 0\nat frame 1".into(),
+        }
+    }
+
+    fn read_entry(bytes: &[u8], name: &str) -> String {
+        let decoder = flate2::read::GzDecoder::new(bytes);
+        let mut archive = tar::Archive::new(decoder);
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            if entry.path().unwrap().to_str() == Some(name) {
+                let mut contents = String::new();
+                entry.read_to_string(&mut contents).unwrap();
+                return contents;
+            }
+        }
+        panic!("entry {name} not found in bundle");
+    }
+
+    #[test]
+    fn ring_buffer_drops_oldest_past_capacity() {
+        let mut logs = RecentLogBuffer::with_capacity(2);
+        for i in 0..3 {
+            logs.push(LogEvent {
+                timestamp_unix_ms: i,
+                level: "info".into(),
+                message: format!("event {i}"),
+            });
+        }
+        let snapshot = logs.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].message, "event 1");
+        assert_eq!(snapshot[1].message, "event 2");
+    }
+
+    #[test]
+    fn bundle_round_trips_with_stable_layout() {
+        let path = std::env::temp_dir().join("transfiguration-forensics-test.tar.gz");
+        write_forensics_bundle(&sample_bundle(false), &path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(read_entry(&bytes, "chunk.txt").contains("secret_key"));
+        assert!(read_entry(&bytes, "logs.json").contains("decode failed"));
+        assert!(read_entry(&bytes, "model_fingerprint.json").contains("model_hash"));
+        assert!(read_entry(&bytes, "backtrace.txt").contains("frame 1"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn redaction_replaces_chunk_content_with_fingerprint() {
+        let path = std::env::temp_dir().join("transfiguration-forensics-redacted-test.tar.gz");
+        write_forensics_bundle(&sample_bundle(true), &path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let chunk_entry = read_entry(&bytes, "chunk.txt");
+        assert!(!chunk_entry.contains("secret_key"));
+        assert!(chunk_entry.contains("content_fingerprint"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+//! Bitbucket: annotations posted to a Code Insights report on a commit.
+//!
+//! Bitbucket's Code Insights API requires annotations to be posted against
+//! an existing report (identified by `report_id`) on a specific commit,
+//! rather than against a pull request directly — the exporter assumes the
+//! caller already created that report and only needs to fill in its
+//! annotations.
+
+use super::{CodeAnnotation, ForgeAnnotationExporter, ForgeError};
+
+pub struct BitbucketReportExporter {
+    client: reqwest::blocking::Client,
+    workspace: String,
+    repo_slug: String,
+    commit: String,
+    report_id: String,
+    token: String,
+}
+
+impl BitbucketReportExporter {
+    pub fn new(
+        workspace: impl Into<String>,
+        repo_slug: impl Into<String>,
+        commit: impl Into<String>,
+        report_id: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        BitbucketReportExporter {
+            client: reqwest::blocking::Client::new(),
+            workspace: workspace.into(),
+            repo_slug: repo_slug.into(),
+            commit: commit.into(),
+            report_id: report_id.into(),
+            token: token.into(),
+        }
+    }
+}
+
+/// A missing line is reported at line 1, since Code Insights annotations
+/// are always anchored to a line — see the module docs on [`super`] for why
+/// this crate's pipeline often has no real line to give one.
+fn build_annotation_payload(annotation: &CodeAnnotation, index: usize) -> serde_json::Value {
+    serde_json::json!({
+        "external_id": format!("transfiguration-{index}"),
+        "path": annotation.source_path.to_string_lossy(),
+        "line": annotation.line.unwrap_or(1),
+        "severity": annotation.severity.bitbucket_severity(),
+        "annotation_type": "CODE_SMELL",
+        "summary": annotation.message,
+    })
+}
+
+impl ForgeAnnotationExporter for BitbucketReportExporter {
+    fn forge_name(&self) -> &str {
+        "bitbucket"
+    }
+
+    fn export_annotations(&self, annotations: &[CodeAnnotation]) -> Result<(), ForgeError> {
+        let endpoint = format!(
+            "https://api.bitbucket.org/2.0/repositories/{}/{}/commit/{}/reports/{}/annotations",
+            self.workspace, self.repo_slug, self.commit, self.report_id
+        );
+
+        let payload: Vec<serde_json::Value> =
+            annotations.iter().enumerate().map(|(index, annotation)| build_annotation_payload(annotation, index)).collect();
+
+        self.client.post(&endpoint).bearer_auth(&self.token).json(&payload).send()?.error_for_status()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::AnnotationSeverity;
+    use std::path::PathBuf;
+
+    fn annotation(line: Option<u32>) -> CodeAnnotation {
+        CodeAnnotation {
+            source_path: PathBuf::from("src/lib.rs"),
+            line,
+            severity: AnnotationSeverity::Notice,
+            message: "looks off".to_string(),
+        }
+    }
+
+    #[test]
+    fn each_annotation_gets_a_distinct_external_id() {
+        let first = build_annotation_payload(&annotation(Some(1)), 0);
+        let second = build_annotation_payload(&annotation(Some(2)), 1);
+        assert_ne!(first["external_id"], second["external_id"]);
+    }
+
+    #[test]
+    fn a_missing_line_falls_back_to_line_one() {
+        let payload = build_annotation_payload(&annotation(None), 0);
+        assert_eq!(payload["line"], 1);
+        assert_eq!(payload["severity"], "LOW");
+    }
+}
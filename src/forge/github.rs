@@ -0,0 +1,106 @@
+//! GitHub: annotations posted against an existing Checks API check run.
+//!
+//! GitHub has no per-annotation API call — every annotation for a check run
+//! update shares one PATCH request, up to 50 annotations per call (GitHub's
+//! own limit); [`GitHubChecksExporter`] sends them in batches of that size
+//! rather than assuming a caller never has more than 50.
+
+use super::{CodeAnnotation, ForgeAnnotationExporter, ForgeError};
+
+/// GitHub's documented maximum annotations per Checks API update call.
+const MAX_ANNOTATIONS_PER_REQUEST: usize = 50;
+
+pub struct GitHubChecksExporter {
+    client: reqwest::blocking::Client,
+    repo: String,
+    check_run_id: u64,
+    token: String,
+}
+
+impl GitHubChecksExporter {
+    pub fn new(repo: impl Into<String>, check_run_id: u64, token: impl Into<String>) -> Self {
+        GitHubChecksExporter {
+            client: reqwest::blocking::Client::new(),
+            repo: repo.into(),
+            check_run_id,
+            token: token.into(),
+        }
+    }
+}
+
+/// A line-less annotation is rendered against line 1, since the Checks API
+/// requires `start_line`/`end_line` on every annotation and has no
+/// file-level-only note type; see the module docs on [`super`] for why this
+/// crate's pipeline often has no real line to give it.
+fn build_annotation_payload(annotation: &CodeAnnotation) -> serde_json::Value {
+    let line = annotation.line.unwrap_or(1);
+    serde_json::json!({
+        "path": annotation.source_path.to_string_lossy(),
+        "start_line": line,
+        "end_line": line,
+        "annotation_level": annotation.severity.github_annotation_level(),
+        "message": annotation.message,
+    })
+}
+
+impl ForgeAnnotationExporter for GitHubChecksExporter {
+    fn forge_name(&self) -> &str {
+        "github"
+    }
+
+    fn export_annotations(&self, annotations: &[CodeAnnotation]) -> Result<(), ForgeError> {
+        let endpoint = format!("https://api.github.com/repos/{}/check-runs/{}", self.repo, self.check_run_id);
+
+        for batch in annotations.chunks(MAX_ANNOTATIONS_PER_REQUEST) {
+            let body = serde_json::json!({
+                "output": {
+                    "title": "transfiguration",
+                    "summary": format!("{} annotation(s) from transfiguration", batch.len()),
+                    "annotations": batch.iter().map(build_annotation_payload).collect::<Vec<_>>(),
+                },
+            });
+
+            self.client
+                .patch(&endpoint)
+                .bearer_auth(&self.token)
+                .header("Accept", "application/vnd.github+json")
+                .json(&body)
+                .send()?
+                .error_for_status()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::AnnotationSeverity;
+    use std::path::PathBuf;
+
+    fn annotation(line: Option<u32>) -> CodeAnnotation {
+        CodeAnnotation {
+            source_path: PathBuf::from("src/lib.rs"),
+            line,
+            severity: AnnotationSeverity::Warning,
+            message: "looks off".to_string(),
+        }
+    }
+
+    #[test]
+    fn a_missing_line_falls_back_to_line_one_rather_than_being_dropped() {
+        let payload = build_annotation_payload(&annotation(None));
+        assert_eq!(payload["start_line"], 1);
+        assert_eq!(payload["end_line"], 1);
+    }
+
+    #[test]
+    fn a_real_line_is_used_for_both_start_and_end() {
+        let payload = build_annotation_payload(&annotation(Some(42)));
+        assert_eq!(payload["start_line"], 42);
+        assert_eq!(payload["end_line"], 42);
+        assert_eq!(payload["annotation_level"], "warning");
+        assert_eq!(payload["path"], "src/lib.rs");
+    }
+}
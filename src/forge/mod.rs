@@ -0,0 +1,152 @@
+//! Forge-agnostic pull/merge request annotation export.
+//!
+//! This crate had no PR annotation exporter to generalize from — there was
+//! no GitHub-specific implementation anywhere in the tree before this —
+//! so [`ForgeAnnotationExporter`] and its three implementations
+//! ([`GitHubChecksExporter`], [`GitLabMrDiscussionExporter`],
+//! [`BitbucketReportExporter`]) are introduced together as one interface,
+//! the same "trait + several interchangeable implementations, selected by
+//! config" shape [`crate::sinks::OutputSink`] and [`crate::plugins::ContractRule`]
+//! already use for pluggable destinations, rather than generalizing a
+//! single-forge version that never existed.
+//!
+//! [`crate::chunk::Chunk`] discards its line span once chunking is done —
+//! `Chunk` only ever carries `source_path` and `content`, see
+//! `Chunk::from_line_span` — and neither [`crate::sinks::SinkRecord`] nor
+//! [`crate::sinks::query::ResultRecord`] carry a line number. So
+//! [`CodeAnnotation`], the file/line mapping layer every exporter here
+//! shares, makes `line` an `Option<u32>`: a caller that still has a real
+//! line number on hand (e.g. `start_line` inside
+//! [`crate::engine::summarize_span`], before it's discarded) can attach
+//! one. [`GitLabMrDiscussionExporter`] renders `line: None` as a plain note
+//! naming the file; the GitHub Checks and Bitbucket Code Insights APIs
+//! require a line on every annotation, so [`GitHubChecksExporter`] and
+//! [`BitbucketReportExporter`] anchor a line-less annotation at line 1.
+
+use std::path::PathBuf;
+
+#[cfg(feature = "forge-annotations")]
+mod github;
+#[cfg(feature = "forge-annotations")]
+mod gitlab;
+#[cfg(feature = "forge-annotations")]
+mod bitbucket;
+
+#[cfg(feature = "forge-annotations")]
+pub use github::GitHubChecksExporter;
+#[cfg(feature = "forge-annotations")]
+pub use gitlab::GitLabMrDiscussionExporter;
+#[cfg(feature = "forge-annotations")]
+pub use bitbucket::BitbucketReportExporter;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForgeError {
+    #[cfg(feature = "forge-annotations")]
+    #[error("http error exporting an annotation: {0}")]
+    Http(#[from] ::reqwest::Error),
+}
+
+/// How serious a [`CodeAnnotation`] is, mapped onto whichever severity
+/// levels the target forge's API supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationSeverity {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// One note attached to a location in the repository — the shared
+/// file/line mapping layer every [`ForgeAnnotationExporter`] implementation
+/// renders into its own forge's API shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeAnnotation {
+    pub source_path: PathBuf,
+    /// 1-indexed line, if the caller has one; see the module docs for why
+    /// this crate's pipeline often doesn't.
+    pub line: Option<u32>,
+    pub severity: AnnotationSeverity,
+    pub message: String,
+}
+
+/// A destination that can post a batch of [`CodeAnnotation`]s against a
+/// specific pull/merge request, check run, or commit — whichever unit the
+/// target forge annotates against.
+pub trait ForgeAnnotationExporter: Send {
+    fn forge_name(&self) -> &str;
+    fn export_annotations(&self, annotations: &[CodeAnnotation]) -> Result<(), ForgeError>;
+}
+
+/// Which forge, and the identifiers needed to address it, a run is
+/// configured to export annotations to — the "selected by config" knob the
+/// request asks for.
+#[cfg(feature = "forge-annotations")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForgeConfig {
+    GitHub { repo: String, check_run_id: u64, token: String },
+    GitLab { project: String, merge_request_iid: u64, token: String },
+    Bitbucket { workspace: String, repo_slug: String, commit: String, report_id: String, token: String },
+}
+
+/// Builds the [`ForgeAnnotationExporter`] `config` selects.
+#[cfg(feature = "forge-annotations")]
+pub fn build_exporter(config: ForgeConfig) -> Box<dyn ForgeAnnotationExporter> {
+    match config {
+        ForgeConfig::GitHub { repo, check_run_id, token } => Box::new(GitHubChecksExporter::new(repo, check_run_id, token)),
+        ForgeConfig::GitLab { project, merge_request_iid, token } => {
+            Box::new(GitLabMrDiscussionExporter::new(project, merge_request_iid, token))
+        }
+        ForgeConfig::Bitbucket { workspace, repo_slug, commit, report_id, token } => {
+            Box::new(BitbucketReportExporter::new(workspace, repo_slug, commit, report_id, token))
+        }
+    }
+}
+
+impl AnnotationSeverity {
+    /// GitHub Checks API's `annotation_level` values.
+    pub(crate) fn github_annotation_level(self) -> &'static str {
+        match self {
+            AnnotationSeverity::Notice => "notice",
+            AnnotationSeverity::Warning => "warning",
+            AnnotationSeverity::Failure => "failure",
+        }
+    }
+
+    /// Bitbucket Code Insights' annotation `severity` values — it has no
+    /// "notice" tier, so one maps down to its closest, `LOW`.
+    pub(crate) fn bitbucket_severity(self) -> &'static str {
+        match self {
+            AnnotationSeverity::Notice => "LOW",
+            AnnotationSeverity::Warning => "MEDIUM",
+            AnnotationSeverity::Failure => "HIGH",
+        }
+    }
+}
+
+#[cfg(all(test, feature = "forge-annotations"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_exporter_picks_the_implementation_named_by_the_config() {
+        let github = build_exporter(ForgeConfig::GitHub { repo: "o/r".into(), check_run_id: 1, token: "t".into() });
+        assert_eq!(github.forge_name(), "github");
+
+        let gitlab = build_exporter(ForgeConfig::GitLab { project: "42".into(), merge_request_iid: 7, token: "t".into() });
+        assert_eq!(gitlab.forge_name(), "gitlab");
+
+        let bitbucket = build_exporter(ForgeConfig::Bitbucket {
+            workspace: "w".into(),
+            repo_slug: "r".into(),
+            commit: "c".into(),
+            report_id: "report".into(),
+            token: "t".into(),
+        });
+        assert_eq!(bitbucket.forge_name(), "bitbucket");
+    }
+
+    #[test]
+    fn severity_maps_to_every_forges_own_vocabulary() {
+        assert_eq!(AnnotationSeverity::Failure.github_annotation_level(), "failure");
+        assert_eq!(AnnotationSeverity::Notice.bitbucket_severity(), "LOW");
+    }
+}
@@ -0,0 +1,314 @@
+//! Incremental hierarchical re-summarization.
+//!
+//! Every existing summarization path in this crate
+//! ([`crate::package::run_package_pipeline`],
+//! [`crate::package::process_directory`]) produces a flat
+//! `BTreeMap<PathBuf, String>` of file-level summaries in
+//! [`crate::report::RunArtifacts::summaries`] and stops there — nothing
+//! rolls those up into a module- or repo-level summary, so there is no
+//! existing hierarchical summarizer to "extend" here. [`SummaryTree`] is
+//! that missing layer: it builds a file → module → repo tree from the same
+//! flat path list this crate already has (treating each path component as
+//! a directory node and the file itself as a leaf), tracks which nodes are
+//! stale via [`SummaryTree::update_file`], and recomputes only the stale
+//! ones via [`SummaryTree::recompute_dirty`] rather than rebuilding every
+//! summary on every run.
+//!
+//! "Dependency tracking between summary nodes" here is exactly the parent/
+//! child edge implied by a file's path: updating `src/engine/jobs.rs`
+//! marks `src/engine` and the repo root dirty, but leaves `src/package`
+//! untouched. A node's new summary is a deterministic rollup of its
+//! children's summaries, in the spirit of
+//! [`crate::engine::heuristic::HeuristicBackend`] — this crate has no
+//! model-driven way to "summarize a summary" either, so
+//! [`recompute_dirty`](SummaryTree::recompute_dirty) takes the rollup as a
+//! caller-supplied closure (defaulting to
+//! [`concatenate_child_summaries`]) instead of assuming one exists.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Identifies one node in a [`SummaryTree`]: a file's full slash-separated
+/// relative path, or a directory's relative path for a module node. The
+/// tree's root uses the empty string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(String);
+
+impl NodeId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Whether a [`SummaryTree`] node is a file leaf or a directory/repo
+/// rollup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    File,
+    Module,
+}
+
+struct Node {
+    kind: NodeKind,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    summary: Option<String>,
+    dirty: bool,
+    content_hash: Option<u64>,
+}
+
+/// A file → module → repo summary hierarchy built from a flat set of file
+/// paths, with dirty-propagation so re-summarizing one changed file only
+/// recomputes that file's node plus the chain of module/repo nodes above
+/// it. See the module docs for why this is the crate's first hierarchical
+/// summarizer rather than an extension of an existing one.
+pub struct SummaryTree {
+    nodes: BTreeMap<NodeId, Node>,
+    root: NodeId,
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+impl SummaryTree {
+    /// Builds the tree's structure from a flat list of file paths, with no
+    /// summaries yet — every node starts clean (not dirty) with no
+    /// summary, since nothing has been summarized. Paths are expected to
+    /// be relative, matching what
+    /// [`crate::package::discover_source_files_filtered`] produces.
+    pub fn new(file_paths: &[impl AsRef<Path>]) -> Self {
+        let root = NodeId(String::new());
+        let mut nodes = BTreeMap::new();
+        nodes.insert(
+            root.clone(),
+            Node { kind: NodeKind::Module, parent: None, children: Vec::new(), summary: None, dirty: false, content_hash: None },
+        );
+
+        for path in file_paths {
+            let key = path_key(path.as_ref());
+            let components: Vec<&str> = key.split('/').filter(|component| !component.is_empty()).collect();
+            let mut parent = root.clone();
+            let mut prefix = String::new();
+
+            for (index, component) in components.iter().enumerate() {
+                if !prefix.is_empty() {
+                    prefix.push('/');
+                }
+                prefix.push_str(component);
+                let id = NodeId(prefix.clone());
+                let is_file = index == components.len() - 1;
+
+                nodes.entry(id.clone()).or_insert_with(|| Node {
+                    kind: if is_file { NodeKind::File } else { NodeKind::Module },
+                    parent: Some(parent.clone()),
+                    children: Vec::new(),
+                    summary: None,
+                    dirty: false,
+                    content_hash: None,
+                });
+
+                let parent_node = nodes.get_mut(&parent).expect("parent was inserted before its children");
+                if !parent_node.children.contains(&id) {
+                    parent_node.children.push(id.clone());
+                }
+
+                parent = id;
+            }
+        }
+
+        SummaryTree { nodes, root }
+    }
+
+    /// This tree's root node — the whole-repo summary once
+    /// [`recompute_dirty`](Self::recompute_dirty) has run.
+    pub fn root(&self) -> &NodeId {
+        &self.root
+    }
+
+    /// The kind of node `id` refers to, or `None` if it isn't in this tree.
+    pub fn kind(&self, id: &NodeId) -> Option<NodeKind> {
+        self.nodes.get(id).map(|node| node.kind)
+    }
+
+    /// `id`'s current summary, or `None` if it has never been computed.
+    pub fn summary(&self, id: &NodeId) -> Option<&str> {
+        self.nodes.get(id).and_then(|node| node.summary.as_deref())
+    }
+
+    /// Whether `id` is stale — has a child whose content changed since
+    /// this node's summary was last computed.
+    pub fn is_dirty(&self, id: &NodeId) -> bool {
+        self.nodes.get(id).is_some_and(|node| node.dirty)
+    }
+
+    /// How many nodes currently need recomputing.
+    pub fn dirty_count(&self) -> usize {
+        self.nodes.values().filter(|node| node.dirty).count()
+    }
+
+    /// Records a freshly computed file-level summary. If `content_hash`
+    /// matches what this file already had, the file didn't actually
+    /// change — e.g. a re-run over unmodified source — so nothing is
+    /// marked dirty and no ancestor recomputation is needed. Otherwise the
+    /// file's own summary is updated immediately and every ancestor up to
+    /// the root is marked dirty, to be recomputed by
+    /// [`recompute_dirty`](Self::recompute_dirty).
+    ///
+    /// Does nothing if `path` isn't a file node in this tree (it wasn't
+    /// part of the paths given to [`SummaryTree::new`]).
+    pub fn update_file(&mut self, path: &Path, content_hash: u64, summary: impl Into<String>) {
+        let id = NodeId(path_key(path));
+        let Some(node) = self.nodes.get_mut(&id).filter(|node| node.kind == NodeKind::File) else {
+            return;
+        };
+        if node.content_hash == Some(content_hash) {
+            return;
+        }
+        node.content_hash = Some(content_hash);
+        node.summary = Some(summary.into());
+        node.dirty = false;
+
+        // Walk up, marking every ancestor dirty. A node already marked
+        // dirty means an earlier file update in this same batch already
+        // propagated past it (and therefore past everything above it
+        // too), so there's nothing further to do.
+        let mut current = node.parent.clone();
+        while let Some(parent_id) = current {
+            let Some(parent) = self.nodes.get_mut(&parent_id) else { break };
+            if parent.dirty {
+                break;
+            }
+            parent.dirty = true;
+            current = parent.parent.clone();
+        }
+    }
+
+    /// Recomputes every dirty module/repo node's summary, deepest first,
+    /// so a node's rollup always sees its children's already-fresh
+    /// summaries. `rollup` receives the already-computed summaries of one
+    /// node's direct children (in path order) and returns that node's new
+    /// summary; [`concatenate_child_summaries`] is a reasonable default.
+    /// Returns how many nodes were recomputed.
+    pub fn recompute_dirty(&mut self, mut rollup: impl FnMut(&[String]) -> String) -> usize {
+        let mut dirty_ids: Vec<NodeId> = self.nodes.iter().filter(|(_, node)| node.dirty).map(|(id, _)| id.clone()).collect();
+        // The root's id is empty, so it sits one level above every
+        // slash-free top-level id rather than tying with them.
+        dirty_ids.sort_by_key(|id| {
+            let depth = if id.as_str().is_empty() { 0 } else { id.as_str().matches('/').count() + 1 };
+            std::cmp::Reverse(depth)
+        });
+
+        for id in &dirty_ids {
+            let child_summaries: Vec<String> = self.nodes[id]
+                .children
+                .iter()
+                .filter_map(|child_id| self.nodes.get(child_id).and_then(|child| child.summary.clone()))
+                .collect();
+            let summary = rollup(&child_summaries);
+            let node = self.nodes.get_mut(id).expect("id came from iterating self.nodes");
+            node.summary = Some(summary);
+            node.dirty = false;
+        }
+
+        dirty_ids.len()
+    }
+}
+
+/// The default module/repo rollup: each child's summary on its own line,
+/// in path order. A caller with a real [`crate::engine::InferenceBackend`]
+/// can pass a closure that feeds this same child-summary list through a
+/// model instead.
+pub fn concatenate_child_summaries(child_summaries: &[String]) -> String {
+    child_summaries.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn node(path: &str) -> NodeId {
+        NodeId(path.to_string())
+    }
+
+    fn sample_tree() -> SummaryTree {
+        SummaryTree::new(&[
+            PathBuf::from("src/engine/jobs.rs"),
+            PathBuf::from("src/engine/agents.rs"),
+            PathBuf::from("src/package/mod.rs"),
+        ])
+    }
+
+    #[test]
+    fn new_builds_a_module_hierarchy_from_flat_file_paths() {
+        let tree = sample_tree();
+        assert_eq!(tree.kind(&node("src/engine/jobs.rs")), Some(NodeKind::File));
+        assert_eq!(tree.kind(&node("src/engine")), Some(NodeKind::Module));
+        assert_eq!(tree.kind(&node("src")), Some(NodeKind::Module));
+        assert_eq!(tree.kind(tree.root()), Some(NodeKind::Module));
+        assert_eq!(tree.kind(&node("src/package/mod.rs")), Some(NodeKind::File));
+    }
+
+    #[test]
+    fn update_file_marks_every_ancestor_dirty_but_not_siblings() {
+        let mut tree = sample_tree();
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "jobs: schedules work");
+
+        assert!(tree.is_dirty(&node("src/engine")));
+        assert!(tree.is_dirty(&node("src")));
+        assert!(tree.is_dirty(tree.root()));
+        assert!(!tree.is_dirty(&node("src/package")));
+        assert_eq!(tree.summary(&node("src/engine/jobs.rs")), Some("jobs: schedules work"));
+    }
+
+    #[test]
+    fn update_file_with_an_unchanged_hash_does_not_mark_anything_dirty() {
+        let mut tree = sample_tree();
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "jobs: schedules work");
+        tree.recompute_dirty(concatenate_child_summaries);
+
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "jobs: schedules work");
+        assert_eq!(tree.dirty_count(), 0);
+    }
+
+    #[test]
+    fn recompute_dirty_rolls_up_child_summaries_bottom_up() {
+        let mut tree = sample_tree();
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "jobs summary");
+        tree.update_file(Path::new("src/engine/agents.rs"), 2, "agents summary");
+
+        let recomputed = tree.recompute_dirty(concatenate_child_summaries);
+        assert_eq!(recomputed, 3); // src/engine, src, root
+
+        assert_eq!(tree.summary(&node("src/engine")), Some("jobs summary\nagents summary"));
+        assert_eq!(tree.summary(&node("src")), Some("jobs summary\nagents summary"));
+        assert_eq!(tree.dirty_count(), 0);
+    }
+
+    #[test]
+    fn recompute_dirty_leaves_unrelated_modules_untouched() {
+        let mut tree = sample_tree();
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "jobs summary");
+        tree.update_file(Path::new("src/package/mod.rs"), 2, "package summary");
+        tree.recompute_dirty(concatenate_child_summaries);
+
+        tree.update_file(Path::new("src/engine/jobs.rs"), 3, "jobs summary v2");
+        let recomputed = tree.recompute_dirty(concatenate_child_summaries);
+
+        // The changed file's ancestor chain (src/engine, src, root) is
+        // recomputed; src/package itself is never touched.
+        assert_eq!(recomputed, 3);
+        assert_eq!(tree.summary(&node("src/package")), Some("package summary"));
+        assert!(!tree.is_dirty(&node("src/package")));
+    }
+
+    #[test]
+    fn a_second_change_under_the_same_ancestor_does_not_duplicate_dirty_marks() {
+        let mut tree = sample_tree();
+        tree.update_file(Path::new("src/engine/jobs.rs"), 1, "a");
+        tree.update_file(Path::new("src/engine/agents.rs"), 2, "b");
+
+        assert_eq!(tree.dirty_count(), 3); // src/engine, src, root — each marked once
+    }
+}
@@ -0,0 +1,46 @@
+//! Core library for the `transfiguration` source analysis and summarization toolkit.
+
+#[cfg(feature = "analysis-pipeline")]
+pub mod analysis;
+pub mod chunk;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod dedup;
+pub mod engine;
+pub mod errors;
+pub mod fingerprint;
+#[cfg(feature = "forge-annotations")]
+pub mod forge;
+#[cfg(feature = "service")]
+pub mod forensics;
+pub mod guardrails;
+pub mod hierarchy;
+pub mod locking;
+pub mod memory;
+pub mod metrics;
+pub mod model;
+pub mod ordering;
+#[cfg(feature = "package-pipeline")]
+pub mod ownership;
+#[cfg(feature = "package-pipeline")]
+pub mod package;
+pub mod pipeline;
+pub mod plugins;
+pub mod report;
+pub mod retention;
+pub mod routing;
+#[cfg(feature = "sandbox")]
+pub mod sandbox;
+#[cfg(feature = "scaling-test")]
+pub mod scaling;
+#[cfg(feature = "signing")]
+pub mod signing;
+pub mod sinks;
+#[cfg(feature = "soak-test")]
+pub mod soak;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod testgen;
+#[cfg(feature = "archive-unpacking")]
+pub mod unpack;
+pub mod validation;
@@ -0,0 +1,164 @@
+//! Advisory file locking and atomic write-rename, so two CLI invocations
+//! that happen to point at the same results/checkpoint directory don't tear
+//! each other's writes.
+//!
+//! This crate has no notion of a "cache directory" of loose files today —
+//! the closest thing, [`crate::dedup::store::EmbeddingStore`], is a SQLite
+//! database, and SQLite already serializes concurrent writers with its own
+//! file locking, so it isn't retrofitted here. The writers that *were*
+//! genuinely unguarded are [`crate::engine::replay::ReplayLog::save_to_file`]
+//! (recorded model exchanges), [`crate::report::RunArtifacts::save_to_file`]
+//! (a run's output), and [`crate::engine::checkpoint::Checkpoint::save_to_file`]
+//! (a resumable run's progress so far); all three now go through
+//! [`FileLock::try_acquire`] and [`atomic_write`] below.
+//!
+//! [`FileLock`] wraps [`std::fs::File::try_lock`] (a real OS-level advisory
+//! lock — `flock` on Unix, `LockFileEx` on Windows) rather than a bespoke
+//! lockfile convention, and additionally records the holding process's pid
+//! in the lock file's contents so a contending process can report *who*
+//! holds the lock, not just that it's held.
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LockError {
+    #[error("io error acquiring or writing a lock file: {0}")]
+    Io(#[from] io::Error),
+    #[error("lock file {path:?} is held by another process (pid {holder_pid:?})")]
+    Contended { path: PathBuf, holder_pid: Option<u32> },
+}
+
+/// An advisory lock on a sidecar `.lock` file, held for as long as this
+/// value is alive. Released automatically when dropped (the OS releases a
+/// `flock`/`LockFileEx` lock when its file handle closes).
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Appends `.lock` to `target_path`'s file name, so a caller locks
+    /// around the file it's actually about to write rather than inventing
+    /// an unrelated lock name.
+    pub fn lock_path_for(target_path: &Path) -> PathBuf {
+        let mut file_name = target_path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".lock");
+        target_path.with_file_name(file_name)
+    }
+
+    /// Tries to acquire the advisory lock at `lock_path` without blocking.
+    /// On contention, returns [`LockError::Contended`] naming the pid
+    /// recorded by whichever process holds it, if it recorded one.
+    pub fn try_acquire(lock_path: impl Into<PathBuf>) -> Result<FileLock, LockError> {
+        let path = lock_path.into();
+        let file = OpenOptions::new().create(true).truncate(false).read(true).write(true).open(&path)?;
+
+        match file.try_lock() {
+            Ok(()) => {
+                record_holder_pid(&file)?;
+                Ok(FileLock { _file: file, path })
+            }
+            Err(TryLockError::WouldBlock) => {
+                Err(LockError::Contended { path: path.clone(), holder_pid: read_holder_pid(&path) })
+            }
+            Err(TryLockError::Error(source)) => Err(LockError::Io(source)),
+        }
+    }
+
+    /// The sidecar `.lock` file path this lock is held on.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn record_holder_pid(file: &File) -> io::Result<()> {
+    file.set_len(0)?;
+    (&*file).write_all(std::process::id().to_string().as_bytes())
+}
+
+fn read_holder_pid(lock_path: &Path) -> Option<u32> {
+    fs::read_to_string(lock_path).ok()?.trim().parse().ok()
+}
+
+/// Writes `contents` to `path` without ever leaving a reader able to observe
+/// a partially-written file: `contents` is written to a sibling temp file
+/// first, then moved into place with [`fs::rename`], which POSIX and Windows
+/// both guarantee is atomic when source and destination share a filesystem
+/// (true here, since the temp file is always a sibling of `path`).
+pub fn atomic_write(path: &Path, contents: &[u8]) -> Result<(), LockError> {
+    let temp_path = sibling_temp_path(path);
+    fs::write(&temp_path, contents)?;
+    if let Err(source) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(LockError::Io(source));
+    }
+    Ok(())
+}
+
+fn sibling_temp_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    path.with_file_name(format!(".{file_name}.tmp-{}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn try_acquire_then_try_acquire_again_reports_the_holding_pid() {
+        let lock_path = temp_path("transfiguration-locking-contention.lock");
+        let held = FileLock::try_acquire(&lock_path).unwrap();
+
+        let error = FileLock::try_acquire(&lock_path).unwrap_err();
+        match error {
+            LockError::Contended { path, holder_pid } => {
+                assert_eq!(path, lock_path);
+                assert_eq!(holder_pid, Some(std::process::id()));
+            }
+            other => panic!("expected Contended, got {other:?}"),
+        }
+
+        drop(held);
+        fs::remove_file(&lock_path).ok();
+    }
+
+    #[test]
+    fn dropping_a_lock_releases_it_for_the_next_acquirer() {
+        let lock_path = temp_path("transfiguration-locking-release.lock");
+        let held = FileLock::try_acquire(&lock_path).unwrap();
+        drop(held);
+
+        assert!(FileLock::try_acquire(&lock_path).is_ok());
+        fs::remove_file(&lock_path).ok();
+    }
+
+    #[test]
+    fn atomic_write_never_leaves_a_temp_file_behind_on_success() {
+        let path = temp_path("transfiguration-locking-atomic.txt");
+        atomic_write(&path, b"hello").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+        assert!(!sibling_temp_path(&path).exists());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn atomic_write_replaces_existing_content_in_one_rename() {
+        let path = temp_path("transfiguration-locking-atomic-replace.txt");
+        atomic_write(&path, b"first").unwrap();
+        atomic_write(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        fs::remove_file(&path).ok();
+    }
+}
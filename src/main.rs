@@ -0,0 +1,47 @@
+//! There is no argv-parsing command loop here yet — see
+//! [`transfiguration::package`] and [`transfiguration::cli::schema`] for why
+//! — so this binary only understands the two tooling-integration flags the
+//! `cli` feature adds (`--help-json`, `--completions <shell>`) and otherwise
+//! just points the user at the pipeline functions a future subcommand would
+//! call.
+
+fn main() {
+    #[cfg(feature = "cli")]
+    if handle_cli_flags() {
+        return;
+    }
+
+    println!("transfiguration: see `transfiguration --help` once the CLI surface lands");
+}
+
+/// Handles `--help-json` and `--completions <shell>` by hand, since there is
+/// no `clap` (or other argv-parsing) dependency in this crate to do it for
+/// us. Returns `true` if a recognized flag was handled, so `main` knows not
+/// to also print the placeholder message.
+#[cfg(feature = "cli")]
+fn handle_cli_flags() -> bool {
+    use transfiguration::cli::schema::{cli_schema, render_completion, Shell};
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let schema = cli_schema();
+
+    if args.iter().any(|arg| arg == "--help-json") {
+        println!("{}", schema.to_json());
+        return true;
+    }
+
+    if let Some(position) = args.iter().position(|arg| arg == "--completions") {
+        let Some(shell_name) = args.get(position + 1) else {
+            eprintln!("--completions requires a shell name (bash, zsh, fish)");
+            return true;
+        };
+        let Some(shell) = Shell::parse(shell_name) else {
+            eprintln!("unrecognized shell `{shell_name}`; expected bash, zsh, or fish");
+            return true;
+        };
+        println!("{}", render_completion(&schema, shell));
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,157 @@
+//! This process's current resident set size (RSS), read natively on each
+//! of the three platforms this crate's docs and CI target: `/proc/self/status`
+//! on Linux, Mach's `task_info` on macOS, and psapi's `GetProcessMemoryInfo`
+//! on Windows.
+//!
+//! Every platform's bindings are hand-written `extern` declarations rather
+//! than a `mach2`/`windows-sys`-style dependency — the same tradeoff
+//! [`crate::sandbox`] makes linking `libc` only for the syscalls its Linux
+//! seccomp filter needs, and [`crate::ordering`] makes hashing spill file
+//! names with [`std::collections::hash_map::DefaultHasher`] instead of
+//! pulling in `rand`: one well-known, narrow FFI surface is cheaper than a
+//! whole crate for the single number each platform needs here.
+//!
+//! [`crate::engine::adaptive_concurrency::AdaptiveConcurrencyController`]
+//! and [`crate::validation::validate_run`] both use [`current_rss_bytes`]
+//! as a live signal rather than a fixed estimate; [`crate::soak`] keeps its
+//! own Linux-only RSS sample instead of calling into this module, since
+//! [`crate::soak::run_soak_test`] is unsupported outright on any platform
+//! this module's macOS/Windows branches exist for (its file descriptor
+//! counting has no such cross-platform story yet).
+
+/// This process's current RSS in bytes, or `0` if it can't be determined
+/// (an unsupported platform, or a read/parse failure on a supported one).
+/// A caller folding this into a budget check should treat `0` as "no
+/// signal" rather than "definitely fine" — it will never itself trip a
+/// real `max_rss_bytes`-style budget, which is the point: a memory signal
+/// this crate can't read shouldn't abort an otherwise-healthy run.
+pub fn current_rss_bytes() -> u64 {
+    read_rss_bytes().unwrap_or(0)
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    let status = std::fs::read_to_string("/proc/self/status")?;
+    for line in status.lines() {
+        if let Some(kib) = line.strip_prefix("VmRSS:") {
+            let kib: u64 = kib.trim().trim_end_matches(" kB").trim().parse().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "VmRSS line in /proc/self/status was not a plain kB count")
+            })?;
+            return Ok(kib * 1024);
+        }
+    }
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no VmRSS line in /proc/self/status"))
+}
+
+#[cfg(target_os = "macos")]
+mod mach {
+    // Just enough of Mach's task_info API to read MACH_TASK_BASIC_INFO —
+    // the struct layout and flavor constant match
+    // `/usr/include/mach/task_info.h` and `/usr/include/mach/mach_init.h`.
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct TimeValue {
+        pub seconds: i32,
+        pub microseconds: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct MachTaskBasicInfo {
+        pub virtual_size: u64,
+        pub resident_size: u64,
+        pub resident_size_max: u64,
+        pub user_time: TimeValue,
+        pub system_time: TimeValue,
+        pub policy: i32,
+        pub suspend_count: i32,
+    }
+
+    pub const MACH_TASK_BASIC_INFO: u32 = 20;
+    pub const MACH_TASK_BASIC_INFO_COUNT: u32 = (std::mem::size_of::<MachTaskBasicInfo>() / std::mem::size_of::<u32>()) as u32;
+
+    extern "C" {
+        pub fn mach_task_self() -> u32;
+        pub fn task_info(target_task: u32, flavor: u32, task_info_out: *mut MachTaskBasicInfo, task_info_out_cnt: *mut u32) -> i32;
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    let mut info = mach::MachTaskBasicInfo::default();
+    let mut count = mach::MACH_TASK_BASIC_INFO_COUNT;
+
+    // Safety: `info`/`count` are sized exactly per MACH_TASK_BASIC_INFO's
+    // documented layout, and `mach_task_self()` always returns a valid
+    // handle to the calling task.
+    let result = unsafe { mach::task_info(mach::mach_task_self(), mach::MACH_TASK_BASIC_INFO, &mut info, &mut count) };
+
+    const KERN_SUCCESS: i32 = 0;
+    if result != KERN_SUCCESS {
+        return Err(std::io::Error::other(format!("task_info failed with kern_return_t {result}")));
+    }
+    Ok(info.resident_size)
+}
+
+#[cfg(target_os = "windows")]
+mod win {
+    // Just enough of PROCESS_MEMORY_COUNTERS (psapi.h) and GetCurrentProcess
+    // (processthreadsapi.h) to read the working set size.
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct ProcessMemoryCounters {
+        pub cb: u32,
+        pub page_fault_count: u32,
+        pub peak_working_set_size: usize,
+        pub working_set_size: usize,
+        pub quota_peak_paged_pool_usage: usize,
+        pub quota_paged_pool_usage: usize,
+        pub quota_peak_non_paged_pool_usage: usize,
+        pub quota_non_paged_pool_usage: usize,
+        pub pagefile_usage: usize,
+        pub peak_pagefile_usage: usize,
+    }
+
+    extern "system" {
+        pub fn GetCurrentProcess() -> isize;
+    }
+
+    #[link(name = "psapi")]
+    extern "system" {
+        pub fn GetProcessMemoryInfo(process: isize, counters: *mut ProcessMemoryCounters, size: u32) -> i32;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    let mut counters = win::ProcessMemoryCounters::default();
+    counters.cb = std::mem::size_of::<win::ProcessMemoryCounters>() as u32;
+
+    // Safety: `counters.cb` is set to this exact struct's size as
+    // GetProcessMemoryInfo requires, and GetCurrentProcess() is a
+    // pseudo-handle that's always valid for the calling process.
+    let succeeded = unsafe { win::GetProcessMemoryInfo(win::GetCurrentProcess(), &mut counters, counters.cb) };
+    if succeeded == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(counters.working_set_size as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "RSS sampling has no implementation for this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_rss_bytes_on_a_running_process_is_nonzero_on_a_supported_platform() {
+        // `0` is the graceful-degradation value for an unsupported
+        // platform or a read failure, neither of which a live test process
+        // on a platform this module supports should ever hit.
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        assert!(current_rss_bytes() > 0);
+    }
+}
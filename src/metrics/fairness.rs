@@ -0,0 +1,260 @@
+//! Per-file queue wait fairness for a parallel run.
+//!
+//! Run-wide throughput numbers ([`crate::metrics::ParallelMetrics`]) hide an
+//! unlucky file: a repo with a few huge files and many small ones can look
+//! healthy on average while one file's chunks sit in the queue far longer
+//! than everyone else's. [`QueueFairnessTracker`] records per-file wait
+//! times so that file is visible instead of averaged away.
+//!
+//! This crate has no hook/plugin system to raise an alarm through; the
+//! closest existing shape is [`crate::sinks::OutputSink`] — a trait a run
+//! fans a record out to, with the caller choosing how many implementations
+//! to register. [`StarvationHook`] follows that same shape for "notify
+//! something when a file's oldest pending chunk crosses an age threshold"
+//! instead of "write a finished result somewhere."
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::chunk::ChunkId;
+
+#[derive(Debug, Clone, Copy)]
+struct PendingChunk {
+    chunk_id: ChunkId,
+    enqueued_at: Instant,
+}
+
+/// Tracks per-source-file queue wait time across a run: when each chunk was
+/// enqueued, and (once dequeued) how long it waited for an agent slot. Like
+/// [`crate::metrics::trace::SchedulerTrace`], this isn't internally
+/// synchronized — a caller mutating it from more than one task wraps it in
+/// a `Mutex` itself, the same as it would for a `SchedulerTrace`.
+#[derive(Debug, Default)]
+pub struct QueueFairnessTracker {
+    pending: HashMap<PathBuf, Vec<PendingChunk>>,
+    finished_waits: HashMap<PathBuf, Vec<Duration>>,
+}
+
+impl QueueFairnessTracker {
+    pub fn new() -> Self {
+        QueueFairnessTracker::default()
+    }
+
+    /// Records `chunk_id` (from `source_path`) entering the queue at
+    /// `enqueued_at`.
+    pub fn chunk_enqueued(&mut self, source_path: &Path, chunk_id: ChunkId, enqueued_at: Instant) {
+        self.pending
+            .entry(source_path.to_path_buf())
+            .or_default()
+            .push(PendingChunk { chunk_id, enqueued_at });
+    }
+
+    /// Moves `chunk_id` out of `source_path`'s pending queue and into its
+    /// finished-wait history, returning how long it waited. Returns `None`
+    /// (and changes nothing) if `chunk_id` was never recorded as pending for
+    /// that path — e.g. a caller that dequeues the same chunk twice.
+    pub fn chunk_dequeued(&mut self, source_path: &Path, chunk_id: ChunkId, dequeued_at: Instant) -> Option<Duration> {
+        let pending = self.pending.get_mut(source_path)?;
+        let index = pending.iter().position(|p| p.chunk_id == chunk_id)?;
+        let entry = pending.remove(index);
+        let wait = dequeued_at.duration_since(entry.enqueued_at);
+        self.finished_waits.entry(source_path.to_path_buf()).or_default().push(wait);
+        Some(wait)
+    }
+
+    /// Per-file wait figures as of `now`, one entry per source file that has
+    /// ever had a chunk enqueued, sorted by path for a stable report order.
+    pub fn file_stats(&self, now: Instant) -> Vec<FileFairnessStats> {
+        let paths: BTreeSet<&PathBuf> = self.pending.keys().chain(self.finished_waits.keys()).collect();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let waits = self.finished_waits.get(path).map(Vec::as_slice).unwrap_or(&[]);
+                let max_wait = waits.iter().copied().max();
+                let mean_wait = if waits.is_empty() {
+                    None
+                } else {
+                    Some(waits.iter().sum::<Duration>() / waits.len() as u32)
+                };
+                let oldest_pending_age = self
+                    .pending
+                    .get(path)
+                    .and_then(|pending| pending.iter().map(|p| now.duration_since(p.enqueued_at)).max());
+
+                FileFairnessStats {
+                    source_path: path.clone(),
+                    max_wait,
+                    mean_wait,
+                    oldest_pending_age,
+                }
+            })
+            .collect()
+    }
+
+    /// Calls `hook` with a [`StarvationAlert`] for every file whose oldest
+    /// still-pending chunk has waited at least `max_age`.
+    pub fn raise_starvation_alarms(&self, now: Instant, max_age: Duration, hook: &mut dyn StarvationHook) {
+        for stats in self.file_stats(now) {
+            if let Some(oldest_pending_age) = stats.oldest_pending_age {
+                if oldest_pending_age >= max_age {
+                    hook.on_starvation(StarvationAlert { source_path: stats.source_path, oldest_pending_age });
+                }
+            }
+        }
+    }
+}
+
+/// One source file's queue wait figures as of the instant
+/// [`QueueFairnessTracker::file_stats`] was called. `max_wait`/`mean_wait`
+/// are `None` until at least one of the file's chunks has been dequeued;
+/// `oldest_pending_age` is `None` when nothing for the file is currently
+/// waiting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFairnessStats {
+    pub source_path: PathBuf,
+    pub max_wait: Option<Duration>,
+    pub mean_wait: Option<Duration>,
+    pub oldest_pending_age: Option<Duration>,
+}
+
+/// Renders `stats` as Prometheus text exposition format, one gauge per
+/// figure per file, so a `/metrics` endpoint can expose per-file fairness
+/// the same way [`crate::metrics::MetricsSnapshot::to_prometheus_text`]
+/// exposes run-wide counters.
+pub fn fairness_to_prometheus_text(stats: &[FileFairnessStats]) -> String {
+    let mut text = String::new();
+    for file in stats {
+        let path = file.source_path.display();
+        if let Some(max_wait) = file.max_wait {
+            text += &format!(
+                "transfiguration_file_queue_max_wait_seconds{{path=\"{path}\"}} {:.6}\n",
+                max_wait.as_secs_f64()
+            );
+        }
+        if let Some(mean_wait) = file.mean_wait {
+            text += &format!(
+                "transfiguration_file_queue_mean_wait_seconds{{path=\"{path}\"}} {:.6}\n",
+                mean_wait.as_secs_f64()
+            );
+        }
+        if let Some(oldest_pending_age) = file.oldest_pending_age {
+            text += &format!(
+                "transfiguration_file_queue_oldest_pending_age_seconds{{path=\"{path}\"}} {:.6}\n",
+                oldest_pending_age.as_secs_f64()
+            );
+        }
+    }
+    text
+}
+
+/// Raised by [`QueueFairnessTracker::raise_starvation_alarms`] for one file
+/// whose oldest pending chunk has waited too long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarvationAlert {
+    pub source_path: PathBuf,
+    pub oldest_pending_age: Duration,
+}
+
+/// Notified when a file's oldest pending chunk crosses the configured
+/// starvation age. Implement this for whatever the caller wants starvation
+/// to do — log a warning, increment an alert counter, page someone — the
+/// same way [`crate::sinks::OutputSink`] implementations decide what
+/// happens to a finished result.
+pub trait StarvationHook {
+    fn on_starvation(&mut self, alert: StarvationAlert);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dequeued_chunk_reports_its_wait_and_leaves_the_pending_set() {
+        let mut tracker = QueueFairnessTracker::new();
+        let base = Instant::now();
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(1), base);
+
+        let wait = tracker.chunk_dequeued(Path::new("a.rs"), ChunkId(1), base + Duration::from_millis(50));
+
+        assert_eq!(wait, Some(Duration::from_millis(50)));
+        let stats = tracker.file_stats(base + Duration::from_millis(50));
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].oldest_pending_age, None);
+        assert_eq!(stats[0].max_wait, Some(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn dequeuing_an_unknown_chunk_is_a_noop() {
+        let mut tracker = QueueFairnessTracker::new();
+        assert_eq!(tracker.chunk_dequeued(Path::new("a.rs"), ChunkId(1), Instant::now()), None);
+    }
+
+    #[test]
+    fn oldest_pending_age_reflects_the_longest_still_waiting_chunk() {
+        let mut tracker = QueueFairnessTracker::new();
+        let base = Instant::now();
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(1), base);
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(2), base + Duration::from_millis(10));
+
+        let stats = tracker.file_stats(base + Duration::from_secs(1));
+
+        assert_eq!(stats[0].oldest_pending_age, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn mean_and_max_wait_are_computed_across_every_dequeued_chunk_for_the_file() {
+        let mut tracker = QueueFairnessTracker::new();
+        let base = Instant::now();
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(1), base);
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(2), base);
+        tracker.chunk_dequeued(Path::new("a.rs"), ChunkId(1), base + Duration::from_millis(10));
+        tracker.chunk_dequeued(Path::new("a.rs"), ChunkId(2), base + Duration::from_millis(30));
+
+        let stats = tracker.file_stats(base + Duration::from_millis(30));
+
+        assert_eq!(stats[0].max_wait, Some(Duration::from_millis(30)));
+        assert_eq!(stats[0].mean_wait, Some(Duration::from_millis(20)));
+    }
+
+    struct RecordingHook {
+        alerts: Vec<StarvationAlert>,
+    }
+
+    impl StarvationHook for RecordingHook {
+        fn on_starvation(&mut self, alert: StarvationAlert) {
+            self.alerts.push(alert);
+        }
+    }
+
+    #[test]
+    fn starvation_alarm_fires_only_once_the_oldest_pending_chunk_crosses_the_age_threshold() {
+        let mut tracker = QueueFairnessTracker::new();
+        let base = Instant::now();
+        tracker.chunk_enqueued(Path::new("hot.rs"), ChunkId(1), base);
+        tracker.chunk_enqueued(Path::new("fine.rs"), ChunkId(2), base);
+        tracker.chunk_dequeued(Path::new("fine.rs"), ChunkId(2), base + Duration::from_millis(5));
+
+        let mut hook = RecordingHook { alerts: Vec::new() };
+        tracker.raise_starvation_alarms(base + Duration::from_secs(30), Duration::from_secs(10), &mut hook);
+
+        assert_eq!(hook.alerts.len(), 1);
+        assert_eq!(hook.alerts[0].source_path, PathBuf::from("hot.rs"));
+        assert_eq!(hook.alerts[0].oldest_pending_age, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn fairness_prometheus_text_includes_every_recorded_figure() {
+        let mut tracker = QueueFairnessTracker::new();
+        let base = Instant::now();
+        tracker.chunk_enqueued(Path::new("a.rs"), ChunkId(1), base);
+        tracker.chunk_dequeued(Path::new("a.rs"), ChunkId(1), base + Duration::from_millis(10));
+
+        let text = fairness_to_prometheus_text(&tracker.file_stats(base + Duration::from_millis(10)));
+
+        assert!(text.contains("transfiguration_file_queue_max_wait_seconds"));
+        assert!(text.contains("transfiguration_file_queue_mean_wait_seconds"));
+        assert!(text.contains("a.rs"));
+    }
+}
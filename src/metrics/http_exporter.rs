@@ -0,0 +1,171 @@
+//! A dependency-free `/metrics` HTTP endpoint, so a long run's Prometheus
+//! text (see [`crate::metrics::MetricsSnapshot::to_prometheus_text`] and
+//! [`crate::engine::session_pool::SessionPoolMetrics::to_prometheus_text`])
+//! can be scraped into Grafana instead of only read off a progress bar.
+//!
+//! Built on [`std::net::TcpListener`] rather than an HTTP framework
+//! dependency — the same "one narrow, well-understood surface beats a whole
+//! crate" tradeoff [`crate::memory`]'s platform FFI and [`crate::sandbox`]'s
+//! syscall-only `libc` linking make. A server that only ever answers one
+//! path with one content type has no routing, headers, or content
+//! negotiation worth a real HTTP library.
+//!
+//! Graceful shutdown mirrors [`crate::engine::batching::MicroBatcher`]'s
+//! `Option<JoinHandle>` + `Drop` pattern, but a raw accept loop has no
+//! channel to close to unblock a blocking `accept()` call the way dropping
+//! `MicroBatcher`'s sender unblocks its worker's `recv()`. So the accept
+//! loop instead runs the listener in non-blocking mode and polls a shared
+//! `AtomicBool` flag between attempts.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long one non-blocking accept poll waits before checking the
+/// shutdown flag again. Short enough that `Drop` doesn't stall a caller
+/// noticeably, long enough not to busy-spin a CPU core while idle.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long [`serve_one`] waits for a client to finish sending its request
+/// before giving up on reading it and responding anyway. A real scraper's
+/// whole request arrives in one packet; this only exists to bound how long
+/// a slow or stalled client can hold the single accept-loop thread.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves Prometheus text exposition format over plain HTTP on a background
+/// thread, re-rendered fresh by calling `render` on every request rather
+/// than caching a snapshot — a scraper should always see the run's current
+/// state, not whatever it looked like when the server started.
+pub struct MetricsHttpServer {
+    local_addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl MetricsHttpServer {
+    /// Binds `addr` (port `0` picks an ephemeral port — see
+    /// [`Self::local_addr`] to discover which one) and starts serving
+    /// `render()`'s output as `text/plain; version=0.0.4` to every
+    /// connection, regardless of the request's method or path: a
+    /// single-endpoint exporter has nothing to route.
+    pub fn spawn(addr: SocketAddr, render: impl Fn() -> String + Send + Sync + 'static) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let worker_shutdown = Arc::clone(&shutdown);
+        let worker = thread::spawn(move || run_accept_loop(listener, worker_shutdown, render));
+
+        Ok(MetricsHttpServer {
+            local_addr,
+            shutdown,
+            worker: Some(worker),
+        })
+    }
+
+    /// The address this server actually bound to — the port to scrape when
+    /// [`Self::spawn`] was given port `0`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for MetricsHttpServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn run_accept_loop(listener: TcpListener, shutdown: Arc<AtomicBool>, render: impl Fn() -> String) {
+    while !shutdown.load(Ordering::Acquire) {
+        match listener.accept() {
+            Ok((stream, _)) => serve_one(stream, &render()),
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+fn serve_one(mut stream: TcpStream, body: &str) {
+    // A scraper's request has nothing this server needs to act on; draining
+    // (rather than parsing) it is enough to keep the connection well-behaved
+    // before writing the response. `accept()` doesn't inherit the listener's
+    // non-blocking mode onto the accepted stream, so without this timeout a
+    // client that connects and never finishes sending a request would block
+    // this read forever — stalling every later scrape and `Drop`'s
+    // `worker.join()` along with it. A timed-out read is treated the same as
+    // a fully-drained one: there's nothing more worth waiting for either way.
+    let mut buffer = [0u8; 1024];
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.read(&mut buffer);
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {}",
+        body.len(),
+        body,
+    );
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn serves_the_rendered_text_over_plain_http() {
+        let server = MetricsHttpServer::spawn("127.0.0.1:0".parse().unwrap(), || "transfiguration_chunks_completed 3\n".to_string()).unwrap();
+
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("transfiguration_chunks_completed 3"));
+    }
+
+    #[test]
+    fn renders_fresh_output_on_every_request() {
+        let calls = Arc::new(AtomicU64::new(0));
+        let render_calls = Arc::clone(&calls);
+        let server = MetricsHttpServer::spawn("127.0.0.1:0".parse().unwrap(), move || {
+            let n = render_calls.fetch_add(1, Ordering::SeqCst) + 1;
+            format!("transfiguration_chunks_completed {n}\n")
+        })
+        .unwrap();
+
+        for expected in 1..=2u64 {
+            let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+            stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.contains(&format!("transfiguration_chunks_completed {expected}")));
+        }
+    }
+
+    #[test]
+    fn dropping_the_server_stops_its_background_thread() {
+        let server = MetricsHttpServer::spawn("127.0.0.1:0".parse().unwrap(), String::new).unwrap();
+        let addr = server.local_addr();
+        drop(server);
+
+        // The listener is gone once `Drop` has joined the worker, so a new
+        // connection attempt no longer reaches a live accept loop.
+        assert!(TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_err());
+    }
+}
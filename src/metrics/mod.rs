@@ -0,0 +1,291 @@
+//! Live metrics for a parallel summarization run.
+//!
+//! Counters used to be plain fields finalized once the run finished, so
+//! nothing else could observe progress mid-run. [`ParallelMetrics`] is a
+//! lock-light structure built on atomics (plus one briefly-held lock around
+//! the latency statistics): any number of callers can
+//! take a [`MetricsSnapshot`] at any time from another thread or task, and
+//! the progress reporter, a Prometheus exporter, and an SLO tracker can all
+//! read the same numbers instead of keeping their own partial counters.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics::streaming::{LatencyStats, LatencyStatsSnapshot};
+
+pub mod fairness;
+#[cfg(feature = "metrics-http")]
+pub mod http_exporter;
+pub mod streaming;
+pub mod trace;
+
+/// Thread-safe counters for an in-progress run. Every method is safe to call
+/// concurrently from any number of threads.
+///
+/// Every counter but one is a plain atomic. `latency_stats` is the one
+/// exception: [`streaming::LatencyStats`] needs mutable state (P² markers,
+/// a running mean/variance) that doesn't decompose into independent atomics
+/// the way a counter does, so it's kept behind a [`Mutex`] instead. The lock
+/// is held only for the O(1) work of recording or reading one observation,
+/// never across a whole run.
+pub struct ParallelMetrics {
+    chunks_completed: AtomicU64,
+    chunks_failed: AtomicU64,
+    active_agents: AtomicU64,
+    total_latency_nanos: AtomicU64,
+    tokens_generated: AtomicU64,
+    latency_stats: Mutex<LatencyStats>,
+    /// When this run's metrics started being tracked, so the first call to
+    /// [`Self::record_chunk_completed`] can derive cold-start latency
+    /// without a caller having to pass its own run-start timestamp through.
+    created_at: Instant,
+    first_summary_recorded: AtomicBool,
+    time_to_first_summary_nanos: AtomicU64,
+}
+
+impl Default for ParallelMetrics {
+    fn default() -> Self {
+        ParallelMetrics {
+            chunks_completed: AtomicU64::new(0),
+            chunks_failed: AtomicU64::new(0),
+            active_agents: AtomicU64::new(0),
+            total_latency_nanos: AtomicU64::new(0),
+            tokens_generated: AtomicU64::new(0),
+            latency_stats: Mutex::new(LatencyStats::new()),
+            created_at: Instant::now(),
+            first_summary_recorded: AtomicBool::new(false),
+            time_to_first_summary_nanos: AtomicU64::new(0),
+        }
+    }
+}
+
+impl ParallelMetrics {
+    pub fn agent_started(&self) {
+        self.active_agents.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn agent_finished(&self) {
+        self.active_agents.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn record_chunk_completed(&self, latency: Duration) {
+        self.chunks_completed.fetch_add(1, Ordering::Relaxed);
+        self.total_latency_nanos
+            .fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+        if let Ok(mut latency_stats) = self.latency_stats.lock() {
+            latency_stats.record(latency);
+        }
+        // Only the very first completed chunk sets this — it's measuring
+        // cold start (construction to first result), not every chunk's own
+        // latency, which `latency_stats` already tracks.
+        if !self.first_summary_recorded.swap(true, Ordering::AcqRel) {
+            self.time_to_first_summary_nanos
+                .store(self.created_at.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_chunk_failed(&self) {
+        self.chunks_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Adds `count` to the running total of tokens produced by completed
+    /// chunks, per [`crate::model::context::estimate_token_count`]. Kept
+    /// separate from [`Self::record_chunk_completed`] (rather than an extra
+    /// parameter on it) the same way [`Self::record_chunk_failed`] is kept
+    /// separate from it — one counter, one call, at whichever call site has
+    /// the text to measure.
+    pub fn record_tokens_generated(&self, count: u64) {
+        self.tokens_generated.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Reads a consistent-enough point-in-time view of the counters. Callers
+    /// on different threads can call this concurrently with the run still
+    /// in progress; the only lock taken is the brief one around
+    /// `latency_stats`, and there is no finalization step to wait on.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let chunks_completed = self.chunks_completed.load(Ordering::Relaxed);
+        let total_latency_nanos = self.total_latency_nanos.load(Ordering::Relaxed);
+        let average_latency = total_latency_nanos
+            .checked_div(chunks_completed)
+            .map(Duration::from_nanos)
+            .unwrap_or(Duration::ZERO);
+
+        let latency_stats = self
+            .latency_stats
+            .lock()
+            .map(|stats| stats.snapshot())
+            .unwrap_or_default();
+
+        let time_to_first_summary = self
+            .first_summary_recorded
+            .load(Ordering::Acquire)
+            .then(|| Duration::from_nanos(self.time_to_first_summary_nanos.load(Ordering::Relaxed)));
+
+        MetricsSnapshot {
+            chunks_completed,
+            chunks_failed: self.chunks_failed.load(Ordering::Relaxed),
+            active_agents: self.active_agents.load(Ordering::Relaxed),
+            average_latency,
+            tokens_generated: self.tokens_generated.load(Ordering::Relaxed),
+            latency_stats,
+            time_to_first_summary,
+        }
+    }
+}
+
+/// An immutable point-in-time read of [`ParallelMetrics`], cheap to clone and
+/// pass to whichever consumer needs it (progress bar, exporter, SLO check).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub chunks_completed: u64,
+    pub chunks_failed: u64,
+    pub active_agents: u64,
+    pub average_latency: Duration,
+    /// Running total of tokens produced by every completed chunk, per
+    /// [`crate::model::context::estimate_token_count`].
+    pub tokens_generated: u64,
+    /// Latency spread and percentiles, computed in O(1) memory via
+    /// [`streaming::LatencyStats`] rather than accumulated from every
+    /// completed chunk.
+    pub latency_stats: LatencyStatsSnapshot,
+    /// Wall-clock time from this [`ParallelMetrics`] being constructed to
+    /// its first completed chunk — the cold-start figure a lazily-filled
+    /// [`crate::engine::worker_pool::WorkerPool`] is meant to shrink.
+    /// `None` until at least one chunk has completed.
+    pub time_to_first_summary: Option<Duration>,
+}
+
+impl MetricsSnapshot {
+    /// `chunks_failed / (chunks_completed + chunks_failed)`, or `0.0` before
+    /// any chunk has finished — an empty run is not itself a failing one.
+    pub fn error_rate(&self) -> f64 {
+        let total = self.chunks_completed + self.chunks_failed;
+        if total == 0 {
+            0.0
+        } else {
+            self.chunks_failed as f64 / total as f64
+        }
+    }
+
+    /// Renders the snapshot as Prometheus text exposition format, so a
+    /// `/metrics` endpoint can serve the exact numbers the progress reporter
+    /// is showing the user.
+    pub fn to_prometheus_text(self) -> String {
+        let mut text = format!(
+            "transfiguration_chunks_completed {}\n\
+             transfiguration_chunks_failed {}\n\
+             transfiguration_active_agents {}\n\
+             transfiguration_average_latency_seconds {:.6}\n\
+             transfiguration_tokens_generated {}\n\
+             transfiguration_error_rate {:.6}\n",
+            self.chunks_completed,
+            self.chunks_failed,
+            self.active_agents,
+            self.average_latency.as_secs_f64(),
+            self.tokens_generated,
+            self.error_rate(),
+        );
+
+        for (name, value) in [
+            ("stddev", self.latency_stats.stddev),
+            ("p50", self.latency_stats.p50),
+            ("p95", self.latency_stats.p95),
+            ("p99", self.latency_stats.p99),
+        ] {
+            if let Some(value) = value {
+                text += &format!("transfiguration_latency_{name}_seconds {:.6}\n", value.as_secs_f64());
+            }
+        }
+
+        if let Some(time_to_first_summary) = self.time_to_first_summary {
+            text += &format!(
+                "transfiguration_time_to_first_summary_seconds {:.6}\n",
+                time_to_first_summary.as_secs_f64()
+            );
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn snapshot_reflects_concurrent_updates_without_finalization() {
+        let metrics = Arc::new(ParallelMetrics::default());
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let metrics = Arc::clone(&metrics);
+                thread::spawn(move || {
+                    metrics.agent_started();
+                    for _ in 0..10 {
+                        metrics.record_chunk_completed(Duration::from_millis(10));
+                    }
+                    metrics.agent_finished();
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.chunks_completed, 40);
+        assert_eq!(snapshot.active_agents, 0);
+        assert_eq!(snapshot.average_latency, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn prometheus_text_includes_every_counter() {
+        let metrics = ParallelMetrics::default();
+        metrics.record_chunk_completed(Duration::from_millis(5));
+        metrics.record_chunk_failed();
+        metrics.record_tokens_generated(42);
+
+        let text = metrics.snapshot().to_prometheus_text();
+        assert!(text.contains("transfiguration_chunks_completed 1"));
+        assert!(text.contains("transfiguration_chunks_failed 1"));
+        assert!(text.contains("transfiguration_tokens_generated 42"));
+        assert!(text.contains("transfiguration_error_rate 0.500000"));
+    }
+
+    #[test]
+    fn error_rate_is_zero_before_any_chunk_has_finished() {
+        let metrics = ParallelMetrics::default();
+        assert_eq!(metrics.snapshot().error_rate(), 0.0);
+    }
+
+    #[test]
+    fn tokens_generated_accumulates_across_calls() {
+        let metrics = ParallelMetrics::default();
+        metrics.record_tokens_generated(10);
+        metrics.record_tokens_generated(5);
+        assert_eq!(metrics.snapshot().tokens_generated, 15);
+    }
+
+    #[test]
+    fn time_to_first_summary_is_absent_before_any_chunk_completes() {
+        let metrics = ParallelMetrics::default();
+        assert_eq!(metrics.snapshot().time_to_first_summary, None);
+    }
+
+    #[test]
+    fn time_to_first_summary_is_recorded_once_and_not_overwritten_by_later_chunks() {
+        let metrics = ParallelMetrics::default();
+        metrics.record_chunk_completed(Duration::from_millis(1));
+        let first = metrics.snapshot().time_to_first_summary;
+        assert!(first.is_some());
+
+        std::thread::sleep(Duration::from_millis(5));
+        metrics.record_chunk_completed(Duration::from_millis(1));
+        let second = metrics.snapshot().time_to_first_summary;
+
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,168 @@
+//! Scheduler trace and critical-path analysis for parallel runs.
+//!
+//! `parallel_efficiency` used to be a single percentage with no way to see
+//! where the lost time actually went. [`SchedulerTrace`] records every
+//! chunk's real start/stop instants against the agent slot that ran it, and
+//! [`CriticalPathAnalysis::from_trace`] turns that into a breakdown of
+//! warmup time, per-agent idle time, and the straggler: the single chunk
+//! that ran longest.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::chunk::ChunkId;
+
+/// Identifies one concurrent worker slot within a run, independent of which
+/// chunk it's currently processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AgentSlot(pub usize);
+
+/// One chunk's observed start/stop instants on a given agent slot.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkInterval {
+    pub chunk_id: ChunkId,
+    pub agent: AgentSlot,
+    pub started_at: Instant,
+    pub finished_at: Instant,
+}
+
+impl ChunkInterval {
+    pub fn duration(&self) -> Duration {
+        self.finished_at.duration_since(self.started_at)
+    }
+}
+
+/// Accumulates [`ChunkInterval`]s as a run progresses, anchored to the
+/// instant the run itself was dispatched so warmup time (before the first
+/// chunk actually started) is visible instead of folded into "busy time".
+pub struct SchedulerTrace {
+    run_started_at: Instant,
+    intervals: Vec<ChunkInterval>,
+}
+
+impl SchedulerTrace {
+    pub fn new() -> Self {
+        SchedulerTrace {
+            run_started_at: Instant::now(),
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, interval: ChunkInterval) {
+        self.intervals.push(interval);
+    }
+
+    pub fn intervals(&self) -> &[ChunkInterval] {
+        &self.intervals
+    }
+}
+
+impl Default for SchedulerTrace {
+    fn default() -> Self {
+        SchedulerTrace::new()
+    }
+}
+
+/// Where a [`SchedulerTrace`]'s wall-clock time went.
+#[derive(Debug, Clone)]
+pub struct CriticalPathAnalysis {
+    pub wall_time: Duration,
+    pub busy_time: Duration,
+    pub warmup: Duration,
+    pub straggler: Option<(ChunkId, Duration)>,
+    pub idle_by_agent: HashMap<AgentSlot, Duration>,
+}
+
+impl CriticalPathAnalysis {
+    /// Total busy time spread across every agent slot that ran anything, as
+    /// a fraction of wall time. This is what `parallel_efficiency` used to
+    /// report as a bare percentage; the rest of this struct is the
+    /// derivation that number was missing.
+    pub fn efficiency(&self) -> f64 {
+        if self.wall_time.is_zero() || self.idle_by_agent.is_empty() {
+            return 0.0;
+        }
+        let agent_count = self.idle_by_agent.len() as f64;
+        self.busy_time.as_secs_f64() / (self.wall_time.as_secs_f64() * agent_count)
+    }
+
+    /// Builds the analysis from a finished trace. Returns `None` for a trace
+    /// with no recorded intervals — there's no critical path to analyze yet.
+    pub fn from_trace(trace: &SchedulerTrace) -> Option<Self> {
+        let intervals = trace.intervals();
+        let run_finished_at = intervals.iter().map(|i| i.finished_at).max()?;
+        let first_chunk_started_at = intervals
+            .iter()
+            .map(|i| i.started_at)
+            .min()
+            .expect("run_finished_at existing implies at least one interval");
+
+        let wall_time = run_finished_at.duration_since(trace.run_started_at);
+        let warmup = first_chunk_started_at.duration_since(trace.run_started_at);
+        let busy_time = intervals.iter().map(|i| i.duration()).sum();
+
+        let mut busy_by_agent: HashMap<AgentSlot, Duration> = HashMap::new();
+        for interval in intervals {
+            *busy_by_agent.entry(interval.agent).or_default() += interval.duration();
+        }
+        let idle_by_agent = busy_by_agent
+            .into_iter()
+            .map(|(agent, busy)| (agent, wall_time.saturating_sub(busy)))
+            .collect();
+
+        let straggler = intervals
+            .iter()
+            .max_by_key(|i| i.duration())
+            .map(|i| (i.chunk_id, i.duration()));
+
+        Some(CriticalPathAnalysis {
+            wall_time,
+            busy_time,
+            warmup,
+            straggler,
+            idle_by_agent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(chunk_id: u64, agent: usize, start_offset: Duration, dur: Duration, base: Instant) -> ChunkInterval {
+        ChunkInterval {
+            chunk_id: ChunkId(chunk_id),
+            agent: AgentSlot(agent),
+            started_at: base + start_offset,
+            finished_at: base + start_offset + dur,
+        }
+    }
+
+    #[test]
+    fn empty_trace_has_no_analysis() {
+        let trace = SchedulerTrace::new();
+        assert!(CriticalPathAnalysis::from_trace(&trace).is_none());
+    }
+
+    #[test]
+    fn identifies_warmup_straggler_and_idle_time() {
+        let mut trace = SchedulerTrace::new();
+        let base = trace.run_started_at;
+
+        // Agent 0 starts right away and runs two quick chunks with a gap.
+        trace.record(interval(1, 0, Duration::from_millis(10), Duration::from_millis(10), base));
+        trace.record(interval(2, 0, Duration::from_millis(40), Duration::from_millis(10), base));
+        // Agent 1 is the straggler: starts late and runs long.
+        trace.record(interval(3, 1, Duration::from_millis(20), Duration::from_millis(100), base));
+
+        let analysis = CriticalPathAnalysis::from_trace(&trace).unwrap();
+
+        assert_eq!(analysis.warmup, Duration::from_millis(10));
+        assert_eq!(analysis.wall_time, Duration::from_millis(120));
+        assert_eq!(analysis.straggler, Some((ChunkId(3), Duration::from_millis(100))));
+        assert_eq!(analysis.idle_by_agent.len(), 2);
+        // Agent 0 was busy 20ms out of 120ms wall time.
+        assert_eq!(analysis.idle_by_agent[&AgentSlot(0)], Duration::from_millis(100));
+        assert!(analysis.efficiency() > 0.0 && analysis.efficiency() <= 1.0);
+    }
+}
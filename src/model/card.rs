@@ -0,0 +1,88 @@
+//! Licensing and capability metadata for a model, so a run can carry (and a
+//! downstream report can show) what the model is licensed for instead of
+//! summaries circulating with no indication of the terms they were produced
+//! under.
+//!
+//! [`load_model_card`] reads an optional `model_card.json` bundled alongside
+//! the model's weights; a model directory with no such file still resolves
+//! to a [`ModelCard`] (every field `None`/empty) rather than an error, since
+//! plenty of model directories in the wild simply don't ship one.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+const MODEL_CARD_FILE_NAME: &str = "model_card.json";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelCardError {
+    #[error("failed to read model card: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse model card: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Licensing and capability metadata discovered for a model.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelCard {
+    /// License identifier or URL, e.g. `"Apache-2.0"` or
+    /// `"https://example.com/model-license"`.
+    pub license: Option<String>,
+    /// Free-text statement of what the model is (and isn't) intended for.
+    pub intended_use: Option<String>,
+    /// Maximum context window in tokens, if the card states one separately
+    /// from `config.json`'s `max_position_embeddings`.
+    pub context_window: Option<usize>,
+    /// Languages the model was trained/evaluated on, e.g. `["en", "ja"]`.
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
+/// Reads `model_card.json` out of `model_dir`, or an empty [`ModelCard`] if
+/// the directory has no such file.
+pub fn load_model_card(model_dir: &Path) -> Result<ModelCard, ModelCardError> {
+    let card_path = model_dir.join(MODEL_CARD_FILE_NAME);
+    let contents = match fs::read_to_string(&card_path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(ModelCard::default()),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(serde_json::from_str(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_every_field_from_a_bundled_card_file() {
+        let dir = std::env::temp_dir().join("transfiguration-model-card-full");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(MODEL_CARD_FILE_NAME),
+            r#"{"license": "Apache-2.0", "intended_use": "code summarization", "context_window": 4096, "languages": ["en", "ja"]}"#,
+        )
+        .unwrap();
+
+        let card = load_model_card(&dir).unwrap();
+        assert_eq!(card.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(card.intended_use.as_deref(), Some("code summarization"));
+        assert_eq!(card.context_window, Some(4096));
+        assert_eq!(card.languages, vec!["en".to_string(), "ja".to_string()]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_card_file_resolves_to_an_empty_card_instead_of_an_error() {
+        let dir = std::env::temp_dir().join("transfiguration-model-card-missing");
+        fs::create_dir_all(&dir).unwrap();
+
+        let card = load_model_card(&dir).unwrap();
+        assert!(card.license.is_none());
+        assert!(card.languages.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
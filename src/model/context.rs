@@ -0,0 +1,141 @@
+//! Discovers a model's real context window and special tokens from its
+//! `config.json` / `generation_config.json` instead of assuming a constant,
+//! and enforces prompt budgets against that real limit.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelConfigError {
+    #[error("failed to read model config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse model config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error(
+        "prompt does not fit in the model's context window: needs {needed} tokens, \
+         budget is {budget} tokens ({max_context} context - {reserved_for_completion} reserved for completion)"
+    )]
+    PromptExceedsContext {
+        needed: usize,
+        budget: usize,
+        max_context: usize,
+        reserved_for_completion: usize,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct RawModelConfig {
+    #[serde(alias = "n_ctx")]
+    max_position_embeddings: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawGenerationConfig {
+    bos_token_id: Option<u32>,
+    eos_token_id: Option<serde_json::Value>,
+}
+
+/// Context window and special tokens discovered from a model directory.
+#[derive(Debug, Clone)]
+pub struct ModelConfig {
+    pub max_context_tokens: usize,
+    pub bos_token_id: Option<u32>,
+    pub eos_token_ids: Vec<u32>,
+}
+
+/// Reads `config.json` (required, for `max_position_embeddings`) and
+/// `generation_config.json` (optional, for special tokens) out of
+/// `model_dir`.
+pub fn load_model_config(model_dir: &Path) -> Result<ModelConfig, ModelConfigError> {
+    let config_json = fs::read_to_string(model_dir.join("config.json"))?;
+    let raw_config: RawModelConfig = serde_json::from_str(&config_json)?;
+
+    let raw_generation: RawGenerationConfig = match fs::read_to_string(model_dir.join("generation_config.json")) {
+        Ok(contents) => serde_json::from_str(&contents)?,
+        Err(_) => RawGenerationConfig::default(),
+    };
+
+    let eos_token_ids = match raw_generation.eos_token_id {
+        Some(serde_json::Value::Number(n)) => n.as_u64().map(|v| vec![v as u32]).unwrap_or_default(),
+        Some(serde_json::Value::Array(values)) => {
+            values.iter().filter_map(|v| v.as_u64()).map(|v| v as u32).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    Ok(ModelConfig {
+        max_context_tokens: raw_config.max_position_embeddings.unwrap_or(2048),
+        bos_token_id: raw_generation.bos_token_id,
+        eos_token_ids,
+    })
+}
+
+/// Rough token estimate used for budget enforcement before real tokenization
+/// runs: whitespace-separated words. A subword-tokenized model often splits
+/// one word into several tokens, so this can under-count the real total.
+///
+/// `pub(crate)` rather than private so [`crate::model::fixture`] can assert
+/// golden counts against the exact function [`enforce_prompt_budget`] calls,
+/// instead of re-deriving its own copy of "what counts as a token" here.
+pub(crate) fn estimate_token_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Errors if `prompt` would not fit alongside `reserved_for_completion`
+/// tokens within `config`'s real context window.
+pub fn enforce_prompt_budget(
+    prompt: &str,
+    config: &ModelConfig,
+    reserved_for_completion: usize,
+) -> Result<(), ModelConfigError> {
+    let needed = estimate_token_count(prompt);
+    let budget = config.max_context_tokens.saturating_sub(reserved_for_completion);
+    if needed > budget {
+        return Err(ModelConfigError::PromptExceedsContext {
+            needed,
+            budget,
+            max_context: config.max_context_tokens,
+            reserved_for_completion,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn discovers_context_window_and_eos_tokens() {
+        let dir = std::env::temp_dir().join("transfiguration-model-config");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{"max_position_embeddings": 4096}"#).unwrap();
+        fs::write(
+            dir.join("generation_config.json"),
+            r#"{"bos_token_id": 1, "eos_token_id": [2, 3]}"#,
+        )
+        .unwrap();
+
+        let config = load_model_config(&dir).unwrap();
+        assert_eq!(config.max_context_tokens, 4096);
+        assert_eq!(config.bos_token_id, Some(1));
+        assert_eq!(config.eos_token_ids, vec![2, 3]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn oversized_prompt_is_rejected_with_clear_error() {
+        let config = ModelConfig {
+            max_context_tokens: 10,
+            bos_token_id: None,
+            eos_token_ids: vec![],
+        };
+        let prompt = "one two three four five six seven eight nine ten eleven";
+        let err = enforce_prompt_budget(prompt, &config, 2).unwrap_err();
+        assert!(err.to_string().contains("does not fit"));
+    }
+}
@@ -0,0 +1,194 @@
+//! Deterministic decode fixtures, in place of a vendored ONNX model.
+//!
+//! This crate has no tokenizer and no real ONNX inference pipeline wired
+//! into [`crate::engine::decode`]: [`ModelLocator`](crate::model::ModelLocator)
+//! only resolves asset *paths*, nothing here loads an `.onnx` file and runs a
+//! forward pass, and [`estimate_token_count`](super::context) is a
+//! whitespace-word stand-in rather than a real tokenizer. There is therefore
+//! nothing to check in a small vendored `.onnx` model *for* — any such file
+//! would sit unexercised by the rest of the crate, which is worse than no
+//! fixture at all.
+//!
+//! What already exists is backend-agnostic: [`decode`](crate::engine::decode::decode)
+//! drives any [`NextTokenLogits`](crate::engine::decode::NextTokenLogits), and
+//! [`enforce_prompt_budget`](super::context::enforce_prompt_budget) enforces a
+//! context budget against any [`ModelConfig`](super::context::ModelConfig).
+//! [`golden_decode_cases`] and [`GOLDEN_TRUNCATION_CASES`] pin a fixed,
+//! byte-reproducible set of inputs and expected outputs against those real
+//! functions, covering the same three behaviors a vendored model + golden
+//! outputs file would (tokenization, stop handling, truncation) without a
+//! binary asset or network access.
+
+use crate::engine::decode::{DecodingStrategy, GenerationConfig, NextTokenLogits};
+use crate::model::context::ModelConfig;
+
+/// A 5-word toy vocabulary: token id is the word's index here, plus `EOS_TOKEN_ID`
+/// for six token ids in all.
+pub const GOLDEN_VOCAB: &[&str] = &["the", "quick", "brown", "fox", "jumps"];
+
+/// Reserved id for the fixture's end-of-sequence token, one past the last
+/// real word in [`GOLDEN_VOCAB`].
+pub const EOS_TOKEN_ID: u32 = GOLDEN_VOCAB.len() as u32;
+
+/// A [`NextTokenLogits`] source that replays a fixed script of per-step
+/// logits, one entry per generation step, repeating the final entry if
+/// [`decode`](crate::engine::decode::decode) asks for more steps than were
+/// scripted. The same role [`FixedLogits`] plays in
+/// `engine::decode`'s own tests, pulled out here so golden cases can be
+/// asserted against by name instead of rebuilt inline per test.
+pub struct ScriptedVocabLogits {
+    steps: Vec<Vec<f32>>,
+}
+
+impl ScriptedVocabLogits {
+    pub fn new(steps: Vec<Vec<f32>>) -> Self {
+        ScriptedVocabLogits { steps }
+    }
+}
+
+impl NextTokenLogits for ScriptedVocabLogits {
+    fn next_token_logits(&mut self, tokens_so_far: &[u32]) -> Vec<f32> {
+        let step = tokens_so_far.len().min(self.steps.len().saturating_sub(1));
+        self.steps[step].clone()
+    }
+}
+
+/// One fixed prompt fed through [`decode`](crate::engine::decode::decode),
+/// with the exact token sequence it must produce.
+pub struct GoldenDecodeCase {
+    pub name: &'static str,
+    pub logits: ScriptedVocabLogits,
+    pub config: GenerationConfig,
+    pub eos_token_ids: Vec<u32>,
+    pub expected_tokens: Vec<u32>,
+}
+
+/// Fixed decode cases covering stop-at-eos and stop-at-`max_new_tokens`
+/// greedy decoding against [`GOLDEN_VOCAB`]. Every logits script and expected
+/// token sequence here is hand-picked and stable; a change in
+/// `engine::decode`'s greedy algorithm that alters these outputs is a
+/// regression, not a fixture to update casually.
+pub fn golden_decode_cases() -> Vec<GoldenDecodeCase> {
+    vec![
+        GoldenDecodeCase {
+            name: "greedy_stops_at_eos",
+            // Step 0: "quick" (id 1) scores highest. Step 1: eos scores
+            // highest, so decoding stops there rather than running to
+            // max_new_tokens.
+            logits: ScriptedVocabLogits::new(vec![
+                vec![0.0, 5.0, 0.0, 0.0, 0.0, -1.0],
+                vec![-1.0, -1.0, -1.0, -1.0, -1.0, 5.0],
+            ]),
+            config: GenerationConfig {
+                strategy: DecodingStrategy::Greedy,
+                max_new_tokens: 5,
+                forbidden_token_ids: Vec::new(),
+            },
+            eos_token_ids: vec![EOS_TOKEN_ID],
+            expected_tokens: vec![1, EOS_TOKEN_ID],
+        },
+        GoldenDecodeCase {
+            name: "greedy_truncates_at_max_new_tokens_without_eos",
+            // "fox" (id 3) always scores highest and eos never does, so
+            // decoding must stop purely because max_new_tokens is reached.
+            logits: ScriptedVocabLogits::new(vec![vec![0.0, 0.0, 0.0, 5.0, 0.0, -1.0]]),
+            config: GenerationConfig {
+                strategy: DecodingStrategy::Greedy,
+                max_new_tokens: 3,
+                forbidden_token_ids: Vec::new(),
+            },
+            eos_token_ids: vec![EOS_TOKEN_ID],
+            expected_tokens: vec![3, 3, 3],
+        },
+    ]
+}
+
+/// A fixed prompt paired with the exact [`estimate_token_count`] result it
+/// must produce, standing in for a tokenization golden output.
+pub struct GoldenTokenizationCase {
+    pub prompt: &'static str,
+    pub expected_token_count: usize,
+}
+
+pub const GOLDEN_TOKENIZATION_CASES: &[GoldenTokenizationCase] = &[
+    GoldenTokenizationCase {
+        prompt: "the quick brown fox jumps",
+        expected_token_count: 5,
+    },
+    GoldenTokenizationCase {
+        prompt: "",
+        expected_token_count: 0,
+    },
+    GoldenTokenizationCase {
+        prompt: "the  quick\tbrown\nfox",
+        expected_token_count: 4,
+    },
+];
+
+/// A fixed prompt and completion reservation, paired with whether
+/// [`enforce_prompt_budget`](super::context::enforce_prompt_budget) must
+/// accept or reject it against [`GOLDEN_MODEL_CONFIG`], standing in for a
+/// truncation golden output.
+pub struct GoldenTruncationCase {
+    pub prompt: &'static str,
+    pub reserved_for_completion: usize,
+    pub fits: bool,
+}
+
+/// A small fixed context window (10 tokens) so truncation cases can exercise
+/// both sides of the budget with short, readable prompts.
+pub const GOLDEN_MODEL_CONFIG: ModelConfig = ModelConfig {
+    max_context_tokens: 10,
+    bos_token_id: None,
+    eos_token_ids: Vec::new(),
+};
+
+pub const GOLDEN_TRUNCATION_CASES: &[GoldenTruncationCase] = &[
+    GoldenTruncationCase {
+        prompt: "the quick brown fox jumps",
+        reserved_for_completion: 2,
+        fits: true,
+    },
+    GoldenTruncationCase {
+        prompt: "the quick brown fox jumps over the lazy dog today",
+        reserved_for_completion: 2,
+        fits: false,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::decode::decode;
+    use crate::model::context::estimate_token_count;
+
+    #[test]
+    fn golden_decode_cases_match_their_pinned_expected_tokens() {
+        for mut case in golden_decode_cases() {
+            let tokens = decode(&mut case.logits, &case.config, &case.eos_token_ids);
+            assert_eq!(tokens, case.expected_tokens, "case `{}` regressed", case.name);
+        }
+    }
+
+    #[test]
+    fn golden_tokenization_cases_match_estimate_token_count() {
+        for case in GOLDEN_TOKENIZATION_CASES {
+            assert_eq!(
+                estimate_token_count(case.prompt),
+                case.expected_token_count,
+                "prompt `{}` regressed",
+                case.prompt
+            );
+        }
+    }
+
+    #[test]
+    fn golden_truncation_cases_match_enforce_prompt_budget() {
+        use crate::model::context::enforce_prompt_budget;
+
+        for case in GOLDEN_TRUNCATION_CASES {
+            let result = enforce_prompt_budget(case.prompt, &GOLDEN_MODEL_CONFIG, case.reserved_for_completion);
+            assert_eq!(result.is_ok(), case.fits, "prompt `{}` regressed", case.prompt);
+        }
+    }
+}
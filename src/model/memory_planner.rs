@@ -0,0 +1,216 @@
+//! Sizing the inference session pool from the model's own memory footprint,
+//! instead of the fixed presets [`crate::validation::HardwarePreset`] picks
+//! [`crate::engine::jobs::SchedulerLimits::session_pool_size`] from today.
+//! Those presets are a reasonable default for the model this crate ships
+//! fixtures for, but they know nothing about a differently-sized or
+//! differently-quantized model — [`plan_session_pool`] estimates what
+//! actually fits: the model's weights (shared once across every session,
+//! not copied per session — this crate's sessions are
+//! [`crate::engine::worker_pool::WorkerPool`] child processes that each load
+//! their own copy of the weights today, but the *memory* accounting here
+//! still separates "paid once" from "paid per session" so a future
+//! shared-weights runtime doesn't need a second planner) plus each
+//! session's own KV cache at the requested context length, and refuses a
+//! configuration that can't fit even one session rather than returning a
+//! nonsensical pool size.
+
+/// How a model's weights are stored in memory, and therefore how many bytes
+/// each parameter costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantization {
+    Fp32,
+    Fp16,
+    Int8,
+    /// 4-bit weights, one byte per two parameters — this crate's own model
+    /// fixtures (e.g. `qwen2.5-0.5b-int4`, see [`crate::model::ModelLocator`]'s
+    /// module doc) are quantized this way.
+    Int4,
+}
+
+impl Quantization {
+    pub fn bytes_per_parameter(self) -> f64 {
+        match self {
+            Quantization::Fp32 => 4.0,
+            Quantization::Fp16 => 2.0,
+            Quantization::Int8 => 1.0,
+            Quantization::Int4 => 0.5,
+        }
+    }
+}
+
+/// The architecture dimensions [`plan_session_pool`] needs to estimate
+/// memory from — the same handful of numbers every `config.json` in the wild
+/// already reports (`hidden_size`, `num_hidden_layers`,
+/// `num_key_value_heads`, and a per-head dimension), plus the quantization
+/// [`crate::model::context::ModelConfig`] doesn't track today.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelMemoryProfile {
+    pub parameter_count: u64,
+    pub quantization: Quantization,
+    pub num_layers: usize,
+    pub num_key_value_heads: usize,
+    pub head_dim: usize,
+    /// Bytes per KV cache entry; usually matches `quantization`'s
+    /// `bytes_per_parameter` but kept separate since some runtimes keep the
+    /// KV cache in a higher precision than the weights (e.g. fp16 cache over
+    /// int4 weights) to avoid compounding quantization error during decode.
+    pub kv_cache_bytes_per_element: f64,
+}
+
+impl ModelMemoryProfile {
+    /// Total weight bytes, paid exactly once regardless of how many
+    /// sessions share them.
+    pub fn weights_bytes(&self) -> u64 {
+        (self.parameter_count as f64 * self.quantization.bytes_per_parameter()).ceil() as u64
+    }
+
+    /// KV cache bytes one session's cache grows by per token: two tensors
+    /// (key and value) per layer, each `num_key_value_heads * head_dim`
+    /// elements wide.
+    fn kv_cache_bytes_per_token(&self) -> f64 {
+        2.0 * self.num_layers as f64 * self.num_key_value_heads as f64 * self.head_dim as f64 * self.kv_cache_bytes_per_element
+    }
+
+    /// KV cache bytes one session needs to hold `context_tokens` of
+    /// history.
+    pub fn kv_cache_bytes_for_context(&self, context_tokens: usize) -> u64 {
+        (self.kv_cache_bytes_per_token() * context_tokens as f64).ceil() as u64
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum MemoryPlanError {
+    #[error(
+        "model weights alone need {weights_bytes} bytes, which already exceeds the \
+         {memory_budget_bytes} byte memory budget; no session pool fits regardless of context length"
+    )]
+    WeightsExceedBudget { weights_bytes: u64, memory_budget_bytes: u64 },
+    #[error(
+        "one session's KV cache at {context_tokens} tokens needs {kv_cache_bytes} bytes, which \
+         combined with {weights_bytes} bytes of weights exceeds the {memory_budget_bytes} byte \
+         memory budget; lower the context length or raise the budget"
+    )]
+    NoSessionFitsAtContext {
+        weights_bytes: u64,
+        kv_cache_bytes: u64,
+        memory_budget_bytes: u64,
+        context_tokens: usize,
+    },
+}
+
+/// What [`plan_session_pool`] computed: the weight and per-session costs it
+/// planned against, and the session count that fits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionPoolPlan {
+    pub weights_bytes: u64,
+    pub per_session_kv_cache_bytes: u64,
+    pub max_safe_sessions: usize,
+}
+
+/// Computes the largest [`SessionPoolPlan::max_safe_sessions`] that fits
+/// `profile`'s weights (paid once) plus that many sessions' worth of KV
+/// cache at `context_tokens` within `memory_budget_bytes`, or an error
+/// explaining precisely why nothing fits.
+pub fn plan_session_pool(
+    profile: &ModelMemoryProfile,
+    context_tokens: usize,
+    memory_budget_bytes: u64,
+) -> Result<SessionPoolPlan, MemoryPlanError> {
+    let weights_bytes = profile.weights_bytes();
+    if weights_bytes > memory_budget_bytes {
+        return Err(MemoryPlanError::WeightsExceedBudget { weights_bytes, memory_budget_bytes });
+    }
+
+    let remaining_for_sessions = memory_budget_bytes - weights_bytes;
+    let kv_cache_bytes = profile.kv_cache_bytes_for_context(context_tokens);
+
+    if kv_cache_bytes > remaining_for_sessions {
+        return Err(MemoryPlanError::NoSessionFitsAtContext {
+            weights_bytes,
+            kv_cache_bytes,
+            memory_budget_bytes,
+            context_tokens,
+        });
+    }
+
+    // `kv_cache_bytes` is `0` only for a zero-token context, which would
+    // make every session free and the division below meaningless; treat it
+    // as "one session is all a zero-length context ever needs".
+    let max_safe_sessions = remaining_for_sessions.checked_div(kv_cache_bytes).unwrap_or(1) as usize;
+
+    Ok(SessionPoolPlan { weights_bytes, per_session_kv_cache_bytes: kv_cache_bytes, max_safe_sessions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(parameter_count: u64, quantization: Quantization) -> ModelMemoryProfile {
+        ModelMemoryProfile {
+            parameter_count,
+            quantization,
+            num_layers: 24,
+            num_key_value_heads: 2,
+            head_dim: 64,
+            kv_cache_bytes_per_element: 2.0,
+        }
+    }
+
+    #[test]
+    fn int4_weights_cost_a_quarter_of_fp16_for_the_same_parameter_count() {
+        let fp16 = profile(500_000_000, Quantization::Fp16);
+        let int4 = profile(500_000_000, Quantization::Int4);
+        assert_eq!(fp16.weights_bytes(), 1_000_000_000);
+        assert_eq!(int4.weights_bytes(), 250_000_000);
+    }
+
+    #[test]
+    fn kv_cache_grows_linearly_with_context_length() {
+        let model = profile(500_000_000, Quantization::Int4);
+        let short = model.kv_cache_bytes_for_context(1024);
+        let long = model.kv_cache_bytes_for_context(4096);
+        assert_eq!(long, short * 4);
+    }
+
+    #[test]
+    fn weights_that_exceed_the_budget_refuse_with_a_precise_explanation() {
+        let model = profile(500_000_000, Quantization::Fp32);
+        let err = plan_session_pool(&model, 2048, 1_000_000_000).unwrap_err();
+        assert!(matches!(err, MemoryPlanError::WeightsExceedBudget { .. }));
+        assert!(err.to_string().contains("2000000000"));
+    }
+
+    #[test]
+    fn a_context_length_too_long_for_even_one_session_is_refused() {
+        let model = profile(500_000_000, Quantization::Int4);
+        let weights = model.weights_bytes();
+        let err = plan_session_pool(&model, 1_000_000, weights + 1).unwrap_err();
+        assert!(matches!(err, MemoryPlanError::NoSessionFitsAtContext { .. }));
+    }
+
+    #[test]
+    fn max_safe_sessions_fits_as_many_sessions_as_the_remaining_budget_allows() {
+        let model = profile(500_000_000, Quantization::Int4);
+        let weights = model.weights_bytes();
+        let per_session = model.kv_cache_bytes_for_context(2048);
+        let budget = weights + per_session * 3;
+
+        let plan = plan_session_pool(&model, 2048, budget).unwrap();
+
+        assert_eq!(plan.weights_bytes, weights);
+        assert_eq!(plan.per_session_kv_cache_bytes, per_session);
+        assert_eq!(plan.max_safe_sessions, 3);
+    }
+
+    #[test]
+    fn leftover_budget_smaller_than_one_more_session_is_not_rounded_up() {
+        let model = profile(500_000_000, Quantization::Int4);
+        let weights = model.weights_bytes();
+        let per_session = model.kv_cache_bytes_for_context(2048);
+        let budget = weights + per_session * 2 + per_session / 2;
+
+        let plan = plan_session_pool(&model, 2048, budget).unwrap();
+
+        assert_eq!(plan.max_safe_sessions, 2);
+    }
+}
@@ -0,0 +1,119 @@
+//! Resolution of on-disk model assets (weights, tokenizer files, configs).
+//!
+//! Earlier builds hardcoded paths like `/Users/amuldotexe/...` or assumed
+//! `models/qwen2.5-0.5b-int4` relative to the current working directory. Neither
+//! survives being checked out on another machine. [`ModelLocator`] replaces both
+//! with an explicit search order and, on failure, an error that lists every
+//! location it actually tried.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub mod card;
+pub mod context;
+pub mod fixture;
+pub mod memory_planner;
+
+/// Environment variable used to point at a directory containing model assets.
+pub const MODEL_DIR_ENV_VAR: &str = "TRANSFIGURATION_MODEL_DIR";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelAssetError {
+    #[error(
+        "could not locate model asset `{asset}`; tried: {}",
+        .tried.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    NotFound { asset: String, tried: Vec<PathBuf> },
+}
+
+/// Resolves named model assets (a weights file, a tokenizer, a config blob)
+/// to absolute paths, searching explicit configuration first and falling back
+/// to environment and platform conventions.
+#[derive(Debug, Clone, Default)]
+pub struct ModelLocator {
+    explicit_dir: Option<PathBuf>,
+}
+
+impl ModelLocator {
+    /// Creates a locator that consults only the automatic search order
+    /// (env var, then XDG data dir, then the legacy `./models` fallback).
+    pub fn from_environment() -> Self {
+        ModelLocator { explicit_dir: None }
+    }
+
+    /// Creates a locator that prefers `dir` above all other search locations,
+    /// for callers that pass `--model-dir` explicitly on the CLI.
+    pub fn with_explicit_directory(dir: impl Into<PathBuf>) -> Self {
+        ModelLocator {
+            explicit_dir: Some(dir.into()),
+        }
+    }
+
+    fn candidate_directories(&self) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        if let Some(dir) = &self.explicit_dir {
+            candidates.push(dir.clone());
+        }
+        if let Ok(env_dir) = env::var(MODEL_DIR_ENV_VAR) {
+            candidates.push(PathBuf::from(env_dir));
+        }
+        if let Some(data_dir) = dirs::data_dir() {
+            candidates.push(data_dir.join("transfiguration").join("models"));
+        }
+        // Legacy fallback: a `models/` directory relative to the current
+        // working directory, kept only so existing checkouts keep working.
+        candidates.push(PathBuf::from("models"));
+        candidates
+    }
+
+    /// Resolves `asset_name` (e.g. `"qwen2.5-0.5b-int4"`) to an absolute path
+    /// that exists on disk, or an error listing every directory that was tried.
+    pub fn resolve_asset_path(&self, asset_name: &str) -> Result<PathBuf, ModelAssetError> {
+        let mut tried = Vec::new();
+        for dir in self.candidate_directories() {
+            let candidate = dir.join(asset_name);
+            if candidate.exists() {
+                return Ok(candidate);
+            }
+            tried.push(candidate);
+        }
+        Err(ModelAssetError::NotFound {
+            asset: asset_name.to_string(),
+            tried,
+        })
+    }
+}
+
+/// Convenience for call sites that already have a known-good directory and
+/// just want to join an asset name onto it without going through discovery.
+pub fn join_asset_under_directory(dir: &Path, asset_name: &str) -> PathBuf {
+    dir.join(asset_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn explicit_directory_is_preferred_over_env() {
+        let explicit = std::env::temp_dir().join("transfiguration-model-explicit");
+        fs::create_dir_all(&explicit).unwrap();
+        fs::write(explicit.join("weights.bin"), b"explicit").unwrap();
+
+        let locator = ModelLocator::with_explicit_directory(&explicit);
+        let resolved = locator.resolve_asset_path("weights.bin").unwrap();
+        assert_eq!(resolved, explicit.join("weights.bin"));
+
+        fs::remove_dir_all(&explicit).unwrap();
+    }
+
+    #[test]
+    fn missing_asset_reports_every_tried_location() {
+        let locator = ModelLocator::with_explicit_directory("/nonexistent-transfiguration-dir");
+        let err = locator.resolve_asset_path("missing.bin").unwrap_err();
+        let ModelAssetError::NotFound { tried, .. } = &err;
+        assert!(tried.len() >= 2);
+        assert!(err.to_string().contains("missing.bin"));
+    }
+}
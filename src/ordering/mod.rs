@@ -0,0 +1,209 @@
+//! Guaranteed per-file output ordering.
+//!
+//! Summarization results complete in whatever order agents finish them,
+//! which breaks any downstream consumer that assumes results for a file
+//! arrive in source order. [`OrderedAggregator`] buffers out-of-order
+//! arrivals and only releases them once every earlier sequence number for
+//! that file has been seen, so callers never have to re-sort afterwards.
+//! Buffering is bounded per file; once the in-memory buffer is full,
+//! further out-of-order arrivals spill to a JSONL file on disk instead of
+//! growing memory unboundedly.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::sinks::SinkRecord;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrderingError {
+    #[error("io error in ordered aggregator spill file: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to (de)serialize spilled record: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+struct FileState {
+    next_expected_sequence: usize,
+    pending: BTreeMap<usize, SinkRecord>,
+    spill_index: BTreeMap<usize, u64>,
+    spill_path: Option<PathBuf>,
+}
+
+impl FileState {
+    fn new() -> Self {
+        FileState {
+            next_expected_sequence: 0,
+            pending: BTreeMap::new(),
+            spill_index: BTreeMap::new(),
+            spill_path: None,
+        }
+    }
+}
+
+/// Buffers results per file and releases them strictly in sequence order.
+pub struct OrderedAggregator {
+    buffer_capacity_per_file: usize,
+    spill_dir: PathBuf,
+    files: HashMap<PathBuf, FileState>,
+}
+
+impl OrderedAggregator {
+    /// `buffer_capacity_per_file` bounds how many out-of-order records each
+    /// file may hold in memory before further arrivals spill to
+    /// `spill_dir` (created on demand).
+    pub fn new(buffer_capacity_per_file: usize, spill_dir: impl Into<PathBuf>) -> Self {
+        OrderedAggregator {
+            buffer_capacity_per_file: buffer_capacity_per_file.max(1),
+            spill_dir: spill_dir.into(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Inserts `record` as sequence number `sequence` within `file`'s
+    /// stream, returning every record now ready for release, in order.
+    /// A single call can return more than one record if it fills a gap
+    /// that unblocks a run of already-buffered later sequences.
+    pub fn push(&mut self, file: &Path, sequence: usize, record: SinkRecord) -> Result<Vec<SinkRecord>, OrderingError> {
+        let state = self.files.entry(file.to_path_buf()).or_insert_with(FileState::new);
+
+        if sequence < state.next_expected_sequence {
+            return Ok(Vec::new());
+        }
+
+        if state.pending.len() >= self.buffer_capacity_per_file && !state.pending.contains_key(&sequence) {
+            spill_record(&self.spill_dir, file, state, sequence, &record)?;
+        } else {
+            state.pending.insert(sequence, record);
+        }
+
+        let mut ready = Vec::new();
+        loop {
+            if let Some(record) = state.pending.remove(&state.next_expected_sequence) {
+                ready.push(record);
+                state.next_expected_sequence += 1;
+                continue;
+            }
+            if let Some(&offset) = state.spill_index.get(&state.next_expected_sequence) {
+                let record = read_spilled_record(state, offset)?;
+                state.spill_index.remove(&state.next_expected_sequence);
+                ready.push(record);
+                state.next_expected_sequence += 1;
+                continue;
+            }
+            break;
+        }
+
+        Ok(ready)
+    }
+}
+
+fn spill_path_for(spill_dir: &Path, file: &Path) -> Result<PathBuf, OrderingError> {
+    std::fs::create_dir_all(spill_dir)?;
+    let mut hasher = DefaultHasher::new();
+    file.hash(&mut hasher);
+    Ok(spill_dir.join(format!("{:016x}.jsonl", hasher.finish())))
+}
+
+fn spill_record(
+    spill_dir: &Path,
+    file: &Path,
+    state: &mut FileState,
+    sequence: usize,
+    record: &SinkRecord,
+) -> Result<(), OrderingError> {
+    let path = match &state.spill_path {
+        Some(path) => path.clone(),
+        None => {
+            let path = spill_path_for(spill_dir, file)?;
+            state.spill_path = Some(path.clone());
+            path
+        }
+    };
+
+    let mut spill_file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let offset = spill_file.metadata()?.len();
+    writeln!(spill_file, "{}", serde_json::to_string(record)?)?;
+    state.spill_index.insert(sequence, offset);
+    Ok(())
+}
+
+fn read_spilled_record(state: &FileState, offset: u64) -> Result<SinkRecord, OrderingError> {
+    let path = state
+        .spill_path
+        .as_ref()
+        .expect("spill_index entry implies spill_path was set");
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line)?;
+    Ok(serde_json::from_str(line.trim_end())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+
+    fn record(text: &str) -> SinkRecord {
+        SinkRecord {
+            run_id: "run-1".into(),
+            chunk_id: ChunkId(0),
+            source_path: PathBuf::from("a.rs"),
+            summary_text: text.to_string(),
+            source_excerpt: None,
+            owners: Vec::new(),
+        }
+    }
+
+    fn scratch_spill_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("transfiguration-ordering-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn in_order_arrivals_release_immediately() {
+        let mut aggregator = OrderedAggregator::new(4, scratch_spill_dir("in-order"));
+        let file = Path::new("a.rs");
+
+        let ready = aggregator.push(file, 0, record("first")).unwrap();
+        assert_eq!(ready.len(), 1);
+        let ready = aggregator.push(file, 1, record("second")).unwrap();
+        assert_eq!(ready.len(), 1);
+    }
+
+    #[test]
+    fn out_of_order_arrivals_release_in_order_once_gap_fills() {
+        let mut aggregator = OrderedAggregator::new(4, scratch_spill_dir("gap-fill"));
+        let file = Path::new("a.rs");
+
+        assert!(aggregator.push(file, 2, record("third")).unwrap().is_empty());
+        assert!(aggregator.push(file, 1, record("second")).unwrap().is_empty());
+
+        let ready = aggregator.push(file, 0, record("first")).unwrap();
+        let texts: Vec<&str> = ready.iter().map(|r| r.summary_text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn buffer_overflow_spills_to_disk_and_still_releases_in_order() {
+        let spill_dir = scratch_spill_dir("spill");
+        let mut aggregator = OrderedAggregator::new(1, spill_dir.clone());
+        let file = Path::new("a.rs");
+
+        // Sequence 2 arrives while the buffer (capacity 1) already holds
+        // sequence 1, forcing sequence 2 to spill to disk.
+        assert!(aggregator.push(file, 1, record("second")).unwrap().is_empty());
+        assert!(aggregator.push(file, 2, record("third")).unwrap().is_empty());
+
+        let ready = aggregator.push(file, 0, record("first")).unwrap();
+        let texts: Vec<&str> = ready.iter().map(|r| r.summary_text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "second", "third"]);
+
+        std::fs::remove_dir_all(&spill_dir).unwrap();
+    }
+}
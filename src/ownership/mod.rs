@@ -0,0 +1,186 @@
+//! CODEOWNERS-style path ownership, so a report can be grouped by the team
+//! that owns each file instead of dumping every result into one flat list.
+//!
+//! [`OwnershipMap`] parses the same line format GitHub's `CODEOWNERS` file
+//! uses — a glob pattern followed by one or more owner tokens — reusing
+//! [`crate::package::glob_match`]'s double-star/single-star/`?` matcher
+//! rather than a second implementation of the same thing. Matching follows
+//! CODEOWNERS' own "last matching rule wins" rule, not "every matching rule
+//! applies": a later, more specific pattern overriding an earlier, broader
+//! one is exactly the behavior a real CODEOWNERS file relies on.
+//!
+//! An optional custom ownership file uses the identical format and is
+//! simply appended after the CODEOWNERS rules, so (by the same last-wins
+//! rule) it overrides CODEOWNERS wherever both files have a rule matching
+//! the same path, without needing a separate merge strategy.
+
+use std::fs;
+use std::path::Path;
+
+use crate::chunk::Chunk;
+use crate::package::glob_match;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OwnershipError {
+    #[error("failed to read ownership file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The metadata key [`attach_owner_metadata`] writes onto [`Chunk::metadata`].
+pub const OWNER_METADATA_KEY: &str = "owner";
+
+struct OwnershipRule {
+    pattern: String,
+    owners: Vec<String>,
+}
+
+/// A parsed CODEOWNERS file, optionally layered with a custom override
+/// file — see the module docs for why layering is just "append the custom
+/// rules after".
+#[derive(Default)]
+pub struct OwnershipMap {
+    rules: Vec<OwnershipRule>,
+}
+
+impl OwnershipMap {
+    /// Loads `codeowners_path` and, if given, `custom_path` (in that
+    /// order, so `custom_path`'s rules take precedence — see the module
+    /// docs). Either path may be absent on disk; a missing file
+    /// contributes no rules rather than erroring, since not every repo has
+    /// a CODEOWNERS file.
+    pub fn load(codeowners_path: Option<&Path>, custom_path: Option<&Path>) -> Result<Self, OwnershipError> {
+        let mut rules = Vec::new();
+        if let Some(path) = codeowners_path {
+            rules.extend(parse_rules(&read_if_present(path)?));
+        }
+        if let Some(path) = custom_path {
+            rules.extend(parse_rules(&read_if_present(path)?));
+        }
+        Ok(OwnershipMap { rules })
+    }
+
+    /// Parses `content` directly, for a caller that already has the
+    /// CODEOWNERS text in hand (e.g. read from a VCS blob rather than a
+    /// local file).
+    pub fn parse(content: &str) -> Self {
+        OwnershipMap { rules: parse_rules(content) }
+    }
+
+    /// The owners of `path`, per the last rule (across both files, in load
+    /// order) whose pattern matches — an empty slice if nothing matches.
+    pub fn owners_for(&self, path: &Path) -> &[String] {
+        let path = path.to_string_lossy();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| glob_match(&rule.pattern, &path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+fn read_if_present(path: &Path) -> Result<String, OwnershipError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn parse_rules(content: &str) -> Vec<OwnershipRule> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let pattern = tokens.next()?.to_string();
+            let owners: Vec<String> = tokens.map(str::to_string).collect();
+            if owners.is_empty() {
+                None
+            } else {
+                Some(OwnershipRule { pattern, owners })
+            }
+        })
+        .collect()
+}
+
+/// Writes each chunk's owners (per `ownership`) into
+/// [`Chunk::metadata`][OWNER_METADATA_KEY] as a comma-joined string, for
+/// chunks whose path has at least one matching rule. A chunk with no
+/// matching rule is left untouched rather than given an empty entry, so
+/// "has no `owner` key" and "is unowned" mean the same thing to a reader of
+/// `metadata`.
+pub fn attach_owner_metadata(chunks: &mut [Chunk], ownership: &OwnershipMap) {
+    for chunk in chunks {
+        let owners = ownership.owners_for(&chunk.source_path);
+        if !owners.is_empty() {
+            chunk.metadata.insert(OWNER_METADATA_KEY.to_string(), owners.join(","));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+
+    #[test]
+    fn a_pattern_matching_the_path_reports_its_owners() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team\n");
+        assert_eq!(ownership.owners_for(Path::new("src/engine/jobs.rs")), &["@platform-team".to_string()]);
+    }
+
+    #[test]
+    fn a_path_with_no_matching_rule_has_no_owners() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team\n");
+        assert!(ownership.owners_for(Path::new("src/report/mod.rs")).is_empty());
+    }
+
+    #[test]
+    fn a_later_more_specific_rule_overrides_an_earlier_broader_one() {
+        let ownership = OwnershipMap::parse("src/**  @platform-team\nsrc/report/**  @docs-team\n");
+        assert_eq!(ownership.owners_for(Path::new("src/report/mod.rs")), &["@docs-team".to_string()]);
+        assert_eq!(ownership.owners_for(Path::new("src/engine/jobs.rs")), &["@platform-team".to_string()]);
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let ownership = OwnershipMap::parse("# this is a comment\n\nsrc/**  @platform-team\n");
+        assert_eq!(ownership.owners_for(Path::new("src/lib.rs")), &["@platform-team".to_string()]);
+    }
+
+    #[test]
+    fn a_custom_file_overrides_codeowners_for_the_same_path() {
+        let codeowners_path = std::env::temp_dir().join("transfiguration-ownership-codeowners.txt");
+        let custom_path = std::env::temp_dir().join("transfiguration-ownership-custom.txt");
+        fs::write(&codeowners_path, "src/**  @platform-team\n").unwrap();
+        fs::write(&custom_path, "src/engine/**  @inference-team\n").unwrap();
+
+        let ownership = OwnershipMap::load(Some(&codeowners_path), Some(&custom_path)).unwrap();
+        assert_eq!(ownership.owners_for(Path::new("src/engine/jobs.rs")), &["@inference-team".to_string()]);
+        assert_eq!(ownership.owners_for(Path::new("src/report/mod.rs")), &["@platform-team".to_string()]);
+    }
+
+    #[test]
+    fn a_missing_codeowners_file_contributes_no_rules() {
+        let missing = std::env::temp_dir().join("transfiguration-ownership-does-not-exist.txt");
+        let _ = fs::remove_file(&missing);
+        let ownership = OwnershipMap::load(Some(&missing), None).unwrap();
+        assert!(ownership.owners_for(Path::new("src/lib.rs")).is_empty());
+    }
+
+    #[test]
+    fn attach_owner_metadata_sets_the_owner_key_only_for_owned_chunks() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team @other-team\n");
+        let mut chunks = vec![
+            Chunk::new(ChunkId(1), "src/engine/jobs.rs", "fn a() {}"),
+            Chunk::new(ChunkId(2), "src/report/mod.rs", "fn b() {}"),
+        ];
+
+        attach_owner_metadata(&mut chunks, &ownership);
+
+        assert_eq!(chunks[0].metadata.get(OWNER_METADATA_KEY), Some(&"@platform-team,@other-team".to_string()));
+        assert_eq!(chunks[1].metadata.get(OWNER_METADATA_KEY), None);
+    }
+}
@@ -0,0 +1,720 @@
+//! Ties the unpacker, repository fingerprinting, and the summarization
+//! engine into the one-call flow the request asks for: take a source
+//! package, extract it, find its source files, summarize them, and hand
+//! back a package-level [`RunArtifacts`] report.
+//!
+//! This crate has no argv-parsing CLI yet — `src/main.rs` is still a
+//! placeholder — so there's no "CLI flow" to add to; [`run_package_pipeline`]
+//! is the function a future subcommand would call. There's also no
+//! `.deb`-specific handling anywhere in this crate: a `.deb` is an `ar`
+//! archive of an inner `data.tar.*`, and neither `ar` nor any Debian-package
+//! crate is a dependency here. The closest honest fit is what [`crate::unpack`]
+//! already ships — `.tar`/`.tar.gz` — so this pipeline accepts any archive
+//! [`ArchiveFormatRegistry`] recognizes; unpacking a `.deb` specifically
+//! would mean extracting its inner `data.tar.*` first by some other means
+//! and pointing this pipeline at that.
+//!
+//! "Detects source files" is done the same way [`crate::fingerprint`]
+//! already tours a tree: walk it, skip the directories nothing ever wants
+//! chunked ([`crate::fingerprint::ALWAYS_SKIPPED_DIRS`]), and read each
+//! remaining file as UTF-8. A file that isn't valid UTF-8 is assumed to be a
+//! binary asset and silently skipped rather than failing the whole run.
+//!
+//! [`process_directory`] is the same pipeline for a tree that's already
+//! unpacked on disk: no archive, a [`DirectoryProcessingPolicy`] in place of
+//! [`crate::unpack::UnpackPolicy`], and — finally giving
+//! [`ChunkingProfile::skip_globs`] a consumer, since nothing matched against
+//! it before this — glob-filtered and optionally `.gitignore`-respecting
+//! file discovery via a hand-rolled [`glob_match`] rather than a new `glob`
+//! dependency.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::chunk::{Chunk, ChunkId};
+use crate::engine::heuristic::HeuristicBackend;
+use crate::engine::{EngineError, SummaryRun};
+use crate::fingerprint::{self, ChunkingProfile, ChunkingStrategy, FingerprintError};
+use crate::report::{FileSkipReason, RunArtifacts, SkippedFile};
+use crate::unpack::{ArchiveError, ArchiveFormatRegistry, UnpackPolicy};
+use crate::validation::Language;
+
+/// Above this many bytes on one line, [`discover_source_files_filtered`]
+/// skips the file with [`FileSkipReason::LineTooLong`] instead of handing it
+/// to the chunker — chosen well past any line a human wrote by hand, to
+/// catch the things that actually produce lines this long: a minified
+/// bundle or a lockfile with its whole dependency graph serialized onto one
+/// line, both pathological input for line-based chunking and prompt sizing.
+const DEFAULT_MAX_LINE_LENGTH: usize = 200_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PackagePipelineError {
+    #[error("failed to discover source files: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+    #[error(transparent)]
+    Fingerprint(#[from] FingerprintError),
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+}
+
+/// Extracts `archive_path` into `workspace_root`, fingerprints the result to
+/// pick a [`ChunkingProfile`], summarizes every discovered source file with
+/// [`HeuristicBackend`] (the only zero-dependency, always-available
+/// [`crate::engine::InferenceBackend`] this crate has), and returns the
+/// combined [`RunArtifacts`] for the whole package, keyed by each file's
+/// path relative to `workspace_root`.
+pub fn run_package_pipeline(
+    archive_path: &Path,
+    workspace_root: &Path,
+    policy: UnpackPolicy,
+) -> Result<RunArtifacts, PackagePipelineError> {
+    run_package_pipeline_with_language(archive_path, workspace_root, policy, Language::default())
+}
+
+/// Like [`run_package_pipeline`], but asks [`HeuristicBackend`] — or rather,
+/// asks the prompt it's handed — to produce every summary in `language`
+/// instead of the unsteered default (English). [`HeuristicBackend`] doesn't
+/// call a real model, so it can't honor the directive itself, but the
+/// directive still reaches its input and `language` is still recorded on
+/// [`RunArtifacts::language`] and on every [`Summary`](crate::engine::Summary),
+/// the same as it would be for any other [`crate::engine::InferenceBackend`].
+pub fn run_package_pipeline_with_language(
+    archive_path: &Path,
+    workspace_root: &Path,
+    policy: UnpackPolicy,
+    language: Language,
+) -> Result<RunArtifacts, PackagePipelineError> {
+    let registry = ArchiveFormatRegistry::with_builtin_formats();
+    crate::unpack::unpack_recursively(&registry, archive_path, workspace_root, policy)?;
+
+    let fingerprint = fingerprint::fingerprint_repository(workspace_root)?;
+    let chunking_profile = fingerprint::select_chunking_profile(&fingerprint);
+    let (source_files, skipped_files) = discover_source_files(workspace_root)?;
+
+    let chunks = build_chunks(&source_files, &chunking_profile);
+    let chunk_paths: Vec<(ChunkId, PathBuf)> = chunks.iter().map(|chunk| (chunk.id, chunk.source_path.clone())).collect();
+
+    let run = SummaryRun::summarize_all_chunks_with_language(HeuristicBackend, chunks, language)?;
+
+    let mut summaries: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for (chunk_id, path) in chunk_paths {
+        let Some(summary) = run.latest_summary_for(chunk_id) else {
+            continue;
+        };
+        summaries
+            .entry(path)
+            .and_modify(|existing| {
+                existing.push('\n');
+                existing.push_str(&summary.text);
+            })
+            .or_insert_with(|| summary.text.clone());
+    }
+
+    Ok(RunArtifacts {
+        summaries,
+        chunking_profile: Some(chunking_profile),
+        model_license: None,
+        critical_path: None,
+        run_metrics: None,
+        file_fairness: Vec::new(),
+        skipped_files,
+        language,
+    })
+}
+
+/// Files found by a discovery walk, paired with the files it skipped —
+/// what [`discover_source_files`]/[`discover_source_files_filtered`] return.
+type DiscoveredFiles = (Vec<(PathBuf, String)>, Vec<SkippedFile>);
+
+/// Walks `root`, skipping [`fingerprint::ALWAYS_SKIPPED_DIRS`], and reads
+/// every remaining file as UTF-8 text, paired with its path relative to
+/// `root`. A file this crate can't or shouldn't chunk (binary, empty, or a
+/// pathologically long line — see [`FileSkipReason`]) is skipped and
+/// reported rather than erroring the whole walk out over it.
+fn discover_source_files(root: &Path) -> Result<DiscoveredFiles, io::Error> {
+    discover_source_files_filtered(root, &[], &[], DEFAULT_MAX_LINE_LENGTH)
+}
+
+/// Like [`discover_source_files`], but a file is only kept when its path
+/// relative to `root` matches at least one of `include_globs` (or
+/// `include_globs` is empty, meaning "everything"), matches none of
+/// `exclude_globs` (patterns matched with [`glob_match`]), and passes the
+/// preflight checks [`FileSkipReason`] documents — the last of those gated
+/// on `max_line_length`.
+fn discover_source_files_filtered(
+    root: &Path,
+    include_globs: &[String],
+    exclude_globs: &[String],
+    max_line_length: usize,
+) -> Result<DiscoveredFiles, io::Error> {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    walk_for_source_files(root, root, include_globs, exclude_globs, max_line_length, &mut files, &mut skipped)?;
+    Ok((files, skipped))
+}
+
+fn walk_for_source_files(
+    root: &Path,
+    dir: &Path,
+    include_globs: &[String],
+    exclude_globs: &[String],
+    max_line_length: usize,
+    files: &mut Vec<(PathBuf, String)>,
+    skipped: &mut Vec<SkippedFile>,
+) -> Result<(), io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if path.is_dir() {
+            if fingerprint::ALWAYS_SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                continue;
+            }
+            walk_for_source_files(root, &path, include_globs, exclude_globs, max_line_length, files, skipped)?;
+            continue;
+        }
+
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        let relative_slashes = relative.to_string_lossy().replace('\\', "/");
+
+        if exclude_globs.iter().any(|pattern| glob_match(pattern, &relative_slashes)) {
+            continue;
+        }
+        if !include_globs.is_empty() && !include_globs.iter().any(|pattern| glob_match(pattern, &relative_slashes)) {
+            continue;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) if content.trim().is_empty() => {
+                skipped.push(SkippedFile { path: relative, reason: FileSkipReason::Empty });
+            }
+            Ok(content) => {
+                let length = longest_line_length(&content);
+                if length > max_line_length {
+                    skipped.push(SkippedFile {
+                        path: relative,
+                        reason: FileSkipReason::LineTooLong { length, max_line_length },
+                    });
+                } else {
+                    files.push((relative, content));
+                }
+            }
+            Err(_) => {
+                skipped.push(SkippedFile { path: relative, reason: FileSkipReason::Binary });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The longest line in `content`, in bytes, found with the same
+/// SIMD-accelerated newline scan [`crate::chunk::LineIndex`] uses rather
+/// than materializing a `Vec<&str>` of every line.
+fn longest_line_length(content: &str) -> usize {
+    let bytes = content.as_bytes();
+    let mut max_len = 0;
+    let mut line_start = 0;
+    for newline_pos in memchr::memchr_iter(b'\n', bytes) {
+        max_len = max_len.max(newline_pos - line_start);
+        line_start = newline_pos + 1;
+    }
+    max_len.max(bytes.len() - line_start)
+}
+
+/// Matches a `/`-separated glob `pattern` (`*` within one path segment,
+/// `**` across any number of segments including zero, `?` for a single
+/// character) against a `/`-separated relative path. Hand-rolled rather
+/// than pulling in a `glob`/`ignore` dependency — the same call this crate
+/// already made for [`crate::testgen`]'s `SplitMix64` and [`crate::chunk`]'s
+/// FNV1a64: a small deterministic algorithm is cheaper to own than a crate.
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern_segments: Vec<&str> = pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.split('/').collect();
+    glob_match_segments(&pattern_segments, &path_segments)
+}
+
+fn glob_match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => {
+            glob_match_segments(&pattern[1..], path) || (!path.is_empty() && glob_match_segments(pattern, &path[1..]))
+        }
+        Some(&segment_pattern) => {
+            !path.is_empty() && glob_match_segment(segment_pattern, path[0]) && glob_match_segments(&pattern[1..], &path[1..])
+        }
+    }
+}
+
+/// Matches a single path segment (no `/`) against a pattern segment that may
+/// contain `*` (any run of characters, possibly empty) and `?` (exactly one
+/// character).
+fn glob_match_segment(pattern: &str, segment: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let segment: Vec<char> = segment.chars().collect();
+    let mut dp = vec![vec![false; segment.len() + 1]; pattern.len() + 1];
+    dp[0][0] = true;
+    for (p_index, &p_char) in pattern.iter().enumerate() {
+        if p_char == '*' {
+            dp[p_index + 1][0] = dp[p_index][0];
+        }
+        for s_index in 0..segment.len() {
+            dp[p_index + 1][s_index + 1] = match p_char {
+                '*' => dp[p_index + 1][s_index] || dp[p_index][s_index + 1],
+                '?' => dp[p_index][s_index],
+                literal => dp[p_index][s_index] && literal == segment[s_index],
+            };
+        }
+    }
+    dp[pattern.len()][segment.len()]
+}
+
+/// Reads `root/.gitignore`, if present, into exclude globs for
+/// [`discover_source_files_filtered`]. Each non-blank, non-comment line
+/// becomes a glob matching that name at any depth (or, for a `/`-anchored
+/// line, only at `root`'s own top level), plus the same pattern with `/**`
+/// appended so a line naming a directory also excludes everything under it.
+/// Negated patterns (a leading `!`, meaning "un-ignore") have no real
+/// `.gitignore` precedent to borrow a matcher from here, so they're skipped
+/// rather than silently mismatched — the common case of plain ignore lines
+/// is handled faithfully, partial `.gitignore` support is the honest
+/// tradeoff for not adding a dependency just for this.
+fn read_gitignore_globs(root: &Path) -> Result<Vec<String>, io::Error> {
+    let gitignore_path = root.join(".gitignore");
+    let content = match fs::read_to_string(&gitignore_path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut globs = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+
+        let anchored = line.starts_with('/');
+        let pattern = line.trim_start_matches('/').trim_end_matches('/');
+        if pattern.is_empty() {
+            continue;
+        }
+
+        if anchored {
+            globs.push(pattern.to_string());
+            globs.push(format!("{pattern}/**"));
+        } else {
+            globs.push(format!("**/{pattern}"));
+            globs.push(format!("**/{pattern}/**"));
+        }
+    }
+    Ok(globs)
+}
+
+/// How [`process_directory`] should decide which files under a tree are
+/// worth chunking, on top of [`fingerprint::ALWAYS_SKIPPED_DIRS`] and
+/// [`ChunkingProfile::skip_globs`] (both always applied).
+#[derive(Debug, Clone)]
+pub struct DirectoryProcessingPolicy {
+    /// Only files matching at least one of these globs are processed. Empty
+    /// means "every file", matching [`run_package_pipeline`]'s behavior.
+    pub include_globs: Vec<String>,
+    /// Whether a `.gitignore` at the root of the walked tree should be read
+    /// and its patterns excluded — see [`read_gitignore_globs`] for the
+    /// (partial — no negation) support this crate gives `.gitignore` syntax.
+    pub respect_gitignore: bool,
+    /// Above this many bytes on one line, a file is skipped with
+    /// [`FileSkipReason::LineTooLong`] instead of being chunked — see
+    /// [`DEFAULT_MAX_LINE_LENGTH`] for the default and why it's that large.
+    pub max_line_length: usize,
+}
+
+impl Default for DirectoryProcessingPolicy {
+    fn default() -> Self {
+        DirectoryProcessingPolicy {
+            include_globs: Vec::new(),
+            respect_gitignore: true,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+        }
+    }
+}
+
+/// Like [`run_package_pipeline`], but for a source tree that's already on
+/// disk rather than packed into an archive — no [`crate::unpack`] step, and
+/// with [`DirectoryProcessingPolicy`] controlling which files under `root`
+/// are actually summarized. This is the entry point for "just point me at a
+/// checked-out repo and summarize it", the same shape
+/// [`run_package_pipeline`] gives a caller that only has an archive.
+pub fn process_directory(root: &Path, policy: DirectoryProcessingPolicy) -> Result<RunArtifacts, PackagePipelineError> {
+    process_directory_with_language(root, policy, Language::default())
+}
+
+/// Like [`process_directory`], but in `language` — see
+/// [`run_package_pipeline_with_language`] for what that does and doesn't
+/// mean for [`HeuristicBackend`].
+pub fn process_directory_with_language(
+    root: &Path,
+    policy: DirectoryProcessingPolicy,
+    language: Language,
+) -> Result<RunArtifacts, PackagePipelineError> {
+    let fingerprint = fingerprint::fingerprint_repository(root)?;
+    let chunking_profile = fingerprint::select_chunking_profile(&fingerprint);
+
+    let mut exclude_globs = chunking_profile.skip_globs.clone();
+    if policy.respect_gitignore {
+        exclude_globs.extend(read_gitignore_globs(root)?);
+    }
+
+    let (source_files, skipped_files) =
+        discover_source_files_filtered(root, &policy.include_globs, &exclude_globs, policy.max_line_length)?;
+    let chunks = build_chunks(&source_files, &chunking_profile);
+    let chunk_paths: Vec<(ChunkId, PathBuf)> = chunks.iter().map(|chunk| (chunk.id, chunk.source_path.clone())).collect();
+
+    let run = SummaryRun::summarize_all_chunks_with_language(HeuristicBackend, chunks, language)?;
+
+    let mut summaries: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for (chunk_id, path) in chunk_paths {
+        let Some(summary) = run.latest_summary_for(chunk_id) else {
+            continue;
+        };
+        summaries
+            .entry(path)
+            .and_modify(|existing| {
+                existing.push('\n');
+                existing.push_str(&summary.text);
+            })
+            .or_insert_with(|| summary.text.clone());
+    }
+
+    Ok(RunArtifacts {
+        summaries,
+        chunking_profile: Some(chunking_profile),
+        model_license: None,
+        critical_path: None,
+        run_metrics: None,
+        file_fairness: Vec::new(),
+        skipped_files,
+        language,
+    })
+}
+
+/// Builds one [`Chunk`] per file under [`ChunkingStrategy::WholeFile`], one
+/// chunk per non-overlapping line window under
+/// [`ChunkingStrategy::LineWindow`], or one chunk per Rust item under
+/// [`ChunkingStrategy::SyntaxAware`] — the strategies
+/// [`fingerprint::select_chunking_profile`] and a caller who opts in
+/// directly choose between.
+///
+/// `LineWindow` delegates to [`Chunk::chunk_by_line_window`], which scans a
+/// file's newlines once no matter how many windows it's split into, rather
+/// than calling [`Chunk::from_line_span`] once per window (which would
+/// rescan the whole file from scratch every time). `SyntaxAware` delegates
+/// to [`Chunk::chunk_by_rust_item_boundaries`] for a file with a `.rs`
+/// extension, and falls back to `LineWindow`'s own
+/// `fallback_window_lines`-sized windows for anything else, since item
+/// boundary detection there only understands Rust.
+fn build_chunks(files: &[(PathBuf, String)], profile: &ChunkingProfile) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut next_id = 0u64;
+
+    for (path, content) in files {
+        match profile.strategy {
+            ChunkingStrategy::WholeFile => {
+                chunks.push(Chunk::new(ChunkId(next_id), path.clone(), content.clone()));
+                next_id += 1;
+            }
+            ChunkingStrategy::LineWindow { window_lines } => {
+                let window_chunks = Chunk::chunk_by_line_window(next_id, path.clone(), content, window_lines);
+                next_id += window_chunks.len() as u64;
+                chunks.extend(window_chunks);
+            }
+            ChunkingStrategy::SyntaxAware { fallback_window_lines } => {
+                let is_rust = path.extension().and_then(|ext| ext.to_str()) == Some("rs");
+                let item_chunks = if is_rust {
+                    Chunk::chunk_by_rust_item_boundaries(next_id, path.clone(), content)
+                } else {
+                    Chunk::chunk_by_line_window(next_id, path.clone(), content, fallback_window_lines)
+                };
+                next_id += item_chunks.len() as u64;
+                chunks.extend(item_chunks);
+            }
+        }
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let file = fs::File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_path(name).unwrap();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *contents).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn run_package_pipeline_extracts_and_summarizes_every_source_file() {
+        let dir = temp_dir("transfiguration-package-pipeline-flat");
+        let archive_path = dir.join("package.tar.gz");
+        write_tar_gz(
+            &archive_path,
+            &[
+                ("src/lib.rs", b"pub fn go() {}\n#[test]\nfn it_works() {}\n"),
+                ("README.md", b"# hello"),
+            ],
+        );
+
+        let workspace = dir.join("workspace");
+        let artifacts = run_package_pipeline(&archive_path, &workspace, UnpackPolicy::default()).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 2);
+        assert!(artifacts.summaries[&PathBuf::from("src/lib.rs")].contains("fn"));
+        assert!(matches!(artifacts.chunking_profile.unwrap().strategy, ChunkingStrategy::WholeFile));
+    }
+
+    #[test]
+    fn build_chunks_under_syntax_aware_splits_rust_files_on_item_boundaries_and_others_by_line_window() {
+        let profile = ChunkingProfile {
+            strategy: ChunkingStrategy::SyntaxAware { fallback_window_lines: 1 },
+            prompt_style: fingerprint::PromptStyle::Concise,
+            skip_globs: Vec::new(),
+        };
+        let files = vec![
+            (PathBuf::from("src/lib.rs"), "use std::fmt;\n\nfn a() {\n    1\n}\n\nfn b() {\n    2\n}\n".to_string()),
+            (PathBuf::from("README.md"), "line one\nline two\n".to_string()),
+        ];
+
+        let chunks = build_chunks(&files, &profile);
+
+        let rust_chunks: Vec<&Chunk> = chunks.iter().filter(|chunk| chunk.source_path == Path::new("src/lib.rs")).collect();
+        assert_eq!(rust_chunks.len(), 3);
+        assert!(rust_chunks[1].content.contains("fn a()"));
+        assert!(rust_chunks[2].content.contains("fn b()"));
+
+        let markdown_chunks: Vec<&Chunk> = chunks.iter().filter(|chunk| chunk.source_path == Path::new("README.md")).collect();
+        assert_eq!(markdown_chunks.len(), 2);
+    }
+
+    #[test]
+    fn run_package_pipeline_skips_binary_and_empty_files() {
+        let dir = temp_dir("transfiguration-package-pipeline-binary");
+        let archive_path = dir.join("package.tar.gz");
+        write_tar_gz(&archive_path, &[("blob.bin", &[0xFF, 0xFE, 0x00, 0xC0]), ("empty.rs", b"")]);
+
+        let workspace = dir.join("workspace");
+        let artifacts = run_package_pipeline(&archive_path, &workspace, UnpackPolicy::default()).unwrap();
+
+        assert!(artifacts.summaries.is_empty());
+    }
+
+    #[test]
+    fn run_package_pipeline_descends_into_nested_archives_before_summarizing() {
+        let dir = temp_dir("transfiguration-package-pipeline-nested");
+        let inner_path = dir.join("inner.tar.gz");
+        write_tar_gz(&inner_path, &[("nested.rs", b"struct Nested;\n")]);
+        let inner_bytes = fs::read(&inner_path).unwrap();
+
+        let outer_path = dir.join("outer.tar.gz");
+        write_tar_gz(&outer_path, &[("inner.tar.gz", &inner_bytes)]);
+
+        let workspace = dir.join("workspace");
+        let artifacts = run_package_pipeline(&outer_path, &workspace, UnpackPolicy::default()).unwrap();
+
+        assert!(artifacts.summaries[&PathBuf::from("nested.rs")].contains("struct"));
+    }
+
+    #[test]
+    fn run_package_pipeline_defaults_to_english_and_records_it_in_artifacts() {
+        let dir = temp_dir("transfiguration-package-pipeline-language-default");
+        let archive_path = dir.join("package.tar.gz");
+        write_tar_gz(&archive_path, &[("src/lib.rs", b"pub fn go() {}\n")]);
+
+        let workspace = dir.join("workspace");
+        let artifacts = run_package_pipeline(&archive_path, &workspace, UnpackPolicy::default()).unwrap();
+
+        assert_eq!(artifacts.language, Language::English);
+    }
+
+    #[test]
+    fn run_package_pipeline_with_language_records_the_requested_language() {
+        let dir = temp_dir("transfiguration-package-pipeline-language-japanese");
+        let archive_path = dir.join("package.tar.gz");
+        write_tar_gz(&archive_path, &[("src/lib.rs", b"pub fn go() {}\n")]);
+
+        let workspace = dir.join("workspace");
+        let artifacts =
+            run_package_pipeline_with_language(&archive_path, &workspace, UnpackPolicy::default(), Language::Japanese).unwrap();
+
+        assert_eq!(artifacts.language, Language::Japanese);
+    }
+
+    #[test]
+    fn glob_match_handles_double_star_and_single_star() {
+        assert!(glob_match("**/*.rs", "src/lib.rs"));
+        assert!(glob_match("**/*.rs", "lib.rs"));
+        assert!(!glob_match("**/*.rs", "src/lib.py"));
+        assert!(glob_match("node_modules/**", "node_modules/foo/bar.js"));
+        assert!(!glob_match("node_modules/**", "src/node_modules.rs"));
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(!glob_match("src/*.rs", "src/nested/lib.rs"));
+    }
+
+    #[test]
+    fn glob_match_handles_question_mark() {
+        assert!(glob_match("lib?.rs", "lib1.rs"));
+        assert!(!glob_match("lib?.rs", "lib.rs"));
+    }
+
+    #[test]
+    fn process_directory_summarizes_only_files_matching_the_include_glob() {
+        let dir = temp_dir("transfiguration-process-directory-include");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src").join("lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(dir.join("README.md"), "# hello").unwrap();
+
+        let policy = DirectoryProcessingPolicy {
+            include_globs: vec!["**/*.rs".to_string()],
+            respect_gitignore: false,
+            ..DirectoryProcessingPolicy::default()
+        };
+        let artifacts = process_directory(&dir, policy).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert!(artifacts.summaries.contains_key(&PathBuf::from("src/lib.rs")));
+    }
+
+    #[test]
+    fn process_directory_respects_gitignore_by_default() {
+        let dir = temp_dir("transfiguration-process-directory-gitignore");
+        fs::write(dir.join(".gitignore"), "generated.rs\n").unwrap();
+        fs::write(dir.join("lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(dir.join("generated.rs"), "pub fn generated() {}\n").unwrap();
+
+        let policy = DirectoryProcessingPolicy {
+            include_globs: vec!["*.rs".to_string()],
+            ..DirectoryProcessingPolicy::default()
+        };
+        let artifacts = process_directory(&dir, policy).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert!(artifacts.summaries.contains_key(&PathBuf::from("lib.rs")));
+    }
+
+    #[test]
+    fn process_directory_can_ignore_gitignore_when_asked() {
+        let dir = temp_dir("transfiguration-process-directory-no-gitignore");
+        fs::write(dir.join(".gitignore"), "generated.rs\n").unwrap();
+        fs::write(dir.join("lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(dir.join("generated.rs"), "pub fn generated() {}\n").unwrap();
+
+        let policy = DirectoryProcessingPolicy {
+            include_globs: vec!["*.rs".to_string()],
+            respect_gitignore: false,
+            ..DirectoryProcessingPolicy::default()
+        };
+        let artifacts = process_directory(&dir, policy).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 2);
+    }
+
+    #[test]
+    fn process_directory_records_the_requested_language() {
+        let dir = temp_dir("transfiguration-process-directory-language");
+        fs::write(dir.join("lib.rs"), "pub fn go() {}\n").unwrap();
+
+        let artifacts =
+            process_directory_with_language(&dir, DirectoryProcessingPolicy::default(), Language::Japanese).unwrap();
+
+        assert_eq!(artifacts.language, Language::Japanese);
+    }
+
+    #[test]
+    fn process_directory_skips_an_empty_file_instead_of_chunking_it() {
+        let dir = temp_dir("transfiguration-process-directory-empty");
+        fs::write(dir.join("lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(dir.join("empty.rs"), "   \n\n").unwrap();
+
+        let artifacts = process_directory(&dir, DirectoryProcessingPolicy::default()).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert_eq!(
+            artifacts.skipped_files,
+            vec![SkippedFile { path: PathBuf::from("empty.rs"), reason: FileSkipReason::Empty }]
+        );
+    }
+
+    #[test]
+    fn process_directory_skips_a_non_utf8_file_as_binary() {
+        let dir = temp_dir("transfiguration-process-directory-binary");
+        fs::write(dir.join("lib.rs"), "pub fn go() {}\n").unwrap();
+        fs::write(dir.join("asset.png"), [0xFFu8, 0xFE, 0x00, 0x01, 0x89, 0x50, 0x4E, 0x47]).unwrap();
+
+        let artifacts = process_directory(&dir, DirectoryProcessingPolicy::default()).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert_eq!(
+            artifacts.skipped_files,
+            vec![SkippedFile { path: PathBuf::from("asset.png"), reason: FileSkipReason::Binary }]
+        );
+    }
+
+    #[test]
+    fn process_directory_skips_a_file_with_a_pathologically_long_line() {
+        let dir = temp_dir("transfiguration-process-directory-long-line");
+        fs::write(dir.join("lib.rs"), "fn go() {}\n").unwrap();
+        fs::write(dir.join("data.lock"), "x".repeat(50)).unwrap();
+
+        let policy = DirectoryProcessingPolicy { max_line_length: 20, ..DirectoryProcessingPolicy::default() };
+        let artifacts = process_directory(&dir, policy).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert_eq!(
+            artifacts.skipped_files,
+            vec![SkippedFile {
+                path: PathBuf::from("data.lock"),
+                reason: FileSkipReason::LineTooLong { length: 50, max_line_length: 20 },
+            }]
+        );
+    }
+
+    #[test]
+    fn one_pathological_file_does_not_abort_the_rest_of_the_batch() {
+        let dir = temp_dir("transfiguration-process-directory-mixed-pathological");
+        fs::write(dir.join("lib.rs"), "fn go() {}\n").unwrap();
+        fs::write(dir.join("empty.rs"), "").unwrap();
+        fs::write(dir.join("asset.png"), [0xFFu8, 0xFE, 0x00]).unwrap();
+        fs::write(dir.join("data.lock"), "y".repeat(50)).unwrap();
+
+        let policy = DirectoryProcessingPolicy { max_line_length: 20, ..DirectoryProcessingPolicy::default() };
+        let artifacts = process_directory(&dir, policy).unwrap();
+
+        assert_eq!(artifacts.summaries.len(), 1);
+        assert_eq!(artifacts.skipped_files.len(), 3);
+    }
+}
@@ -0,0 +1,156 @@
+//! Overlaps tokenization with inference.
+//!
+//! Tokenization used to happen inline on the same thread as inference, right
+//! before each chunk was fed to the model, leaving the inference session idle
+//! during every encode. [`TokenizerPipeline`] runs a small worker pool that
+//! pre-encodes upcoming chunks into a bounded ready-queue while the caller
+//! decodes the chunk it already has, and tracks how much time the consumer
+//! spent waiting on an empty queue so that stall shows up in metrics.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::chunk::{Chunk, ChunkId};
+
+/// Minimal tokenizer contract needed by the pipeline; a richer, pluggable
+/// abstraction lives in the inference module.
+pub trait Tokenizer: Send + Sync {
+    fn encode_to_tensor(&self, text: &str) -> Vec<f32>;
+}
+
+/// A chunk that has already been tokenized and is ready for inference.
+pub struct EncodedChunk {
+    pub chunk_id: ChunkId,
+    pub tensor: Vec<f32>,
+}
+
+/// Thread-safe counters describing how well tokenization kept up with
+/// inference during a run.
+#[derive(Default)]
+pub struct PipelineMetrics {
+    stall_nanos: AtomicU64,
+}
+
+impl PipelineMetrics {
+    fn record_stall(&self, stall: Duration) {
+        self.stall_nanos.fetch_add(stall.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Total time inference spent waiting for the next tokenized chunk.
+    pub fn total_stall(&self) -> Duration {
+        Duration::from_nanos(self.stall_nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// Pre-encodes chunks on a worker pool into a bounded ready-queue that the
+/// inference loop drains via [`TokenizerPipeline::recv_next_encoded`].
+pub struct TokenizerPipeline {
+    workers: Vec<JoinHandle<()>>,
+    ready_queue: Receiver<EncodedChunk>,
+    metrics: Arc<PipelineMetrics>,
+}
+
+impl TokenizerPipeline {
+    /// Spawns `worker_count` tokenizer workers that pull from `chunks` and
+    /// push encoded results into a ready-queue bounded at `queue_capacity`.
+    pub fn spawn(
+        tokenizer: Arc<dyn Tokenizer>,
+        chunks: Vec<Chunk>,
+        worker_count: usize,
+        queue_capacity: usize,
+    ) -> Self {
+        let pending: Arc<Mutex<VecDeque<Chunk>>> = Arc::new(Mutex::new(chunks.into()));
+        let (sender, receiver): (SyncSender<EncodedChunk>, Receiver<EncodedChunk>) = sync_channel(queue_capacity.max(1));
+        let metrics = Arc::new(PipelineMetrics::default());
+
+        let workers = (0..worker_count.max(1))
+            .map(|_| {
+                let pending = Arc::clone(&pending);
+                let sender = sender.clone();
+                let tokenizer = Arc::clone(&tokenizer);
+                thread::spawn(move || {
+                    loop {
+                        let chunk = {
+                            let mut queue = pending.lock().expect("tokenizer pipeline input queue poisoned");
+                            queue.pop_front()
+                        };
+                        let Some(chunk) = chunk else { break };
+                        let tensor = tokenizer.encode_to_tensor(&chunk.content);
+                        if sender
+                            .send(EncodedChunk {
+                                chunk_id: chunk.id,
+                                tensor,
+                            })
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+
+        TokenizerPipeline {
+            workers,
+            ready_queue: receiver,
+            metrics,
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<PipelineMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Blocks for the next encoded chunk, attributing any wait time to
+    /// pipeline stall so a tokenizer pool that can't keep up shows up in
+    /// metrics instead of just looking like slow inference.
+    pub fn recv_next_encoded(&self) -> Option<EncodedChunk> {
+        #[cfg(feature = "otel")]
+        let _span = tracing::trace_span!("recv_next_encoded").entered();
+
+        let waited_from = Instant::now();
+        let result = self.ready_queue.recv().ok();
+        self.metrics.record_stall(waited_from.elapsed());
+        result
+    }
+}
+
+impl Drop for TokenizerPipeline {
+    fn drop(&mut self) {
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct LengthTokenizer;
+    impl Tokenizer for LengthTokenizer {
+        fn encode_to_tensor(&self, text: &str) -> Vec<f32> {
+            vec![text.len() as f32]
+        }
+    }
+
+    #[test]
+    fn pipeline_encodes_every_chunk() {
+        let chunks: Vec<Chunk> = (0..5)
+            .map(|i| Chunk::new(ChunkId(i), "f.rs", "x".repeat(i as usize + 1)))
+            .collect();
+        let pipeline = TokenizerPipeline::spawn(Arc::new(LengthTokenizer), chunks, 2, 2);
+
+        let mut seen = Vec::new();
+        while let Some(encoded) = pipeline.recv_next_encoded() {
+            seen.push(encoded.chunk_id);
+        }
+        seen.sort();
+        assert_eq!(seen, (0..5).map(ChunkId).collect::<Vec<_>>());
+    }
+}
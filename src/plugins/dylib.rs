@@ -0,0 +1,69 @@
+//! Loads an out-of-tree plugin compiled as a `cdylib` rather than a regular
+//! crate dependency, for a downstream team that wants to ship a contract
+//! rule or sink as a standalone binary artifact instead of a Rust crate the
+//! host has to depend on and rebuild against.
+//!
+//! Rust has no stable ABI, so a dylib plugin only works at all if it was
+//! built with the same compiler version against the same version of this
+//! crate's types — there is no way for this loader to verify that beyond
+//! the [`PLUGIN_API_VERSION`] check below, which catches a plugin built
+//! against a deliberately different API version but cannot catch every
+//! possible compiler/struct-layout mismatch. A plugin that needs to be
+//! robust against that should prefer the in-process [`PluginRegistry::load`]
+//! path (a regular crate dependency, checked by the compiler) over this one.
+//!
+//! A plugin dylib exports two `extern "C"` symbols:
+//!
+//! - `transfiguration_plugin_api_version() -> u32` — must return
+//!   [`PLUGIN_API_VERSION`], checked before `transfiguration_plugin_init`
+//!   is ever called.
+//! - `transfiguration_plugin_init(&mut PluginRegistry)` — registers
+//!   whatever [`ContractRule`]s and [`OutputSink`]s the plugin provides,
+//!   exactly like [`PluginInitFn`] does for an in-process plugin.
+
+use std::path::Path;
+
+use super::{PluginError, PluginRegistry, PLUGIN_API_VERSION};
+
+const API_VERSION_SYMBOL: &[u8] = b"transfiguration_plugin_api_version\0";
+const INIT_SYMBOL: &[u8] = b"transfiguration_plugin_init\0";
+
+/// Loads the dylib at `path`, checks its reported API version against
+/// [`PLUGIN_API_VERSION`], and — if they match — calls its init function to
+/// register whatever it provides into `registry`. The loaded library is
+/// kept alive on `registry` for as long as the registry itself is, so any
+/// rule/sink it registered stays valid.
+pub fn load_dylib_plugin(registry: &mut PluginRegistry, path: &Path) -> Result<(), PluginError> {
+    // SAFETY: loading an arbitrary dylib and calling into it is inherently
+    // unsafe — the caller is trusting `path` the same way `dlopen`/`LoadLibrary`
+    // always require trusting the library being loaded. The API-version
+    // check below is the only verification this loader can do before
+    // handing control to the plugin's code.
+    unsafe {
+        let library = libloading::Library::new(path)?;
+
+        let api_version: libloading::Symbol<unsafe extern "C" fn() -> u32> = library.get(API_VERSION_SYMBOL)?;
+        let plugin_version = api_version();
+        if plugin_version != PLUGIN_API_VERSION {
+            return Err(PluginError::ApiVersionMismatch { plugin_version, crate_version: PLUGIN_API_VERSION });
+        }
+
+        let init: libloading::Symbol<unsafe extern "C" fn(&mut PluginRegistry)> = library.get(INIT_SYMBOL)?;
+        init(registry);
+
+        registry.loaded_libraries.push(library);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loading_a_nonexistent_dylib_reports_an_io_style_error_rather_than_panicking() {
+        let mut registry = PluginRegistry::new();
+        let result = load_dylib_plugin(&mut registry, Path::new("/nonexistent/plugin.so"));
+        assert!(result.is_err());
+    }
+}
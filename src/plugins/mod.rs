@@ -0,0 +1,213 @@
+//! A registry downstream teams can add custom contract rules and output
+//! sinks to without forking this crate.
+//!
+//! [`validation::validate_run`](crate::validation::validate_run)'s checks
+//! and [`ValidationViolation`](crate::validation::ValidationViolation)'s
+//! [`ViolationKind`](crate::validation::ViolationKind) are a closed set —
+//! every kind's remediation is baked into
+//! [`crate::validation`]'s own remediation table, so a downstream rule can't
+//! add a new kind without editing this crate. [`ContractRule`] is a
+//! parallel, open-ended check instead: a plugin implements it, registers an
+//! instance with [`PluginRegistry::register_contract_rule`], and its
+//! [`PluginViolation`]s are reported alongside (not merged into)
+//! [`crate::validation::ValidationReport`]. [`crate::sinks::OutputSink`]
+//! already is the right shape for "custom exporter" — a plugin registers
+//! more of those via [`PluginRegistry::register_sink`] rather than this
+//! module inventing a second sink trait.
+//!
+//! [`PLUGIN_API_VERSION`] exists because a dylib plugin (loaded with the
+//! `dylib-plugins` feature; see [`load_dylib_plugin`]) is compiled
+//! independently of, and possibly against a different checkout of, this
+//! crate's trait definitions — a version mismatch must be caught before the
+//! mismatched vtable is ever called into, not after.
+
+use crate::sinks::OutputSink;
+use crate::validation::SummaryRecord;
+
+#[cfg(feature = "dylib-plugins")]
+mod dylib;
+#[cfg(feature = "dylib-plugins")]
+pub use dylib::load_dylib_plugin;
+
+/// Bumped whenever [`ContractRule`], [`OutputSink`], or [`PluginRegistry`]'s
+/// shape changes in a way that would break a dylib plugin built against an
+/// older version. In-process (compiled-in) plugins don't need to check
+/// this themselves — a mismatch there is just a normal compile error — but
+/// [`load_dylib_plugin`] checks it before calling into the plugin's code.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PluginError {
+    #[cfg(feature = "dylib-plugins")]
+    #[error("failed to load plugin dylib: {0}")]
+    Dylib(#[from] ::libloading::Error),
+    #[error(
+        "plugin dylib was built against plugin API v{plugin_version}, but this build of transfiguration expects v{crate_version}"
+    )]
+    ApiVersionMismatch { plugin_version: u32, crate_version: u32 },
+}
+
+/// One failure a [`ContractRule`] found in a [`SummaryRecord`], reported
+/// alongside (not merged into) [`crate::validation::ValidationReport`]'s
+/// fixed-kind violations — see the module doc for why the two are kept
+/// separate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginViolation {
+    pub rule_name: String,
+    pub detail: String,
+    pub remediation: Option<String>,
+}
+
+/// A contract rule a downstream team owns out-of-tree, checked against every
+/// [`SummaryRecord`] a run produces.
+pub trait ContractRule: Send + Sync {
+    /// Identifies this rule in [`PluginViolation::rule_name`] and in any
+    /// list of registered rules a caller prints for diagnostics.
+    fn name(&self) -> &str;
+    /// Returns every violation `record` trips against this rule; empty if
+    /// it passes.
+    fn check(&self, record: &SummaryRecord) -> Vec<PluginViolation>;
+}
+
+/// Every [`ContractRule`] and [`OutputSink`] a run has registered, whether
+/// compiled in directly or loaded from a dylib with [`load_dylib_plugin`].
+#[derive(Default)]
+pub struct PluginRegistry {
+    contract_rules: Vec<Box<dyn ContractRule>>,
+    sinks: Vec<Box<dyn OutputSink>>,
+    /// Keeps every loaded dylib mapped for as long as the registry is
+    /// alive: a [`ContractRule`] or [`OutputSink`] registered from one is a
+    /// vtable pointing into that dylib's code, so unloading it out from
+    /// under a still-registered rule/sink would be undefined behavior.
+    /// Declared last so it's dropped last — see `PluginRegistry`'s `Drop`
+    /// order, which drops fields top to bottom.
+    #[cfg(feature = "dylib-plugins")]
+    loaded_libraries: Vec<::libloading::Library>,
+}
+
+/// The function signature every in-process (compiled-in) plugin exposes to
+/// register whatever [`ContractRule`]s and [`OutputSink`]s it provides. A
+/// dylib plugin exposes the same signature under an `extern "C"` symbol
+/// instead — see [`load_dylib_plugin`].
+pub type PluginInitFn = fn(&mut PluginRegistry);
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        PluginRegistry::default()
+    }
+
+    /// Runs `init`, letting it register whatever rules/sinks it provides.
+    /// The in-process equivalent of [`load_dylib_plugin`], for a plugin
+    /// that's a regular compiled-in crate dependency rather than a dylib.
+    pub fn load(&mut self, init: PluginInitFn) {
+        init(self);
+    }
+
+    pub fn register_contract_rule(&mut self, rule: Box<dyn ContractRule>) {
+        self.contract_rules.push(rule);
+    }
+
+    pub fn register_sink(&mut self, sink: Box<dyn OutputSink>) {
+        self.sinks.push(sink);
+    }
+
+    pub fn contract_rule_names(&self) -> Vec<&str> {
+        self.contract_rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    /// Runs every registered [`ContractRule`] against `record`, collecting
+    /// every violation rather than stopping at the first rule that trips.
+    pub fn check_all(&self, record: &SummaryRecord) -> Vec<PluginViolation> {
+        self.contract_rules.iter().flat_map(|rule| rule.check(record)).collect()
+    }
+
+    /// Every registered sink, in registration order, ready to pass to
+    /// [`crate::sinks::write_to_all_sinks`] alongside any sinks a caller
+    /// built directly rather than through a plugin.
+    pub fn sinks_mut(&mut self) -> &mut [Box<dyn OutputSink>] {
+        &mut self.sinks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkClass;
+    use crate::sinks::{SinkError, SinkRecord};
+
+    struct BannedWordRule {
+        banned: &'static str,
+    }
+
+    impl ContractRule for BannedWordRule {
+        fn name(&self) -> &str {
+            "banned_word"
+        }
+
+        fn check(&self, record: &SummaryRecord) -> Vec<PluginViolation> {
+            if record.text.to_lowercase().contains(self.banned) {
+                vec![PluginViolation {
+                    rule_name: self.name().to_string(),
+                    detail: format!("summary contains banned word {:?}", self.banned),
+                    remediation: Some(format!("remove {:?} from the summary", self.banned)),
+                }]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    struct CountingSink {
+        count: usize,
+    }
+
+    impl OutputSink for CountingSink {
+        fn sink_name(&self) -> &str {
+            "counting"
+        }
+
+        fn write_record(&mut self, _record: &SinkRecord) -> Result<(), SinkError> {
+            self.count += 1;
+            Ok(())
+        }
+    }
+
+    fn record(text: &str) -> SummaryRecord {
+        SummaryRecord { chunk_class: ChunkClass::Production, text: text.to_string() }
+    }
+
+    fn init_test_plugin(registry: &mut PluginRegistry) {
+        registry.register_contract_rule(Box::new(BannedWordRule { banned: "todo" }));
+        registry.register_sink(Box::new(CountingSink { count: 0 }));
+    }
+
+    #[test]
+    fn a_registered_rule_flags_only_records_that_trip_it() {
+        let mut registry = PluginRegistry::new();
+        registry.register_contract_rule(Box::new(BannedWordRule { banned: "todo" }));
+
+        assert!(registry.check_all(&record("finished and correct")).is_empty());
+        let violations = registry.check_all(&record("still has a TODO in it"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule_name, "banned_word");
+    }
+
+    #[test]
+    fn load_runs_an_in_process_plugins_init_function() {
+        let mut registry = PluginRegistry::new();
+        registry.load(init_test_plugin);
+
+        assert_eq!(registry.contract_rule_names(), vec!["banned_word"]);
+        assert_eq!(registry.sinks_mut().len(), 1);
+    }
+
+    #[test]
+    fn multiple_rules_each_contribute_their_own_violations() {
+        let mut registry = PluginRegistry::new();
+        registry.register_contract_rule(Box::new(BannedWordRule { banned: "todo" }));
+        registry.register_contract_rule(Box::new(BannedWordRule { banned: "fixme" }));
+
+        let violations = registry.check_all(&record("todo: fixme later"));
+        assert_eq!(violations.len(), 2);
+    }
+}
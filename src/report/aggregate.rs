@@ -0,0 +1,205 @@
+//! Time-series aggregation of [`RunArtifacts`] across many runs, for
+//! dashboarding nightly-summarizer trends.
+//!
+//! A single run's artifacts answer "how did this run go"; a nightly job
+//! wants "is this getting better or worse over time". [`aggregate_runs`]
+//! turns an ordered sequence of runs into one [`AggregationPoint`] per run,
+//! and [`to_csv`]/[`to_json`] export that series for a dashboard to plot.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::{ReportError, RunArtifacts};
+
+/// One run as fed into [`aggregate_runs`]: its artifacts plus enough
+/// identity/timing to place it on a time series.
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub recorded_at: SystemTime,
+    pub artifacts: RunArtifacts,
+}
+
+/// One run's trend figures, ready to plot against `recorded_at_unix_ms` on a
+/// dashboard's x-axis.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AggregationPoint {
+    pub recorded_at_unix_ms: u64,
+    pub throughput_chunks_per_sec: Option<f64>,
+    pub contract_violations: Option<usize>,
+    pub cache_hit_rate: Option<f64>,
+    /// Files whose summary text changed, was added, or was removed relative
+    /// to the immediately preceding run in the series; `None` for the first
+    /// run, since there's nothing to diff it against.
+    pub summary_churn: Option<usize>,
+}
+
+/// Sorts `runs` by `recorded_at` and computes one [`AggregationPoint`] per
+/// run, including [`AggregationPoint::summary_churn`] against each run's
+/// immediate predecessor in the sorted order.
+pub fn aggregate_runs(runs: &[RunRecord]) -> Vec<AggregationPoint> {
+    let mut sorted: Vec<&RunRecord> = runs.iter().collect();
+    sorted.sort_by_key(|run| run.recorded_at);
+
+    let mut points = Vec::with_capacity(sorted.len());
+    let mut previous: Option<&RunRecord> = None;
+    for run in sorted {
+        let run_metrics = run.artifacts.run_metrics;
+        points.push(AggregationPoint {
+            recorded_at_unix_ms: unix_millis(run.recorded_at),
+            throughput_chunks_per_sec: run_metrics.and_then(|m| m.throughput_chunks_per_sec()),
+            contract_violations: run_metrics.map(|m| m.contract_violations),
+            cache_hit_rate: run_metrics.and_then(|m| m.cache_hit_rate()),
+            summary_churn: previous.map(|previous| summary_churn(&previous.artifacts, &run.artifacts)),
+        });
+        previous = Some(run);
+    }
+    points
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Counts files whose summary changed, was added, or was removed between
+/// `previous` and `current`, as a single churn figure for a trend line
+/// rather than [`crate::report::generate_change_report`]'s full per-file
+/// breakdown (which also requires a live backend to narrate the diff).
+fn summary_churn(previous: &RunArtifacts, current: &RunArtifacts) -> usize {
+    let previous_paths: BTreeSet<&PathBuf> = previous.summaries.keys().collect();
+    let current_paths: BTreeSet<&PathBuf> = current.summaries.keys().collect();
+
+    let added_or_removed = previous_paths.symmetric_difference(&current_paths).count();
+    let changed = previous_paths
+        .intersection(&current_paths)
+        .filter(|path| previous.summaries[**path] != current.summaries[**path])
+        .count();
+    added_or_removed + changed
+}
+
+/// Renders `points` as CSV, one row per run, for spreadsheet import or a
+/// dashboard's charting library to parse directly.
+pub fn to_csv(points: &[AggregationPoint]) -> String {
+    let mut csv = String::from("recorded_at_unix_ms,throughput_chunks_per_sec,contract_violations,cache_hit_rate,summary_churn\n");
+    for point in points {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            point.recorded_at_unix_ms,
+            optional_to_csv_field(point.throughput_chunks_per_sec),
+            optional_to_csv_field(point.contract_violations),
+            optional_to_csv_field(point.cache_hit_rate),
+            optional_to_csv_field(point.summary_churn),
+        ));
+    }
+    csv
+}
+
+fn optional_to_csv_field<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Renders `points` as a JSON array, for a dashboard that fetches the series
+/// directly rather than parsing CSV.
+pub fn to_json(points: &[AggregationPoint]) -> Result<String, ReportError> {
+    Ok(serde_json::to_string_pretty(points)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::RunMetricsSummary;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn artifacts(summaries: &[(&str, &str)], run_metrics: Option<RunMetricsSummary>) -> RunArtifacts {
+        RunArtifacts {
+            summaries: summaries.iter().map(|(path, text)| (PathBuf::from(path), text.to_string())).collect(),
+            run_metrics,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn points_are_ordered_by_recorded_at_regardless_of_input_order() {
+        let now = SystemTime::now();
+        let runs = vec![
+            RunRecord {
+                run_id: "second".into(),
+                recorded_at: now + Duration::from_secs(60),
+                artifacts: artifacts(&[], None),
+            },
+            RunRecord {
+                run_id: "first".into(),
+                recorded_at: now,
+                artifacts: artifacts(&[], None),
+            },
+        ];
+
+        let points = aggregate_runs(&runs);
+        assert!(points[0].recorded_at_unix_ms < points[1].recorded_at_unix_ms);
+    }
+
+    #[test]
+    fn first_run_has_no_churn_but_later_runs_do() {
+        let now = SystemTime::now();
+        let runs = vec![
+            RunRecord {
+                run_id: "a".into(),
+                recorded_at: now,
+                artifacts: artifacts(&[("a.rs", "old")], None),
+            },
+            RunRecord {
+                run_id: "b".into(),
+                recorded_at: now + Duration::from_secs(1),
+                artifacts: artifacts(&[("a.rs", "new"), ("b.rs", "added")], None),
+            },
+        ];
+
+        let points = aggregate_runs(&runs);
+        assert_eq!(points[0].summary_churn, None);
+        assert_eq!(points[1].summary_churn, Some(2));
+    }
+
+    #[test]
+    fn run_metrics_are_derived_into_rates() {
+        let now = SystemTime::now();
+        let metrics = RunMetricsSummary {
+            chunks_completed: 50,
+            chunks_failed: 0,
+            duration_ms: 10_000,
+            contract_violations: 2,
+            cache_hits: 9,
+            cache_misses: 1,
+        };
+        let runs = vec![RunRecord {
+            run_id: "a".into(),
+            recorded_at: now,
+            artifacts: artifacts(&[], Some(metrics)),
+        }];
+
+        let points = aggregate_runs(&runs);
+        assert_eq!(points[0].throughput_chunks_per_sec, Some(5.0));
+        assert_eq!(points[0].contract_violations, Some(2));
+        assert_eq!(points[0].cache_hit_rate, Some(0.9));
+    }
+
+    #[test]
+    fn csv_and_json_both_cover_every_point() {
+        let now = SystemTime::now();
+        let runs = vec![RunRecord {
+            run_id: "a".into(),
+            recorded_at: now,
+            artifacts: artifacts(&[], None),
+        }];
+        let points = aggregate_runs(&runs);
+
+        let csv = to_csv(&points);
+        assert_eq!(csv.lines().count(), 2);
+
+        let json = to_json(&points).unwrap();
+        assert!(json.contains("recorded_at_unix_ms"));
+    }
+}
@@ -0,0 +1,143 @@
+//! Rendering [`FailureReport`](crate::engine::agents::FailureReport) so a
+//! run's failures can be read off a report instead of grepped out of logs.
+//!
+//! [`ReportFormat`] has existed since the run-artifact format was split out,
+//! but nothing rendered against it — every other renderer in this crate
+//! ([`crate::report::aggregate::to_csv`], [`crate::report::aggregate::to_json`],
+//! [`crate::report::html::render_html_report`]) picks its format by which
+//! function the caller calls rather than by matching on the enum.
+//! [`render_failure_report`] is the first renderer that actually takes a
+//! [`ReportFormat`] and dispatches on it.
+
+use std::fmt::Write as _;
+
+use crate::engine::agents::{FailedChunk, FailureReport};
+use crate::report::html::escape_html;
+use crate::report::ReportFormat;
+
+/// Renders `report` as `format`. Groups are visited in their [`BTreeMap`](std::collections::BTreeMap)
+/// order, so the category names always come out sorted the same way
+/// regardless of which order failures happened in.
+pub fn render_failure_report(report: &FailureReport, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => render_markdown(report),
+        ReportFormat::Html => render_html(report),
+    }
+}
+
+fn render_markdown(report: &FailureReport) -> String {
+    let mut markdown = String::from("# Failures\n");
+    if report.groups.is_empty() {
+        markdown.push_str("\nNo failures.\n");
+        return markdown;
+    }
+    for (category, chunks) in &report.groups {
+        let _ = writeln!(markdown, "\n## {category} ({} chunk(s))", chunks.len());
+        for chunk in chunks {
+            markdown.push_str(&render_markdown_chunk(chunk));
+        }
+    }
+    markdown
+}
+
+fn render_markdown_chunk(chunk: &FailedChunk) -> String {
+    let file = chunk
+        .source_path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "(unknown source file)".to_string());
+    let mut block = format!("\n- chunk {} — {file}\n", chunk.chunk_id.0);
+    for (depth, cause) in chunk.error_chain.iter().enumerate() {
+        let _ = writeln!(block, "  {}{cause}", "  ".repeat(depth));
+    }
+    block
+}
+
+fn render_html(report: &FailureReport) -> String {
+    if report.groups.is_empty() {
+        return "<section id=\"failures\"><h2>Failures</h2><p>No failures.</p></section>".to_string();
+    }
+
+    let mut groups_html = String::new();
+    for (category, chunks) in &report.groups {
+        let _ = write!(groups_html, "<h3>{} ({})</h3><ul>", escape_html(category), chunks.len());
+        for chunk in chunks {
+            groups_html.push_str(&render_html_chunk(chunk));
+        }
+        groups_html.push_str("</ul>");
+    }
+
+    format!("<section id=\"failures\"><h2>Failures</h2>{groups_html}</section>")
+}
+
+fn render_html_chunk(chunk: &FailedChunk) -> String {
+    let file = chunk
+        .source_path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "(unknown source file)".to_string());
+    let chain: String = chunk
+        .error_chain
+        .iter()
+        .map(|cause| format!("<li>{}</li>", escape_html(cause)))
+        .collect();
+    format!(
+        "<li>chunk {chunk_id} — {file}<ul>{chain}</ul></li>",
+        chunk_id = chunk.chunk_id.0,
+        file = escape_html(&file),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+    use std::path::PathBuf;
+
+    fn chunk(id: u64, source_path: Option<&str>, error_chain: &[&str]) -> FailedChunk {
+        FailedChunk {
+            chunk_id: ChunkId(id),
+            source_path: source_path.map(PathBuf::from),
+            error_chain: error_chain.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn markdown_groups_failures_by_category_in_sorted_order() {
+        let mut report = FailureReport::default();
+        report.groups.insert("unknown_chunk", vec![chunk(2, Some("b.rs"), &["no summary exists yet"])]);
+        report.groups.insert("backend_failed", vec![chunk(1, Some("a.rs"), &["inference backend failed: timed out"])]);
+
+        let markdown = render_failure_report(&report, ReportFormat::Markdown);
+
+        let backend_index = markdown.find("## backend_failed").unwrap();
+        let unknown_index = markdown.find("## unknown_chunk").unwrap();
+        assert!(backend_index < unknown_index);
+        assert!(markdown.contains("a.rs"));
+        assert!(markdown.contains("inference backend failed: timed out"));
+    }
+
+    #[test]
+    fn html_escapes_chunk_content_and_reports_no_failures_cleanly() {
+        let empty = FailureReport::default();
+        assert!(render_failure_report(&empty, ReportFormat::Html).contains("No failures."));
+
+        let mut report = FailureReport::default();
+        report.groups.insert(
+            "backend_failed",
+            vec![chunk(1, Some("<evil>.rs"), &["<script>alert(1)</script>"])],
+        );
+        let html = render_failure_report(&report, ReportFormat::Html);
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn a_chunk_with_no_known_source_path_still_renders() {
+        let mut report = FailureReport::default();
+        report.groups.insert("backend_failed", vec![chunk(1, None, &["inference backend failed: timed out"])]);
+
+        let markdown = render_failure_report(&report, ReportFormat::Markdown);
+        assert!(markdown.contains("(unknown source file)"));
+    }
+}
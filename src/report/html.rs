@@ -0,0 +1,169 @@
+//! Self-contained HTML report rendering (`--format html`): a single file with
+//! embedded CSS/JS and no server, so it can be opened straight from a
+//! filesystem or attached to an email with a searchable, filterable view of
+//! every chunk's summary.
+
+use std::path::PathBuf;
+
+use crate::chunk::ChunkClass;
+use crate::report::CriticalPathSummary;
+
+/// One row of the HTML report's table.
+#[derive(Debug, Clone)]
+pub struct ReportRow {
+    pub file: PathBuf,
+    pub summary: String,
+    pub class: ChunkClass,
+    pub confidence: f32,
+    pub latency_ms: u64,
+}
+
+/// Renders `rows` into a single self-contained HTML document: a searchable
+/// table grouped by file, a confidence threshold filter, and a simple bar
+/// chart of per-chunk latency, all driven by inline JS with no external
+/// script or stylesheet references. `critical_path`, if present, renders as
+/// a summary block above the table showing where the run's wall-clock time
+/// went.
+pub fn render_html_report(rows: &[ReportRow], critical_path: Option<&CriticalPathSummary>) -> String {
+    let critical_path_section = critical_path.map(render_critical_path_section).unwrap_or_default();
+
+    // A stable sort by file keeps each file's rows together, in input order.
+    let mut sorted_rows: Vec<&ReportRow> = rows.iter().collect();
+    sorted_rows.sort_by(|a, b| a.file.cmp(&b.file));
+    let max_latency_ms = rows.iter().map(|row| row.latency_ms).max().unwrap_or(0).max(1);
+
+    let table_rows: String = sorted_rows
+        .iter()
+        .map(|row| {
+            format!(
+                "<tr data-confidence=\"{confidence}\" data-latency=\"{latency}\">\
+                    <td>{file}</td><td>{class}</td><td>{summary}</td>\
+                    <td>{confidence:.2}</td><td>{latency} ms<div class=\"bar\" style=\"width: {bar_width}px\"></div></td>\
+                </tr>",
+                file = escape_html(&row.file.display().to_string()),
+                class = row.class.as_str(),
+                summary = escape_html(&row.summary),
+                confidence = row.confidence,
+                latency = row.latency_ms,
+                bar_width = row.latency_ms.saturating_mul(200) / max_latency_ms,
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>transfiguration report</title>
+<style>
+  body {{ font-family: sans-serif; margin: 2rem; }}
+  table {{ border-collapse: collapse; width: 100%; }}
+  th, td {{ border: 1px solid #ccc; padding: 0.4rem 0.6rem; text-align: left; }}
+  .bar {{ background: #4a7; height: 0.6rem; }}
+</style>
+</head>
+<body>
+<h1>transfiguration report</h1>
+{critical_path_section}
+<input id="search" type="text" placeholder="Search summaries or files...">
+<label>Min confidence: <input id="confidence" type="range" min="0" max="1" step="0.05" value="0"></label>
+<table id="report-table">
+<thead><tr><th>File</th><th>Class</th><th>Summary</th><th>Confidence</th><th>Latency</th></tr></thead>
+<tbody>
+{table_rows}
+</tbody>
+</table>
+<script>
+const search = document.getElementById('search');
+const confidence = document.getElementById('confidence');
+const rows = Array.from(document.querySelectorAll('#report-table tbody tr'));
+
+function applyFilters() {{
+  const query = search.value.toLowerCase();
+  const minConfidence = parseFloat(confidence.value);
+  for (const row of rows) {{
+    const matchesQuery = row.textContent.toLowerCase().includes(query);
+    const matchesConfidence = parseFloat(row.dataset.confidence) >= minConfidence;
+    row.style.display = (matchesQuery && matchesConfidence) ? '' : 'none';
+  }}
+}}
+
+search.addEventListener('input', applyFilters);
+confidence.addEventListener('input', applyFilters);
+</script>
+</body>
+</html>
+"#
+    )
+}
+
+fn render_critical_path_section(summary: &CriticalPathSummary) -> String {
+    let straggler = match (summary.straggler_chunk, summary.straggler_duration_ms) {
+        (Some(chunk_id), Some(duration_ms)) => format!("chunk {} ({duration_ms} ms)", chunk_id.0),
+        _ => "none".to_string(),
+    };
+    format!(
+        "<section id=\"critical-path\">\
+            <h2>Critical path</h2>\
+            <ul>\
+                <li>Wall time: {wall_time_ms} ms</li>\
+                <li>Busy time: {busy_time_ms} ms</li>\
+                <li>Warmup: {warmup_ms} ms</li>\
+                <li>Efficiency: {efficiency:.1}%</li>\
+                <li>Straggler: {straggler}</li>\
+            </ul>\
+        </section>",
+        wall_time_ms = summary.wall_time_ms,
+        busy_time_ms = summary.busy_time_ms,
+        warmup_ms = summary.warmup_ms,
+        efficiency = summary.efficiency * 100.0,
+    )
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+
+    #[test]
+    fn report_is_self_contained_and_escapes_input() {
+        let rows = vec![ReportRow {
+            file: PathBuf::from("a.rs"),
+            summary: "<script>alert(1)</script>".into(),
+            class: ChunkClass::Production,
+            confidence: 0.9,
+            latency_ms: 12,
+        }];
+        let html = render_html_report(&rows, None);
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("http://") && !html.contains("https://"));
+    }
+
+    #[test]
+    fn critical_path_section_only_renders_when_provided() {
+        let rows = vec![];
+        assert!(!render_html_report(&rows, None).contains("critical-path"));
+
+        let summary = CriticalPathSummary {
+            wall_time_ms: 120,
+            busy_time_ms: 90,
+            warmup_ms: 10,
+            efficiency: 0.75,
+            straggler_chunk: Some(ChunkId(3)),
+            straggler_duration_ms: Some(100),
+        };
+        let html = render_html_report(&rows, Some(&summary));
+        assert!(html.contains("critical-path"));
+        assert!(html.contains("chunk 3 (100 ms)"));
+        assert!(html.contains("75.0%"));
+    }
+}
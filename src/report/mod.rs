@@ -0,0 +1,336 @@
+//! Persisted run artifacts and the diffing logic used to build human-readable
+//! reports across runs (change reports today, richer dashboards later).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkId;
+use crate::engine::{EngineError, InferenceBackend};
+use crate::fingerprint::ChunkingProfile;
+use crate::locking::{self, FileLock, LockError};
+use crate::metrics::fairness::FileFairnessStats;
+use crate::metrics::trace::CriticalPathAnalysis;
+use crate::validation::Language;
+
+pub mod aggregate;
+#[cfg(feature = "service")]
+pub mod failures;
+pub mod html;
+#[cfg(feature = "package-pipeline")]
+pub mod ownership;
+pub mod privacy;
+
+/// Which on-disk shape a run's report should be rendered as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    #[error("failed to read/write run artifacts: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to (de)serialize run artifacts: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Engine(#[from] EngineError),
+    #[error(transparent)]
+    Lock(#[from] LockError),
+}
+
+/// One summarization run's output, keyed by source file, saved to disk so the
+/// next run can diff against it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunArtifacts {
+    pub summaries: BTreeMap<PathBuf, String>,
+    /// The chunking/prompt profile this run used, whether chosen by
+    /// [`crate::fingerprint::select_chunking_profile`] or overridden by the
+    /// caller, so a later run (or a human reading the manifest) can see why
+    /// chunking behaved the way it did.
+    pub chunking_profile: Option<ChunkingProfile>,
+    /// The model's license, from [`crate::model::card::ModelCard::license`],
+    /// so downstream distribution of these summaries can respect the terms
+    /// the model that produced them was licensed under.
+    pub model_license: Option<String>,
+    /// Where this run's wall-clock time went, from
+    /// [`crate::metrics::trace::CriticalPathAnalysis`], if the caller
+    /// recorded a scheduler trace for the run.
+    pub critical_path: Option<CriticalPathSummary>,
+    /// Throughput, validation, and cache figures for this run, if the caller
+    /// recorded them, so [`aggregate`] can build time-series trends across
+    /// many runs without re-deriving them from raw logs.
+    pub run_metrics: Option<RunMetricsSummary>,
+    /// Per-file queue wait fairness, from
+    /// [`crate::metrics::fairness::QueueFairnessTracker`], if the caller
+    /// tracked it for this run. Empty rather than absent when tracking
+    /// happened but found nothing to report, so a reader can tell "not
+    /// tracked" from "tracked, every file was fine" by checking `is_empty`
+    /// against whether the caller says it recorded fairness at all.
+    pub file_fairness: Vec<FileFairnessSummary>,
+    /// Files a run walked past without chunking, and why — see
+    /// [`FileSkipReason`]. Empty rather than absent when a run found nothing
+    /// to skip, the same "tracked vs. not tracked" reason
+    /// [`file_fairness`](Self::file_fairness) is. Artifacts persisted before
+    /// this field existed default to empty, since they predate any run that
+    /// could have populated it.
+    #[serde(default)]
+    pub skipped_files: Vec<SkippedFile>,
+    /// The language this run's summaries were written in — see
+    /// [`Language::prompt_directive`] and
+    /// [`crate::engine::SummaryRun::summarize_all_chunks_with_language`].
+    /// Defaults to [`Language::English`], the language every summary in
+    /// this crate was written in before this field existed, so artifacts
+    /// persisted by an older version deserialize correctly.
+    #[serde(default)]
+    pub language: Language,
+}
+
+/// Why a source discovery walk (e.g.
+/// [`crate::package::discover_source_files_filtered`], behind the
+/// `package-pipeline` feature) left a file out of a run's batch instead of
+/// chunking it, surfaced on [`RunArtifacts::skipped_files`] instead of the
+/// whole run failing over one bad file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileSkipReason {
+    /// Reading the file as UTF-8 failed — almost always a binary asset
+    /// (a `.png`, a compiled object), though a permission error or a
+    /// concurrent delete would also land here; those are rare enough next
+    /// to "binary asset" that a dedicated reason isn't worth it.
+    Binary,
+    /// The file decoded as UTF-8 but every byte in it is whitespace.
+    Empty,
+    /// At least one line's byte length exceeded `max_line_length`.
+    LineTooLong { length: usize, max_line_length: usize },
+}
+
+/// One file a source discovery walk left out of the batch, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: FileSkipReason,
+}
+
+/// A [`FileFairnessStats`] flattened to millisecond precision for
+/// persistence, the same reason [`CriticalPathSummary`] flattens
+/// [`CriticalPathAnalysis`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFairnessSummary {
+    pub source_path: PathBuf,
+    pub max_wait_ms: Option<u64>,
+    pub mean_wait_ms: Option<u64>,
+    pub oldest_pending_age_ms: Option<u64>,
+}
+
+impl From<&FileFairnessStats> for FileFairnessSummary {
+    fn from(stats: &FileFairnessStats) -> Self {
+        FileFairnessSummary {
+            source_path: stats.source_path.clone(),
+            max_wait_ms: stats.max_wait.map(|d| d.as_millis() as u64),
+            mean_wait_ms: stats.mean_wait.map(|d| d.as_millis() as u64),
+            oldest_pending_age_ms: stats.oldest_pending_age.map(|d| d.as_millis() as u64),
+        }
+    }
+}
+
+/// Per-run figures worth tracking across many runs: how fast it went, how
+/// many [`crate::validation::ValidationReport`] violations it produced, and
+/// how much the embedding cache ([`crate::dedup::store::EmbeddingStore`])
+/// saved it from re-embedding unchanged chunks.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RunMetricsSummary {
+    pub chunks_completed: u64,
+    pub chunks_failed: u64,
+    pub duration_ms: u64,
+    pub contract_violations: usize,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+impl RunMetricsSummary {
+    /// `chunks_completed` per second of `duration_ms`, or `None` for a
+    /// zero-duration run rather than dividing by zero.
+    pub fn throughput_chunks_per_sec(&self) -> Option<f64> {
+        if self.duration_ms == 0 {
+            return None;
+        }
+        Some(self.chunks_completed as f64 / (self.duration_ms as f64 / 1000.0))
+    }
+
+    /// Fraction of cache lookups that hit, or `None` if nothing was looked
+    /// up yet.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            return None;
+        }
+        Some(self.cache_hits as f64 / total as f64)
+    }
+}
+
+/// A [`CriticalPathAnalysis`] flattened to millisecond precision so it can be
+/// persisted in a [`RunArtifacts`] file without `Instant`/`Duration`'s
+/// platform-dependent serialization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriticalPathSummary {
+    pub wall_time_ms: u64,
+    pub busy_time_ms: u64,
+    pub warmup_ms: u64,
+    pub efficiency: f64,
+    pub straggler_chunk: Option<ChunkId>,
+    pub straggler_duration_ms: Option<u64>,
+}
+
+impl From<&CriticalPathAnalysis> for CriticalPathSummary {
+    fn from(analysis: &CriticalPathAnalysis) -> Self {
+        CriticalPathSummary {
+            wall_time_ms: analysis.wall_time.as_millis() as u64,
+            busy_time_ms: analysis.busy_time.as_millis() as u64,
+            warmup_ms: analysis.warmup.as_millis() as u64,
+            efficiency: analysis.efficiency(),
+            straggler_chunk: analysis.straggler.map(|(chunk_id, _)| chunk_id),
+            straggler_duration_ms: analysis.straggler.map(|(_, duration)| duration.as_millis() as u64),
+        }
+    }
+}
+
+impl RunArtifacts {
+    /// Acquires an advisory lock on a sidecar `.lock` file before writing, so
+    /// two runs targeting the same output path don't interleave their
+    /// writes, and writes via [`locking::atomic_write`] so a reader never
+    /// observes a partially-written file.
+    pub fn save_to_file(&self, path: &Path) -> Result<(), ReportError> {
+        let lock = FileLock::try_acquire(FileLock::lock_path_for(path))?;
+
+        let json = serde_json::to_string_pretty(self)?;
+        locking::atomic_write(path, json.as_bytes())?;
+
+        drop(lock);
+        Ok(())
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self, ReportError> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// What changed between two runs, plus a model-generated paragraph
+/// describing the overall drift for a human skimming a weekly digest.
+#[derive(Debug, Clone)]
+pub struct ChangeReport {
+    pub changed_files: Vec<PathBuf>,
+    pub new_files: Vec<PathBuf>,
+    pub deleted_files: Vec<PathBuf>,
+    pub drift_narrative: String,
+}
+
+/// Diffs `previous` against `current` and asks `backend` for a narrative
+/// paragraph describing the drift, reusing the same model already warm for
+/// summarization.
+pub fn generate_change_report(
+    previous: &RunArtifacts,
+    current: &RunArtifacts,
+    backend: &impl InferenceBackend,
+) -> Result<ChangeReport, ReportError> {
+    let previous_paths: BTreeSet<&PathBuf> = previous.summaries.keys().collect();
+    let current_paths: BTreeSet<&PathBuf> = current.summaries.keys().collect();
+
+    let new_files: Vec<PathBuf> = current_paths
+        .difference(&previous_paths)
+        .map(|p| (*p).clone())
+        .collect();
+    let deleted_files: Vec<PathBuf> = previous_paths
+        .difference(&current_paths)
+        .map(|p| (*p).clone())
+        .collect();
+    let changed_files: Vec<PathBuf> = previous_paths
+        .intersection(&current_paths)
+        .filter(|p| previous.summaries[**p] != current.summaries[**p])
+        .map(|p| (*p).clone())
+        .collect();
+
+    let prompt = drift_narrative_prompt(&changed_files, &new_files, &deleted_files);
+    let drift_narrative = backend.generate_completion_text(&prompt)?;
+
+    Ok(ChangeReport {
+        changed_files,
+        new_files,
+        deleted_files,
+        drift_narrative,
+    })
+}
+
+fn drift_narrative_prompt(changed: &[PathBuf], new: &[PathBuf], deleted: &[PathBuf]) -> String {
+    format!(
+        "Write one paragraph describing overall codebase drift given: \
+         {} files changed, {} files added, {} files deleted.",
+        changed.len(),
+        new.len(),
+        deleted.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+    impl InferenceBackend for EchoBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            Ok(format!("echo: {}", prompt.len()))
+        }
+    }
+
+    #[test]
+    fn detects_new_deleted_and_changed_files() {
+        let mut previous = RunArtifacts::default();
+        previous.summaries.insert(PathBuf::from("a.rs"), "old a".into());
+        previous.summaries.insert(PathBuf::from("b.rs"), "same b".into());
+
+        let mut current = RunArtifacts::default();
+        current.summaries.insert(PathBuf::from("a.rs"), "new a".into());
+        current.summaries.insert(PathBuf::from("b.rs"), "same b".into());
+        current.summaries.insert(PathBuf::from("c.rs"), "new c".into());
+
+        let report = generate_change_report(&previous, &current, &EchoBackend).unwrap();
+        assert_eq!(report.changed_files, vec![PathBuf::from("a.rs")]);
+        assert_eq!(report.new_files, vec![PathBuf::from("c.rs")]);
+        assert!(report.deleted_files.is_empty());
+        assert!(!report.drift_narrative.is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let mut artifacts = RunArtifacts::default();
+        artifacts.summaries.insert(PathBuf::from("a.rs"), "summary".into());
+        let path = std::env::temp_dir().join("transfiguration-report-roundtrip.json");
+
+        artifacts.save_to_file(&path).unwrap();
+        let loaded = RunArtifacts::load_from_file(&path).unwrap();
+        assert_eq!(loaded.summaries, artifacts.summaries);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_fairness_round_trips_through_disk() {
+        let mut artifacts = RunArtifacts::default();
+        artifacts.file_fairness.push(FileFairnessSummary {
+            source_path: PathBuf::from("hot.rs"),
+            max_wait_ms: Some(30_000),
+            mean_wait_ms: Some(12_000),
+            oldest_pending_age_ms: None,
+        });
+        let path = std::env::temp_dir().join("transfiguration-report-fairness-roundtrip.json");
+
+        artifacts.save_to_file(&path).unwrap();
+        let loaded = RunArtifacts::load_from_file(&path).unwrap();
+        assert_eq!(loaded.file_fairness, artifacts.file_fairness);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Grouping a run's files by owning team and rendering that as a report
+//! section, the same way [`crate::report::failures`] groups failures by
+//! category — see that module's docs for why [`ReportFormat`] dispatch
+//! lives on the renderer rather than on [`RunArtifacts`] itself.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::ownership::OwnershipMap;
+use crate::report::html::escape_html;
+use crate::report::{ReportFormat, RunArtifacts};
+
+/// The group a file falls into when no [`OwnershipMap`] rule matches it, so
+/// every file a run summarized is accounted for in
+/// [`group_files_by_owner`]'s totals rather than silently dropped.
+pub const UNOWNED_LABEL: &str = "(unowned)";
+
+/// One owning team's share of a run, for a per-team statistics row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerStats {
+    pub owner: String,
+    pub file_count: usize,
+}
+
+/// Groups every file [`RunArtifacts::summaries`] covers by its owner(s), per
+/// `ownership`'s CODEOWNERS semantics. A file with more than one owner
+/// token on its matching rule (e.g. `@team-a @team-b`) appears once under
+/// each, so per-team counts reflect everyone responsible rather than only
+/// the first-listed owner.
+pub fn group_files_by_owner(run: &RunArtifacts, ownership: &OwnershipMap) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in run.summaries.keys() {
+        let owners = ownership.owners_for(path);
+        if owners.is_empty() {
+            groups.entry(UNOWNED_LABEL.to_string()).or_default().push(path.clone());
+        } else {
+            for owner in owners {
+                groups.entry(owner.clone()).or_default().push(path.clone());
+            }
+        }
+    }
+    groups
+}
+
+/// Per-team file counts from an already-built [`group_files_by_owner`] map,
+/// in the map's own (alphabetical-by-owner) order.
+pub fn owner_stats(groups: &BTreeMap<String, Vec<PathBuf>>) -> Vec<OwnerStats> {
+    groups.iter().map(|(owner, files)| OwnerStats { owner: owner.clone(), file_count: files.len() }).collect()
+}
+
+/// Renders `groups` as `format`. Groups are visited in their
+/// [`BTreeMap`] order, matching [`crate::report::failures::render_failure_report`]'s
+/// guarantee that the same input always renders the same way regardless of
+/// discovery order.
+pub fn render_owner_report(groups: &BTreeMap<String, Vec<PathBuf>>, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => render_markdown(groups),
+        ReportFormat::Html => render_html(groups),
+    }
+}
+
+fn render_markdown(groups: &BTreeMap<String, Vec<PathBuf>>) -> String {
+    let mut markdown = String::from("# Ownership\n");
+    if groups.is_empty() {
+        markdown.push_str("\nNo files.\n");
+        return markdown;
+    }
+    for (owner, files) in groups {
+        let _ = writeln!(markdown, "\n## {owner} ({} file(s))", files.len());
+        for file in files {
+            let _ = writeln!(markdown, "- {}", file.display());
+        }
+    }
+    markdown
+}
+
+fn render_html(groups: &BTreeMap<String, Vec<PathBuf>>) -> String {
+    if groups.is_empty() {
+        return "<section id=\"ownership\"><h2>Ownership</h2><p>No files.</p></section>".to_string();
+    }
+
+    let mut groups_html = String::new();
+    for (owner, files) in groups {
+        let _ = write!(groups_html, "<h3>{} ({})</h3><ul>", escape_html(owner), files.len());
+        for file in files {
+            let _ = write!(groups_html, "<li>{}</li>", escape_html(&file.display().to_string()));
+        }
+        groups_html.push_str("</ul>");
+    }
+
+    format!("<section id=\"ownership\"><h2>Ownership</h2>{groups_html}</section>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_run() -> RunArtifacts {
+        let mut run = RunArtifacts::default();
+        run.summaries.insert(PathBuf::from("src/engine/jobs.rs"), "does a thing".to_string());
+        run.summaries.insert(PathBuf::from("src/report/mod.rs"), "does another thing".to_string());
+        run.summaries.insert(PathBuf::from("README.md"), "describes the project".to_string());
+        run
+    }
+
+    #[test]
+    fn unmatched_files_fall_under_the_unowned_label() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team\n");
+        let groups = group_files_by_owner(&sample_run(), &ownership);
+
+        assert_eq!(groups.get("@platform-team").unwrap(), &[PathBuf::from("src/engine/jobs.rs")]);
+        let unowned = groups.get(UNOWNED_LABEL).unwrap();
+        assert_eq!(unowned.len(), 2);
+    }
+
+    #[test]
+    fn a_file_with_two_owner_tokens_counts_once_for_each() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team @inference-team\n");
+        let groups = group_files_by_owner(&sample_run(), &ownership);
+
+        assert!(groups.contains_key("@platform-team"));
+        assert!(groups.contains_key("@inference-team"));
+        assert_eq!(groups["@platform-team"], groups["@inference-team"]);
+    }
+
+    #[test]
+    fn owner_stats_reports_file_counts_per_owner() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team\nsrc/report/**  @docs-team\n");
+        let groups = group_files_by_owner(&sample_run(), &ownership);
+        let stats = owner_stats(&groups);
+
+        assert!(stats.contains(&OwnerStats { owner: "@platform-team".to_string(), file_count: 1 }));
+        assert!(stats.contains(&OwnerStats { owner: "@docs-team".to_string(), file_count: 1 }));
+        assert!(stats.contains(&OwnerStats { owner: UNOWNED_LABEL.to_string(), file_count: 1 }));
+    }
+
+    #[test]
+    fn markdown_renders_every_group_with_its_files() {
+        let ownership = OwnershipMap::parse("src/engine/**  @platform-team\n");
+        let groups = group_files_by_owner(&sample_run(), &ownership);
+
+        let markdown = render_owner_report(&groups, ReportFormat::Markdown);
+        assert!(markdown.contains("## @platform-team (1 file(s))"));
+        assert!(markdown.contains("src/engine/jobs.rs"));
+    }
+
+    #[test]
+    fn html_escapes_owner_names_and_file_paths() {
+        let ownership = OwnershipMap::parse("<script>  @<b>team\n");
+        let mut run = RunArtifacts::default();
+        run.summaries.insert(PathBuf::from("<script>"), "x".to_string());
+        let groups = group_files_by_owner(&run, &ownership);
+
+        let html = render_owner_report(&groups, ReportFormat::Html);
+        assert!(!html.contains("<script>team"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn an_empty_run_renders_cleanly_in_both_formats() {
+        let groups = group_files_by_owner(&RunArtifacts::default(), &OwnershipMap::parse(""));
+        assert_eq!(render_owner_report(&groups, ReportFormat::Markdown), "# Ownership\n\nNo files.\n");
+        assert!(render_owner_report(&groups, ReportFormat::Html).contains("No files."));
+    }
+}
@@ -0,0 +1,288 @@
+//! Laplace noise for aggregate metrics leaving this process, so a dashboard
+//! fed by [`crate::report::aggregate::to_csv`]/[`to_json`][aggregate::to_json]
+//! publishes plausible-deniability figures instead of exact counts.
+//!
+//! This only ever touches the already-aggregated [`AggregationPoint`] series
+//! [`aggregate_runs`][crate::report::aggregate::aggregate_runs] produces, not
+//! a [`RunArtifacts`][crate::report::RunArtifacts]'s raw per-file summaries —
+//! noising raw results would make them useless for the diffing
+//! [`crate::report::generate_change_report`] already does locally, and that
+//! never leaves this process anyway. [`NoiseConfig`] records the epsilon a
+//! caller chose, and [`apply_laplace_noise`] returns it alongside the noised
+//! series in a [`PrivateAggregateExport`] so whoever receives the export can
+//! see exactly how much privacy budget was spent producing it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::aggregate::{self, AggregationPoint};
+use crate::report::ReportError;
+
+/// How many more (or fewer) chunks/violations/churned-files a single run
+/// could plausibly have contributed relative to its neighbours — the
+/// per-metric sensitivity Laplace noise is scaled against. These are
+/// deliberately generous round numbers rather than derived from any one
+/// fleet's actual run distribution, since this crate has no telemetry on
+/// real-world run-to-run variance to derive them from; a caller running at
+/// a scale where these undersell or oversell the true sensitivity should
+/// tune them, which is why they're exposed on [`NoiseConfig`] rather than
+/// hardcoded constants.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricSensitivities {
+    pub throughput_chunks_per_sec: f64,
+    pub contract_violations: f64,
+    pub cache_hit_rate: f64,
+    pub summary_churn: f64,
+}
+
+impl Default for MetricSensitivities {
+    fn default() -> Self {
+        MetricSensitivities {
+            throughput_chunks_per_sec: 5.0,
+            contract_violations: 3.0,
+            cache_hit_rate: 0.05,
+            summary_churn: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PrivacyError {
+    #[error("epsilon must be positive, got {epsilon}; smaller epsilon means more noise, it can never be zero or negative")]
+    NonPositiveEpsilon { epsilon: f64 },
+}
+
+/// The privacy budget an aggregate export is noised under: how much Laplace
+/// noise to add (smaller `epsilon` is more noise, more privacy) and how
+/// sensitive each metric is assumed to be to a single run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseConfig {
+    epsilon: f64,
+    sensitivities: MetricSensitivities,
+}
+
+impl NoiseConfig {
+    /// Builds a config from `epsilon` and [`MetricSensitivities::default`].
+    /// Rejects non-positive epsilon rather than silently adding infinite or
+    /// backwards noise.
+    pub fn with_epsilon(epsilon: f64) -> Result<Self, PrivacyError> {
+        Self::with_epsilon_and_sensitivities(epsilon, MetricSensitivities::default())
+    }
+
+    pub fn with_epsilon_and_sensitivities(
+        epsilon: f64,
+        sensitivities: MetricSensitivities,
+    ) -> Result<Self, PrivacyError> {
+        if !epsilon.is_finite() || epsilon <= 0.0 {
+            return Err(PrivacyError::NonPositiveEpsilon { epsilon });
+        }
+        Ok(NoiseConfig { epsilon, sensitivities })
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+/// A noised [`AggregationPoint`] series plus the epsilon it was noised
+/// under, so a reader downstream of the export (a human, or a retention
+/// policy) can see the privacy budget it was published at without a
+/// side-channel document to keep in sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateAggregateExport {
+    pub epsilon: f64,
+    pub points: Vec<AggregationPoint>,
+}
+
+impl PrivateAggregateExport {
+    /// [`aggregate::to_json`] of [`Self::points`], wrapped with `epsilon` so
+    /// the manifest a consumer receives is self-describing.
+    pub fn to_json(&self) -> Result<String, ReportError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// [`aggregate::to_csv`] of [`Self::points`], prefixed with a comment
+    /// line recording `epsilon` for consumers (e.g. spreadsheet import) that
+    /// can't read a surrounding JSON envelope.
+    pub fn to_csv(&self) -> String {
+        format!("# epsilon={}\n{}", self.epsilon, aggregate::to_csv(&self.points))
+    }
+}
+
+/// Minimal deterministic PRNG, the same SplitMix64 construction
+/// [`crate::testgen`] uses for synthetic corpus generation, so sampling
+/// Laplace noise here doesn't need a `rand` dependency this crate doesn't
+/// otherwise have. Seeded explicitly by the caller (rather than from system
+/// entropy) so a noised export is reproducible for testing, same rationale
+/// as `testgen`'s seeding.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn seeded_from_value(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_raw_value(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `[0.0, 1.0)`, built from the top 53 bits of
+    /// [`Self::next_raw_value`] so every representable `f64` mantissa value
+    /// in range is reachable.
+    fn next_f64_unit(&mut self) -> f64 {
+        (self.next_raw_value() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Samples one draw from `Laplace(0, scale)` via inverse-CDF sampling: a
+/// uniform draw `u` on `[-0.5, 0.5)` maps to `-scale * sign(u) * ln(1 - 2|u|)`.
+fn sample_laplace_noise(rng: &mut SplitMix64, scale: f64) -> f64 {
+    let u = rng.next_f64_unit() - 0.5;
+    // `1.0 - 2.0 * |u|` reaches exactly `0.0` only when `u` is `-0.5`, i.e.
+    // when `next_f64_unit` returns exactly `0.0`; clamp away from it rather
+    // than ever feeding `ln` a zero.
+    let magnitude = (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE);
+    -scale * u.signum() * magnitude.ln()
+}
+
+fn noisy_count(exact: usize, rng: &mut SplitMix64, sensitivity: f64, epsilon: f64) -> usize {
+    let noise = sample_laplace_noise(rng, sensitivity / epsilon);
+    (exact as f64 + noise).round().max(0.0) as usize
+}
+
+fn noisy_point(point: AggregationPoint, rng: &mut SplitMix64, config: &NoiseConfig) -> AggregationPoint {
+    let epsilon = config.epsilon;
+    AggregationPoint {
+        // The timestamp is a coordinate on the x-axis, not a measured
+        // count derived from run contents, so it carries nothing worth
+        // hiding and is left exact.
+        recorded_at_unix_ms: point.recorded_at_unix_ms,
+        throughput_chunks_per_sec: point.throughput_chunks_per_sec.map(|value| {
+            let scale = config.sensitivities.throughput_chunks_per_sec / epsilon;
+            (value + sample_laplace_noise(rng, scale)).max(0.0)
+        }),
+        contract_violations: point
+            .contract_violations
+            .map(|value| noisy_count(value, rng, config.sensitivities.contract_violations, epsilon)),
+        cache_hit_rate: point.cache_hit_rate.map(|value| {
+            let scale = config.sensitivities.cache_hit_rate / epsilon;
+            (value + sample_laplace_noise(rng, scale)).clamp(0.0, 1.0)
+        }),
+        summary_churn: point
+            .summary_churn
+            .map(|value| noisy_count(value, rng, config.sensitivities.summary_churn, epsilon)),
+    }
+}
+
+/// Adds Laplace noise scaled by `config`'s epsilon and sensitivities to
+/// every metric in `points` (everything but the timestamp — see
+/// [`noisy_point`]), deterministically from `seed`, and returns the result
+/// bundled with the epsilon it was noised under.
+pub fn apply_laplace_noise(points: &[AggregationPoint], config: &NoiseConfig, seed: u64) -> PrivateAggregateExport {
+    let mut rng = SplitMix64::seeded_from_value(seed);
+    let noised = points.iter().map(|point| noisy_point(*point, &mut rng, config)).collect();
+    PrivateAggregateExport { epsilon: config.epsilon, points: noised }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(throughput: f64, violations: usize, hit_rate: f64, churn: usize) -> AggregationPoint {
+        AggregationPoint {
+            recorded_at_unix_ms: 1_000,
+            throughput_chunks_per_sec: Some(throughput),
+            contract_violations: Some(violations),
+            cache_hit_rate: Some(hit_rate),
+            summary_churn: Some(churn),
+        }
+    }
+
+    #[test]
+    fn non_positive_epsilon_is_rejected() {
+        assert!(matches!(NoiseConfig::with_epsilon(0.0), Err(PrivacyError::NonPositiveEpsilon { .. })));
+        assert!(matches!(NoiseConfig::with_epsilon(-1.0), Err(PrivacyError::NonPositiveEpsilon { .. })));
+    }
+
+    #[test]
+    fn noising_is_deterministic_for_a_fixed_seed() {
+        let config = NoiseConfig::with_epsilon(1.0).unwrap();
+        let points = vec![point(10.0, 2, 0.9, 3)];
+
+        let first = apply_laplace_noise(&points, &config, 42);
+        let second = apply_laplace_noise(&points, &config, 42);
+
+        assert_eq!(first.points[0].throughput_chunks_per_sec, second.points[0].throughput_chunks_per_sec);
+        assert_eq!(first.points[0].contract_violations, second.points[0].contract_violations);
+    }
+
+    #[test]
+    fn different_seeds_usually_produce_different_noise() {
+        let config = NoiseConfig::with_epsilon(1.0).unwrap();
+        let points = vec![point(10.0, 2, 0.9, 3)];
+
+        let first = apply_laplace_noise(&points, &config, 1);
+        let second = apply_laplace_noise(&points, &config, 2);
+
+        assert_ne!(first.points[0].throughput_chunks_per_sec, second.points[0].throughput_chunks_per_sec);
+    }
+
+    #[test]
+    fn a_smaller_epsilon_adds_more_noise_on_average() {
+        let tight = NoiseConfig::with_epsilon(10.0).unwrap();
+        let loose = NoiseConfig::with_epsilon(0.01).unwrap();
+        let points: Vec<AggregationPoint> = (0..200).map(|_| point(100.0, 10, 0.5, 5)).collect();
+
+        let deviation = |config: &NoiseConfig, seed: u64| -> f64 {
+            let noised = apply_laplace_noise(&points, config, seed);
+            noised
+                .points
+                .iter()
+                .map(|p| (p.throughput_chunks_per_sec.unwrap() - 100.0).abs())
+                .sum::<f64>()
+                / noised.points.len() as f64
+        };
+
+        assert!(deviation(&loose, 7) > deviation(&tight, 7));
+    }
+
+    #[test]
+    fn timestamp_is_never_noised() {
+        let config = NoiseConfig::with_epsilon(0.5).unwrap();
+        let noised = apply_laplace_noise(&[point(1.0, 1, 1.0, 1)], &config, 9);
+        assert_eq!(noised.points[0].recorded_at_unix_ms, 1_000);
+    }
+
+    #[test]
+    fn cache_hit_rate_noise_stays_clamped_to_a_probability() {
+        let config = NoiseConfig::with_epsilon(0.001).unwrap();
+        let extreme_points: Vec<AggregationPoint> = (0..50).map(|_| point(0.0, 0, 1.0, 0)).collect();
+
+        let noised = apply_laplace_noise(&extreme_points, &config, 123);
+        for p in &noised.points {
+            let rate = p.cache_hit_rate.unwrap();
+            assert!((0.0..=1.0).contains(&rate));
+        }
+    }
+
+    #[test]
+    fn to_json_records_epsilon_for_the_consumer() {
+        let config = NoiseConfig::with_epsilon(2.5).unwrap();
+        let export = apply_laplace_noise(&[point(1.0, 1, 1.0, 1)], &config, 9);
+        let json = export.to_json().unwrap();
+        assert!(json.contains("\"epsilon\": 2.5"));
+    }
+
+    #[test]
+    fn to_csv_prefixes_the_epsilon_as_a_comment_line() {
+        let config = NoiseConfig::with_epsilon(2.5).unwrap();
+        let export = apply_laplace_noise(&[point(1.0, 1, 1.0, 1)], &config, 9);
+        let csv = export.to_csv();
+        assert!(csv.starts_with("# epsilon=2.5\n"));
+    }
+}
@@ -0,0 +1,273 @@
+//! Age/count/size-based cleanup for on-disk run directories.
+//!
+//! Every summarization run writes its [`crate::report::RunArtifacts`] (and
+//! whatever else it produces) into its own directory under a runs root.
+//! Nothing ever deletes those, so long-lived installs accumulate runs
+//! indefinitely. [`gc_runs`] applies a [`RetentionPolicy`] to clear old ones
+//! out, while never touching a run a caller has marked as protected (e.g.
+//! one a checkpoint or a published report still points at).
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    #[error("io error scanning/removing run directories: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One run directory as discovered on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunMetadata {
+    pub run_id: String,
+    pub path: PathBuf,
+    pub created_at: SystemTime,
+    pub size_bytes: u64,
+}
+
+/// Limits a cleanup pass enforces. Any combination may be set; an unset
+/// limit is simply not checked.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionPolicy {
+    pub max_age: Option<Duration>,
+    pub max_count: Option<usize>,
+    pub max_total_bytes: Option<u64>,
+}
+
+/// What a cleanup pass did, so a CLI or log line can report it.
+#[derive(Debug, Clone, Default)]
+pub struct GcOutcome {
+    pub kept: Vec<String>,
+    pub deleted: Vec<String>,
+    pub protected: Vec<String>,
+}
+
+/// Lists every immediate subdirectory of `runs_root` as a run, using the
+/// directory name as the run id and the recursive size of its contents as
+/// `size_bytes`.
+pub fn list_runs(runs_root: &Path) -> Result<Vec<RunMetadata>, RetentionError> {
+    let mut runs = Vec::new();
+    if !runs_root.exists() {
+        return Ok(runs);
+    }
+    for entry in fs::read_dir(runs_root)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let run_id = entry.file_name().to_string_lossy().into_owned();
+        let created_at = entry.metadata()?.modified()?;
+        let size_bytes = directory_size(&path)?;
+        runs.push(RunMetadata {
+            run_id,
+            path,
+            created_at,
+            size_bytes,
+        });
+    }
+    Ok(runs)
+}
+
+fn directory_size(dir: &Path) -> Result<u64, RetentionError> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            total += directory_size(&entry.path())?;
+        } else {
+            total += metadata.len();
+        }
+    }
+    Ok(total)
+}
+
+/// Decides which of `runs` to keep vs delete under `policy` as of `now`,
+/// without touching the filesystem. Separated from [`gc_runs`] so the
+/// policy logic can be tested without real directories or real clock time.
+///
+/// Runs in `protected_run_ids` are always kept and never count against
+/// `max_count`/`max_total_bytes` budgets.
+pub fn apply_policy(
+    runs: &[RunMetadata],
+    policy: &RetentionPolicy,
+    protected_run_ids: &HashSet<String>,
+    now: SystemTime,
+) -> GcOutcome {
+    let mut sorted: Vec<&RunMetadata> = runs.iter().collect();
+    sorted.sort_by_key(|run| std::cmp::Reverse(run.created_at));
+
+    let mut outcome = GcOutcome::default();
+    let mut survivors: Vec<&RunMetadata> = Vec::new();
+
+    for run in sorted {
+        if protected_run_ids.contains(&run.run_id) {
+            outcome.protected.push(run.run_id.clone());
+            continue;
+        }
+
+        if let Some(max_age) = policy.max_age {
+            let age = now.duration_since(run.created_at).unwrap_or(Duration::ZERO);
+            if age > max_age {
+                outcome.deleted.push(run.run_id.clone());
+                continue;
+            }
+        }
+
+        survivors.push(run);
+    }
+
+    if let Some(max_count) = policy.max_count {
+        for run in survivors.split_off(max_count.min(survivors.len())) {
+            outcome.deleted.push(run.run_id.clone());
+        }
+    }
+
+    if let Some(max_total_bytes) = policy.max_total_bytes {
+        let mut running_total = 0u64;
+        let mut within_budget = Vec::new();
+        for run in survivors {
+            running_total += run.size_bytes;
+            if running_total > max_total_bytes {
+                outcome.deleted.push(run.run_id.clone());
+            } else {
+                within_budget.push(run);
+            }
+        }
+        survivors = within_budget;
+    }
+
+    outcome.kept = survivors.into_iter().map(|run| run.run_id.clone()).collect();
+    outcome
+}
+
+/// Scans `runs_root`, applies `policy`, and removes every run directory the
+/// policy marks for deletion, leaving protected and surviving runs in
+/// place.
+pub fn gc_runs(
+    runs_root: &Path,
+    policy: &RetentionPolicy,
+    protected_run_ids: &HashSet<String>,
+) -> Result<GcOutcome, RetentionError> {
+    let runs = list_runs(runs_root)?;
+    let outcome = apply_policy(&runs, policy, protected_run_ids, SystemTime::now());
+
+    let runs_by_id: std::collections::HashMap<&str, &RunMetadata> =
+        runs.iter().map(|run| (run.run_id.as_str(), run)).collect();
+    for run_id in &outcome.deleted {
+        if let Some(run) = runs_by_id.get(run_id.as_str()) {
+            fs::remove_dir_all(&run.path)?;
+        }
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(run_id: &str, age: Duration, size_bytes: u64, now: SystemTime) -> RunMetadata {
+        RunMetadata {
+            run_id: run_id.to_string(),
+            path: PathBuf::from(run_id),
+            created_at: now - age,
+            size_bytes,
+        }
+    }
+
+    #[test]
+    fn max_age_deletes_only_older_runs() {
+        let now = SystemTime::now();
+        let runs = vec![
+            run("fresh", Duration::from_secs(60), 10, now),
+            run("stale", Duration::from_secs(10_000), 10, now),
+        ];
+        let policy = RetentionPolicy {
+            max_age: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+
+        let outcome = apply_policy(&runs, &policy, &HashSet::new(), now);
+        assert_eq!(outcome.kept, vec!["fresh".to_string()]);
+        assert_eq!(outcome.deleted, vec!["stale".to_string()]);
+    }
+
+    #[test]
+    fn max_count_keeps_only_the_newest_runs() {
+        let now = SystemTime::now();
+        let runs = vec![
+            run("newest", Duration::from_secs(1), 10, now),
+            run("middle", Duration::from_secs(2), 10, now),
+            run("oldest", Duration::from_secs(3), 10, now),
+        ];
+        let policy = RetentionPolicy {
+            max_count: Some(2),
+            ..Default::default()
+        };
+
+        let outcome = apply_policy(&runs, &policy, &HashSet::new(), now);
+        assert_eq!(outcome.kept, vec!["newest".to_string(), "middle".to_string()]);
+        assert_eq!(outcome.deleted, vec!["oldest".to_string()]);
+    }
+
+    #[test]
+    fn max_total_bytes_drops_oldest_once_budget_is_exceeded() {
+        let now = SystemTime::now();
+        let runs = vec![
+            run("newest", Duration::from_secs(1), 60, now),
+            run("oldest", Duration::from_secs(2), 60, now),
+        ];
+        let policy = RetentionPolicy {
+            max_total_bytes: Some(100),
+            ..Default::default()
+        };
+
+        let outcome = apply_policy(&runs, &policy, &HashSet::new(), now);
+        assert_eq!(outcome.kept, vec!["newest".to_string()]);
+        assert_eq!(outcome.deleted, vec!["oldest".to_string()]);
+    }
+
+    #[test]
+    fn protected_runs_survive_every_policy() {
+        let now = SystemTime::now();
+        let runs = vec![run("checkpointed", Duration::from_secs(10_000), 10, now)];
+        let policy = RetentionPolicy {
+            max_age: Some(Duration::from_secs(1)),
+            max_count: Some(0),
+            max_total_bytes: Some(0),
+        };
+        let protected: HashSet<String> = ["checkpointed".to_string()].into_iter().collect();
+
+        let outcome = apply_policy(&runs, &policy, &protected, now);
+        assert_eq!(outcome.protected, vec!["checkpointed".to_string()]);
+        assert!(outcome.deleted.is_empty());
+    }
+
+    #[test]
+    fn gc_runs_removes_stale_directories_from_disk() {
+        let root = std::env::temp_dir().join("transfiguration-retention-gc");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("keep-me")).unwrap();
+        fs::create_dir_all(root.join("delete-me")).unwrap();
+        fs::write(root.join("delete-me").join("manifest.json"), b"{}").unwrap();
+
+        let policy = RetentionPolicy {
+            max_count: Some(1),
+            ..Default::default()
+        };
+        let outcome = gc_runs(&root, &policy, &HashSet::new()).unwrap();
+
+        assert_eq!(outcome.kept.len() + outcome.deleted.len(), 2);
+        let remaining: HashSet<String> = fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(remaining.len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,364 @@
+//! Routes a chunk to a different (typically cheaper) backend and prompt
+//! based on what kind of source it is, rather than running every chunk
+//! through the same backend regardless of whether it's a config file or
+//! a page of async Rust.
+//!
+//! [`SummaryRun`](crate::engine::SummaryRun) is generic over exactly one
+//! `B: InferenceBackend`, so it can't itself hold several different backend
+//! types at once; [`ChunkRouter`] instead keeps a name-keyed registry of
+//! type-erased [`Arc<dyn InferenceBackend>`] backends (the same erasure
+//! [`crate::engine::InferenceBackend`]'s blanket `Arc<T>` impl already
+//! supports) and picks one per chunk based on [`detect_source_language`] and
+//! [`complexity_score`], recording the choice as a [`RoutingDecision`] a
+//! caller can keep alongside each [`Summary`].
+//!
+//! "Aggregate cost savings" has no real per-token billing data behind it in
+//! this crate — [`RouteRule::relative_cost`] is a caller-supplied estimate
+//! (1.0 for "costs the same as the default backend"), and
+//! [`aggregate_routing_savings`] only ever reports against that estimate,
+//! not a measured dollar figure.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::chunk::{Chunk, ChunkId};
+use crate::engine::{initial_summary_prompt, EngineError, InferenceBackend, Summary, SummarySource};
+use crate::validation::Language;
+
+/// The source language [`detect_source_language`] guesses a chunk is
+/// written in, purely from its file extension — good enough to tell "this
+/// is a config file" from "this is code" without a real parser for every
+/// language this crate might ever see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceLanguage {
+    Rust,
+    Toml,
+    Json,
+    Yaml,
+    Sql,
+    Shell,
+    Markdown,
+    Other,
+}
+
+/// Guesses `path`'s [`SourceLanguage`] from its extension,
+/// case-insensitively. Unrecognized or missing extensions map to
+/// [`SourceLanguage::Other`] rather than failing — a router rule that wants
+/// to catch everything else matches on that.
+pub fn detect_source_language(path: &Path) -> SourceLanguage {
+    match path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_ascii_lowercase().as_str() {
+        "rs" => SourceLanguage::Rust,
+        "toml" => SourceLanguage::Toml,
+        "json" => SourceLanguage::Json,
+        "yaml" | "yml" => SourceLanguage::Yaml,
+        "sql" => SourceLanguage::Sql,
+        "sh" | "bash" => SourceLanguage::Shell,
+        "md" | "markdown" => SourceLanguage::Markdown,
+        _ => SourceLanguage::Other,
+    }
+}
+
+/// Branching/control-flow tokens whose count correlates with how much a
+/// chunk actually needs a model to reason about, as opposed to content that
+/// just needs transcribing (e.g. a flat config file's key/value pairs).
+const BRANCHING_TOKENS: &[&str] = &["if ", "else", "for ", "while ", "match ", "&&", "||", "catch", "except"];
+
+/// A cheap, dependency-free proxy for how much a chunk's content actually
+/// needs a model to reason through: branching-token hits plus the deepest
+/// bracket nesting in the content. Not a real static-analysis complexity
+/// metric (no AST, no cyclomatic complexity) — just enough signal to tell a
+/// handful of flat config lines from a deeply nested function.
+pub fn complexity_score(content: &str) -> u32 {
+    let branching_hits: u32 = BRANCHING_TOKENS.iter().map(|token| content.matches(token).count() as u32).sum();
+    branching_hits + max_bracket_nesting_depth(content)
+}
+
+fn max_bracket_nesting_depth(content: &str) -> u32 {
+    let mut depth: i32 = 0;
+    let mut max_depth: i32 = 0;
+    for ch in content.chars() {
+        match ch {
+            '{' | '(' | '[' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    max_depth.max(0) as u32
+}
+
+/// One routing rule: chunks of `language` whose [`complexity_score`] is at
+/// most `max_complexity` (or any score, if `max_complexity` is `None`) are
+/// sent to the backend registered under `backend_name`, with `prompt_prefix`
+/// (if any) prepended to the usual prompt.
+#[derive(Debug, Clone)]
+pub struct RouteRule {
+    pub language: SourceLanguage,
+    pub max_complexity: Option<u32>,
+    pub backend_name: String,
+    pub prompt_prefix: Option<String>,
+    /// This rule's backend cost per call, relative to the default backend's
+    /// cost of `1.0` — see the module doc for why this is an estimate, not
+    /// a measured figure.
+    pub relative_cost: f64,
+}
+
+/// What [`ChunkRouter::route`] decided for one chunk: which
+/// [`SourceLanguage`]/[`complexity_score`] it detected, and which backend
+/// that sent it to. Returned alongside the chunk's [`Summary`] so a caller
+/// can record "why" next to "what" in its own report, and fed into
+/// [`aggregate_routing_savings`] across a whole run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingDecision {
+    pub chunk_id: ChunkId,
+    pub language: SourceLanguage,
+    pub complexity_score: u32,
+    pub backend_name: String,
+    pub relative_cost: f64,
+}
+
+/// A name-keyed registry of [`RouteRule`]s and the backends they route to.
+/// Rules are tried in registration order; the first whose `language` and
+/// `max_complexity` both match wins. A chunk matching no rule goes to the
+/// backend registered under `default_backend_name`.
+pub struct ChunkRouter {
+    rules: Vec<RouteRule>,
+    backends: HashMap<String, Arc<dyn InferenceBackend>>,
+    default_backend_name: String,
+}
+
+impl ChunkRouter {
+    /// `default_backend` is registered under `default_backend_name` and
+    /// used for any chunk no rule claims.
+    pub fn new(default_backend_name: impl Into<String>, default_backend: Arc<dyn InferenceBackend>) -> Self {
+        let default_backend_name = default_backend_name.into();
+        let mut backends = HashMap::new();
+        backends.insert(default_backend_name.clone(), default_backend);
+        ChunkRouter { rules: Vec::new(), backends, default_backend_name }
+    }
+
+    pub fn register_backend(&mut self, name: impl Into<String>, backend: Arc<dyn InferenceBackend>) {
+        self.backends.insert(name.into(), backend);
+    }
+
+    /// Appends `rule` to the rule list. Earlier-registered rules take
+    /// priority, matching how [`crate::validation`]'s remediation table and
+    /// most configuration-by-rule-list code in this crate resolves ties: the
+    /// first applicable entry wins.
+    pub fn add_rule(&mut self, rule: RouteRule) {
+        self.rules.push(rule);
+    }
+
+    fn matching_rule(&self, language: SourceLanguage, complexity: u32) -> Option<&RouteRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.language == language && rule.max_complexity.is_none_or(|max| complexity <= max))
+    }
+
+    /// Classifies `chunk` and picks the rule (or default) that applies,
+    /// without calling any backend — split out from
+    /// [`ChunkRouter::summarize`] so a caller can inspect the routing
+    /// decision a chunk would get before paying for the call.
+    pub fn route(&self, chunk: &Chunk) -> RoutingDecision {
+        let language = detect_source_language(&chunk.source_path);
+        let complexity_score = complexity_score(&chunk.content);
+        let rule = self.matching_rule(language, complexity_score);
+        RoutingDecision {
+            chunk_id: chunk.id,
+            language,
+            complexity_score,
+            backend_name: rule.map(|rule| rule.backend_name.clone()).unwrap_or_else(|| self.default_backend_name.clone()),
+            relative_cost: rule.map(|rule| rule.relative_cost).unwrap_or(1.0),
+        }
+    }
+
+    /// Routes `chunk`, then summarizes it against whichever backend that
+    /// routed it to, asking for the summary in `language` the same way
+    /// [`crate::engine::SummaryRun`] would.
+    pub fn summarize(&self, chunk: &Chunk, language: Language) -> Result<(Summary, RoutingDecision, Duration), EngineError> {
+        let decision = self.route(chunk);
+        let backend = self.backends.get(&decision.backend_name).ok_or_else(|| {
+            EngineError::BackendFailed(format!("no backend registered under route name {:?}", decision.backend_name))
+        })?;
+
+        let class = chunk.classify();
+        let prompt_prefix = self
+            .matching_rule(decision.language, decision.complexity_score)
+            .and_then(|rule| rule.prompt_prefix.as_deref())
+            .unwrap_or_default();
+        let prompt = format!("{prompt_prefix}{}", initial_summary_prompt(chunk, class, language));
+
+        let started_at = Instant::now();
+        let text = backend.generate_completion_text(&prompt)?;
+        let latency = started_at.elapsed();
+
+        let summary =
+            Summary { chunk_id: chunk.id, class, text, instruction: None, source: SummarySource::Model, language };
+        Ok((summary, decision, latency))
+    }
+}
+
+/// What routing saved (or cost) across a whole run, compared to the
+/// counterfactual of every chunk running at the default backend's relative
+/// cost of `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoutingSavingsReport {
+    pub chunks_routed: usize,
+    pub total_relative_cost: f64,
+    pub baseline_relative_cost: f64,
+    /// Fraction of `baseline_relative_cost` avoided; negative if routing
+    /// actually cost more than the baseline would have (e.g. every chunk
+    /// happened to match a rule with `relative_cost > 1.0`).
+    pub estimated_cost_savings_fraction: f64,
+    pub total_latency: Duration,
+}
+
+/// Aggregates `decisions` (each paired with the latency its call actually
+/// took) into a [`RoutingSavingsReport`].
+pub fn aggregate_routing_savings(decisions: &[(RoutingDecision, Duration)]) -> RoutingSavingsReport {
+    let chunks_routed = decisions.len();
+    let total_relative_cost: f64 = decisions.iter().map(|(decision, _)| decision.relative_cost).sum();
+    let baseline_relative_cost = chunks_routed as f64;
+    let estimated_cost_savings_fraction = if baseline_relative_cost > 0.0 {
+        1.0 - (total_relative_cost / baseline_relative_cost)
+    } else {
+        0.0
+    };
+    let total_latency = decisions.iter().map(|(_, latency)| *latency).sum();
+
+    RoutingSavingsReport {
+        chunks_routed,
+        total_relative_cost,
+        baseline_relative_cost,
+        estimated_cost_savings_fraction,
+        total_latency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkId;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingBackend {
+        calls: AtomicUsize,
+    }
+
+    impl InferenceBackend for CountingBackend {
+        fn generate_completion_text(&self, prompt: &str) -> Result<String, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("summary for {} bytes", prompt.len()))
+        }
+    }
+
+    fn router_with_toml_rule() -> (ChunkRouter, Arc<CountingBackend>, Arc<CountingBackend>) {
+        let default_backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0) });
+        let cheap_backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0) });
+
+        let mut router = ChunkRouter::new("default", default_backend.clone());
+        router.register_backend("cheap", cheap_backend.clone());
+        router.add_rule(RouteRule {
+            language: SourceLanguage::Toml,
+            max_complexity: None,
+            backend_name: "cheap".to_string(),
+            prompt_prefix: Some("Briefly: ".to_string()),
+            relative_cost: 0.1,
+        });
+
+        (router, default_backend, cheap_backend)
+    }
+
+    #[test]
+    fn detect_source_language_recognizes_common_extensions() {
+        assert_eq!(detect_source_language(Path::new("Cargo.toml")), SourceLanguage::Toml);
+        assert_eq!(detect_source_language(Path::new("src/lib.rs")), SourceLanguage::Rust);
+        assert_eq!(detect_source_language(Path::new("schema.SQL")), SourceLanguage::Sql);
+        assert_eq!(detect_source_language(Path::new("README")), SourceLanguage::Other);
+    }
+
+    #[test]
+    fn complexity_score_rewards_branching_and_nesting_over_flat_content() {
+        let flat = "key = \"value\"\nother = 1\n";
+        let branchy = "fn f(x: i32) -> i32 {\n    if x > 0 {\n        for i in 0..x {\n            if i % 2 == 0 { return i; }\n        }\n    }\n    0\n}";
+        assert!(complexity_score(branchy) > complexity_score(flat));
+    }
+
+    #[test]
+    fn a_toml_chunk_routes_to_the_cheap_backend_and_gets_its_prompt_prefix() {
+        let (router, default_backend, cheap_backend) = router_with_toml_rule();
+        let chunk = Chunk::new(ChunkId(1), "Cargo.toml", "name = \"x\"");
+
+        let (_, decision, _) = router.summarize(&chunk, Language::English).unwrap();
+
+        assert_eq!(decision.backend_name, "cheap");
+        assert_eq!(decision.relative_cost, 0.1);
+        assert_eq!(cheap_backend.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(default_backend.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn a_rust_chunk_falls_through_to_the_default_backend() {
+        let (router, default_backend, cheap_backend) = router_with_toml_rule();
+        let chunk = Chunk::new(ChunkId(2), "lib.rs", "fn f() {}");
+
+        let (_, decision, _) = router.summarize(&chunk, Language::English).unwrap();
+
+        assert_eq!(decision.backend_name, "default");
+        assert_eq!(decision.relative_cost, 1.0);
+        assert_eq!(default_backend.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cheap_backend.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn aggregate_routing_savings_reflects_a_mix_of_cheap_and_default_routes() {
+        let decisions = vec![
+            (
+                RoutingDecision {
+                    chunk_id: ChunkId(1),
+                    language: SourceLanguage::Toml,
+                    complexity_score: 0,
+                    backend_name: "cheap".to_string(),
+                    relative_cost: 0.1,
+                },
+                Duration::from_millis(5),
+            ),
+            (
+                RoutingDecision {
+                    chunk_id: ChunkId(2),
+                    language: SourceLanguage::Rust,
+                    complexity_score: 9,
+                    backend_name: "default".to_string(),
+                    relative_cost: 1.0,
+                },
+                Duration::from_millis(50),
+            ),
+        ];
+
+        let report = aggregate_routing_savings(&decisions);
+        assert_eq!(report.chunks_routed, 2);
+        assert!((report.total_relative_cost - 1.1).abs() < f64::EPSILON);
+        assert!((report.estimated_cost_savings_fraction - 0.45).abs() < 1e-9);
+        assert_eq!(report.total_latency, Duration::from_millis(55));
+    }
+
+    #[test]
+    fn an_unregistered_backend_name_reports_a_backend_failed_error_instead_of_panicking() {
+        let default_backend = Arc::new(CountingBackend { calls: AtomicUsize::new(0) });
+        let mut router = ChunkRouter::new("default", default_backend);
+        router.add_rule(RouteRule {
+            language: SourceLanguage::Sql,
+            max_complexity: None,
+            backend_name: "never-registered".to_string(),
+            prompt_prefix: None,
+            relative_cost: 0.2,
+        });
+
+        let chunk = Chunk::new(ChunkId(3), "query.sql", "SELECT 1;");
+        assert!(router.summarize(&chunk, Language::English).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Opt-in execution sandbox for summarizing untrusted input: a Linux seccomp
+//! filter that denies network and most filesystem-mutation syscalls before
+//! chunking or inference touches attacker-controlled bytes.
+//!
+//! # Threat model
+//!
+//! In scope: a chunk's `content` (and, by extension, whatever a backend's
+//! prompt construction does with it) comes from a source the caller does not
+//! trust — a cloned repo of unknown provenance, a file upload, etc. The
+//! sandbox's job is to make sure that even if a backend process is tricked
+//! into running attacker-influenced code (e.g. a crafted ONNX model, a
+//! vulnerable parser), it cannot open a socket, spawn a process, or open,
+//! create, rename, truncate or delete a file. The filter is not scoped to
+//! any directory: without [`Capability::FilesystemWrite`] every `openat` is
+//! denied, read-only opens included, while `read`/`write` on descriptors
+//! opened before the filter was installed are not filtered.
+//!
+//! Out of scope: compromise of the host kernel itself, side channels, and
+//! resource-exhaustion (CPU/memory) denial of service — none of those are
+//! addressed by a syscall filter and need separate controls (cgroups,
+//! `rlimit`, timeouts). This module also does not sandbox the calling
+//! process itself, only the worker that opts in by calling
+//! [`install_seccomp_filter`].
+//!
+//! A no-op on every non-Linux target: there's no seccomp equivalent to fall
+//! back to, so [`install_seccomp_filter`] returns
+//! [`SandboxError::UnsupportedPlatform`] rather than silently granting full
+//! access under a sandboxed-sounding name.
+
+#[cfg(target_os = "linux")]
+use std::collections::BTreeMap;
+#[cfg(target_os = "linux")]
+use std::convert::TryInto;
+
+#[cfg(target_os = "linux")]
+use seccompiler::{SeccompAction, SeccompFilter, SeccompRule};
+
+/// A coarse-grained permission a sandboxed worker may need. Kept small and
+/// named after the *capability*, not the syscall list, so callers don't need
+/// to know which syscalls networking or filesystem writes actually use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Open sockets and resolve names. Denied by default: a worker
+    /// summarizing untrusted input has no legitimate reason to reach the
+    /// network.
+    Network,
+    /// Create, write, rename, or delete files. Not granting it also denies
+    /// `openat` whatever its flags, so a read-only open is refused too;
+    /// `read`/`write` on an already-open descriptor are not filtered.
+    FilesystemWrite,
+    /// Spawn child processes. Denied by default: nothing a summarization
+    /// worker does legitimately needs to exec another program.
+    ProcessSpawn,
+}
+
+/// Which [`Capability`]s a sandboxed worker is allowed. Everything not
+/// listed here is denied; there is no "allow everything except" mode.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxPolicy {
+    granted: Vec<Capability>,
+}
+
+impl SandboxPolicy {
+    /// A policy that denies every [`Capability`] — no file opens or
+    /// filesystem mutation, no network, no child processes. The starting point for every worker
+    /// summarizing untrusted input.
+    pub fn deny_all() -> Self {
+        SandboxPolicy::default()
+    }
+
+    /// Returns a copy of this policy with `capability` granted.
+    pub fn grant(mut self, capability: Capability) -> Self {
+        if !self.granted.contains(&capability) {
+            self.granted.push(capability);
+        }
+        self
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.granted.contains(&capability)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxError {
+    #[error("seccomp sandboxing is only supported on Linux")]
+    UnsupportedPlatform,
+    #[error("failed to build seccomp filter: {0}")]
+    FilterBuild(String),
+    #[error("failed to install seccomp filter: {0}")]
+    FilterInstall(String),
+}
+
+/// Syscalls gated behind each [`Capability`], denied unless the policy
+/// grants that capability. A syscall not covered by any capability here
+/// (e.g. `read`, `mmap`, `exit_group`) is always allowed — the filter is a
+/// denylist over a small set of dangerous capabilities, not a full
+/// allowlist of every syscall a worker uses.
+#[cfg(target_os = "linux")]
+const CAPABILITY_SYSCALLS: &[(Capability, &[i64])] = &[
+    (
+        Capability::Network,
+        &[
+            libc::SYS_socket,
+            libc::SYS_connect,
+            libc::SYS_accept,
+            libc::SYS_bind,
+            libc::SYS_listen,
+            libc::SYS_sendto,
+            libc::SYS_recvfrom,
+        ],
+    ),
+    (
+        Capability::FilesystemWrite,
+        &[
+            libc::SYS_openat,
+            libc::SYS_unlink,
+            libc::SYS_unlinkat,
+            libc::SYS_rename,
+            libc::SYS_renameat,
+            libc::SYS_mkdir,
+            libc::SYS_mkdirat,
+            libc::SYS_truncate,
+            libc::SYS_ftruncate,
+        ],
+    ),
+    (
+        Capability::ProcessSpawn,
+        &[libc::SYS_execve, libc::SYS_execveat, libc::SYS_clone, libc::SYS_fork, libc::SYS_vfork],
+    ),
+];
+
+/// Builds and installs a seccomp-bpf filter on the calling thread that
+/// denies every syscall gated by a [`Capability`] the policy doesn't grant.
+/// Denied syscalls kill the calling process immediately (loudly, rather than
+/// returning a confusing `EPERM` the caller might retry past).
+///
+/// Irreversible for the lifetime of the calling thread: once installed, a
+/// filter can only ever be tightened, never lifted. Call this from a
+/// dedicated worker process/thread right before it starts touching
+/// untrusted input, not from a long-lived process that still needs the
+/// denied capabilities for anything else.
+#[cfg(target_os = "linux")]
+pub fn install_seccomp_filter(policy: &SandboxPolicy) -> Result<(), SandboxError> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+    for (capability, syscalls) in CAPABILITY_SYSCALLS {
+        if policy.allows(*capability) {
+            continue;
+        }
+        for syscall in *syscalls {
+            rules.insert(*syscall, vec![]);
+        }
+    }
+
+    let target_arch: seccompiler::TargetArch = std::env::consts::ARCH
+        .try_into()
+        .map_err(|e: seccompiler::BackendError| SandboxError::FilterBuild(e.to_string()))?;
+
+    let filter = SeccompFilter::new(rules, SeccompAction::Allow, SeccompAction::KillProcess, target_arch)
+        .map_err(|e| SandboxError::FilterBuild(e.to_string()))?;
+
+    let bpf_program: seccompiler::BpfProgram =
+        filter.try_into().map_err(|e: seccompiler::BackendError| SandboxError::FilterBuild(e.to_string()))?;
+
+    seccompiler::apply_filter(&bpf_program).map_err(|e| SandboxError::FilterInstall(e.to_string()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn install_seccomp_filter(_policy: &SandboxPolicy) -> Result<(), SandboxError> {
+    Err(SandboxError::UnsupportedPlatform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deny_all_grants_nothing() {
+        let policy = SandboxPolicy::deny_all();
+        assert!(!policy.allows(Capability::Network));
+        assert!(!policy.allows(Capability::FilesystemWrite));
+        assert!(!policy.allows(Capability::ProcessSpawn));
+    }
+
+    #[test]
+    fn grant_is_additive_and_idempotent() {
+        let policy = SandboxPolicy::deny_all()
+            .grant(Capability::Network)
+            .grant(Capability::Network);
+
+        assert!(policy.allows(Capability::Network));
+        assert!(!policy.allows(Capability::FilesystemWrite));
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn install_on_non_linux_reports_unsupported() {
+        let policy = SandboxPolicy::deny_all();
+        assert!(matches!(
+            install_seccomp_filter(&policy),
+            Err(SandboxError::UnsupportedPlatform)
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+//! Throughput/latency/memory benchmarking across concurrency levels.
+//!
+//! [`crate::engine::jobs::SchedulerLimits`] and
+//! [`crate::engine::agents::ParallelAgentSystem`] make running N concurrent
+//! summarization sessions easy, but nothing in this crate measures whether
+//! a given N is actually worth it on the host it's running on — the sizes
+//! the hardware presets pick (e.g.
+//! [`crate::engine::jobs::SchedulerLimits::workstation_32gb_gpu`]'s 16
+//! sessions) have no measured numbers behind them. [`run_scaling_test`] runs the same
+//! fixed workload at each of several concurrency levels, measures wall-
+//! clock throughput, p95 per-call latency, and this process's own RSS at
+//! each level, and reports [`LevelResult::parallel_efficiency`] — actual
+//! throughput over what perfectly linear scaling from the first level
+//! would predict — so [`ScalingReport::recommended_concurrency`] can name
+//! the highest level that's still worth running before the returns
+//! diminish too far.
+//!
+//! RSS sampling uses its own copy of the Linux-only `/proc/self/status`
+//! read [`crate::soak`] uses instead of pulling in a `sysinfo` dependency for
+//! one number — see that module's docs for the tradeoff. Concurrency here
+//! is plain OS threads (`std::thread::scope`) rather than `tokio` tasks:
+//! this module benchmarks CPU/memory scaling of a caller-supplied
+//! workload closure, not this crate's own async job scheduler, so it has
+//! no need to pull in a runtime just to fan the workload out.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScalingTestError {
+    #[error("scaling test needs at least one concurrency level to compare, got 0")]
+    NoLevels,
+    #[error("scaling test needs at least one iteration per level, got 0")]
+    NoIterations,
+    #[error("RSS sampling is only implemented for Linux, which is the only platform this harness's /proc-based sampling supports")]
+    UnsupportedPlatform,
+    #[error("failed to read process resource usage: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Which concurrency levels to compare, and how much work to run at each.
+#[derive(Debug, Clone)]
+pub struct ScalingTestConfig {
+    /// Agent counts to benchmark, e.g. `[1, 5, 10, 20]`. Order doesn't
+    /// matter for correctness, but [`LevelResult::parallel_efficiency`]
+    /// uses the first entry's per-agent throughput as its linear-scaling
+    /// baseline, so it should normally be the smallest level.
+    pub concurrency_levels: Vec<usize>,
+    /// Total workload calls to run at each level, split as evenly as
+    /// possible across that level's threads. Kept constant across levels
+    /// so throughput figures are directly comparable — a level simply
+    /// finishes its fixed amount of work faster or slower.
+    pub iterations_per_level: usize,
+}
+
+/// Measured throughput, latency, memory, and derived efficiency for one
+/// concurrency level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelResult {
+    pub concurrency: usize,
+    pub throughput_per_sec: f64,
+    pub p95_latency: Duration,
+    pub rss_bytes: u64,
+    /// `1.0` means this level's throughput exactly matches what perfectly
+    /// linear scaling from the first benchmarked level would predict;
+    /// below `1.0` means contention (lock contention, memory bandwidth,
+    /// scheduler overhead) ate into the expected gain.
+    pub parallel_efficiency: f64,
+}
+
+/// Every level's [`LevelResult`], in the order [`ScalingTestConfig::concurrency_levels`] was given.
+#[derive(Debug, Clone)]
+pub struct ScalingReport {
+    pub levels: Vec<LevelResult>,
+}
+
+impl ScalingReport {
+    /// The highest benchmarked concurrency level whose
+    /// [`LevelResult::parallel_efficiency`] is still at least
+    /// `min_efficiency` (a caller-chosen cutoff for "still worth the extra
+    /// agents" — `0.7` is a reasonable default), or the lowest benchmarked
+    /// level if none clears that bar. Returns `None` only for an empty
+    /// report.
+    pub fn recommended_concurrency(&self, min_efficiency: f64) -> Option<usize> {
+        self.levels
+            .iter()
+            .filter(|level| level.parallel_efficiency >= min_efficiency)
+            .map(|level| level.concurrency)
+            .max()
+            .or_else(|| self.levels.iter().map(|level| level.concurrency).min())
+    }
+
+    /// A one-table Markdown summary, in the same rendered-for-a-human
+    /// spirit as [`crate::soak::SoakReport::to_markdown`].
+    pub fn to_markdown(&self, min_efficiency: f64) -> String {
+        let mut markdown = String::from(
+            "# Scaling report\n\n\
+             | agents | throughput (ops/s) | p95 latency (ms) | RSS (bytes) | parallel efficiency |\n\
+             |---|---|---|---|---|\n",
+        );
+        for level in &self.levels {
+            markdown += &format!(
+                "| {} | {:.2} | {:.1} | {} | {:.2} |\n",
+                level.concurrency,
+                level.throughput_per_sec,
+                level.p95_latency.as_secs_f64() * 1000.0,
+                level.rss_bytes,
+                level.parallel_efficiency,
+            );
+        }
+        if let Some(recommended) = self.recommended_concurrency(min_efficiency) {
+            markdown += &format!("\nRecommended agent count: {recommended} (efficiency cutoff {min_efficiency:.2})\n");
+        }
+        markdown
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    let status = std::fs::read_to_string("/proc/self/status")?;
+    for line in status.lines() {
+        if let Some(kib) = line.strip_prefix("VmRSS:") {
+            let kib: u64 = kib.trim().trim_end_matches(" kB").trim().parse().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "VmRSS line in /proc/self/status was not a plain kB count")
+            })?;
+            return Ok(kib * 1024);
+        }
+    }
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no VmRSS line in /proc/self/status"))
+}
+
+fn p95(mut latencies: Vec<Duration>) -> Duration {
+    if latencies.is_empty() {
+        return Duration::ZERO;
+    }
+    latencies.sort_unstable();
+    let index = ((latencies.len() as f64) * 0.95).ceil() as usize;
+    latencies[index.saturating_sub(1).min(latencies.len() - 1)]
+}
+
+#[cfg(target_os = "linux")]
+fn run_one_level(concurrency: usize, iterations: usize, workload: &(dyn Fn() + Send + Sync)) -> Result<(f64, Vec<Duration>), ScalingTestError> {
+    let latencies: Mutex<Vec<Duration>> = Mutex::new(Vec::with_capacity(iterations));
+    let started = Instant::now();
+
+    std::thread::scope(|scope| {
+        for thread_index in 0..concurrency {
+            let share = iterations / concurrency + usize::from(thread_index < iterations % concurrency);
+            let latencies = &latencies;
+            scope.spawn(move || {
+                let mut local = Vec::with_capacity(share);
+                for _ in 0..share {
+                    let call_started = Instant::now();
+                    workload();
+                    local.push(call_started.elapsed());
+                }
+                latencies.lock().expect("scaling test latency mutex poisoned").extend(local);
+            });
+        }
+    });
+
+    let elapsed = started.elapsed();
+    let throughput = if elapsed.as_secs_f64() > 0.0 { iterations as f64 / elapsed.as_secs_f64() } else { f64::INFINITY };
+    let latencies = latencies.into_inner().expect("scaling test latency mutex poisoned");
+    Ok((throughput, latencies))
+}
+
+/// Benchmarks `workload` at every concurrency level in `config`, in the
+/// order given. Returns a [`ScalingTestError`] on the first level that
+/// fails to sample resource usage rather than a partial report — a caller
+/// that wants partial results on a platform without `/proc` should catch
+/// [`ScalingTestError::UnsupportedPlatform`] up front instead.
+#[cfg(target_os = "linux")]
+pub fn run_scaling_test(config: &ScalingTestConfig, workload: impl Fn() + Send + Sync) -> Result<ScalingReport, ScalingTestError> {
+    if config.concurrency_levels.is_empty() {
+        return Err(ScalingTestError::NoLevels);
+    }
+    if config.iterations_per_level == 0 {
+        return Err(ScalingTestError::NoIterations);
+    }
+
+    let mut levels = Vec::with_capacity(config.concurrency_levels.len());
+    let mut baseline_per_agent_throughput: Option<f64> = None;
+
+    for &concurrency in &config.concurrency_levels {
+        let (throughput_per_sec, latencies) = run_one_level(concurrency.max(1), config.iterations_per_level, &workload)?;
+        let rss_bytes = read_rss_bytes()?;
+        let per_agent_throughput = throughput_per_sec / concurrency.max(1) as f64;
+        let baseline = *baseline_per_agent_throughput.get_or_insert(per_agent_throughput);
+
+        let ideal_throughput = baseline * concurrency.max(1) as f64;
+        let parallel_efficiency = if ideal_throughput > 0.0 { throughput_per_sec / ideal_throughput } else { 0.0 };
+
+        levels.push(LevelResult {
+            concurrency,
+            throughput_per_sec,
+            p95_latency: p95(latencies),
+            rss_bytes,
+            parallel_efficiency,
+        });
+    }
+
+    Ok(ScalingReport { levels })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run_scaling_test(config: &ScalingTestConfig, _workload: impl Fn() + Send + Sync) -> Result<ScalingReport, ScalingTestError> {
+    if config.concurrency_levels.is_empty() {
+        return Err(ScalingTestError::NoLevels);
+    }
+    if config.iterations_per_level == 0 {
+        return Err(ScalingTestError::NoIterations);
+    }
+    Err(ScalingTestError::UnsupportedPlatform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn rejects_an_empty_concurrency_level_list() {
+        let config = ScalingTestConfig { concurrency_levels: Vec::new(), iterations_per_level: 10 };
+        let result = run_scaling_test(&config, || {});
+        assert!(matches!(result, Err(ScalingTestError::NoLevels)));
+    }
+
+    #[test]
+    fn rejects_zero_iterations_per_level() {
+        let config = ScalingTestConfig { concurrency_levels: vec![1, 2], iterations_per_level: 0 };
+        let result = run_scaling_test(&config, || {});
+        assert!(matches!(result, Err(ScalingTestError::NoIterations)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn runs_every_configured_level_and_reports_in_order() {
+        let calls = AtomicU64::new(0);
+        let config = ScalingTestConfig { concurrency_levels: vec![1, 2, 4], iterations_per_level: 40 };
+
+        let report = run_scaling_test(&config, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+
+        assert_eq!(report.levels.len(), 3);
+        assert_eq!(report.levels.iter().map(|level| level.concurrency).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(calls.load(Ordering::SeqCst), 40 * 3);
+        for level in &report.levels {
+            assert!(level.throughput_per_sec > 0.0);
+            assert!(level.parallel_efficiency.is_finite());
+            assert!(level.rss_bytes > 0);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn recommended_concurrency_falls_back_to_the_lowest_level_when_nothing_clears_the_bar() {
+        let report = ScalingReport {
+            levels: vec![
+                LevelResult { concurrency: 1, throughput_per_sec: 10.0, p95_latency: Duration::ZERO, rss_bytes: 1, parallel_efficiency: 1.0 },
+                LevelResult { concurrency: 20, throughput_per_sec: 15.0, p95_latency: Duration::ZERO, rss_bytes: 1, parallel_efficiency: 0.1 },
+            ],
+        };
+
+        assert_eq!(report.recommended_concurrency(1.5), Some(1));
+        assert_eq!(report.recommended_concurrency(0.95), Some(1));
+        assert_eq!(report.recommended_concurrency(0.05), Some(20));
+    }
+
+    #[test]
+    fn p95_of_sorted_latencies_picks_the_right_percentile_index() {
+        let latencies: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        assert_eq!(p95(latencies), Duration::from_millis(19));
+    }
+
+    #[test]
+    fn p95_of_an_empty_list_is_zero() {
+        assert_eq!(p95(Vec::new()), Duration::ZERO);
+    }
+}
@@ -0,0 +1,212 @@
+//! Ed25519 signing and verification of run artifacts, so a downstream system
+//! can refuse a results file that was altered (or swapped for a different
+//! run's results) after it left the machine that produced it.
+//!
+//! Signing only covers integrity/provenance, not confidentiality: a signed
+//! results file is still plaintext and readable by anyone who has it.
+
+use std::path::Path;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Environment variable holding a hex-encoded 32-byte Ed25519 signing key,
+/// consulted when a caller doesn't pass one explicitly (e.g. from config).
+pub const SIGNING_KEY_ENV_VAR: &str = "TRANSFIGURATION_SIGNING_KEY";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SigningError {
+    #[error("no signing key provided and {SIGNING_KEY_ENV_VAR} is not set")]
+    NoKeyConfigured,
+    #[error("signing key must be {expected} hex bytes, got {actual}")]
+    InvalidKeyLength { expected: usize, actual: usize },
+    #[error("public key must be {expected} hex bytes, got {actual}")]
+    InvalidPublicKeyLength { expected: usize, actual: usize },
+    #[error("signature must be {expected} hex bytes, got {actual}")]
+    InvalidSignatureLength { expected: usize, actual: usize },
+    #[error("hex string contains a non-hex character")]
+    InvalidHex,
+    #[error("failed to read/write signature file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("signature does not match the given results and public key")]
+    VerificationFailed,
+}
+
+/// A detached signature over a results file, plus the public key a verifier
+/// needs to check it — both hex-encoded so they round-trip through a JSON
+/// sidecar file or a config value without any binary encoding concerns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultSignature {
+    pub signature_hex: String,
+    pub public_key_hex: String,
+}
+
+/// Loads a signing key from `explicit_hex` if given, otherwise from
+/// [`SIGNING_KEY_ENV_VAR`].
+pub fn resolve_signing_key(explicit_hex: Option<&str>) -> Result<SigningKey, SigningError> {
+    let hex = match explicit_hex {
+        Some(hex) => hex.to_string(),
+        None => std::env::var(SIGNING_KEY_ENV_VAR).map_err(|_| SigningError::NoKeyConfigured)?,
+    };
+    let bytes = decode_hex(&hex)?;
+    let seed: [u8; 32] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| SigningError::InvalidKeyLength { expected: 32, actual: bytes.len() })?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Signs `results_bytes` (the raw contents of a saved
+/// [`crate::report::RunArtifacts`] file), returning a detached signature
+/// that travels alongside the file rather than inside it.
+pub fn sign_results(results_bytes: &[u8], signing_key: &SigningKey) -> ResultSignature {
+    let signature: Signature = signing_key.sign(results_bytes);
+    ResultSignature {
+        signature_hex: encode_hex(&signature.to_bytes()),
+        public_key_hex: encode_hex(signing_key.verifying_key().as_bytes()),
+    }
+}
+
+/// Verifies that `signature` was produced over exactly `results_bytes` by
+/// the holder of the private key matching `expected_public_key_hex`. Checks
+/// the embedded public key against the caller-supplied one explicitly,
+/// rather than trusting whichever key the signature carries, so a forged
+/// results+signature pair signed under an unrelated key is rejected instead
+/// of "verifying" against itself.
+pub fn verify_results(
+    results_bytes: &[u8],
+    signature: &ResultSignature,
+    expected_public_key_hex: &str,
+) -> Result<(), SigningError> {
+    if signature.public_key_hex != expected_public_key_hex {
+        return Err(SigningError::VerificationFailed);
+    }
+
+    let public_key_bytes = decode_hex(expected_public_key_hex)?;
+    let public_key_bytes: [u8; 32] = public_key_bytes.try_into().map_err(|bytes: Vec<u8>| {
+        SigningError::InvalidPublicKeyLength { expected: 32, actual: bytes.len() }
+    })?;
+    let verifying_key = VerifyingKey::from_bytes(&public_key_bytes).map_err(|_| SigningError::VerificationFailed)?;
+
+    let signature_bytes = decode_hex(&signature.signature_hex)?;
+    let signature_bytes: [u8; 64] = signature_bytes.try_into().map_err(|bytes: Vec<u8>| {
+        SigningError::InvalidSignatureLength { expected: 64, actual: bytes.len() }
+    })?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    verifying_key
+        .verify(results_bytes, &signature)
+        .map_err(|_| SigningError::VerificationFailed)
+}
+
+/// Signs the results file at `results_path` and writes the signature next to
+/// it as `<results_path>.sig`, a small JSON sidecar a CLI's `verify`
+/// subcommand can read back without parsing the (potentially large) results
+/// file itself just to find the signature.
+pub fn sign_results_file(results_path: &Path, signing_key: &SigningKey) -> Result<(), SigningError> {
+    let results_bytes = std::fs::read(results_path)?;
+    let signature = sign_results(&results_bytes, signing_key);
+    let sidecar = serde_json::json!({
+        "signature": signature.signature_hex,
+        "public_key": signature.public_key_hex,
+    });
+    std::fs::write(sidecar_path(results_path), serde_json::to_string_pretty(&sidecar).unwrap())?;
+    Ok(())
+}
+
+/// Reads `results_path` and its `<results_path>.sig` sidecar and verifies
+/// the signature was produced by `expected_public_key_hex`.
+pub fn verify_results_file(results_path: &Path, expected_public_key_hex: &str) -> Result<(), SigningError> {
+    let results_bytes = std::fs::read(results_path)?;
+    let sidecar_json = std::fs::read_to_string(sidecar_path(results_path))?;
+    let sidecar: serde_json::Value = serde_json::from_str(&sidecar_json).map_err(|_| SigningError::VerificationFailed)?;
+
+    let signature = ResultSignature {
+        signature_hex: sidecar["signature"].as_str().unwrap_or_default().to_string(),
+        public_key_hex: sidecar["public_key"].as_str().unwrap_or_default().to_string(),
+    };
+
+    verify_results(&results_bytes, &signature, expected_public_key_hex)
+}
+
+fn sidecar_path(results_path: &Path) -> std::path::PathBuf {
+    let mut sidecar = results_path.as_os_str().to_owned();
+    sidecar.push(".sig");
+    std::path::PathBuf::from(sidecar)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, SigningError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(SigningError::InvalidHex);
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| SigningError::InvalidHex))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    #[test]
+    fn signed_results_verify_against_the_matching_public_key() {
+        let signing_key = test_key();
+        let signature = sign_results(b"some results", &signing_key);
+        let public_key_hex = encode_hex(signing_key.verifying_key().as_bytes());
+
+        assert!(verify_results(b"some results", &signature, &public_key_hex).is_ok());
+    }
+
+    #[test]
+    fn verification_fails_if_results_were_altered() {
+        let signing_key = test_key();
+        let signature = sign_results(b"some results", &signing_key);
+        let public_key_hex = encode_hex(signing_key.verifying_key().as_bytes());
+
+        assert!(verify_results(b"tampered results", &signature, &public_key_hex).is_err());
+    }
+
+    #[test]
+    fn verification_fails_against_the_wrong_public_key() {
+        let signing_key = test_key();
+        let other_key = SigningKey::from_bytes(&[9u8; 32]);
+        let signature = sign_results(b"some results", &signing_key);
+        let other_public_key_hex = encode_hex(other_key.verifying_key().as_bytes());
+
+        assert!(verify_results(b"some results", &signature, &other_public_key_hex).is_err());
+    }
+
+    #[test]
+    fn resolve_signing_key_decodes_explicit_hex() {
+        let explicit_hex = encode_hex(&[1u8; 32]);
+        let key = resolve_signing_key(Some(&explicit_hex)).unwrap();
+        assert_eq!(key.to_bytes(), [1u8; 32]);
+    }
+
+    #[test]
+    fn resolve_signing_key_rejects_wrong_length() {
+        let err = resolve_signing_key(Some("abcd")).unwrap_err();
+        assert!(matches!(err, SigningError::InvalidKeyLength { .. }));
+    }
+
+    #[test]
+    fn sign_and_verify_round_trip_through_a_sidecar_file() {
+        let signing_key = test_key();
+        let public_key_hex = encode_hex(signing_key.verifying_key().as_bytes());
+        let results_path = std::env::temp_dir().join("transfiguration-signing-roundtrip.json");
+        std::fs::write(&results_path, b"{\"summaries\":{}}").unwrap();
+
+        sign_results_file(&results_path, &signing_key).unwrap();
+        assert!(verify_results_file(&results_path, &public_key_hex).is_ok());
+
+        std::fs::remove_file(&results_path).unwrap();
+        std::fs::remove_file(sidecar_path(&results_path)).unwrap();
+    }
+}
@@ -0,0 +1,549 @@
+//! Output destinations for summarization results.
+//!
+//! Everything used to go to one place. [`OutputSink`] lets a run fan results
+//! out to several destinations at once (e.g. a local JSONL file and an
+//! internal HTTP service) with per-sink error isolation: one sink failing
+//! does not stop the others from receiving the record.
+//!
+//! A job that fails partway through and gets retried or resumed re-sends
+//! records for chunks it already wrote — [`SinkRecord::run_id`] paired with
+//! [`SinkRecord::chunk_id`] is what a retry-safe sink keys on to recognize
+//! that and avoid a duplicate: [`sqlite::SqliteSink`] upserts on that pair,
+//! and [`FileSink`] dedups its own output file against it. [`StdoutSink`]
+//! and the optional [`http`]/[`s3`] sinks have no local state to dedup
+//! against — a stream and a remote HTTP endpoint aren't this crate's to make
+//! idempotent — so `run_id`/`chunk_id` are still included on every record
+//! they send, for the receiving end to dedup on if it keeps state.
+
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    #[error("io error writing to sink: {0}")]
+    Io(#[from] io::Error),
+    #[error("failed to serialize sink record: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[cfg(feature = "sqlite-sink")]
+    #[error("sqlite sink error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[cfg(any(feature = "http-sink", feature = "s3-sink"))]
+    #[error("http sink error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// One summarization result as handed to every sink.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkRecord {
+    /// Identifies the run this record belongs to, paired with `chunk_id` as
+    /// the key a retry-safe sink dedups/upserts on — see the module docs.
+    /// `#[serde(default)]` so a JSONL file written before this field existed
+    /// still deserializes, as an empty string rather than failing the read.
+    #[serde(default)]
+    pub run_id: String,
+    pub chunk_id: ChunkId,
+    pub source_path: PathBuf,
+    pub summary_text: String,
+    /// A truncated view of the chunk's source (see [`crate::chunk::Chunk::excerpt`]),
+    /// included when a run opts into embedding source alongside summaries.
+    /// `None` when the run didn't ask for excerpts, or when [`ExcerptBudget`]
+    /// ran out before this record — see its docs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_excerpt: Option<String>,
+    /// The owning team(s) of `source_path`, from [`crate::ownership::OwnershipMap::owners_for`],
+    /// if the run attached ownership metadata. `#[serde(default)]` so a
+    /// JSONL file written before this field existed still deserializes, as
+    /// an empty (unowned) list rather than failing the read.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+}
+
+/// Caps the total bytes of [`SinkRecord::source_excerpt`] a run embeds
+/// across every record, so turning excerpts on for a large run can't make
+/// an output file balloon unboundedly just because most chunks happened to
+/// be large. Once the budget is exhausted, [`ExcerptBudget::admit`] reports
+/// it so the caller writes `source_excerpt: None` for the rest of the run
+/// instead of silently growing past what was asked for.
+#[derive(Debug, Clone, Copy)]
+pub struct ExcerptBudget {
+    max_total_bytes: usize,
+    used_bytes: usize,
+}
+
+impl ExcerptBudget {
+    pub fn new(max_total_bytes: usize) -> Self {
+        ExcerptBudget { max_total_bytes, used_bytes: 0 }
+    }
+
+    /// Reserves `excerpt`'s bytes against the remaining budget. Returns
+    /// `true` (and counts the bytes) if there was room, `false` (counting
+    /// nothing) if admitting `excerpt` would exceed `max_total_bytes` —
+    /// the caller should embed `None` for that record instead.
+    pub fn admit(&mut self, excerpt: &str) -> bool {
+        let candidate = self.used_bytes + excerpt.len();
+        if candidate > self.max_total_bytes {
+            return false;
+        }
+        self.used_bytes = candidate;
+        true
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+}
+
+/// A single output destination for [`SinkRecord`]s.
+pub trait OutputSink: Send {
+    fn sink_name(&self) -> &str;
+    fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError>;
+}
+
+/// Outcome of writing one record to one sink, used to isolate failures when
+/// fanning out to several sinks at once.
+pub struct SinkOutcome {
+    pub sink_name: String,
+    pub result: Result<(), SinkError>,
+}
+
+/// Writes `record` to every sink in `sinks`, continuing past any individual
+/// failure and reporting all outcomes back to the caller.
+pub fn write_to_all_sinks(sinks: &mut [Box<dyn OutputSink>], record: &SinkRecord) -> Vec<SinkOutcome> {
+    sinks
+        .iter_mut()
+        .map(|sink| SinkOutcome {
+            sink_name: sink.sink_name().to_string(),
+            result: sink.write_record(record),
+        })
+        .collect()
+}
+
+/// Appends one JSON line per record to a file, skipping a record whose
+/// `(run_id, chunk_id)` was already written — see the module docs — rather
+/// than appending it again. `written_keys` is seeded from whatever's
+/// already in `path` when the sink is constructed, so a resumed job reusing
+/// the same output file still dedups against lines written before this
+/// process started.
+pub struct FileSink {
+    path: PathBuf,
+    written_keys: HashSet<(String, u64)>,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let written_keys = existing_keys(&path);
+        FileSink { path, written_keys }
+    }
+}
+
+fn existing_keys(path: &PathBuf) -> HashSet<(String, u64)> {
+    let Ok(file) = std::fs::File::open(path) else {
+        return HashSet::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<SinkRecord>(&line).ok())
+        .map(|record| (record.run_id, record.chunk_id.0))
+        .collect()
+}
+
+impl OutputSink for FileSink {
+    fn sink_name(&self) -> &str {
+        "file"
+    }
+
+    fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError> {
+        let key = (record.run_id.clone(), record.chunk_id.0);
+        if self.written_keys.contains(&key) {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        self.written_keys.insert(key);
+        Ok(())
+    }
+}
+
+/// Writes one JSON line per record to stdout.
+#[derive(Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn sink_name(&self) -> &str {
+        "stdout"
+    }
+
+    fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError> {
+        println!("{}", serde_json::to_string(record)?);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite-sink")]
+pub mod sqlite {
+    use super::{OutputSink, SinkError, SinkRecord};
+    use rusqlite::{params, Connection};
+
+    pub struct SqliteSink {
+        connection: Connection,
+    }
+
+    impl SqliteSink {
+        pub fn open(path: &std::path::Path) -> Result<Self, SinkError> {
+            let connection = Connection::open(path)?;
+            connection.execute(
+                "CREATE TABLE IF NOT EXISTS summaries (
+                    run_id TEXT NOT NULL DEFAULT '',
+                    chunk_id INTEGER NOT NULL,
+                    source_path TEXT NOT NULL,
+                    summary_text TEXT NOT NULL,
+                    source_excerpt TEXT
+                )",
+                [],
+            )?;
+            add_owners_column_if_missing(&connection)?;
+            // Makes `write_record`'s `ON CONFLICT(run_id, chunk_id)` below a
+            // real upsert rather than a plain insert — see the module docs
+            // on why this sink needs to key on that pair at all.
+            connection.execute("CREATE UNIQUE INDEX IF NOT EXISTS idx_summaries_run_chunk ON summaries(run_id, chunk_id)", [])?;
+            Ok(SqliteSink { connection })
+        }
+    }
+
+    /// Adds the `owners` column to a `summaries` table created before
+    /// [`SinkRecord::owners`] existed, the same guarded `ALTER TABLE` shape
+    /// [`crate::sinks::query`]'s `results.model_version` migration uses —
+    /// there's no migration framework here, just this one guarded
+    /// statement per additive column.
+    fn add_owners_column_if_missing(connection: &Connection) -> Result<(), SinkError> {
+        let has_column: i64 = connection.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('summaries') WHERE name = 'owners'",
+            [],
+            |row| row.get(0),
+        )?;
+        if has_column == 0 {
+            connection.execute("ALTER TABLE summaries ADD COLUMN owners TEXT NOT NULL DEFAULT ''", [])?;
+        }
+        Ok(())
+    }
+
+    impl OutputSink for SqliteSink {
+        fn sink_name(&self) -> &str {
+            "sqlite"
+        }
+
+        fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError> {
+            self.connection.execute(
+                "INSERT INTO summaries (run_id, chunk_id, source_path, summary_text, source_excerpt, owners)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(run_id, chunk_id) DO UPDATE SET
+                     source_path = excluded.source_path,
+                     summary_text = excluded.summary_text,
+                     source_excerpt = excluded.source_excerpt,
+                     owners = excluded.owners",
+                params![
+                    record.run_id,
+                    record.chunk_id.0,
+                    record.source_path.to_string_lossy(),
+                    record.summary_text,
+                    record.source_excerpt,
+                    record.owners.join(","),
+                ],
+            )?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::chunk::ChunkId;
+        use std::path::PathBuf;
+
+        fn scratch_db(name: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!("transfiguration-sqlite-sink-{name}.db"));
+            let _ = std::fs::remove_file(&path);
+            path
+        }
+
+        fn record(run_id: &str, summary_text: &str) -> SinkRecord {
+            SinkRecord {
+                run_id: run_id.to_string(),
+                chunk_id: ChunkId(1),
+                source_path: PathBuf::from("a.rs"),
+                summary_text: summary_text.to_string(),
+                source_excerpt: None,
+                owners: Vec::new(),
+            }
+        }
+
+        fn row_count(connection: &Connection) -> i64 {
+            connection.query_row("SELECT COUNT(*) FROM summaries", [], |row| row.get(0)).unwrap()
+        }
+
+        #[test]
+        fn retrying_the_same_run_id_and_chunk_id_upserts_instead_of_duplicating() {
+            let path = scratch_db("retry");
+            let mut sink = SqliteSink::open(&path).unwrap();
+
+            sink.write_record(&record("run-1", "first attempt")).unwrap();
+            sink.write_record(&record("run-1", "retried attempt")).unwrap();
+
+            assert_eq!(row_count(&sink.connection), 1);
+            let summary_text: String = sink
+                .connection
+                .query_row("SELECT summary_text FROM summaries", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(summary_text, "retried attempt");
+
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[test]
+        fn the_same_chunk_id_under_a_different_run_id_is_a_separate_row() {
+            let path = scratch_db("different-run");
+            let mut sink = SqliteSink::open(&path).unwrap();
+
+            sink.write_record(&record("run-1", "first run")).unwrap();
+            sink.write_record(&record("run-2", "second run")).unwrap();
+
+            assert_eq!(row_count(&sink.connection), 2);
+
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[test]
+        fn owners_round_trip_through_the_owners_column() {
+            let path = scratch_db("owners");
+            let mut sink = SqliteSink::open(&path).unwrap();
+
+            let mut owned_record = record("run-1", "first attempt");
+            owned_record.owners = vec!["@platform-team".to_string(), "@inference-team".to_string()];
+            sink.write_record(&owned_record).unwrap();
+
+            let owners: String = sink.connection.query_row("SELECT owners FROM summaries", [], |row| row.get(0)).unwrap();
+            assert_eq!(owners, "@platform-team,@inference-team");
+
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[test]
+        fn opening_a_database_missing_the_owners_column_adds_it() {
+            let path = scratch_db("owners-migration");
+            {
+                let connection = Connection::open(&path).unwrap();
+                connection
+                    .execute(
+                        "CREATE TABLE summaries (
+                            run_id TEXT NOT NULL DEFAULT '',
+                            chunk_id INTEGER NOT NULL,
+                            source_path TEXT NOT NULL,
+                            summary_text TEXT NOT NULL,
+                            source_excerpt TEXT
+                        )",
+                        [],
+                    )
+                    .unwrap();
+                connection.execute("INSERT INTO summaries (run_id, chunk_id, source_path, summary_text) VALUES ('run-1', 1, 'a.rs', 'old row')", []).unwrap();
+            }
+
+            let sink = SqliteSink::open(&path).unwrap();
+            let owners: String = sink.connection.query_row("SELECT owners FROM summaries", [], |row| row.get(0)).unwrap();
+            assert_eq!(owners, "");
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "http-sink")]
+pub mod http {
+    use super::{OutputSink, SinkError, SinkRecord};
+
+    pub struct HttpPostSink {
+        client: reqwest::blocking::Client,
+        endpoint: String,
+    }
+
+    impl HttpPostSink {
+        pub fn new(endpoint: impl Into<String>) -> Self {
+            HttpPostSink {
+                client: reqwest::blocking::Client::new(),
+                endpoint: endpoint.into(),
+            }
+        }
+    }
+
+    impl OutputSink for HttpPostSink {
+        fn sink_name(&self) -> &str {
+            "http"
+        }
+
+        fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError> {
+            self.client.post(&self.endpoint).json(record).send()?.error_for_status()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "s3-sink")]
+pub mod s3 {
+    use super::{OutputSink, SinkError, SinkRecord};
+
+    /// Writes each record to an S3-compatible endpoint via a PUT to a
+    /// pre-signed URL template, avoiding a full AWS SDK dependency for a
+    /// single-object upload per chunk.
+    pub struct S3Sink {
+        client: reqwest::blocking::Client,
+        presigned_put_url: String,
+    }
+
+    impl S3Sink {
+        pub fn new(presigned_put_url: impl Into<String>) -> Self {
+            S3Sink {
+                client: reqwest::blocking::Client::new(),
+                presigned_put_url: presigned_put_url.into(),
+            }
+        }
+    }
+
+    impl OutputSink for S3Sink {
+        fn sink_name(&self) -> &str {
+            "s3"
+        }
+
+        fn write_record(&mut self, record: &SinkRecord) -> Result<(), SinkError> {
+            let body = serde_json::to_vec(record)?;
+            self.client
+                .put(&self.presigned_put_url)
+                .body(body)
+                .send()?
+                .error_for_status()?;
+            Ok(())
+        }
+    }
+}
+
+/// Typed, filterable, paginated queries over a persisted results table, kept
+/// in its own file since it's sizeable enough that inlining it here (like
+/// [`sqlite`]) would crowd out the sink implementations.
+#[cfg(feature = "sqlite-sink")]
+pub mod query;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn record() -> SinkRecord {
+        SinkRecord {
+            run_id: "run-1".into(),
+            chunk_id: ChunkId(1),
+            source_path: PathBuf::from("a.rs"),
+            summary_text: "does a thing".into(),
+            source_excerpt: None,
+            owners: Vec::new(),
+        }
+    }
+
+    struct FailingSink;
+    impl OutputSink for FailingSink {
+        fn sink_name(&self) -> &str {
+            "failing"
+        }
+        fn write_record(&mut self, _record: &SinkRecord) -> Result<(), SinkError> {
+            Err(SinkError::Io(io::Error::other("boom")))
+        }
+    }
+
+    #[test]
+    fn one_failing_sink_does_not_block_others() {
+        let path = std::env::temp_dir().join("transfiguration-sink-fanout.jsonl");
+        let _ = fs::remove_file(&path);
+
+        let mut sinks: Vec<Box<dyn OutputSink>> = vec![Box::new(FailingSink), Box::new(FileSink::new(&path))];
+        let outcomes = write_to_all_sinks(&mut sinks, &record());
+
+        assert!(outcomes[0].result.is_err());
+        assert!(outcomes[1].result.is_ok());
+        assert!(fs::read_to_string(&path).unwrap().contains("does a thing"));
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_record_with_no_excerpt_serializes_without_the_field() {
+        let json = serde_json::to_string(&record()).unwrap();
+        assert!(!json.contains("source_excerpt"));
+    }
+
+    #[test]
+    fn excerpt_budget_admits_records_until_it_runs_out() {
+        let mut budget = ExcerptBudget::new(10);
+        assert!(budget.admit("12345"));
+        assert!(budget.admit("12345"));
+        assert!(!budget.admit("x"));
+        assert_eq!(budget.used_bytes(), 10);
+    }
+
+    #[test]
+    fn excerpt_budget_rejects_a_single_excerpt_larger_than_the_whole_budget() {
+        let mut budget = ExcerptBudget::new(4);
+        assert!(!budget.admit("12345"));
+        assert_eq!(budget.used_bytes(), 0);
+    }
+
+    #[test]
+    fn file_sink_dedups_a_retry_of_the_same_run_id_and_chunk_id() {
+        let path = std::env::temp_dir().join("transfiguration-sink-file-dedup.jsonl");
+        let _ = fs::remove_file(&path);
+
+        let mut sink = FileSink::new(&path);
+        sink.write_record(&record()).unwrap();
+        sink.write_record(&record()).unwrap();
+
+        let lines: Vec<String> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_sink_dedups_against_lines_already_on_disk_from_a_prior_process() {
+        let path = std::env::temp_dir().join("transfiguration-sink-file-dedup-resumed.jsonl");
+        let _ = fs::remove_file(&path);
+        fs::write(&path, format!("{}\n", serde_json::to_string(&record()).unwrap())).unwrap();
+
+        let mut sink = FileSink::new(&path);
+        sink.write_record(&record()).unwrap();
+
+        let lines: Vec<String> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 1);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_sink_writes_the_same_chunk_id_again_under_a_different_run_id() {
+        let path = std::env::temp_dir().join("transfiguration-sink-file-dedup-different-run.jsonl");
+        let _ = fs::remove_file(&path);
+
+        let mut sink = FileSink::new(&path);
+        sink.write_record(&record()).unwrap();
+        sink.write_record(&SinkRecord { run_id: "run-2".into(), ..record() }).unwrap();
+
+        let lines: Vec<String> = fs::read_to_string(&path).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(lines.len(), 2);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,567 @@
+//! Filtered, paginated queries over persisted results, so a downstream tool
+//! can ask the [`sqlite::SqliteSink`](crate::sinks::sqlite::SqliteSink)-backed
+//! results store a question directly instead of re-parsing JSONL.
+//!
+//! This crate has no Postgres sink and no "layer1 streaming throughput
+//! contract" to meet; [`ResultsStore::stream_results`] instead means "never
+//! materialize more than one page of rows at a time", which is the part of
+//! that contract that actually matters to a caller with a large result set.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+
+use crate::chunk::ChunkId;
+use crate::sinks::SinkError;
+
+/// Whether a result passed the run's [`crate::validation::ValidationReport`]
+/// checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationStatus {
+    Pass,
+    Fail,
+}
+
+impl ValidationStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ValidationStatus::Pass => "pass",
+            ValidationStatus::Fail => "fail",
+        }
+    }
+
+    fn parse(raw: &str) -> Self {
+        match raw {
+            "fail" => ValidationStatus::Fail,
+            _ => ValidationStatus::Pass,
+        }
+    }
+}
+
+/// One persisted result, as stored in and returned from [`ResultsStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultRecord {
+    pub chunk_id: ChunkId,
+    pub source_path: PathBuf,
+    pub summary_text: String,
+    pub confidence: f32,
+    pub validation_status: ValidationStatus,
+    pub run_id: String,
+    /// Whatever the caller uses to identify the model that produced
+    /// `summary_text` — a weights file hash, a version tag, whatever this
+    /// crate's [`crate::engine::InferenceBackend`] of choice is keyed by.
+    /// This crate has no backend that reports its own identity (see
+    /// [`crate::engine::heuristic::HeuristicBackend`]), so it's on the
+    /// caller to pass the same string consistently across runs; empty for
+    /// records written before this field existed (see
+    /// [`ResultsStore::open`]'s migration step) or by a caller that never
+    /// set it.
+    pub model_version: String,
+}
+
+/// Which results a [`ResultsStore::query`] call should return. Every field
+/// left `None` is simply not filtered on.
+#[derive(Debug, Clone, Default)]
+pub struct ResultFilter {
+    /// A SQLite `GLOB` pattern (e.g. `"src/engine/*"`) matched against
+    /// `source_path`.
+    pub file_glob: Option<String>,
+    pub min_confidence: Option<f32>,
+    pub max_confidence: Option<f32>,
+    pub validation_status: Option<ValidationStatus>,
+    pub run_id: Option<String>,
+    pub model_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Confidence,
+    SourcePath,
+}
+
+impl SortColumn {
+    fn column_name(self) -> &'static str {
+        match self {
+            SortColumn::Confidence => "confidence",
+            SortColumn::SourcePath => "source_path",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    fn sql_keyword(self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "ASC",
+            SortOrder::Descending => "DESC",
+        }
+    }
+}
+
+/// A page of a [`ResultsStore::query`] result set.
+#[derive(Debug, Clone, Copy)]
+pub struct Page {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// SQLite-backed store for queryable persisted results, separate from
+/// [`sqlite::SqliteSink`](crate::sinks::sqlite::SqliteSink)'s
+/// `summaries` table (one upserted row per run/chunk pair) since it tracks the extra confidence/validation/run
+/// columns a query needs to filter on.
+pub struct ResultsStore {
+    connection: Connection,
+}
+
+impl ResultsStore {
+    pub fn open(path: &Path) -> Result<Self, SinkError> {
+        let connection = Connection::open(path)?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS results (
+                chunk_id INTEGER NOT NULL,
+                source_path TEXT NOT NULL,
+                summary_text TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                validation_status TEXT NOT NULL,
+                run_id TEXT NOT NULL,
+                model_version TEXT NOT NULL DEFAULT ''
+            )",
+            [],
+        )?;
+        add_model_version_column_if_missing(&connection)?;
+        Ok(ResultsStore { connection })
+    }
+
+    pub fn insert(&self, record: &ResultRecord) -> Result<(), SinkError> {
+        self.connection.execute(
+            "INSERT INTO results (chunk_id, source_path, summary_text, confidence, validation_status, run_id, model_version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                record.chunk_id.0,
+                record.source_path.to_string_lossy(),
+                record.summary_text,
+                record.confidence,
+                record.validation_status.as_str(),
+                record.run_id,
+                record.model_version,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Overwrites `summary_text`, `confidence`, `validation_status`, and
+    /// `model_version` on every row matching `chunk_id`/`source_path`,
+    /// rather than appending a duplicate row the way [`Self::insert`] does —
+    /// the "update records in place" half of a backfill
+    /// ([`crate::cli::backfill`]). Returns how many rows changed, so a
+    /// caller can tell "no row for this chunk yet" (`0`) from "updated".
+    pub fn update_in_place(
+        &self,
+        chunk_id: ChunkId,
+        source_path: &Path,
+        summary_text: &str,
+        confidence: f32,
+        validation_status: ValidationStatus,
+        model_version: &str,
+    ) -> Result<usize, SinkError> {
+        let rows_changed = self.connection.execute(
+            "UPDATE results SET summary_text = ?1, confidence = ?2, validation_status = ?3, model_version = ?4
+             WHERE chunk_id = ?5 AND source_path = ?6",
+            params![
+                summary_text,
+                confidence,
+                validation_status.as_str(),
+                model_version,
+                chunk_id.0,
+                source_path.to_string_lossy(),
+            ],
+        )?;
+        Ok(rows_changed)
+    }
+
+    /// Every record whose `model_version` doesn't match `current_model_version`
+    /// — the entries a backfill should regenerate. A model hash (unlike a
+    /// version number) has no natural ordering, so "produced by an older
+    /// model" is implemented as "not produced by the current one" rather
+    /// than a true less-than comparison; see [`crate::cli::backfill`].
+    pub fn stale_entries(&self, current_model_version: &str) -> Result<Vec<ResultRecord>, SinkError> {
+        let mut statement = self.connection.prepare(
+            "SELECT chunk_id, source_path, summary_text, confidence, validation_status, run_id, model_version
+             FROM results WHERE model_version != ?1",
+        )?;
+        let rows = statement.query_map(params![current_model_version], row_to_record)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(SinkError::from)
+    }
+
+    /// Returns the results matching `filter`, sorted by `sort_by`/`order`,
+    /// limited to one `page`.
+    pub fn query(&self, filter: &ResultFilter, sort_by: SortColumn, order: SortOrder, page: Page) -> Result<Vec<ResultRecord>, SinkError> {
+        let (where_clause, bound_values) = build_where_clause(filter);
+        let sql = format!(
+            "SELECT chunk_id, source_path, summary_text, confidence, validation_status, run_id, model_version
+             FROM results
+             {where_clause}
+             ORDER BY {column} {direction}
+             LIMIT ?{limit_index} OFFSET ?{offset_index}",
+            column = sort_by.column_name(),
+            direction = order.sql_keyword(),
+            limit_index = bound_values.len() + 1,
+            offset_index = bound_values.len() + 2,
+        );
+
+        let mut statement = self.connection.prepare(&sql)?;
+        let mut params: Vec<&dyn rusqlite::ToSql> = bound_values.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+        params.push(&page.limit);
+        params.push(&page.offset);
+
+        let rows = statement.query_map(params.as_slice(), row_to_record)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(SinkError::from)
+    }
+
+    /// Total number of rows matching `filter`, ignoring pagination, so a
+    /// caller can compute page counts without fetching every page first.
+    pub fn count(&self, filter: &ResultFilter) -> Result<u64, SinkError> {
+        let (where_clause, bound_values) = build_where_clause(filter);
+        let sql = format!("SELECT COUNT(*) FROM results {where_clause}");
+        let params: Vec<&dyn rusqlite::ToSql> = bound_values.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+        Ok(self.connection.query_row(&sql, params.as_slice(), |row| row.get(0))?)
+    }
+
+    /// Iterates every result matching `filter`, fetching one `page_size`
+    /// batch at a time so a caller never holds more than one page of rows in
+    /// memory regardless of how large the result set is.
+    pub fn stream_results(&self, filter: ResultFilter, page_size: usize) -> ResultsStream<'_> {
+        ResultsStream {
+            store: self,
+            filter,
+            page_size: page_size.max(1),
+            next_offset: 0,
+            exhausted: false,
+            buffer: std::collections::VecDeque::new(),
+        }
+    }
+}
+
+enum BoundValue {
+    Text(String),
+    Real(f32),
+}
+
+impl rusqlite::ToSql for BoundValue {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        match self {
+            BoundValue::Text(text) => text.to_sql(),
+            BoundValue::Real(value) => value.to_sql(),
+        }
+    }
+}
+
+fn build_where_clause(filter: &ResultFilter) -> (String, Vec<BoundValue>) {
+    let mut clauses = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(glob) = &filter.file_glob {
+        clauses.push("source_path GLOB ?".to_string());
+        values.push(BoundValue::Text(glob.clone()));
+    }
+    if let Some(min_confidence) = filter.min_confidence {
+        clauses.push("confidence >= ?".to_string());
+        values.push(BoundValue::Real(min_confidence));
+    }
+    if let Some(max_confidence) = filter.max_confidence {
+        clauses.push("confidence <= ?".to_string());
+        values.push(BoundValue::Real(max_confidence));
+    }
+    if let Some(validation_status) = filter.validation_status {
+        clauses.push("validation_status = ?".to_string());
+        values.push(BoundValue::Text(validation_status.as_str().to_string()));
+    }
+    if let Some(run_id) = &filter.run_id {
+        clauses.push("run_id = ?".to_string());
+        values.push(BoundValue::Text(run_id.clone()));
+    }
+    if let Some(model_version) = &filter.model_version {
+        clauses.push("model_version = ?".to_string());
+        values.push(BoundValue::Text(model_version.clone()));
+    }
+
+    if clauses.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!("WHERE {}", clauses.join(" AND ")), values)
+    }
+}
+
+/// Adds the `model_version` column to a `results` table created before that
+/// column existed. New databases already get it from [`ResultsStore::open`]'s
+/// `CREATE TABLE IF NOT EXISTS`, so this is a no-op for them; it only matters
+/// for a database file that predates this column, since SQLite has no
+/// `ADD COLUMN IF NOT EXISTS` and `CREATE TABLE IF NOT EXISTS` never alters
+/// an existing table. One of two such guarded `ALTER TABLE` steps in this
+/// crate (the other adds `summaries.owners` in [`crate::sinks::sqlite`]) —
+/// there's no migration framework here.
+fn add_model_version_column_if_missing(connection: &Connection) -> Result<(), SinkError> {
+    let already_has_column: bool = connection.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('results') WHERE name = 'model_version'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+    if !already_has_column {
+        connection.execute("ALTER TABLE results ADD COLUMN model_version TEXT NOT NULL DEFAULT ''", [])?;
+    }
+    Ok(())
+}
+
+fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<ResultRecord> {
+    let chunk_id: u64 = row.get(0)?;
+    let source_path: String = row.get(1)?;
+    let summary_text: String = row.get(2)?;
+    let confidence: f32 = row.get(3)?;
+    let validation_status: String = row.get(4)?;
+    let run_id: String = row.get(5)?;
+    let model_version: String = row.get(6)?;
+    Ok(ResultRecord {
+        chunk_id: ChunkId(chunk_id),
+        source_path: PathBuf::from(source_path),
+        summary_text,
+        confidence,
+        validation_status: ValidationStatus::parse(&validation_status),
+        run_id,
+        model_version,
+    })
+}
+
+/// Bounded-memory iterator returned by [`ResultsStore::stream_results`].
+pub struct ResultsStream<'store> {
+    store: &'store ResultsStore,
+    filter: ResultFilter,
+    page_size: usize,
+    next_offset: usize,
+    exhausted: bool,
+    buffer: std::collections::VecDeque<ResultRecord>,
+}
+
+impl Iterator for ResultsStream<'_> {
+    type Item = Result<ResultRecord, SinkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(record) = self.buffer.pop_front() {
+            return Some(Ok(record));
+        }
+        if self.exhausted {
+            return None;
+        }
+
+        let page = Page {
+            limit: self.page_size,
+            offset: self.next_offset,
+        };
+        match self.store.query(&self.filter, SortColumn::SourcePath, SortOrder::Ascending, page) {
+            Ok(records) => {
+                if records.len() < self.page_size {
+                    self.exhausted = true;
+                }
+                self.next_offset += records.len();
+                self.buffer.extend(records);
+                self.buffer.pop_front().map(Ok)
+            }
+            Err(error) => {
+                self.exhausted = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(chunk_id: u64, source_path: &str, confidence: f32, status: ValidationStatus, run_id: &str) -> ResultRecord {
+        ResultRecord {
+            chunk_id: ChunkId(chunk_id),
+            source_path: PathBuf::from(source_path),
+            summary_text: format!("summary {chunk_id}"),
+            confidence,
+            validation_status: status,
+            run_id: run_id.to_string(),
+            model_version: String::new(),
+        }
+    }
+
+    fn open_temp_store(name: &str) -> ResultsStore {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        ResultsStore::open(&path).unwrap()
+    }
+
+    #[test]
+    fn filters_by_confidence_range_and_validation_status() {
+        let store = open_temp_store("transfiguration-results-store-filter.sqlite");
+        store.insert(&record(1, "a.rs", 0.9, ValidationStatus::Pass, "run-1")).unwrap();
+        store.insert(&record(2, "b.rs", 0.2, ValidationStatus::Fail, "run-1")).unwrap();
+        store.insert(&record(3, "c.rs", 0.5, ValidationStatus::Pass, "run-1")).unwrap();
+
+        let filter = ResultFilter {
+            min_confidence: Some(0.4),
+            validation_status: Some(ValidationStatus::Pass),
+            ..Default::default()
+        };
+        let results = store
+            .query(&filter, SortColumn::Confidence, SortOrder::Descending, Page { limit: 10, offset: 0 })
+            .unwrap();
+
+        assert_eq!(results.iter().map(|r| r.chunk_id).collect::<Vec<_>>(), vec![ChunkId(1), ChunkId(3)]);
+    }
+
+    #[test]
+    fn filters_by_file_glob_and_run_id() {
+        let store = open_temp_store("transfiguration-results-store-glob.sqlite");
+        store.insert(&record(1, "src/engine/mod.rs", 0.9, ValidationStatus::Pass, "run-1")).unwrap();
+        store.insert(&record(2, "src/report/mod.rs", 0.9, ValidationStatus::Pass, "run-1")).unwrap();
+        store.insert(&record(3, "src/engine/jobs.rs", 0.9, ValidationStatus::Pass, "run-2")).unwrap();
+
+        let filter = ResultFilter {
+            file_glob: Some("src/engine/*".to_string()),
+            run_id: Some("run-1".to_string()),
+            ..Default::default()
+        };
+        let results = store
+            .query(&filter, SortColumn::SourcePath, SortOrder::Ascending, Page { limit: 10, offset: 0 })
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id, ChunkId(1));
+    }
+
+    #[test]
+    fn pagination_slices_the_sorted_result_set() {
+        let store = open_temp_store("transfiguration-results-store-paginate.sqlite");
+        for i in 0..5 {
+            store.insert(&record(i, &format!("f{i}.rs"), i as f32, ValidationStatus::Pass, "run-1")).unwrap();
+        }
+
+        let first_page = store
+            .query(&ResultFilter::default(), SortColumn::Confidence, SortOrder::Ascending, Page { limit: 2, offset: 0 })
+            .unwrap();
+        let second_page = store
+            .query(&ResultFilter::default(), SortColumn::Confidence, SortOrder::Ascending, Page { limit: 2, offset: 2 })
+            .unwrap();
+
+        assert_eq!(first_page.iter().map(|r| r.chunk_id.0).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(second_page.iter().map(|r| r.chunk_id.0).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn count_ignores_pagination_but_respects_filters() {
+        let store = open_temp_store("transfiguration-results-store-count.sqlite");
+        for i in 0..5 {
+            store.insert(&record(i, &format!("f{i}.rs"), 0.9, ValidationStatus::Pass, "run-1")).unwrap();
+        }
+        store.insert(&record(99, "f99.rs", 0.1, ValidationStatus::Fail, "run-1")).unwrap();
+
+        let count = store
+            .count(&ResultFilter {
+                validation_status: Some(ValidationStatus::Pass),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn stream_results_visits_every_row_across_page_boundaries() {
+        let store = open_temp_store("transfiguration-results-store-stream.sqlite");
+        for i in 0..7 {
+            store.insert(&record(i, &format!("f{i}.rs"), 0.9, ValidationStatus::Pass, "run-1")).unwrap();
+        }
+
+        let visited: Vec<u64> = store
+            .stream_results(ResultFilter::default(), 3)
+            .map(|result| result.unwrap().chunk_id.0)
+            .collect();
+
+        assert_eq!(visited, vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn opening_a_database_missing_the_model_version_column_adds_it() {
+        let path = std::env::temp_dir().join("transfiguration-results-store-migration.sqlite");
+        let _ = std::fs::remove_file(&path);
+        {
+            let connection = Connection::open(&path).unwrap();
+            connection
+                .execute(
+                    "CREATE TABLE results (
+                        chunk_id INTEGER NOT NULL,
+                        source_path TEXT NOT NULL,
+                        summary_text TEXT NOT NULL,
+                        confidence REAL NOT NULL,
+                        validation_status TEXT NOT NULL,
+                        run_id TEXT NOT NULL
+                    )",
+                    [],
+                )
+                .unwrap();
+            connection
+                .execute(
+                    "INSERT INTO results (chunk_id, source_path, summary_text, confidence, validation_status, run_id)
+                     VALUES (1, 'a.rs', 'pre-migration summary', 0.9, 'pass', 'run-0')",
+                    [],
+                )
+                .unwrap();
+        }
+
+        let store = ResultsStore::open(&path).unwrap();
+        let rows = store
+            .query(&ResultFilter::default(), SortColumn::SourcePath, SortOrder::Ascending, Page { limit: 10, offset: 0 })
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].model_version, "");
+    }
+
+    #[test]
+    fn update_in_place_overwrites_the_matching_row_instead_of_appending() {
+        let store = open_temp_store("transfiguration-results-store-update.sqlite");
+        store.insert(&record(1, "a.rs", 0.5, ValidationStatus::Fail, "run-1")).unwrap();
+
+        let rows_changed = store
+            .update_in_place(ChunkId(1), Path::new("a.rs"), "regenerated summary", 0.95, ValidationStatus::Pass, "model-v2")
+            .unwrap();
+        assert_eq!(rows_changed, 1);
+
+        let rows = store
+            .query(&ResultFilter::default(), SortColumn::SourcePath, SortOrder::Ascending, Page { limit: 10, offset: 0 })
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].summary_text, "regenerated summary");
+        assert_eq!(rows[0].model_version, "model-v2");
+        assert_eq!(rows[0].validation_status, ValidationStatus::Pass);
+    }
+
+    #[test]
+    fn stale_entries_returns_every_row_not_produced_by_the_current_model_version() {
+        let store = open_temp_store("transfiguration-results-store-stale.sqlite");
+        store
+            .insert(&ResultRecord {
+                model_version: "model-v1".to_string(),
+                ..record(1, "a.rs", 0.9, ValidationStatus::Pass, "run-1")
+            })
+            .unwrap();
+        store
+            .insert(&ResultRecord {
+                model_version: "model-v2".to_string(),
+                ..record(2, "b.rs", 0.9, ValidationStatus::Pass, "run-1")
+            })
+            .unwrap();
+
+        let stale = store.stale_entries("model-v2").unwrap();
+        assert_eq!(stale.iter().map(|r| r.chunk_id).collect::<Vec<_>>(), vec![ChunkId(1)]);
+    }
+}
@@ -0,0 +1,351 @@
+//! Long-running resource-leak detection: loops a caller-provided workload for
+//! a chosen duration, periodically sampling this process's own RSS and open
+//! file descriptor count, and flags growth past a configured budget.
+//!
+//! Unit tests catch a session or file handle never being released within a
+//! single short-lived run, but a leak that only shows up after thousands of
+//! iterations — a retained `Arc`, an unflushed buffer, a socket never closed
+//! on an error path — looks identical to healthy steady-state memory use in
+//! any run short enough to fit in a test suite. [`run_soak_test`] is built
+//! to instead be pointed at hours (the `soak` binary this module backs is
+//! meant for a nightly job, not `cargo test`), but every knob is a
+//! [`SoakConfig`] field so the same harness also runs in seconds for this
+//! module's own tests.
+//!
+//! Linux-only: both samples this module takes (`read_rss_bytes`,
+//! `count_open_fds`) read `/proc/self/status` and `/proc/self/fd`
+//! directly rather than pulling in a `sysinfo`-style dependency for numbers
+//! this crate only needs on the platform it's actually deployed to — the
+//! same tradeoff [`crate::sandbox`] makes for its Linux-only seccomp filter.
+//! On any other target, [`run_soak_test`] fails immediately with
+//! [`SoakError::UnsupportedPlatform`] rather than silently skipping the
+//! checks it claims to run.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How long to run, how often to sample, and how much RSS/FD growth across
+/// the whole run counts as a leak rather than normal steady-state variance.
+#[derive(Debug, Clone, Copy)]
+pub struct SoakConfig {
+    pub duration: Duration,
+    pub sample_interval: Duration,
+    pub max_rss_growth_bytes: u64,
+    pub max_fd_growth: i64,
+}
+
+impl Default for SoakConfig {
+    /// A multi-hour nightly-job default: sample every 30 seconds, tolerate
+    /// up to 64 MiB of RSS growth and 16 extra file descriptors across the
+    /// whole run before calling it a leak.
+    fn default() -> Self {
+        SoakConfig {
+            duration: Duration::from_secs(4 * 60 * 60),
+            sample_interval: Duration::from_secs(30),
+            max_rss_growth_bytes: 64 * 1024 * 1024,
+            max_fd_growth: 16,
+        }
+    }
+}
+
+/// One point-in-time reading taken during a soak run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceSample {
+    pub elapsed: Duration,
+    pub rss_bytes: u64,
+    pub open_fd_count: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SoakError {
+    #[error("soak testing is only implemented for Linux, which is the only platform this harness's /proc-based sampling supports")]
+    UnsupportedPlatform,
+    #[error("failed to read process resource usage: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(
+        "RSS grew by {actual_bytes} bytes over the soak run (from {first_bytes} to {last_bytes}), \
+         exceeding the {max_bytes} byte budget — likely a memory leak"
+    )]
+    RssGrowthExceeded { first_bytes: u64, last_bytes: u64, actual_bytes: u64, max_bytes: u64 },
+    #[error(
+        "open file descriptor count grew by {actual} over the soak run (from {first} to {last}), \
+         exceeding the budget of {max} — likely a descriptor leak"
+    )]
+    FdGrowthExceeded { first: usize, last: usize, actual: i64, max: i64 },
+}
+
+/// Every sample taken across one soak run, plus how many workload
+/// iterations it managed to complete in `duration`.
+#[derive(Debug, Clone)]
+pub struct SoakReport {
+    pub samples: Vec<ResourceSample>,
+    pub iterations_completed: u64,
+}
+
+impl SoakReport {
+    /// `last.rss_bytes - first.rss_bytes`, or `0` for a report with fewer
+    /// than two samples.
+    pub fn rss_growth_bytes(&self) -> i64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.rss_bytes as i64 - first.rss_bytes as i64,
+            _ => 0,
+        }
+    }
+
+    /// `last.open_fd_count - first.open_fd_count`, or `0` for a report with
+    /// fewer than two samples.
+    pub fn fd_growth(&self) -> i64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.open_fd_count as i64 - first.open_fd_count as i64,
+            _ => 0,
+        }
+    }
+
+    /// A one-table-per-run Markdown summary, in the same rendered-for-a-
+    /// human spirit as [`crate::report::html`] and [`crate::analysis`]'s
+    /// reports, for a nightly job to attach to its run log.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Soak report\n\n\
+             Iterations completed: {}\n\
+             RSS growth: {} bytes\n\
+             FD growth: {}\n\n\
+             | elapsed (s) | RSS (bytes) | open FDs |\n\
+             |---|---|---|\n",
+            self.iterations_completed,
+            self.rss_growth_bytes(),
+            self.fd_growth(),
+        );
+        for sample in &self.samples {
+            markdown += &format!(
+                "| {:.1} | {} | {} |\n",
+                sample.elapsed.as_secs_f64(),
+                sample.rss_bytes,
+                sample.open_fd_count,
+            );
+        }
+        markdown
+    }
+}
+
+impl fmt::Display for SoakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_markdown())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes() -> std::io::Result<u64> {
+    let status = std::fs::read_to_string("/proc/self/status")?;
+    for line in status.lines() {
+        if let Some(kib) = line.strip_prefix("VmRSS:") {
+            let kib: u64 = kib.trim().trim_end_matches(" kB").trim().parse().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "VmRSS line in /proc/self/status was not a plain kB count")
+            })?;
+            return Ok(kib * 1024);
+        }
+    }
+    Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no VmRSS line in /proc/self/status"))
+}
+
+#[cfg(target_os = "linux")]
+fn count_open_fds() -> std::io::Result<usize> {
+    Ok(std::fs::read_dir("/proc/self/fd")?.count())
+}
+
+#[cfg(target_os = "linux")]
+fn take_sample(start: Instant) -> Result<ResourceSample, SoakError> {
+    Ok(ResourceSample {
+        elapsed: start.elapsed(),
+        rss_bytes: read_rss_bytes()?,
+        open_fd_count: count_open_fds()?,
+    })
+}
+
+/// Runs `workload` (called once per iteration, with the iteration index)
+/// repeatedly until `config.duration` elapses, sampling RSS/FD counts every
+/// `config.sample_interval`, then checks the first sample against the last
+/// for growth past `config.max_rss_growth_bytes`/`config.max_fd_growth`.
+///
+/// A sample is always taken at the start and at the end, so even a run
+/// shorter than one `sample_interval` compares those two. Returns the full
+/// [`SoakReport`] on success and a [`SoakError`] describing exactly which
+/// budget was blown otherwise, so a nightly job's failure message is
+/// immediately actionable rather than just "soak test failed".
+#[cfg(target_os = "linux")]
+pub fn run_soak_test(config: &SoakConfig, mut workload: impl FnMut(u64)) -> Result<SoakReport, SoakError> {
+    let start = Instant::now();
+    let mut samples = vec![take_sample(start)?];
+    let mut next_sample_at = config.sample_interval;
+    let mut iterations_completed = 0u64;
+
+    while start.elapsed() < config.duration {
+        workload(iterations_completed);
+        iterations_completed += 1;
+
+        if start.elapsed() >= next_sample_at {
+            samples.push(take_sample(start)?);
+            next_sample_at += config.sample_interval;
+        }
+    }
+    samples.push(take_sample(start)?);
+
+    let report = SoakReport { samples, iterations_completed };
+
+    // `config.max_rss_growth_bytes` is a `u64` that can legitimately be
+    // `u64::MAX` (a test or caller disabling the RSS check); casting it
+    // down to `i64` for the comparison would wrap it to `-1` and make
+    // every run look over budget. Compare the other direction instead:
+    // a negative (shrinking) `rss_growth` is never a leak, so only a
+    // positive growth needs checking against the `u64` budget directly.
+    let rss_growth = report.rss_growth_bytes();
+    if rss_growth > 0 && rss_growth as u64 > config.max_rss_growth_bytes {
+        let first = report.samples.first().expect("at least one sample was always pushed above");
+        let last = report.samples.last().expect("at least one sample was always pushed above");
+        return Err(SoakError::RssGrowthExceeded {
+            first_bytes: first.rss_bytes,
+            last_bytes: last.rss_bytes,
+            actual_bytes: rss_growth as u64,
+            max_bytes: config.max_rss_growth_bytes,
+        });
+    }
+
+    let fd_growth = report.fd_growth();
+    if fd_growth > config.max_fd_growth {
+        let first = report.samples.first().expect("at least one sample was always pushed above");
+        let last = report.samples.last().expect("at least one sample was always pushed above");
+        return Err(SoakError::FdGrowthExceeded {
+            first: first.open_fd_count,
+            last: last.open_fd_count,
+            actual: fd_growth,
+            max: config.max_fd_growth,
+        });
+    }
+
+    Ok(report)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run_soak_test(_config: &SoakConfig, _workload: impl FnMut(u64)) -> Result<SoakReport, SoakError> {
+    Err(SoakError::UnsupportedPlatform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn a_short_run_completes_and_reports_every_iteration() {
+        let config = SoakConfig {
+            duration: Duration::from_millis(50),
+            sample_interval: Duration::from_millis(10),
+            max_rss_growth_bytes: u64::MAX,
+            max_fd_growth: i64::MAX,
+        };
+        let mut iterations_seen = 0u64;
+
+        let report = run_soak_test(&config, |_| {
+            iterations_seen += 1;
+        })
+        .unwrap();
+
+        assert_eq!(report.iterations_completed, iterations_seen);
+        assert!(iterations_seen > 0);
+        assert!(report.samples.len() >= 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rss_growth_past_the_budget_is_reported_precisely() {
+        // A budget of zero bytes, with every iteration leaking 64 KiB,
+        // makes the failure path all but certain to be taken.
+        let config = SoakConfig {
+            duration: Duration::from_millis(20),
+            sample_interval: Duration::from_millis(5),
+            max_rss_growth_bytes: 0,
+            max_fd_growth: i64::MAX,
+        };
+
+        let result = run_soak_test(&config, |_| {
+            // Deliberately leaked so RSS visibly grows across the run,
+            // exercising the failure path without waiting for a real leak.
+            std::mem::forget(vec![0u8; 64 * 1024]);
+        });
+
+        match result {
+            Err(SoakError::RssGrowthExceeded { max_bytes, .. }) => assert_eq!(max_bytes, 0),
+            Ok(report) if report.iterations_completed == 0 => {
+                // The run was too short to leak even once; not a meaningful
+                // assertion either way, but not a failure of this harness.
+            }
+            other => panic!("expected RssGrowthExceeded, got {other:?}"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn fd_growth_past_the_budget_is_reported_precisely() {
+        let config = SoakConfig {
+            duration: Duration::from_millis(20),
+            sample_interval: Duration::from_millis(5),
+            max_rss_growth_bytes: u64::MAX,
+            max_fd_growth: 0,
+        };
+        // Leaked on purpose: every iteration opens a file and never closes
+        // it, exercising the FD-growth failure path deterministically.
+        let leaks: std::cell::RefCell<Vec<std::fs::File>> = std::cell::RefCell::new(Vec::new());
+
+        let result = run_soak_test(&config, |i| {
+            if let Ok(file) = std::fs::File::open("/proc/self/status") {
+                leaks.borrow_mut().push(file);
+            }
+            let _ = i;
+        });
+
+        match result {
+            Err(SoakError::FdGrowthExceeded { max, .. }) => assert_eq!(max, 0),
+            Ok(report) if report.iterations_completed == 0 => {
+                // The run was too short to open even one file; not a
+                // meaningful assertion either way, but not a failure of
+                // this harness.
+            }
+            other => panic!("expected FdGrowthExceeded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn to_markdown_includes_every_sample_and_both_growth_figures() {
+        let report = SoakReport {
+            samples: vec![
+                ResourceSample { elapsed: Duration::ZERO, rss_bytes: 1_000, open_fd_count: 5 },
+                ResourceSample { elapsed: Duration::from_secs(30), rss_bytes: 1_500, open_fd_count: 6 },
+            ],
+            iterations_completed: 42,
+        };
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("Iterations completed: 42"));
+        assert!(markdown.contains("RSS growth: 500 bytes"));
+        assert!(markdown.contains("FD growth: 1"));
+        assert!(markdown.contains("| 0.0 | 1000 | 5 |"));
+        assert!(markdown.contains("| 30.0 | 1500 | 6 |"));
+    }
+
+    #[test]
+    fn growth_figures_are_zero_for_a_report_with_fewer_than_two_samples() {
+        let report = SoakReport {
+            samples: vec![ResourceSample { elapsed: Duration::ZERO, rss_bytes: 1_000, open_fd_count: 5 }],
+            iterations_completed: 0,
+        };
+        assert_eq!(report.rss_growth_bytes(), 0);
+        assert_eq!(report.fd_growth(), 0);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn non_linux_reports_unsupported() {
+        let config = SoakConfig::default();
+        assert!(matches!(run_soak_test(&config, |_| {}), Err(SoakError::UnsupportedPlatform)));
+    }
+}
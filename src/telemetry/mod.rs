@@ -0,0 +1,153 @@
+//! OTLP export of trace spans and run metrics.
+//!
+//! Spans are correlated across chunking, queueing, and inference without
+//! threading a context parameter through every function signature: [`run_span`]
+//! opens one root span carrying `run_id`/`job_id`, a caller enters it once
+//! for the lifetime of a run, and child spans opened underneath it (chunking,
+//! queueing, inference call sites) inherit that context automatically via
+//! `tracing`'s span stack.
+//!
+//! The OTLP exporter ships spans over plain HTTP using `reqwest`'s blocking
+//! client (the same client `http-sink` already uses) rather than the default
+//! gRPC/tonic transport, so enabling telemetry never pulls in an async
+//! runtime just to export spans.
+
+use std::collections::HashMap;
+use std::env;
+
+use opentelemetry::global;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+
+use crate::metrics::MetricsSnapshot;
+
+/// Environment variable used to point at an OTLP collector when no endpoint
+/// is configured explicitly.
+pub const OTLP_ENDPOINT_ENV_VAR: &str = "TRANSFIGURATION_OTLP_ENDPOINT";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("no OTLP endpoint configured; pass one explicitly or set `{OTLP_ENDPOINT_ENV_VAR}`")]
+    NoEndpointConfigured,
+    #[error("failed to build OTLP exporter: {0}")]
+    ExporterBuild(String),
+}
+
+/// Where (and with what headers, e.g. an auth token) to ship OTLP spans.
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryConfig {
+    endpoint: Option<String>,
+    headers: HashMap<String, String>,
+}
+
+impl TelemetryConfig {
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    fn resolve_endpoint(&self) -> Result<String, TelemetryError> {
+        self.endpoint
+            .clone()
+            .or_else(|| env::var(OTLP_ENDPOINT_ENV_VAR).ok())
+            .ok_or(TelemetryError::NoEndpointConfigured)
+    }
+}
+
+/// Identifies a single run for span correlation; see the module docs.
+#[derive(Debug, Clone)]
+pub struct RunContext {
+    pub run_id: String,
+    pub job_id: String,
+}
+
+/// Opens the root span a run should stay entered in for its whole lifetime,
+/// so every chunking/queueing/inference span opened underneath it inherits
+/// `run_id`/`job_id` without those functions taking a context parameter.
+pub fn run_span(context: &RunContext) -> tracing::Span {
+    tracing::info_span!("transfiguration_run", run_id = %context.run_id, job_id = %context.job_id)
+}
+
+/// RAII handle returned by [`init_telemetry`]. Shuts the tracer provider
+/// down on drop so buffered spans flush instead of being silently lost when
+/// the process exits.
+pub struct TelemetryGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(err) = self.provider.shutdown() {
+            tracing::warn!("failed to shut down OTLP tracer provider: {err}");
+        }
+    }
+}
+
+/// Installs a global tracer provider that batches spans to an OTLP
+/// collector over HTTP, returning a guard that must be kept alive for the
+/// duration telemetry should be collected.
+pub fn init_telemetry(config: &TelemetryConfig) -> Result<TelemetryGuard, TelemetryError> {
+    let endpoint = config.resolve_endpoint()?;
+
+    let mut exporter_builder = SpanExporter::builder().with_http().with_endpoint(endpoint);
+    if !config.headers.is_empty() {
+        exporter_builder = exporter_builder.with_headers(config.headers.clone());
+    }
+    let exporter = exporter_builder
+        .build()
+        .map_err(|err| TelemetryError::ExporterBuild(err.to_string()))?;
+
+    let provider = SdkTracerProvider::builder().with_batch_exporter(exporter).build();
+    global::set_tracer_provider(provider.clone());
+
+    Ok(TelemetryGuard { provider })
+}
+
+/// Records a point-in-time [`MetricsSnapshot`] as OTel instruments on
+/// `meter`, mirroring the pull-based design of [`crate::metrics`]: call this
+/// whenever a snapshot is taken rather than maintaining live instruments.
+pub fn record_run_metrics(meter: &opentelemetry::metrics::Meter, snapshot: &MetricsSnapshot) {
+    meter.u64_counter("chunks_completed").build().add(snapshot.chunks_completed, &[]);
+    meter.u64_counter("chunks_failed").build().add(snapshot.chunks_failed, &[]);
+    meter.u64_gauge("active_agents").build().record(snapshot.active_agents, &[]);
+    meter
+        .f64_gauge("average_latency_seconds")
+        .build()
+        .record(snapshot.average_latency.as_secs_f64(), &[]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_endpoint_uses_the_explicit_value_when_set() {
+        let config = TelemetryConfig::default().with_endpoint("http://explicit:4318");
+        assert_eq!(config.resolve_endpoint().unwrap(), "http://explicit:4318");
+    }
+
+    #[test]
+    fn resolve_endpoint_reports_unconfigured_without_an_explicit_value_or_env_var() {
+        // Relies on the test process not having `OTLP_ENDPOINT_ENV_VAR` set,
+        // which holds in CI and any normal dev shell; this mirrors how
+        // `signing::resolve_signing_key`'s tests avoid mutating process env.
+        let config = TelemetryConfig::default();
+        if env::var(OTLP_ENDPOINT_ENV_VAR).is_err() {
+            assert!(matches!(config.resolve_endpoint(), Err(TelemetryError::NoEndpointConfigured)));
+        }
+    }
+
+    #[test]
+    fn run_span_can_be_entered_without_a_subscriber_installed() {
+        // No global subscriber is installed in this test process, so the
+        // span comes back disabled; the point of this test is just that
+        // building and entering one never panics regardless.
+        let context = RunContext { run_id: "run-1".to_string(), job_id: "job-1".to_string() };
+        let _entered = run_span(&context).entered();
+    }
+}
@@ -0,0 +1,192 @@
+//! Deterministic synthetic codebase generation for benchmarks and integration tests.
+//!
+//! The benchmark harness previously relied on a checked-out copy of `tokio` on the
+//! author's machine, which made results non-reproducible off that machine and
+//! impossible to scale to a specific size. [`generate_synthetic_corpus`] builds a
+//! fake Rust or Python codebase from a seed instead, with controllable size,
+//! function density and duplicate ratio.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Source language to emit synthetic files in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorpusLanguage {
+    Rust,
+    Python,
+}
+
+impl CorpusLanguage {
+    fn extension(self) -> &'static str {
+        match self {
+            CorpusLanguage::Rust => "rs",
+            CorpusLanguage::Python => "py",
+        }
+    }
+}
+
+/// Parameters controlling a generated synthetic corpus.
+#[derive(Debug, Clone)]
+pub struct CorpusSpec {
+    pub seed: u64,
+    pub language: CorpusLanguage,
+    pub file_count: usize,
+    pub functions_per_file: usize,
+    /// Fraction in `[0.0, 1.0]` of functions that are exact duplicates of an
+    /// earlier generated function, used to exercise dedup logic.
+    pub duplicate_ratio: f64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TestgenError {
+    #[error("corpus spec is invalid: {0}")]
+    InvalidSpec(String),
+    #[error("failed to write synthetic corpus: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Summary of what [`generate_synthetic_corpus`] actually wrote to disk.
+#[derive(Debug, Clone)]
+pub struct CorpusManifest {
+    pub files: Vec<PathBuf>,
+    pub total_functions: usize,
+    pub duplicate_functions: usize,
+}
+
+/// Minimal deterministic PRNG (SplitMix64) so corpus generation has no
+/// external dependency and reproduces byte-for-byte across platforms.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn seeded_from_value(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_raw_value(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_bounded_usize(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            (self.next_raw_value() % bound as u64) as usize
+        }
+    }
+}
+
+/// Generates a synthetic codebase under `dest_dir` matching `spec`, returning a
+/// manifest of what was written. The same `spec` always produces byte-identical
+/// output, which is what lets the benchmark harness and integration tests treat
+/// it as a fixture instead of a path into someone's home directory.
+pub fn generate_synthetic_corpus(
+    spec: &CorpusSpec,
+    dest_dir: &Path,
+) -> Result<CorpusManifest, TestgenError> {
+    if spec.file_count == 0 {
+        return Err(TestgenError::InvalidSpec("file_count must be > 0".into()));
+    }
+    if !(0.0..=1.0).contains(&spec.duplicate_ratio) {
+        return Err(TestgenError::InvalidSpec(
+            "duplicate_ratio must be within [0.0, 1.0]".into(),
+        ));
+    }
+
+    fs::create_dir_all(dest_dir)?;
+    let mut rng = SplitMix64::seeded_from_value(spec.seed);
+    let mut bodies: Vec<String> = Vec::new();
+    let mut files = Vec::with_capacity(spec.file_count);
+    let mut duplicate_functions = 0usize;
+
+    for file_index in 0..spec.file_count {
+        let mut functions = String::new();
+        for fn_index in 0..spec.functions_per_file {
+            let name = format!("synthetic_fn_{file_index}_{fn_index}");
+            let is_duplicate =
+                !bodies.is_empty() && rng.next_bounded_usize(1_000_000) as f64 / 1_000_000.0 < spec.duplicate_ratio;
+            let body = if is_duplicate {
+                duplicate_functions += 1;
+                let source_index = rng.next_bounded_usize(bodies.len());
+                bodies[source_index].clone()
+            } else {
+                render_function_body(spec.language, &mut rng)
+            };
+            bodies.push(body.clone());
+            functions.push_str(&render_function(spec.language, &name, &body));
+            functions.push('\n');
+        }
+
+        let file_path = dest_dir.join(format!("module_{file_index}.{}", spec.language.extension()));
+        fs::write(&file_path, functions)?;
+        files.push(file_path);
+    }
+
+    Ok(CorpusManifest {
+        files,
+        total_functions: spec.file_count * spec.functions_per_file,
+        duplicate_functions,
+    })
+}
+
+fn render_function_body(language: CorpusLanguage, rng: &mut SplitMix64) -> String {
+    let value = rng.next_bounded_usize(1000);
+    match language {
+        CorpusLanguage::Rust => format!("    {value}"),
+        CorpusLanguage::Python => format!("    return {value}"),
+    }
+}
+
+fn render_function(language: CorpusLanguage, name: &str, body: &str) -> String {
+    match language {
+        CorpusLanguage::Rust => format!("pub fn {name}() -> usize {{\n{body}\n}}\n"),
+        CorpusLanguage::Python => format!("def {name}():\n{body}\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_spec(seed: u64) -> CorpusSpec {
+        CorpusSpec {
+            seed,
+            language: CorpusLanguage::Rust,
+            file_count: 4,
+            functions_per_file: 5,
+            duplicate_ratio: 0.3,
+        }
+    }
+
+    #[test]
+    fn same_seed_produces_identical_corpus() {
+        let dir_a = std::env::temp_dir().join("transfiguration-testgen-a");
+        let dir_b = std::env::temp_dir().join("transfiguration-testgen-b");
+        let _ = fs::remove_dir_all(&dir_a);
+        let _ = fs::remove_dir_all(&dir_b);
+
+        let manifest_a = generate_synthetic_corpus(&default_spec(42), &dir_a).unwrap();
+        let manifest_b = generate_synthetic_corpus(&default_spec(42), &dir_b).unwrap();
+
+        assert_eq!(manifest_a.total_functions, manifest_b.total_functions);
+        assert_eq!(manifest_a.duplicate_functions, manifest_b.duplicate_functions);
+        for (a, b) in manifest_a.files.iter().zip(manifest_b.files.iter()) {
+            assert_eq!(fs::read_to_string(a).unwrap(), fs::read_to_string(b).unwrap());
+        }
+
+        fs::remove_dir_all(&dir_a).unwrap();
+        fs::remove_dir_all(&dir_b).unwrap();
+    }
+
+    #[test]
+    fn rejects_empty_file_count() {
+        let mut spec = default_spec(1);
+        spec.file_count = 0;
+        assert!(generate_synthetic_corpus(&spec, &std::env::temp_dir()).is_err());
+    }
+}
@@ -0,0 +1,644 @@
+//! Recursive archive extraction with pluggable formats.
+//!
+//! This crate had no unpacker at all before this module — no recursion
+//! logic, baked-in or otherwise — so there's nothing to retrofit a plugin
+//! point onto. Instead [`ArchiveFormat`] is the plugin point from the start:
+//! a trait of `detect`/`list`/`extract_entry`, registered into an
+//! [`ArchiveFormatRegistry`] that [`unpack_recursively`] consults to pick a
+//! format for each archive it finds, including ones nested inside other
+//! archives. Depth limiting ([`ArchiveError::DepthLimitExceeded`]) and
+//! path-traversal rejection ([`sanitize_entry_path`]) live in
+//! [`unpack_recursively`] itself, not in any format implementation, so every
+//! plugged-in format gets them uniformly and can't opt out by getting it
+//! wrong.
+//!
+//! Only [`TarArchiveFormat`] and [`TarGzArchiveFormat`] ship here, since
+//! `tar`/`flate2` are the only archive-format dependencies already in this
+//! crate (pulled in for [`crate::forensics`]'s bundle writer). 7z, cpio, and
+//! squashfs — the formats the request names — would each be another
+//! [`ArchiveFormat`] impl that shells out to an external tool for
+//! `list`/`extract_entry`; none are implemented here since this crate has no
+//! existing convention for invoking an external archive binary to build
+//! that on top of (`engine::worker_pool` shells out to a *cooperating*
+//! worker process over a fixed protocol, which is a different problem).
+//!
+//! Symlinks and permission bits are faithfully reported by [`ArchiveEntry`]
+//! and, by default, *not* blindly trusted: [`UnpackPolicy`] rejects a
+//! symlink whose target is absolute or escapes `destination_root`, and only
+//! applies an entry's archived mode bits if [`UnpackPolicy::apply_file_permissions`]
+//! is turned on. Numeric ownership is read off the tar header and carried
+//! into the [`ExtractionManifestEntry`] audit trail [`unpack_recursively`]
+//! returns, but it's never `chown`ed onto the extracted file — trusting an
+//! untrusted archive's claimed uid/gid is its own hazard, and applying one
+//! generally requires running as root anyway.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use flate2::read::GzDecoder;
+use tar::Archive;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("io error while unpacking archive: {0}")]
+    Io(#[from] io::Error),
+    #[error("no registered ArchiveFormat recognizes {0:?}")]
+    UnsupportedFormat(PathBuf),
+    #[error("archive entry {0:?} would escape the extraction root")]
+    PathTraversal(PathBuf),
+    #[error("symlink entry {0:?} targets {1:?}, which is absolute or escapes the extraction root")]
+    UnsafeSymlinkTarget(PathBuf, PathBuf),
+    #[error("nested archive recursion exceeded the configured max depth of {max_depth}")]
+    DepthLimitExceeded { max_depth: usize },
+    #[error("archive entry {0:?} not found")]
+    EntryNotFound(PathBuf),
+}
+
+/// What kind of filesystem object an [`ArchiveEntry`] extracts to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveEntryKind {
+    File,
+    Directory,
+    /// A symlink and the raw target path recorded in the archive, unresolved
+    /// and unsanitized — [`unpack_recursively`] is responsible for rejecting
+    /// one that's absolute or escapes the extraction root before creating it.
+    Symlink { target: PathBuf },
+}
+
+/// One entry in an archive, as reported by [`ArchiveFormat::list`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub path: PathBuf,
+    pub kind: ArchiveEntryKind,
+    pub size: u64,
+    /// The Unix permission bits recorded in the archive, if the format
+    /// carries them. `None` on formats that don't (there are none yet, since
+    /// both shipped formats are tar-based and tar always has a mode field).
+    pub mode: Option<u32>,
+    /// The numeric (uid, gid) recorded in the archive, if any, kept only for
+    /// the extraction manifest's audit trail — [`unpack_recursively`] never
+    /// applies an archived owner to the extracted file, since doing so would
+    /// usually require running as root and trusting an untrusted archive's
+    /// claimed ownership is its own hazard.
+    pub owner: Option<(u32, u32)>,
+}
+
+/// A pluggable archive format: whether a file is one, what's inside it, and
+/// how to pull a single entry out. [`unpack_recursively`] is the only
+/// intended caller of `extract_entry`/`list` — implementations should stay
+/// mechanical and leave path-traversal/depth policy to the caller.
+pub trait ArchiveFormat: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Whether this format recognizes `path` as one of its archives. Cheap
+    /// (extension-based) by design, since [`ArchiveFormatRegistry::detect_format`]
+    /// calls this on every file [`unpack_recursively`] extracts to check for
+    /// nested archives.
+    fn detect(&self, path: &Path) -> bool;
+    fn list(&self, archive_path: &Path) -> Result<Vec<ArchiveEntry>, ArchiveError>;
+    /// Extracts the single entry at `entry_path` (as reported by [`Self::list`])
+    /// from `archive_path` to `destination_path`, a concrete output file
+    /// path the caller has already sanitized and created parent directories
+    /// for.
+    fn extract_entry(&self, archive_path: &Path, entry_path: &Path, destination_path: &Path) -> Result<(), ArchiveError>;
+}
+
+/// An uncompressed `.tar` archive.
+pub struct TarArchiveFormat;
+
+impl ArchiveFormat for TarArchiveFormat {
+    fn name(&self) -> &'static str {
+        "tar"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        path.extension().and_then(|ext| ext.to_str()) == Some("tar")
+    }
+
+    fn list(&self, archive_path: &Path) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+        list_tar_entries(Archive::new(File::open(archive_path)?))
+    }
+
+    fn extract_entry(&self, archive_path: &Path, entry_path: &Path, destination_path: &Path) -> Result<(), ArchiveError> {
+        extract_tar_entry(Archive::new(File::open(archive_path)?), entry_path, destination_path)
+    }
+}
+
+/// A gzip-compressed `.tar.gz`/`.tgz` archive.
+pub struct TarGzArchiveFormat;
+
+impl ArchiveFormat for TarGzArchiveFormat {
+    fn name(&self) -> &'static str {
+        "tar.gz"
+    }
+
+    fn detect(&self, path: &Path) -> bool {
+        let name = path.to_string_lossy();
+        name.ends_with(".tar.gz") || name.ends_with(".tgz")
+    }
+
+    fn list(&self, archive_path: &Path) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+        list_tar_entries(Archive::new(GzDecoder::new(File::open(archive_path)?)))
+    }
+
+    fn extract_entry(&self, archive_path: &Path, entry_path: &Path, destination_path: &Path) -> Result<(), ArchiveError> {
+        extract_tar_entry(Archive::new(GzDecoder::new(File::open(archive_path)?)), entry_path, destination_path)
+    }
+}
+
+fn list_tar_entries<R: Read>(mut archive: Archive<R>) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let kind = if header.entry_type().is_symlink() {
+            ArchiveEntryKind::Symlink {
+                target: entry.link_name()?.map(|target| target.into_owned()).unwrap_or_default(),
+            }
+        } else if header.entry_type().is_dir() {
+            ArchiveEntryKind::Directory
+        } else {
+            ArchiveEntryKind::File
+        };
+        entries.push(ArchiveEntry {
+            path: entry.path()?.into_owned(),
+            kind,
+            size: header.size()?,
+            mode: header.mode().ok(),
+            owner: match (header.uid(), header.gid()) {
+                (Ok(uid), Ok(gid)) => Some((uid as u32, gid as u32)),
+                _ => None,
+            },
+        });
+    }
+    Ok(entries)
+}
+
+fn extract_tar_entry<R: Read>(mut archive: Archive<R>, entry_path: &Path, destination_path: &Path) -> Result<(), ArchiveError> {
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() == entry_path {
+            let mut file = File::create(destination_path)?;
+            io::copy(&mut entry, &mut file)?;
+            return Ok(());
+        }
+    }
+    Err(ArchiveError::EntryNotFound(entry_path.to_path_buf()))
+}
+
+/// Where [`ArchiveFormat`] implementations are registered for
+/// [`unpack_recursively`] to pick from.
+#[derive(Default)]
+pub struct ArchiveFormatRegistry {
+    formats: Vec<Arc<dyn ArchiveFormat>>,
+}
+
+impl ArchiveFormatRegistry {
+    pub fn new() -> Self {
+        ArchiveFormatRegistry::default()
+    }
+
+    /// A registry pre-populated with every format this crate ships: plain
+    /// `.tar` and gzip-compressed `.tar.gz`/`.tgz`.
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(TarGzArchiveFormat));
+        registry.register(Arc::new(TarArchiveFormat));
+        registry
+    }
+
+    pub fn register(&mut self, format: Arc<dyn ArchiveFormat>) {
+        self.formats.push(format);
+    }
+
+    /// The first registered format that recognizes `path`, checked in
+    /// registration order so a caller registering a more specific format
+    /// ahead of a generic one can override it.
+    pub fn detect_format(&self, path: &Path) -> Option<Arc<dyn ArchiveFormat>> {
+        self.formats.iter().find(|format| format.detect(path)).cloned()
+    }
+}
+
+/// Rejects an entry path that's absolute or climbs out of the extraction
+/// root via `..`, the same check regardless of which [`ArchiveFormat`]
+/// produced it.
+fn sanitize_entry_path(entry_path: &Path) -> Result<PathBuf, ArchiveError> {
+    let mut sanitized = PathBuf::new();
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => sanitized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(ArchiveError::PathTraversal(entry_path.to_path_buf()));
+            }
+        }
+    }
+    if sanitized.as_os_str().is_empty() {
+        return Err(ArchiveError::PathTraversal(entry_path.to_path_buf()));
+    }
+    Ok(sanitized)
+}
+
+/// Bounds and safety toggles for [`unpack_recursively`], the equivalent for
+/// archive extraction of [`crate::sandbox::SandboxPolicy`] for worker
+/// isolation: everything risky is either capped or denied by default, and a
+/// caller that trusts its input opts back in explicitly.
+#[derive(Debug, Clone, Copy)]
+pub struct UnpackPolicy {
+    /// How many archives-within-archives may be unpacked beyond the
+    /// top-level one. `0` means the top-level archive is extracted but any
+    /// archive found inside it makes extraction fail with
+    /// [`ArchiveError::DepthLimitExceeded`] rather than being left alone —
+    /// there's no "leave it as an opaque file" mode today, so a caller that
+    /// wants the top-level archive's other entries on disk despite a nested
+    /// archive exceeding the limit needs to catch that error itself.
+    pub max_depth: usize,
+    /// Reject a symlink entry whose target is absolute or whose resolved
+    /// target would land outside `destination_root`. On by default — an
+    /// untrusted archive's symlinks should never be followed blindly.
+    pub reject_escaping_symlinks: bool,
+    /// Apply each entry's archived Unix permission bits to the extracted
+    /// file. Off by default: trusting an untrusted archive's mode bits
+    /// (e.g. a setuid bit) is its own hazard, so this is opt-in for callers
+    /// that trust the archive's source.
+    pub apply_file_permissions: bool,
+}
+
+impl Default for UnpackPolicy {
+    fn default() -> Self {
+        UnpackPolicy {
+            max_depth: 4,
+            reject_escaping_symlinks: true,
+            apply_file_permissions: false,
+        }
+    }
+}
+
+/// One line of the audit trail [`unpack_recursively`] returns: what was
+/// written, what kind of filesystem object it is, and the metadata that was
+/// either applied (permissions, if [`UnpackPolicy::apply_file_permissions`])
+/// or merely recorded for review (ownership is always just recorded; see
+/// [`ArchiveEntry::owner`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionManifestEntry {
+    pub path: PathBuf,
+    pub kind: ArchiveEntryKind,
+    pub mode: Option<u32>,
+    pub owner: Option<(u32, u32)>,
+}
+
+/// Rejects a symlink `target` that's absolute or that, once resolved against
+/// the directory containing the symlink (`entry_dir`, relative to the
+/// extraction root), would climb above the extraction root via `..`.
+fn symlink_target_escapes_root(entry_dir: &Path, target: &Path) -> bool {
+    if target.is_absolute() {
+        return true;
+    }
+    let mut resolved: Vec<&std::ffi::OsStr> = entry_dir
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect();
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if resolved.pop().is_none() {
+                    return true;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return true,
+        }
+    }
+    false
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, destination_path: &Path) -> Result<(), ArchiveError> {
+    std::os::unix::fs::symlink(target, destination_path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _destination_path: &Path) -> Result<(), ArchiveError> {
+    Err(ArchiveError::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlink extraction is only supported on unix",
+    )))
+}
+
+#[cfg(unix)]
+fn apply_mode(destination_path: &Path, mode: u32) -> Result<(), ArchiveError> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(destination_path, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_destination_path: &Path, _mode: u32) -> Result<(), ArchiveError> {
+    Ok(())
+}
+
+/// Extracts `archive_path` into `destination_root` using whichever
+/// registered format recognizes it, recursing into any extracted file that
+/// itself looks like a registered archive format (up to `policy.max_depth`
+/// levels deep). Every extracted entry's path is run through
+/// [`sanitize_entry_path`] before it's joined onto `destination_root`,
+/// regardless of which format produced it, and every symlink entry's target
+/// is checked against `policy.reject_escaping_symlinks`. Returns an
+/// [`ExtractionManifestEntry`] per file and symlink written, top-level and
+/// nested, for auditing.
+pub fn unpack_recursively(
+    registry: &ArchiveFormatRegistry,
+    archive_path: &Path,
+    destination_root: &Path,
+    policy: UnpackPolicy,
+) -> Result<Vec<ExtractionManifestEntry>, ArchiveError> {
+    unpack_at_depth(registry, archive_path, destination_root, &policy, 0)
+}
+
+fn unpack_at_depth(
+    registry: &ArchiveFormatRegistry,
+    archive_path: &Path,
+    destination_root: &Path,
+    policy: &UnpackPolicy,
+    depth: usize,
+) -> Result<Vec<ExtractionManifestEntry>, ArchiveError> {
+    let format = registry
+        .detect_format(archive_path)
+        .ok_or_else(|| ArchiveError::UnsupportedFormat(archive_path.to_path_buf()))?;
+
+    let mut manifest = Vec::new();
+    for entry in format.list(archive_path)? {
+        if entry.kind == ArchiveEntryKind::Directory {
+            continue;
+        }
+
+        let sanitized = sanitize_entry_path(&entry.path)?;
+        let destination_path = destination_root.join(&sanitized);
+        if let Some(parent) = destination_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if let ArchiveEntryKind::Symlink { target } = &entry.kind {
+            let entry_dir = sanitized.parent().unwrap_or_else(|| Path::new(""));
+            if policy.reject_escaping_symlinks && symlink_target_escapes_root(entry_dir, target) {
+                return Err(ArchiveError::UnsafeSymlinkTarget(entry.path.clone(), target.clone()));
+            }
+            create_symlink(target, &destination_path)?;
+            manifest.push(ExtractionManifestEntry {
+                path: destination_path,
+                kind: entry.kind.clone(),
+                mode: entry.mode,
+                owner: entry.owner,
+            });
+            continue;
+        }
+
+        format.extract_entry(archive_path, &entry.path, &destination_path)?;
+        if policy.apply_file_permissions {
+            if let Some(mode) = entry.mode {
+                apply_mode(&destination_path, mode)?;
+            }
+        }
+        manifest.push(ExtractionManifestEntry {
+            path: destination_path.clone(),
+            kind: entry.kind,
+            mode: entry.mode,
+            owner: entry.owner,
+        });
+
+        if registry.detect_format(&destination_path).is_some() {
+            if depth >= policy.max_depth {
+                return Err(ArchiveError::DepthLimitExceeded { max_depth: policy.max_depth });
+            }
+            let nested = unpack_at_depth(registry, &destination_path, destination_root, policy, depth + 1)?;
+            manifest.extend(nested);
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+        let file = File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_path(name).unwrap();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *contents).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    /// Writes a tar entry whose path string is never sanitized by this
+    /// helper, to exercise `unpack_recursively`'s own path-traversal check
+    /// rather than the `tar` crate's.
+    fn write_tar_gz_with_raw_entry_path(path: &Path, entry_path: &str, contents: &[u8]) {
+        let file = File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        // `set_path` on a GNU header rejects `..` components outright, so the
+        // path bytes are written directly into the header to reach
+        // `unpack_recursively`'s own check.
+        let path_field = header.as_gnu_mut().unwrap().name.as_mut();
+        let bytes = entry_path.as_bytes();
+        path_field[..bytes.len()].copy_from_slice(bytes);
+        header.set_cksum();
+        builder.append(&header, contents).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    /// Writes a tar archive containing a single symlink entry pointing at
+    /// `target`, exactly as an untrusted archive's own headers would record
+    /// it (no sanitization).
+    fn write_tar_gz_with_symlink(path: &Path, entry_name: &str, target: &str) {
+        let file = File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_path(entry_name).unwrap();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_link_name(target).unwrap();
+        header.set_size(0);
+        header.set_mode(0o777);
+        header.set_cksum();
+        builder.append(&header, io::empty()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn detect_format_picks_the_matching_registered_format() {
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        assert_eq!(registry.detect_format(Path::new("a.tar.gz")).unwrap().name(), "tar.gz");
+        assert_eq!(registry.detect_format(Path::new("a.tgz")).unwrap().name(), "tar.gz");
+        assert_eq!(registry.detect_format(Path::new("a.tar")).unwrap().name(), "tar");
+        assert!(registry.detect_format(Path::new("a.7z")).is_none());
+    }
+
+    #[test]
+    fn unpack_recursively_extracts_every_file_in_a_flat_archive() {
+        let dir = temp_dir("transfiguration-unpack-flat");
+        let archive_path = dir.join("archive.tar.gz");
+        write_tar_gz(&archive_path, &[("a.txt", b"hello"), ("b.txt", b"world")]);
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let extracted = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default()).unwrap();
+
+        assert_eq!(extracted.len(), 2);
+        assert_eq!(fs::read_to_string(destination.join("a.txt")).unwrap(), "hello");
+        assert_eq!(fs::read_to_string(destination.join("b.txt")).unwrap(), "world");
+    }
+
+    #[test]
+    fn unpack_recursively_descends_into_a_nested_archive() {
+        let dir = temp_dir("transfiguration-unpack-nested");
+        let inner_path = dir.join("inner.tar.gz");
+        write_tar_gz(&inner_path, &[("secret.txt", b"buried")]);
+        let inner_bytes = fs::read(&inner_path).unwrap();
+
+        let outer_path = dir.join("outer.tar.gz");
+        write_tar_gz(&outer_path, &[("inner.tar.gz", &inner_bytes)]);
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let extracted = unpack_recursively(&registry, &outer_path, &destination, UnpackPolicy::default()).unwrap();
+
+        assert_eq!(extracted.len(), 2, "both the nested archive and the file inside it are reported");
+        assert_eq!(fs::read_to_string(destination.join("secret.txt")).unwrap(), "buried");
+    }
+
+    #[test]
+    fn recursion_past_max_depth_is_rejected() {
+        let dir = temp_dir("transfiguration-unpack-depth-limit");
+        let inner_path = dir.join("inner.tar.gz");
+        write_tar_gz(&inner_path, &[("secret.txt", b"buried")]);
+        let inner_bytes = fs::read(&inner_path).unwrap();
+
+        let outer_path = dir.join("outer.tar.gz");
+        write_tar_gz(&outer_path, &[("inner.tar.gz", &inner_bytes)]);
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let result = unpack_recursively(
+            &registry,
+            &outer_path,
+            &destination,
+            UnpackPolicy { max_depth: 0, ..UnpackPolicy::default() },
+        );
+
+        assert!(matches!(result, Err(ArchiveError::DepthLimitExceeded { max_depth: 0 })));
+    }
+
+    #[test]
+    fn an_entry_path_that_climbs_out_of_the_root_is_rejected() {
+        let dir = temp_dir("transfiguration-unpack-traversal");
+        let archive_path = dir.join("evil.tar.gz");
+        write_tar_gz_with_raw_entry_path(&archive_path, "../../etc/passwd", b"pwned");
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let result = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default());
+
+        assert!(matches!(result, Err(ArchiveError::PathTraversal(_))));
+        assert!(!destination.exists(), "nothing should be written once a traversal attempt is detected");
+    }
+
+    #[test]
+    fn an_unrecognized_file_extension_is_rejected() {
+        let dir = temp_dir("transfiguration-unpack-unsupported");
+        let archive_path = dir.join("archive.7z");
+        fs::write(&archive_path, b"not really a 7z file").unwrap();
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let result = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default());
+
+        assert!(matches!(result, Err(ArchiveError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn a_symlink_within_the_root_is_extracted_and_reported_in_the_manifest() {
+        let dir = temp_dir("transfiguration-unpack-symlink-ok");
+        let archive_path = dir.join("archive.tar.gz");
+        write_tar_gz_with_symlink(&archive_path, "link.txt", "target.txt");
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let manifest = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default()).unwrap();
+
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0].kind, ArchiveEntryKind::Symlink { target: PathBuf::from("target.txt") });
+        assert_eq!(fs::read_link(destination.join("link.txt")).unwrap(), PathBuf::from("target.txt"));
+    }
+
+    #[test]
+    fn a_symlink_with_an_absolute_target_is_rejected() {
+        let dir = temp_dir("transfiguration-unpack-symlink-absolute");
+        let archive_path = dir.join("archive.tar.gz");
+        write_tar_gz_with_symlink(&archive_path, "link.txt", "/etc/passwd");
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let result = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default());
+
+        assert!(matches!(result, Err(ArchiveError::UnsafeSymlinkTarget(_, _))));
+    }
+
+    #[test]
+    fn a_symlink_that_climbs_out_of_the_root_is_rejected() {
+        let dir = temp_dir("transfiguration-unpack-symlink-escape");
+        let archive_path = dir.join("archive.tar.gz");
+        write_tar_gz_with_symlink(&archive_path, "nested/link.txt", "../../etc/passwd");
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let result = unpack_recursively(&registry, &archive_path, &destination, UnpackPolicy::default());
+
+        assert!(matches!(result, Err(ArchiveError::UnsafeSymlinkTarget(_, _))));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn apply_file_permissions_applies_the_archived_mode_when_enabled() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("transfiguration-unpack-permissions");
+        let archive_path = dir.join("archive.tar.gz");
+        write_tar_gz(&archive_path, &[("a.txt", b"hello")]);
+
+        let destination = dir.join("out");
+        let registry = ArchiveFormatRegistry::with_builtin_formats();
+        let policy = UnpackPolicy { apply_file_permissions: true, ..UnpackPolicy::default() };
+        let manifest = unpack_recursively(&registry, &archive_path, &destination, policy).unwrap();
+
+        assert_eq!(manifest[0].mode, Some(0o644));
+        let applied_mode = fs::metadata(destination.join("a.txt")).unwrap().permissions().mode() & 0o777;
+        assert_eq!(applied_mode, 0o644);
+    }
+}
@@ -0,0 +1,751 @@
+//! Pre-flight checks for a run's configuration, so an obviously-bad setting
+//! (more agents than the memory budget allows, a timeout too tight for any
+//! chunk to finish, summaries that blow past their length limit) fails fast
+//! with a concrete fix instead of surfacing as a confusing failure or
+//! garbled report midway through a run.
+//!
+//! Summary length limits are keyed by [`ChunkClass`] and [`Language`] rather
+//! than one fixed number: a German or Japanese summary legitimately runs
+//! longer than an English one for the same content, and a test-class
+//! summary tolerates more detail than a one-line production summary.
+//!
+//! [`Language`] also now reaches into the prompt itself:
+//! [`Language::prompt_directive`] is the instruction
+//! [`crate::engine::SummaryRun::summarize_all_chunks_with_language`] appends
+//! to a chunk's prompt asking the model to actually write in that language,
+//! and [`RunArtifacts::language`](crate::report::RunArtifacts::language)
+//! records which one a run used, the same way `model_license` records which
+//! license produced a run's summaries.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::ChunkClass;
+
+/// Natural language a summary's prose is written in. Length limits are
+/// tuned per language because some languages are routinely more verbose
+/// than others for equivalent content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    German,
+    Japanese,
+    Chinese,
+}
+
+impl Default for Language {
+    /// Every prompt in this crate is written in English; `English` is the
+    /// language a run uses unless a caller opts into another one.
+    fn default() -> Self {
+        Language::English
+    }
+}
+
+impl Language {
+    /// The instruction appended to a chunk's prompt asking the model to
+    /// respond in this language, or `None` for [`Language::English`] since
+    /// every prompt here is already written in English and needs no extra
+    /// steering.
+    pub fn prompt_directive(self) -> Option<&'static str> {
+        match self {
+            Language::English => None,
+            Language::German => Some("Write the summary in German."),
+            Language::Japanese => Some("Write the summary in Japanese."),
+            Language::Chinese => Some("Write the summary in Chinese."),
+        }
+    }
+}
+
+/// How a [`LengthLimit`] measures a summary's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// Raw UTF-8 byte count; penalizes any multi-byte script.
+    Bytes,
+    /// Unicode scalar ("char") count — closer to "glyph count" than bytes
+    /// for most scripts.
+    Chars,
+    /// Whitespace-delimited word count, used as a cheap token-count proxy
+    /// without pulling in a real tokenizer.
+    Tokens,
+}
+
+impl LengthUnit {
+    fn measure(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => text.len(),
+            LengthUnit::Chars => text.chars().count(),
+            LengthUnit::Tokens => text.split_whitespace().count(),
+        }
+    }
+}
+
+/// A length limit expressed in a specific [`LengthUnit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthLimit {
+    pub unit: LengthUnit,
+    pub max: usize,
+}
+
+/// An explicit override that replaces the per-[`ChunkClass`]/[`Language`]
+/// default lookup for every summary in a run, regardless of its class or
+/// language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryStyle {
+    Concise,
+    Detailed,
+    /// A single line of prose: no embedded newlines, no code fences. Paired
+    /// with [`crate::engine::decode::one_line_forbidden_token_ids`] so a
+    /// constrained decode steers the model away from both instead of a
+    /// post-hoc truncation losing whatever came after the first line break.
+    OneLine,
+}
+
+impl SummaryStyle {
+    fn length_limit(self) -> LengthLimit {
+        match self {
+            SummaryStyle::Concise => LengthLimit {
+                unit: LengthUnit::Chars,
+                max: 120,
+            },
+            SummaryStyle::Detailed => LengthLimit {
+                unit: LengthUnit::Chars,
+                max: 600,
+            },
+            SummaryStyle::OneLine => LengthLimit {
+                unit: LengthUnit::Chars,
+                max: 100,
+            },
+        }
+    }
+
+    /// Whether a summary in this style must not contain a literal newline.
+    /// Checked independently of [`length_limit`](Self::length_limit) since a
+    /// short-but-multi-line summary would otherwise pass the length check.
+    pub fn forbids_embedded_newlines(self) -> bool {
+        matches!(self, SummaryStyle::OneLine)
+    }
+}
+
+/// Default length limits by (chunk class, language), since a verbose
+/// language or a more detail-tolerant content kind shouldn't be held to the
+/// same limit as a one-line English production summary. Any pair missing
+/// from this table falls back to [`FALLBACK_LENGTH_LIMIT`].
+const DEFAULT_LENGTH_LIMITS: &[(ChunkClass, Language, LengthLimit)] = &[
+    (
+        ChunkClass::Production,
+        Language::English,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 120,
+        },
+    ),
+    (
+        ChunkClass::Production,
+        Language::German,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 160,
+        },
+    ),
+    (
+        ChunkClass::Production,
+        Language::Japanese,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 200,
+        },
+    ),
+    (
+        ChunkClass::Production,
+        Language::Chinese,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 200,
+        },
+    ),
+    (
+        ChunkClass::Test,
+        Language::English,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 160,
+        },
+    ),
+    (
+        ChunkClass::Bench,
+        Language::English,
+        LengthLimit {
+            unit: LengthUnit::Chars,
+            max: 160,
+        },
+    ),
+];
+
+const FALLBACK_LENGTH_LIMIT: LengthLimit = LengthLimit {
+    unit: LengthUnit::Chars,
+    max: 120,
+};
+
+fn default_length_limit(chunk_class: ChunkClass, language: Language) -> LengthLimit {
+    DEFAULT_LENGTH_LIMITS
+        .iter()
+        .find(|(class, lang, _)| *class == chunk_class && *lang == language)
+        .map(|(_, _, limit)| *limit)
+        .unwrap_or(FALLBACK_LENGTH_LIMIT)
+}
+
+/// The limit actually enforced for a summary: `summary_style`, if set,
+/// overrides the per-class/per-language default table for every summary.
+fn effective_length_limit(summary_style: Option<SummaryStyle>, chunk_class: ChunkClass, language: Language) -> LengthLimit {
+    summary_style
+        .map(SummaryStyle::length_limit)
+        .unwrap_or_else(|| default_length_limit(chunk_class, language))
+}
+
+/// A produced summary as handed to the validator: its text plus enough
+/// context to look up the right length limit for it.
+#[derive(Debug, Clone)]
+pub struct SummaryRecord {
+    pub chunk_class: ChunkClass,
+    pub text: String,
+}
+
+/// The settings a run is about to execute with, as handed to the validator
+/// before any inference happens.
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    pub agent_count: usize,
+    pub memory_budget_mb: u64,
+    pub chunk_timeout: Duration,
+    /// Language the run's summaries are written in, used to pick a default
+    /// length limit per [`ChunkClass`]. Ignored if `summary_style` is set.
+    pub language: Language,
+    /// Overrides the per-class/per-language default length limit for every
+    /// summary in the run. Leave `None` to use [`DEFAULT_LENGTH_LIMITS`].
+    pub summary_style: Option<SummaryStyle>,
+}
+
+/// Named hardware-sizing presets: an agreed-on vocabulary a config file or a
+/// future CLI `--hardware-preset` flag can select [`RunConfig`] (and
+/// [`crate::engine::jobs::SchedulerLimits`]) by name instead of each call
+/// site picking agent_count/memory_budget_mb/chunk_timeout by trial and
+/// error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardwarePreset {
+    Laptop8Gb,
+    Workstation32GbGpu,
+    Ci2Core,
+}
+
+impl HardwarePreset {
+    /// Parses a preset name in the form a CLI flag would take
+    /// (`laptop-8gb`, `workstation-32gb-gpu`, `ci-2core`). `None` for
+    /// anything else, so a caller resolving a user-supplied flag value can
+    /// report an unrecognized name instead of silently falling back to a
+    /// default.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "laptop-8gb" => Some(HardwarePreset::Laptop8Gb),
+            "workstation-32gb-gpu" => Some(HardwarePreset::Workstation32GbGpu),
+            "ci-2core" => Some(HardwarePreset::Ci2Core),
+            _ => None,
+        }
+    }
+}
+
+/// Rough per-agent memory footprint used to size `agent_count` against
+/// `memory_budget_mb`. Conservative on purpose: it's better to warn a run
+/// that would have fit than to let one OOM mid-run.
+const ESTIMATED_MB_PER_AGENT: u64 = 512;
+
+/// Below this, a chunk has a realistic chance of being killed by its own
+/// timeout before a backend can respond at all.
+const MINIMUM_VIABLE_CHUNK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Distinguishes one kind of pre-flight violation from another, so output
+/// formats and the remediation table only ever need to match on this once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    TooManyAgentsForMemoryBudget,
+    MemoryBudgetExceededAtRuntime,
+    ChunkTimeoutTooLow,
+    SummaryExceedsStyleLimit,
+    EmbeddedNewlineNotAllowed,
+}
+
+/// Maps a [`ViolationKind`] to the fix a human should try first. Kept as a
+/// flat table rather than inlined in each check, so every violation of a
+/// given kind reports the same canonical remediation regardless of which
+/// check produced it.
+const REMEDIATION_RULES: &[(ViolationKind, &str)] = &[
+    (
+        ViolationKind::TooManyAgentsForMemoryBudget,
+        "reduce agents to fit memory, or raise the memory budget",
+    ),
+    (
+        ViolationKind::MemoryBudgetExceededAtRuntime,
+        "this process is already over its memory budget right now — reduce agents, raise the budget, or investigate a leak with crate::soak",
+    ),
+    (
+        ViolationKind::ChunkTimeoutTooLow,
+        "increase chunk timeout so a chunk has time to complete before being aborted",
+    ),
+    (
+        ViolationKind::SummaryExceedsStyleLimit,
+        "summaries exceed their length limit — switch SummaryStyle, or set the run's Language if it's being measured against the wrong default",
+    ),
+    (
+        ViolationKind::EmbeddedNewlineNotAllowed,
+        "a SummaryStyle::OneLine summary contains a newline — mask newline (and code-fence) token ids during decoding instead of truncating afterwards",
+    ),
+];
+
+fn remediation_for(kind: ViolationKind) -> &'static str {
+    REMEDIATION_RULES
+        .iter()
+        .find(|(rule_kind, _)| *rule_kind == kind)
+        .map(|(_, hint)| *hint)
+        .expect("every ViolationKind has a remediation rule")
+}
+
+/// One failed check: which rule it was, what specifically was observed, and
+/// what to do about it.
+#[derive(Debug, Clone)]
+pub struct ValidationViolation {
+    pub kind: ViolationKind,
+    pub detail: String,
+    pub remediation: &'static str,
+}
+
+impl ValidationViolation {
+    fn new(kind: ViolationKind, detail: String) -> Self {
+        ValidationViolation {
+            kind,
+            detail,
+            remediation: remediation_for(kind),
+        }
+    }
+}
+
+/// The outcome of validating a [`RunConfig`] (and, optionally, the summaries
+/// it produced) against the rules above.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub violations: Vec<ValidationViolation>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// One line per violation: what failed and what to do about it. Used
+    /// for terminal/log output where a human wants the fix at a glance.
+    pub fn summary(&self) -> String {
+        if self.violations.is_empty() {
+            return "PASS: no configuration violations".to_string();
+        }
+        self.violations
+            .iter()
+            .map(|violation| format!("FAIL: {} — {}", violation.detail, violation.remediation))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        if self.violations.is_empty() {
+            return "**Validation: PASS** — no configuration violations.".to_string();
+        }
+        let mut markdown = String::from("**Validation: FAIL**\n\n");
+        for violation in &self.violations {
+            markdown.push_str(&format!(
+                "- {}\n  - Remediation: {}\n",
+                violation.detail, violation.remediation
+            ));
+        }
+        markdown
+    }
+
+    pub fn to_html(&self) -> String {
+        if self.violations.is_empty() {
+            return "<p class=\"validation pass\">PASS: no configuration violations</p>".to_string();
+        }
+        let items: String = self
+            .violations
+            .iter()
+            .map(|violation| {
+                format!(
+                    "<li>{}<br><em>Remediation: {}</em></li>",
+                    escape_html(&violation.detail),
+                    escape_html(violation.remediation)
+                )
+            })
+            .collect();
+        format!("<p class=\"validation fail\">Validation: FAIL</p><ul>{items}</ul>")
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl RunConfig {
+    /// Resolves a [`HardwarePreset`] to the [`RunConfig`] it stands for.
+    pub fn from_preset(preset: HardwarePreset) -> Self {
+        match preset {
+            HardwarePreset::Laptop8Gb => RunConfig::laptop_8gb(),
+            HardwarePreset::Workstation32GbGpu => RunConfig::workstation_32gb_gpu(),
+            HardwarePreset::Ci2Core => RunConfig::ci_2core(),
+        }
+    }
+
+    /// Tuned for a typical 8 GB laptop running the summarizer alongside
+    /// other work: few enough agents that [`ESTIMATED_MB_PER_AGENT`] stays
+    /// well under the budget, and a generous chunk timeout since a laptop
+    /// CPU backend is the slowest this crate expects to run against.
+    ///
+    /// No preset carries a measured "N chunks/sec" figure ([`crate::scaling`]
+    /// can measure one for a given host) — expected throughput here is
+    /// "noticeably the slowest of the three presets; tuned to finish
+    /// without OOMing or timing out rather than for speed."
+    pub fn laptop_8gb() -> Self {
+        RunConfig {
+            agent_count: 2,
+            memory_budget_mb: 6144,
+            chunk_timeout: Duration::from_secs(60),
+            language: Language::English,
+            summary_style: None,
+        }
+    }
+
+    /// Tuned for a 32 GB workstation with a GPU-backed inference backend:
+    /// enough agents to keep a GPU backend saturated without approaching
+    /// the memory budget, and a tighter chunk timeout since GPU inference
+    /// latency is much lower than CPU.
+    ///
+    /// Expected throughput: the fastest of the three presets — once a real
+    /// GPU-backed [`crate::engine::InferenceBackend`] is plugged in, the
+    /// backend itself becomes the bottleneck rather than this config.
+    pub fn workstation_32gb_gpu() -> Self {
+        RunConfig {
+            agent_count: 16,
+            memory_budget_mb: 24576,
+            chunk_timeout: Duration::from_secs(15),
+            language: Language::English,
+            summary_style: None,
+        }
+    }
+
+    /// Tuned for a 2-core CI runner: few agents (more would just thrash the
+    /// scheduler on two cores), a conservative memory budget matching CI's
+    /// typically-capped container limits, and a short chunk timeout so a
+    /// genuinely stuck chunk fails the job instead of burning the whole CI
+    /// time budget.
+    ///
+    /// Expected throughput: low, but "low and predictable" is the actual
+    /// goal in CI rather than raw speed.
+    pub fn ci_2core() -> Self {
+        RunConfig {
+            agent_count: 1,
+            memory_budget_mb: 2048,
+            chunk_timeout: Duration::from_secs(20),
+            language: Language::English,
+            summary_style: None,
+        }
+    }
+}
+
+/// Runs every pre-flight check against `config` and the summaries it (or a
+/// dry run of it) produced, collecting every violation rather than stopping
+/// at the first one so a human can fix them all in one pass.
+pub fn validate_run(config: &RunConfig, summaries: &[SummaryRecord]) -> ValidationReport {
+    let mut violations = Vec::new();
+
+    let estimated_memory_mb = config.agent_count as u64 * ESTIMATED_MB_PER_AGENT;
+    if estimated_memory_mb > config.memory_budget_mb {
+        violations.push(ValidationViolation::new(
+            ViolationKind::TooManyAgentsForMemoryBudget,
+            format!(
+                "{} agents at ~{ESTIMATED_MB_PER_AGENT} MB each need ~{estimated_memory_mb} MB, \
+                 over the {} MB budget",
+                config.agent_count, config.memory_budget_mb
+            ),
+        ));
+    }
+
+    if let Some(violation) = runtime_memory_budget_violation(config) {
+        violations.push(violation);
+    }
+
+    if config.chunk_timeout < MINIMUM_VIABLE_CHUNK_TIMEOUT {
+        violations.push(ValidationViolation::new(
+            ViolationKind::ChunkTimeoutTooLow,
+            format!(
+                "chunk timeout of {:?} is below the {:?} minimum a backend needs to respond",
+                config.chunk_timeout, MINIMUM_VIABLE_CHUNK_TIMEOUT
+            ),
+        ));
+    }
+
+    if let Some(violation) = worst_length_violation(config, summaries) {
+        violations.push(violation);
+    }
+
+    if let Some(violation) = first_embedded_newline_violation(config, summaries) {
+        violations.push(violation);
+    }
+
+    ValidationReport { violations }
+}
+
+/// Unlike [`ESTIMATED_MB_PER_AGENT`]'s static, pre-flight estimate, this
+/// checks [`crate::memory::current_rss_bytes`] — this call's own process's
+/// *actual* memory use right now — against `config.memory_budget_mb`.
+/// `None` both when it fits and when the platform's RSS read failed
+/// ([`crate::memory::current_rss_bytes`] returns `0` for either), so a
+/// platform this crate can't introspect never reports a false violation.
+fn runtime_memory_budget_violation(config: &RunConfig) -> Option<ValidationViolation> {
+    let actual_bytes = crate::memory::current_rss_bytes();
+    if actual_bytes == 0 {
+        return None;
+    }
+    let actual_mb = actual_bytes / (1024 * 1024);
+    if actual_mb > config.memory_budget_mb {
+        return Some(ValidationViolation::new(
+            ViolationKind::MemoryBudgetExceededAtRuntime,
+            format!("this process is already using ~{actual_mb} MB, over the {} MB budget", config.memory_budget_mb),
+        ));
+    }
+    None
+}
+
+/// Finds the summary that exceeds its own (class/language-specific) length
+/// limit by the widest margin, if any do, so the report surfaces the single
+/// most actionable offender instead of one entry per summary.
+fn worst_length_violation(config: &RunConfig, summaries: &[SummaryRecord]) -> Option<ValidationViolation> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let limit = effective_length_limit(config.summary_style, summary.chunk_class, config.language);
+            let measured = limit.unit.measure(&summary.text);
+            (measured > limit.max).then(|| (summary, limit, measured, measured - limit.max))
+        })
+        .max_by_key(|(_, _, _, overflow)| *overflow)
+        .map(|(summary, limit, measured, _)| {
+            let snippet: String = summary.text.chars().take(40).collect();
+            ValidationViolation::new(
+                ViolationKind::SummaryExceedsStyleLimit,
+                format!(
+                    "a {:?} summary measures {measured} {:?}, over the {} limit for {:?}/{:?} \
+                     (starts with: \"{snippet}...\")",
+                    summary.chunk_class, limit.unit, limit.max, summary.chunk_class, config.language
+                ),
+            )
+        })
+}
+
+/// Finds the first summary that still contains a literal newline despite
+/// `SummaryStyle::OneLine` being selected — a sign a constrained decode
+/// wasn't actually wired in for this run, since [`SummaryStyle::OneLine`]
+/// masks newline tokens at generation time rather than stripping them after
+/// the fact. A no-op for every other style.
+fn first_embedded_newline_violation(config: &RunConfig, summaries: &[SummaryRecord]) -> Option<ValidationViolation> {
+    if !config.summary_style.map(SummaryStyle::forbids_embedded_newlines).unwrap_or(false) {
+        return None;
+    }
+
+    summaries.iter().find(|summary| summary.text.contains('\n')).map(|summary| {
+        let snippet: String = summary.text.chars().take(40).collect();
+        ValidationViolation::new(
+            ViolationKind::EmbeddedNewlineNotAllowed,
+            format!("a {:?} summary contains a newline (starts with: \"{snippet}...\")", summary.chunk_class),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passing_config() -> RunConfig {
+        RunConfig {
+            agent_count: 2,
+            memory_budget_mb: 4096,
+            chunk_timeout: Duration::from_secs(30),
+            language: Language::English,
+            summary_style: None,
+        }
+    }
+
+    fn summary(chunk_class: ChunkClass, text: &str) -> SummaryRecord {
+        SummaryRecord {
+            chunk_class,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn passing_config_produces_no_violations() {
+        let report = validate_run(&passing_config(), &[summary(ChunkClass::Production, "a short summary")]);
+        assert!(report.is_valid());
+        assert_eq!(report.summary(), "PASS: no configuration violations");
+    }
+
+    #[test]
+    fn every_violation_carries_a_remediation_hint() {
+        let config = RunConfig {
+            agent_count: 100,
+            memory_budget_mb: 1024,
+            chunk_timeout: Duration::from_millis(100),
+            ..passing_config()
+        };
+        let long_summary = summary(ChunkClass::Production, &"x".repeat(200));
+
+        let report = validate_run(&config, &[long_summary]);
+
+        assert_eq!(report.violations.len(), 3);
+        assert!(report.violations.iter().all(|v| !v.remediation.is_empty()));
+        assert!(report.summary().contains("reduce agents to fit memory"));
+        assert!(report.summary().contains("increase chunk timeout"));
+        assert!(report.summary().contains("switch SummaryStyle"));
+    }
+
+    #[test]
+    fn one_line_style_rejects_a_summary_containing_a_newline() {
+        let config = RunConfig {
+            summary_style: Some(SummaryStyle::OneLine),
+            ..passing_config()
+        };
+        let report = validate_run(&config, &[summary(ChunkClass::Production, "first line\nsecond line")]);
+
+        assert!(!report.is_valid());
+        assert!(report.summary().contains("mask newline"));
+    }
+
+    #[test]
+    fn one_line_style_accepts_a_single_line_summary() {
+        let config = RunConfig {
+            summary_style: Some(SummaryStyle::OneLine),
+            ..passing_config()
+        };
+        let report = validate_run(&config, &[summary(ChunkClass::Production, "one tidy line")]);
+
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn other_styles_ignore_embedded_newlines() {
+        let report = validate_run(&passing_config(), &[summary(ChunkClass::Production, "first line\nsecond line")]);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn verbose_languages_tolerate_longer_summaries_by_default() {
+        let text = "x".repeat(150);
+
+        let english_report = validate_run(
+            &RunConfig {
+                language: Language::English,
+                ..passing_config()
+            },
+            &[summary(ChunkClass::Production, &text)],
+        );
+        let japanese_report = validate_run(
+            &RunConfig {
+                language: Language::Japanese,
+                ..passing_config()
+            },
+            &[summary(ChunkClass::Production, &text)],
+        );
+
+        assert!(!english_report.is_valid());
+        assert!(japanese_report.is_valid());
+    }
+
+    #[test]
+    fn test_class_summaries_get_a_more_tolerant_default_than_production() {
+        let text = "x".repeat(140);
+
+        let production_report = validate_run(&passing_config(), &[summary(ChunkClass::Production, &text)]);
+        let test_report = validate_run(&passing_config(), &[summary(ChunkClass::Test, &text)]);
+
+        assert!(!production_report.is_valid());
+        assert!(test_report.is_valid());
+    }
+
+    #[test]
+    fn explicit_summary_style_overrides_the_per_language_default() {
+        let text = "x".repeat(150);
+        let config = RunConfig {
+            language: Language::Japanese,
+            summary_style: Some(SummaryStyle::Concise),
+            ..passing_config()
+        };
+
+        let report = validate_run(&config, &[summary(ChunkClass::Production, &text)]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn token_unit_counts_words_not_characters() {
+        assert_eq!(LengthUnit::Tokens.measure("the quick brown fox"), 4);
+        assert_eq!(LengthUnit::Chars.measure("the quick brown fox"), 19);
+    }
+
+    #[test]
+    fn markdown_and_html_both_surface_every_remediation() {
+        let config = RunConfig {
+            agent_count: 100,
+            memory_budget_mb: 1024,
+            ..passing_config()
+        };
+        let report = validate_run(&config, &[]);
+
+        let markdown = report.to_markdown();
+        let html = report.to_html();
+        assert!(markdown.contains("reduce agents to fit memory"));
+        assert!(html.contains("reduce agents to fit memory"));
+        assert!(html.contains("<li>"));
+    }
+
+    #[test]
+    fn hardware_preset_parse_recognizes_every_named_preset_and_rejects_garbage() {
+        assert_eq!(HardwarePreset::parse("laptop-8gb"), Some(HardwarePreset::Laptop8Gb));
+        assert_eq!(HardwarePreset::parse("workstation-32gb-gpu"), Some(HardwarePreset::Workstation32GbGpu));
+        assert_eq!(HardwarePreset::parse("ci-2core"), Some(HardwarePreset::Ci2Core));
+        assert_eq!(HardwarePreset::parse("supercomputer"), None);
+    }
+
+    #[test]
+    fn every_preset_passes_its_own_validation() {
+        for preset in [HardwarePreset::Laptop8Gb, HardwarePreset::Workstation32GbGpu, HardwarePreset::Ci2Core] {
+            let config = RunConfig::from_preset(preset);
+            let report = validate_run(&config, &[]);
+            assert!(report.is_valid(), "{preset:?} should be internally consistent: {}", report.summary());
+        }
+    }
+
+    #[test]
+    fn ci_2core_is_the_most_conservative_preset() {
+        let ci = RunConfig::ci_2core();
+        let laptop = RunConfig::laptop_8gb();
+        let workstation = RunConfig::workstation_32gb_gpu();
+
+        assert!(ci.agent_count <= laptop.agent_count);
+        assert!(laptop.agent_count <= workstation.agent_count);
+        assert!(ci.memory_budget_mb <= laptop.memory_budget_mb);
+        assert!(laptop.memory_budget_mb <= workstation.memory_budget_mb);
+    }
+
+    #[test]
+    fn html_escapes_user_controlled_summary_text() {
+        let config = passing_config();
+        let text = "<script>alert(1)</script>".repeat(10);
+        let html = validate_run(&config, &[summary(ChunkClass::Production, &text)]).to_html();
+        assert!(!html.contains("<script>alert"));
+    }
+}